@@ -0,0 +1,17 @@
+-- Full-text index of repository contents (default branch)
+
+CREATE TABLE IF NOT EXISTS code_search_documents (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id BIGINT NOT NULL,
+    commit_sha TEXT NOT NULL,
+    path TEXT NOT NULL,
+    language TEXT,
+    content TEXT NOT NULL,
+    content_tsv TSVECTOR GENERATED ALWAYS AS (to_tsvector('simple', content)) STORED,
+    indexed_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE,
+    UNIQUE(repository_id, path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_code_search_documents_tsv ON code_search_documents USING GIN (content_tsv);
+CREATE INDEX IF NOT EXISTS idx_code_search_documents_language ON code_search_documents (language);
@@ -0,0 +1,11 @@
+-- Cached repository statistics, keyed by the head commit they were computed for
+
+CREATE TABLE IF NOT EXISTS repository_insights (
+    repository_id BIGINT NOT NULL,
+    kind TEXT NOT NULL CHECK(kind IN ('languages', 'contributors', 'activity')),
+    head_sha TEXT NOT NULL,
+    data_json TEXT NOT NULL,
+    computed_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (repository_id, kind),
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE
+);
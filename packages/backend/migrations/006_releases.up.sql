@@ -0,0 +1,36 @@
+-- Releases tied to git tags and their uploaded binary assets
+
+CREATE TABLE IF NOT EXISTS releases (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id BIGINT NOT NULL,
+    tag_name TEXT NOT NULL,
+    target_sha TEXT NOT NULL,
+    title TEXT NOT NULL,
+    notes TEXT,
+    is_draft BOOLEAN NOT NULL DEFAULT false,
+    is_prerelease BOOLEAN NOT NULL DEFAULT false,
+    author_id BIGINT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    published_at TIMESTAMPTZ,
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES users (id),
+    UNIQUE(repository_id, tag_name)
+);
+
+CREATE TABLE IF NOT EXISTS release_assets (
+    id BIGSERIAL PRIMARY KEY,
+    release_id BIGINT NOT NULL,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size BIGINT NOT NULL,
+    sha256 TEXT NOT NULL,
+    storage_path TEXT NOT NULL,
+    download_count BIGINT NOT NULL DEFAULT 0,
+    uploader_id BIGINT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (release_id) REFERENCES releases (id) ON DELETE CASCADE,
+    FOREIGN KEY (uploader_id) REFERENCES users (id),
+    UNIQUE(release_id, file_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_releases_repository_id ON releases (repository_id);
@@ -0,0 +1,15 @@
+-- GPG and SSH public keys used to verify commit signatures
+
+CREATE TABLE IF NOT EXISTS user_signing_keys (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    key_type TEXT NOT NULL CHECK(key_type IN ('gpg', 'ssh')),
+    title TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    UNIQUE(user_id, fingerprint)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_signing_keys_user_id ON user_signing_keys (user_id);
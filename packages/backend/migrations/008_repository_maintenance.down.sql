@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS repository_maintenance;
+
+ALTER TABLE users DROP COLUMN IF EXISTS is_admin;
@@ -0,0 +1,15 @@
+-- Background repository maintenance (gc, repack, commit-graph) and administrators
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_admin BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS repository_maintenance (
+    repository_id BIGINT PRIMARY KEY,
+    loose_objects BIGINT NOT NULL DEFAULT 0,
+    packs BIGINT NOT NULL DEFAULT 0,
+    last_run_at TIMESTAMPTZ,
+    last_status TEXT CHECK(last_status IN ('success', 'failed')),
+    last_error TEXT,
+    last_duration_ms BIGINT,
+    checked_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS commit_statuses;
+DROP TABLE IF EXISTS api_tokens;
@@ -0,0 +1,36 @@
+-- Built-in pipeline runs triggered on push and their steps
+
+CREATE TABLE IF NOT EXISTS pipeline_runs (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id BIGINT NOT NULL,
+    commit_sha TEXT NOT NULL,
+    ref_name TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued'
+        CHECK(status IN ('queued', 'running', 'success', 'failed', 'error')),
+    trigger_user_id BIGINT,
+    error TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ,
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE,
+    FOREIGN KEY (trigger_user_id) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_pipeline_runs_repository_id ON pipeline_runs (repository_id, id DESC);
+
+CREATE TABLE IF NOT EXISTS pipeline_steps (
+    id BIGSERIAL PRIMARY KEY,
+    run_id BIGINT NOT NULL,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    timeout_secs INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK(status IN ('pending', 'running', 'success', 'failed', 'timed_out', 'skipped')),
+    exit_code INTEGER,
+    log TEXT,
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ,
+    FOREIGN KEY (run_id) REFERENCES pipeline_runs (id) ON DELETE CASCADE,
+    UNIQUE(run_id, position)
+);
@@ -0,0 +1,3 @@
+-- Template repositories that new repositories can be generated from
+
+ALTER TABLE repositories ADD COLUMN IF NOT EXISTS is_template BOOLEAN NOT NULL DEFAULT FALSE;
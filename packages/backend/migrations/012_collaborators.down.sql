@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS repository_collaborators;
+DROP TABLE IF EXISTS project_collaborators;
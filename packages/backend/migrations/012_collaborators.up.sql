@@ -0,0 +1,27 @@
+-- Collaborators with read, write or admin access to a project or a single repository
+
+CREATE TABLE IF NOT EXISTS project_collaborators (
+    id BIGSERIAL PRIMARY KEY,
+    project_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    role TEXT NOT NULL CHECK(role IN ('read', 'write', 'admin')),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    UNIQUE(project_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_collaborators_user_id ON project_collaborators (user_id);
+
+CREATE TABLE IF NOT EXISTS repository_collaborators (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    role TEXT NOT NULL CHECK(role IN ('read', 'write', 'admin')),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    UNIQUE(repository_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_repository_collaborators_user_id ON repository_collaborators (user_id);
@@ -0,0 +1,23 @@
+-- Deploy keys: repository-scoped credentials for machines, not tied to a user account
+
+CREATE TABLE IF NOT EXISTS deploy_keys (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id BIGINT NOT NULL,
+    title TEXT NOT NULL,
+    key_type TEXT NOT NULL CHECK(key_type IN ('ssh', 'http')),
+    -- SSH public key and its SHA256 fingerprint (ssh keys only)
+    public_key TEXT,
+    fingerprint TEXT,
+    -- Hash of the generated access token (http keys only)
+    token_hash TEXT UNIQUE,
+    token_prefix TEXT,
+    read_only BOOLEAN NOT NULL DEFAULT TRUE,
+    creator_id BIGINT,
+    last_used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE,
+    FOREIGN KEY (creator_id) REFERENCES users (id) ON DELETE SET NULL,
+    UNIQUE(repository_id, fingerprint)
+);
+
+CREATE INDEX IF NOT EXISTS idx_deploy_keys_repository_id ON deploy_keys (repository_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_notification_user_id;
+ALTER TABLE notification DROP COLUMN IF EXISTS user_id;
+DROP TABLE IF EXISTS secret_findings;
@@ -0,0 +1,31 @@
+-- Secret scanning: findings in pushed blobs and per-user notifications
+
+CREATE TABLE IF NOT EXISTS secret_findings (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id BIGINT NOT NULL,
+    rule TEXT NOT NULL,
+    -- Blob and location where the secret was found
+    blob_sha TEXT NOT NULL,
+    path TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    -- Matched value with the middle part masked
+    redacted TEXT NOT NULL,
+    commit_sha TEXT NOT NULL,
+    ref_name TEXT NOT NULL,
+    state TEXT NOT NULL DEFAULT 'open' CHECK(state IN ('open', 'resolved', 'ignored')),
+    pusher_id BIGINT,
+    resolved_by BIGINT,
+    resolved_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE,
+    FOREIGN KEY (pusher_id) REFERENCES users (id) ON DELETE SET NULL,
+    FOREIGN KEY (resolved_by) REFERENCES users (id) ON DELETE SET NULL,
+    UNIQUE(repository_id, blob_sha, rule, line)
+);
+
+CREATE INDEX IF NOT EXISTS idx_secret_findings_repository_id ON secret_findings (repository_id, state);
+
+-- Notifications addressed to a single user; NULL keeps the notification global
+ALTER TABLE notification ADD COLUMN IF NOT EXISTS user_id BIGINT REFERENCES users (id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_notification_user_id ON notification (user_id);
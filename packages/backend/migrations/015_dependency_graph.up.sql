@@ -0,0 +1,5 @@
+-- Dependency graphs are cached alongside other repository insights
+
+ALTER TABLE repository_insights DROP CONSTRAINT IF EXISTS repository_insights_kind_check;
+ALTER TABLE repository_insights ADD CONSTRAINT repository_insights_kind_check
+    CHECK(kind IN ('languages', 'contributors', 'activity', 'dependencies'));
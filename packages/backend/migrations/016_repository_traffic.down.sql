@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS repository_traffic_referrers;
+DROP TABLE IF EXISTS repository_traffic_visitors;
+DROP TABLE IF EXISTS repository_traffic;
@@ -0,0 +1,30 @@
+-- Repository traffic: daily view, clone and fetch counters with unique visitors
+
+CREATE TABLE IF NOT EXISTS repository_traffic (
+    repository_id BIGINT NOT NULL,
+    day DATE NOT NULL,
+    kind TEXT NOT NULL CHECK(kind IN ('view', 'clone', 'fetch')),
+    count BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (repository_id, day, kind),
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE
+);
+
+-- Distinct visitors per day: "u:<id>" for users, a hash of address and agent otherwise
+CREATE TABLE IF NOT EXISTS repository_traffic_visitors (
+    repository_id BIGINT NOT NULL,
+    day DATE NOT NULL,
+    kind TEXT NOT NULL CHECK(kind IN ('view', 'clone', 'fetch')),
+    visitor TEXT NOT NULL,
+    PRIMARY KEY (repository_id, day, kind, visitor),
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE
+);
+
+-- Referring paths of repository views
+CREATE TABLE IF NOT EXISTS repository_traffic_referrers (
+    repository_id BIGINT NOT NULL,
+    day DATE NOT NULL,
+    path TEXT NOT NULL,
+    count BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (repository_id, day, path),
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE
+);
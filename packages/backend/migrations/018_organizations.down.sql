@@ -0,0 +1,12 @@
+ALTER TABLE spark_packages DROP COLUMN IF EXISTS organization_id;
+
+DROP INDEX IF EXISTS idx_projects_organization_namespace;
+DROP INDEX IF EXISTS idx_projects_user_namespace;
+DELETE FROM repositories
+    WHERE project_id IN (SELECT id FROM projects WHERE organization_id IS NOT NULL);
+DELETE FROM projects WHERE organization_id IS NOT NULL;
+ALTER TABLE projects DROP COLUMN IF EXISTS organization_id;
+ALTER TABLE projects ADD CONSTRAINT projects_name_owner_id_key UNIQUE (name, owner_id);
+
+DROP TABLE IF EXISTS organization_members;
+DROP TABLE IF EXISTS organizations;
@@ -0,0 +1,35 @@
+-- Organizations that own projects and packages and share the URL namespace with users
+
+CREATE TABLE IF NOT EXISTS organizations (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    display_name TEXT,
+    description TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    organization_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    role TEXT NOT NULL CHECK(role IN ('owner', 'admin', 'member')),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (organization_id, user_id),
+    FOREIGN KEY (organization_id) REFERENCES organizations (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_members_user_id ON organization_members (user_id);
+
+-- Organization projects keep the creating user in owner_id
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS organization_id BIGINT
+    REFERENCES organizations (id) ON DELETE CASCADE;
+
+-- Project names are unique within a namespace: a user or an organization
+ALTER TABLE projects DROP CONSTRAINT IF EXISTS projects_name_owner_id_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_projects_user_namespace
+    ON projects (owner_id, name) WHERE organization_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_projects_organization_namespace
+    ON projects (organization_id, name) WHERE organization_id IS NOT NULL;
+
+ALTER TABLE spark_packages ADD COLUMN IF NOT EXISTS organization_id BIGINT
+    REFERENCES organizations (id) ON DELETE CASCADE;
@@ -0,0 +1,21 @@
+-- History of project configuration revisions for diffs and rollback
+
+CREATE TABLE IF NOT EXISTS project_config_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    project_id BIGINT NOT NULL,
+    -- Sequential number within the project, starting at 1
+    revision INTEGER NOT NULL,
+    config_json TEXT NOT NULL,
+    author_id BIGINT,
+    -- Revision whose configuration was restored by a rollback
+    restored_from INTEGER,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES users (id) ON DELETE SET NULL,
+    UNIQUE(project_id, revision)
+);
+
+-- Current configurations become the first revision
+INSERT INTO project_config_revisions (project_id, revision, config_json, created_at)
+SELECT project_id, 1, config_json, updated_at FROM project_configs
+ON CONFLICT (project_id, revision) DO NOTHING;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_projects_deleted_at;
+ALTER TABLE projects DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE projects DROP COLUMN IF EXISTS archived_at;
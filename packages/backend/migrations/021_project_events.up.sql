@@ -0,0 +1,20 @@
+-- Project activity log: repositories created, configuration changes, pushes,
+-- published packages and new members
+
+CREATE TABLE IF NOT EXISTS project_events (
+    id BIGSERIAL PRIMARY KEY,
+    project_id BIGINT NOT NULL,
+    -- Repository the event belongs to; NULL for project-wide events
+    repository_id BIGINT,
+    -- NULL for deploy keys and deleted users
+    actor_id BIGINT,
+    kind VARCHAR(50) NOT NULL,
+    -- Event details as a JSON object
+    payload TEXT NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE,
+    FOREIGN KEY (actor_id) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_events_project ON project_events (project_id, id DESC);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_project_watches_user;
+DROP TABLE IF EXISTS project_watches;
+DROP INDEX IF EXISTS idx_project_stars_user;
+DROP TABLE IF EXISTS project_stars;
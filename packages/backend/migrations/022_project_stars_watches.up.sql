@@ -0,0 +1,26 @@
+-- Project stars and watch subscriptions
+
+CREATE TABLE IF NOT EXISTS project_stars (
+    project_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, user_id),
+    FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_stars_user ON project_stars (user_id);
+
+-- Explicit watch levels; project members without a row watch all activity
+CREATE TABLE IF NOT EXISTS project_watches (
+    project_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    level VARCHAR(20) NOT NULL CHECK (level IN ('all', 'releases', 'ignore')),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, user_id),
+    FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_watches_user ON project_watches (user_id);
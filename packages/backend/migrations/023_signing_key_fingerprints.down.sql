@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_user_signing_keys_fingerprint;
+ALTER TABLE user_signing_keys DROP COLUMN IF EXISTS subkey_fingerprints;
@@ -0,0 +1,13 @@
+-- Signatures are attributed by the signing key, so a key may belong to one account only.
+-- GPG subkey fingerprints let signatures made by subkeys find their primary key;
+-- GPG keys added earlier must be re-added to match signatures made by their subkeys.
+
+ALTER TABLE user_signing_keys
+    ADD COLUMN IF NOT EXISTS subkey_fingerprints TEXT[] NOT NULL DEFAULT '{}';
+
+DELETE FROM user_signing_keys k
+USING user_signing_keys earlier
+WHERE k.fingerprint = earlier.fingerprint AND k.id > earlier.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_signing_keys_fingerprint
+    ON user_signing_keys (fingerprint);
@@ -0,0 +1,18 @@
+-- Pending project transfers; the new owner has to accept a transfer before it happens
+
+CREATE TABLE IF NOT EXISTS project_transfers (
+    project_id BIGINT PRIMARY KEY,
+    -- Exactly one of the targets is set
+    target_user_id BIGINT,
+    target_organization_id BIGINT,
+    requested_by BIGINT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((target_user_id IS NULL) <> (target_organization_id IS NULL)),
+    FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+    FOREIGN KEY (target_user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (target_organization_id) REFERENCES organizations (id) ON DELETE CASCADE,
+    FOREIGN KEY (requested_by) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_transfers_user ON project_transfers (target_user_id);
+CREATE INDEX IF NOT EXISTS idx_project_transfers_organization ON project_transfers (target_organization_id);
@@ -0,0 +1 @@
+DROP VIEW IF EXISTS project_participants;
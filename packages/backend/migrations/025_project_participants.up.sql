@@ -0,0 +1,19 @@
+-- Users with project-wide access, who watch the project by default:
+-- the personal owner, project members and collaborators, members of teams
+-- added to the project and members of the owning organization
+CREATE OR REPLACE VIEW project_participants AS
+SELECT id AS project_id, owner_id AS user_id
+FROM projects
+WHERE organization_id IS NULL
+UNION
+SELECT project_id, user_id FROM project_members
+UNION
+SELECT project_id, user_id FROM project_collaborators
+UNION
+SELECT pt.project_id, tm.user_id
+FROM project_teams pt
+JOIN team_members tm ON tm.team_id = pt.team_id
+UNION
+SELECT p.id, om.user_id
+FROM projects p
+JOIN organization_members om ON om.organization_id = p.organization_id;
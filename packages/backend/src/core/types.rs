@@ -45,9 +45,20 @@ pub enum SortDirection {
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    /// Внешний адрес сервера (схема и хост) для ссылок в лентах
+    pub public_url: String,
     pub database_url: String,
     pub jwt_secret: String,
     pub repositories_path: String,
+    pub assets_path: String,
+    /// Интервал обслуживания репозиториев в секундах (0 — отключено)
+    pub maintenance_interval: u64,
+    /// Сколько репозиториев обслуживается одновременно
+    pub maintenance_concurrency: usize,
+    /// Сколько пайплайнов выполняется одновременно (0 — пайплайны отключены)
+    pub pipeline_concurrency: usize,
+    /// Непривилегированный пользователь, от имени которого выполняются шаги
+    pub pipeline_user: String,
 }
 
 impl Default for ServerConfig {
@@ -55,9 +66,15 @@ impl Default for ServerConfig {
         Self {
             host: "127.0.0.1".to_string(),
             port: 8000,
+            public_url: "http://localhost:8000".to_string(),
             database_url: "gitea.db".to_string(),
             jwt_secret: "your-secret-key-change-in-production".to_string(),
             repositories_path: "repositories".to_string(),
+            assets_path: "assets".to_string(),
+            maintenance_interval: 3600,
+            maintenance_concurrency: 1,
+            pipeline_concurrency: 2,
+            pipeline_user: "nobody".to_string(),
         }
     }
 }
@@ -86,3 +86,103 @@ impl RefreshToken {
         Ok(result.rows_affected())
     }
 }
+
+/// Персональный API токен (для CI и скриптов); хранится только хэш
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: Option<i64>,
+    pub user_id: i64,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    /// Начало токена, чтобы пользователь мог его узнать
+    pub token_prefix: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    pub async fn create(&self, pool: &PgPool) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query!(
+            "INSERT INTO api_tokens (user_id, name, token_hash, token_prefix, expires_at) \
+             VALUES ($1, $2, $3, $4, $5) RETURNING id",
+            self.user_id,
+            self.name,
+            self.token_hash,
+            self.token_prefix,
+            self.expires_at
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result.id)
+    }
+
+    pub async fn find_by_user(user_id: i64, pool: &PgPool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            "SELECT id, user_id, name, token_hash, token_prefix, expires_at, last_used_at, created_at \
+             FROM api_tokens WHERE user_id = $1 ORDER BY created_at DESC",
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(
+        id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            "SELECT id, user_id, name, token_hash, token_prefix, expires_at, last_used_at, created_at \
+             FROM api_tokens WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Находит действующий (не истёкший) токен по хэшу
+    pub async fn find_active_by_hash(
+        hash: &str,
+        pool: &PgPool,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            "SELECT id, user_id, name, token_hash, token_prefix, expires_at, last_used_at, created_at \
+             FROM api_tokens \
+             WHERE token_hash = $1 AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)",
+            hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn touch(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE api_tokens SET last_used_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Удаляет токен пользователя; возвращает false, если токен не найден
+    pub async fn delete(id: i64, user_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM api_tokens WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,185 @@
+//! Доменная модель соавторов проектов и репозиториев
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Допустимые роли соавтора по возрастанию прав
+pub const ROLES: [&str; 3] = ["read", "write", "admin"];
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Соавтор проекта или репозитория
+#[derive(Debug, Clone, Serialize)]
+pub struct Collaborator {
+    pub user_id: i64,
+    pub username: String,
+    /// read, write или admin
+    pub role: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl Collaborator {
+    /// Соавторы проекта
+    pub async fn find_by_project(
+        project_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<Collaborator>, sqlx::Error> {
+        sqlx::query_as!(
+            Collaborator,
+            "SELECT c.user_id, u.username, c.role, c.created_at
+             FROM project_collaborators c
+             JOIN users u ON u.id = c.user_id
+             WHERE c.project_id = $1
+             ORDER BY u.username",
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Соавторы отдельного репозитория (без соавторов проекта)
+    pub async fn find_by_repository(
+        repository_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<Collaborator>, sqlx::Error> {
+        sqlx::query_as!(
+            Collaborator,
+            "SELECT c.user_id, u.username, c.role, c.created_at
+             FROM repository_collaborators c
+             JOIN users u ON u.id = c.user_id
+             WHERE c.repository_id = $1
+             ORDER BY u.username",
+            repository_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Выдаёт роль в проекте или меняет существующую
+    pub async fn upsert_for_project(
+        project_id: i64,
+        user_id: i64,
+        role: &str,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO project_collaborators (project_id, user_id, role)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (project_id, user_id) DO UPDATE SET role = $3",
+            project_id,
+            user_id,
+            role
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Выдаёт роль в репозитории или меняет существующую
+    pub async fn upsert_for_repository(
+        repository_id: i64,
+        user_id: i64,
+        role: &str,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO repository_collaborators (repository_id, user_id, role)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (repository_id, user_id) DO UPDATE SET role = $3",
+            repository_id,
+            user_id,
+            role
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Отзывает доступ к проекту; возвращает false, если соавтора не было
+    pub async fn delete_from_project(
+        project_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_collaborators WHERE project_id = $1 AND user_id = $2",
+            project_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Отзывает доступ к репозиторию; возвращает false, если соавтора не было
+    pub async fn delete_from_repository(
+        repository_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM repository_collaborators WHERE repository_id = $1 AND user_id = $2",
+            repository_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Роли пользователя в проекте и в репозитории (если выданы)
+    pub async fn roles_for_user(
+        project_id: i64,
+        repository_id: Option<i64>,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT role AS "role!" FROM project_collaborators
+               WHERE project_id = $1 AND user_id = $3
+               UNION ALL
+               SELECT role AS "role!" FROM repository_collaborators
+               WHERE repository_id = $2 AND user_id = $3"#,
+            project_id,
+            repository_id,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| row.role).collect())
+    }
+
+    /// Роли пользователя в отдельных репозиториях проекта: `(repository_id, role)`
+    pub async fn repository_roles_for_user(
+        project_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<(i64, String)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT rc.repository_id, rc.role FROM repository_collaborators rc
+               JOIN repositories r ON r.id = rc.repository_id
+               WHERE r.project_id = $1 AND rc.user_id = $2"#,
+            project_id,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.repository_id, row.role))
+            .collect())
+    }
+}
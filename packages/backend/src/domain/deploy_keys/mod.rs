@@ -0,0 +1,137 @@
+//! Доменная модель ключей развёртывания репозиториев
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Тип ключей развёртывания с токеном; только такие ключи и создаются
+pub const KEY_TYPE_HTTP: &str = "http";
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Доступ к одному репозиторию для машин (сборка, устройства Spark)
+///
+/// Ключ не привязан к пользователю и задаётся сгенерированным токеном, от
+/// которого хранится только хэш (в модель он не загружается). Поля SSH ключа
+/// остались от схемы, новые ключи их не заполняют.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployKey {
+    pub id: i64,
+    pub title: String,
+    /// ssh или http
+    pub key_type: String,
+    pub public_key: Option<String>,
+    pub fingerprint: Option<String>,
+    /// Начало токена, чтобы его можно было узнать
+    pub token_prefix: Option<String>,
+    /// Только чтение (clone/fetch) или также push
+    pub read_only: bool,
+    pub creator_id: Option<i64>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Данные нового ключа
+#[derive(Debug)]
+pub struct NewDeployKey {
+    pub repository_id: i64,
+    pub title: String,
+    pub key_type: &'static str,
+    pub public_key: Option<String>,
+    pub fingerprint: Option<String>,
+    pub token_hash: Option<String>,
+    pub token_prefix: Option<String>,
+    pub read_only: bool,
+    pub creator_id: Option<i64>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl DeployKey {
+    pub async fn create(key: &NewDeployKey, pool: &PgPool) -> Result<DeployKey, sqlx::Error> {
+        sqlx::query_as!(
+            DeployKey,
+            "INSERT INTO deploy_keys (repository_id, title, key_type, public_key, fingerprint,
+                                      token_hash, token_prefix, read_only, creator_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             RETURNING id, title, key_type, public_key, fingerprint, token_prefix, read_only,
+                       creator_id, last_used_at, created_at",
+            key.repository_id,
+            key.title,
+            key.key_type,
+            key.public_key,
+            key.fingerprint,
+            key.token_hash,
+            key.token_prefix,
+            key.read_only,
+            key.creator_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Ключи репозитория в порядке добавления
+    pub async fn find_by_repository(
+        repository_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<DeployKey>, sqlx::Error> {
+        sqlx::query_as!(
+            DeployKey,
+            "SELECT id, title, key_type, public_key, fingerprint, token_prefix, read_only,
+                    creator_id, last_used_at, created_at
+             FROM deploy_keys
+             WHERE repository_id = $1
+             ORDER BY id",
+            repository_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Находит HTTP ключ репозитория по хэшу токена
+    pub async fn find_by_token_hash(
+        repository_id: i64,
+        token_hash: &str,
+        pool: &PgPool,
+    ) -> Result<Option<DeployKey>, sqlx::Error> {
+        sqlx::query_as!(
+            DeployKey,
+            "SELECT id, title, key_type, public_key, fingerprint, token_prefix, read_only,
+                    creator_id, last_used_at, created_at
+             FROM deploy_keys
+             WHERE repository_id = $1 AND token_hash = $2",
+            repository_id,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn touch(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE deploy_keys SET last_used_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Удаляет ключ репозитория; возвращает false, если ключ не найден
+    pub async fn delete(id: i64, repository_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM deploy_keys WHERE id = $1 AND repository_id = $2",
+            id,
+            repository_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
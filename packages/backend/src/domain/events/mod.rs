@@ -0,0 +1,164 @@
+//! Доменная модель журнала событий проекта
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgPool;
+
+/// Виды событий
+pub const EVENT_REPOSITORY_CREATED: &str = "repository_created";
+pub const EVENT_CONFIG_CHANGED: &str = "config_changed";
+pub const EVENT_PUSH: &str = "push";
+pub const EVENT_PACKAGE_PUBLISHED: &str = "package_published";
+pub const EVENT_MEMBER_ADDED: &str = "member_added";
+pub const EVENT_RELEASE_PUBLISHED: &str = "release_published";
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Событие проекта
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectEvent {
+    pub id: i64,
+    pub project_id: i64,
+    #[serde(skip_serializing)]
+    pub repository_id: Option<i64>,
+    /// Полное имя проекта `{owner}/{project}`
+    pub project: String,
+    /// Репозиторий события; `None` у событий всего проекта
+    pub repository: Option<String>,
+    #[serde(skip_serializing)]
+    pub actor_id: Option<i64>,
+    /// Автор; `None` у ключей развёртывания и удалённых пользователей
+    pub actor: Option<String>,
+    /// repository_created, config_changed, push, package_published, member_added
+    /// или release_published
+    pub kind: String,
+    /// Подробности, зависящие от вида события
+    pub payload: Value,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl ProjectEvent {
+    /// Записывает событие
+    pub async fn create(
+        project_id: i64,
+        repository_id: Option<i64>,
+        actor_id: Option<i64>,
+        kind: &str,
+        payload: &Value,
+        pool: &PgPool,
+    ) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query!(
+            "INSERT INTO project_events (project_id, repository_id, actor_id, kind, payload)
+             VALUES ($1, $2, $3, $4, $5) RETURNING id",
+            project_id,
+            repository_id,
+            actor_id,
+            kind,
+            payload.to_string()
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.id)
+    }
+
+    /// Событие по идентификатору
+    pub async fn find_by_id(id: i64, pool: &PgPool) -> Result<Option<ProjectEvent>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT e.id, e.project_id, e.repository_id, e.actor_id,
+                      COALESCE(o.name, pu.username) || '/' || p.name AS "project!",
+                      r.name AS "repository?", u.username AS "actor?",
+                      e.kind, e.payload, e.created_at
+               FROM project_events e
+               JOIN projects p ON p.id = e.project_id
+               JOIN users pu ON pu.id = p.owner_id
+               LEFT JOIN organizations o ON o.id = p.organization_id
+               LEFT JOIN repositories r ON r.id = e.repository_id
+               LEFT JOIN users u ON u.id = e.actor_id
+               WHERE e.id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(|row| ProjectEvent {
+            id: row.id,
+            project_id: row.project_id,
+            repository_id: row.repository_id,
+            project: row.project,
+            repository: row.repository,
+            actor_id: row.actor_id,
+            actor: row.actor,
+            kind: row.kind,
+            payload: parse_payload(&row.payload),
+            created_at: row.created_at,
+        }))
+    }
+
+    /// События проектов `project_ids` и релизы проектов `release_project_ids`,
+    /// новые первыми
+    ///
+    /// События репозиториев попадают в выборку, только если репозиторий есть
+    /// в `repository_ids`; события всего проекта — всегда.
+    pub async fn find_for_projects(
+        project_ids: &[i64],
+        release_project_ids: &[i64],
+        repository_ids: &[i64],
+        limit: i64,
+        offset: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ProjectEvent>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT e.id, e.project_id, e.repository_id, e.actor_id,
+                      COALESCE(o.name, pu.username) || '/' || p.name AS "project!",
+                      r.name AS "repository?", u.username AS "actor?",
+                      e.kind, e.payload, e.created_at
+               FROM project_events e
+               JOIN projects p ON p.id = e.project_id
+               JOIN users pu ON pu.id = p.owner_id
+               LEFT JOIN organizations o ON o.id = p.organization_id
+               LEFT JOIN repositories r ON r.id = e.repository_id
+               LEFT JOIN users u ON u.id = e.actor_id
+               WHERE (e.project_id = ANY($1)
+                      OR (e.project_id = ANY($2) AND e.kind = $3))
+                 AND (e.repository_id IS NULL OR e.repository_id = ANY($4))
+               ORDER BY e.id DESC
+               LIMIT $5 OFFSET $6"#,
+            project_ids,
+            release_project_ids,
+            EVENT_RELEASE_PUBLISHED,
+            repository_ids,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ProjectEvent {
+                id: row.id,
+                project_id: row.project_id,
+                repository_id: row.repository_id,
+                project: row.project,
+                repository: row.repository,
+                actor_id: row.actor_id,
+                actor: row.actor,
+                kind: row.kind,
+                payload: parse_payload(&row.payload),
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+}
+
+fn parse_payload(payload: &str) -> Value {
+    serde_json::from_str(payload).unwrap_or(Value::Null)
+}
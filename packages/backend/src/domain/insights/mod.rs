@@ -0,0 +1,89 @@
+//! Доменная модель кэша статистики репозиториев
+
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::PgPool;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Вид статистики репозитория
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InsightKind {
+    Languages,
+    Contributors,
+    Activity,
+    Dependencies,
+}
+
+impl InsightKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InsightKind::Languages => "languages",
+            InsightKind::Contributors => "contributors",
+            InsightKind::Activity => "activity",
+            InsightKind::Dependencies => "dependencies",
+        }
+    }
+}
+
+/// Кэш вычисленной статистики, привязанный к SHA головы ветки
+pub struct InsightsCache;
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl InsightsCache {
+    /// Возвращает закэшированные данные, если они вычислены для `head_sha`
+    pub async fn load<T: DeserializeOwned>(
+        repository_id: i64,
+        kind: InsightKind,
+        head_sha: &str,
+        pool: &PgPool,
+    ) -> Result<Option<T>, sqlx::Error> {
+        let result = sqlx::query!(
+            "SELECT data_json FROM repository_insights
+             WHERE repository_id = $1 AND kind = $2 AND head_sha = $3",
+            repository_id,
+            kind.as_str(),
+            head_sha
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        // Повреждённый кэш просто пересчитывается
+        Ok(result.and_then(|row| serde_json::from_str(&row.data_json).ok()))
+    }
+
+    /// Сохраняет вычисленные данные для `head_sha`
+    pub async fn store<T: Serialize>(
+        repository_id: i64,
+        kind: InsightKind,
+        head_sha: &str,
+        data: &T,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        let data_json = serde_json::to_string(data).map_err(|_| {
+            sqlx::Error::Decode(Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Failed to serialize",
+            )))
+        })?;
+
+        sqlx::query!(
+            "INSERT INTO repository_insights (repository_id, kind, head_sha, data_json)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (repository_id, kind) DO UPDATE
+             SET head_sha = $3, data_json = $4, computed_at = CURRENT_TIMESTAMP",
+            repository_id,
+            kind.as_str(),
+            head_sha,
+            data_json
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! Доменная модель состояния обслуживания репозиториев
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Статус успешного обслуживания
+pub const STATUS_SUCCESS: &str = "success";
+/// Статус неудачного обслуживания
+pub const STATUS_FAILED: &str = "failed";
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Состояние обслуживания репозитория
+///
+/// Для репозиториев, которые ещё ни разу не проверялись, поля статистики пусты.
+#[derive(Debug, Clone, Serialize)]
+pub struct RepositoryMaintenance {
+    pub repository_id: i64,
+    pub repository_name: String,
+    /// Loose-объекты на момент последней проверки
+    pub loose_objects: Option<i64>,
+    pub packs: Option<i64>,
+    /// Время последнего запуска gc/commit-graph
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// success или failed
+    pub last_status: Option<String>,
+    pub last_error: Option<String>,
+    pub last_duration_ms: Option<i64>,
+    /// Время последнего подсчёта объектов
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl RepositoryMaintenance {
+    /// Состояние обслуживания всех репозиториев
+    pub async fn find_all(pool: &PgPool) -> Result<Vec<RepositoryMaintenance>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            RepositoryMaintenance,
+            r#"SELECT r.id AS "repository_id!", r.name AS repository_name,
+                      m.loose_objects AS "loose_objects?", m.packs AS "packs?",
+                      m.last_run_at, m.last_status, m.last_error, m.last_duration_ms,
+                      m.checked_at
+               FROM repositories r
+               LEFT JOIN repository_maintenance m ON m.repository_id = r.id
+               ORDER BY r.id"#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Сохраняет результат подсчёта объектов
+    pub async fn record_check(
+        repository_id: i64,
+        loose_objects: i64,
+        packs: i64,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO repository_maintenance (repository_id, loose_objects, packs)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (repository_id) DO UPDATE
+             SET loose_objects = $2, packs = $3, checked_at = CURRENT_TIMESTAMP",
+            repository_id,
+            loose_objects,
+            packs
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Сохраняет результат обслуживания и статистику объектов после него
+    pub async fn record_run(
+        repository_id: i64,
+        status: &str,
+        error: Option<&str>,
+        duration_ms: i64,
+        loose_objects: i64,
+        packs: i64,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO repository_maintenance
+                 (repository_id, loose_objects, packs, last_run_at, last_status,
+                  last_error, last_duration_ms)
+             VALUES ($1, $2, $3, CURRENT_TIMESTAMP, $4, $5, $6)
+             ON CONFLICT (repository_id) DO UPDATE
+             SET loose_objects = $2, packs = $3, last_run_at = CURRENT_TIMESTAMP,
+                 last_status = $4, last_error = $5, last_duration_ms = $6,
+                 checked_at = CURRENT_TIMESTAMP",
+            repository_id,
+            loose_objects,
+            packs,
+            status,
+            error,
+            duration_ms
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+//! Доменная модель участников проектов
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Роли участника проекта по убыванию прав
+pub const MEMBER_ROLES: [&str; 5] = ["owner", "maintainer", "developer", "reporter", "guest"];
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Участник проекта
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectMember {
+    pub user_id: i64,
+    pub username: String,
+    /// owner, maintainer, developer, reporter или guest
+    pub role: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl ProjectMember {
+    /// Участники проекта
+    pub async fn find_by_project(
+        project_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ProjectMember>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMember,
+            "SELECT m.user_id, u.username, m.role, m.created_at
+             FROM project_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.project_id = $1
+             ORDER BY u.username",
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Участник проекта по пользователю
+    pub async fn find(
+        project_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Option<ProjectMember>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMember,
+            "SELECT m.user_id, u.username, m.role, m.created_at
+             FROM project_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.project_id = $1 AND m.user_id = $2",
+            project_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Добавляет участника или меняет его роль
+    pub async fn upsert(
+        project_id: i64,
+        user_id: i64,
+        role: &str,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO project_members (project_id, user_id, role)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (project_id, user_id) DO UPDATE SET role = $3",
+            project_id,
+            user_id,
+            role
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Исключает участника; возвращает false, если его не было
+    pub async fn delete(project_id: i64, user_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_members WHERE project_id = $1 AND user_id = $2",
+            project_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Роли пользователя в проекте: личная и выданные его командам
+    pub async fn roles_for_user(
+        project_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT role AS "role!" FROM project_members
+               WHERE project_id = $1 AND user_id = $2
+               UNION ALL
+               SELECT pt.role AS "role!" FROM project_teams pt
+               JOIN team_members tm ON tm.team_id = pt.team_id
+               WHERE pt.project_id = $1 AND tm.user_id = $2"#,
+            project_id,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| row.role).collect())
+    }
+}
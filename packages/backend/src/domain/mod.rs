@@ -2,7 +2,24 @@
 
 pub mod auth;
 pub mod chats;
+pub mod collaborators;
+pub mod deploy_keys;
+pub mod events;
+pub mod insights;
+pub mod maintenance;
+pub mod members;
 pub mod notifications;
+pub mod organizations;
+pub mod pipelines;
 pub mod projects;
+pub mod releases;
 pub mod repos;
+pub mod search;
+pub mod secret_findings;
+pub mod signing_keys;
+pub mod statuses;
+pub mod teams;
+pub mod traffic;
+pub mod transfers;
 pub mod users;
+pub mod watches;
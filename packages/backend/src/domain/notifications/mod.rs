@@ -17,6 +17,8 @@ pub struct Notification {
     pub name: String,
     /// Содержимое
     pub content: String,
+    /// Получатель; `None` — уведомление для всех пользователей
+    pub user_id: Option<i64>,
     /// Дата создания уведомления
     pub created_at: Option<DateTime<Utc>>,
 }
@@ -29,9 +31,10 @@ impl Notification {
     /// Создаёт новое уведомление в базе данных
     pub async fn create(&self, pool: &PgPool) -> Result<i64, sqlx::Error> {
         let result = sqlx::query!(
-            "INSERT INTO notification (name, content) VALUES ($1, $2) RETURNING id",
+            "INSERT INTO notification (name, content, user_id) VALUES ($1, $2, $3) RETURNING id",
             self.name,
-            self.content
+            self.content,
+            self.user_id
         )
         .fetch_one(pool)
         .await?;
@@ -39,11 +42,17 @@ impl Notification {
         Ok(result.id)
     }
 
-    /// Получает все уведомления из базы данных
-    pub async fn find_all(pool: &PgPool) -> Result<Vec<Notification>, sqlx::Error> {
+    /// Получает общие уведомления и уведомления, адресованные пользователю
+    pub async fn find_for_user(
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<Notification>, sqlx::Error> {
         let notifications = sqlx::query_as!(
             Notification,
-            "SELECT id, name, content, created_at FROM notification ORDER BY created_at DESC"
+            "SELECT id, name, content, user_id, created_at FROM notification
+             WHERE user_id IS NULL OR user_id = $1
+             ORDER BY created_at DESC",
+            user_id
         )
         .fetch_all(pool)
         .await?;
@@ -58,7 +67,7 @@ impl Notification {
     ) -> Result<Option<Notification>, sqlx::Error> {
         let notification = sqlx::query_as!(
             Notification,
-            "SELECT id, name, content, created_at FROM notification WHERE name = $1",
+            "SELECT id, name, content, user_id, created_at FROM notification WHERE name = $1",
             name
         )
         .fetch_optional(pool)
@@ -71,7 +80,7 @@ impl Notification {
     pub async fn find_by_id(id: i64, pool: &PgPool) -> Result<Option<Notification>, sqlx::Error> {
         let notification = sqlx::query_as!(
             Notification,
-            "SELECT id, name, content, created_at FROM notification WHERE id = $1",
+            "SELECT id, name, content, user_id, created_at FROM notification WHERE id = $1",
             id
         )
         .fetch_optional(pool)
@@ -80,6 +89,11 @@ impl Notification {
         Ok(notification)
     }
 
+    /// Доступно ли уведомление пользователю: общее или адресовано ему
+    pub fn is_visible_to(&self, user_id: i64) -> bool {
+        self.user_id.is_none_or(|recipient| recipient == user_id)
+    }
+
     /// Обновляет уведомление в базе данных
     pub async fn update(&self, pool: &PgPool) -> Result<(), sqlx::Error> {
         if let Some(id) = self.id {
@@ -123,6 +137,7 @@ impl Notification {
             id: None,
             name,
             content,
+            user_id: None,
             created_at: None,
         })
     }
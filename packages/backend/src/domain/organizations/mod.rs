@@ -0,0 +1,215 @@
+//! Доменная модель организаций — пространств имён проектов наравне с пользователями
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Роли участника организации по убыванию прав
+pub const ORGANIZATION_ROLES: [&str; 3] = ["owner", "admin", "member"];
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Организация
+#[derive(Debug, Clone, Serialize)]
+pub struct Organization {
+    pub id: i64,
+    /// Имя в URL; не совпадает ни с одним именем пользователя
+    pub name: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Участник организации
+#[derive(Debug, Clone, Serialize)]
+pub struct OrganizationMember {
+    pub user_id: i64,
+    pub username: String,
+    /// owner, admin или member
+    pub role: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl Organization {
+    /// Создаёт организацию и делает создателя её владельцем
+    pub async fn create(
+        name: &str,
+        display_name: Option<&str>,
+        description: Option<&str>,
+        creator_id: i64,
+        pool: &PgPool,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let row = sqlx::query!(
+            "INSERT INTO organizations (name, display_name, description)
+             VALUES ($1, $2, $3) RETURNING id",
+            name,
+            display_name,
+            description
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO organization_members (organization_id, user_id, role)
+             VALUES ($1, $2, 'owner')",
+            row.id,
+            creator_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(row.id)
+    }
+
+    /// Организация по имени
+    pub async fn find_by_name(
+        name: &str,
+        pool: &PgPool,
+    ) -> Result<Option<Organization>, sqlx::Error> {
+        sqlx::query_as!(
+            Organization,
+            "SELECT id, name, display_name, description, created_at
+             FROM organizations WHERE name = $1",
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Организация по идентификатору
+    pub async fn find_by_id(id: i64, pool: &PgPool) -> Result<Option<Organization>, sqlx::Error> {
+        sqlx::query_as!(
+            Organization,
+            "SELECT id, name, display_name, description, created_at
+             FROM organizations WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Организации, в которых состоит пользователь
+    pub async fn find_for_user(
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<Organization>, sqlx::Error> {
+        sqlx::query_as!(
+            Organization,
+            "SELECT o.id, o.name, o.display_name, o.description, o.created_at
+             FROM organizations o
+             JOIN organization_members m ON m.organization_id = o.id
+             WHERE m.user_id = $1
+             ORDER BY o.name",
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Участники организации
+    pub async fn members(
+        organization_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<OrganizationMember>, sqlx::Error> {
+        sqlx::query_as!(
+            OrganizationMember,
+            "SELECT m.user_id, u.username, m.role, m.created_at
+             FROM organization_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.organization_id = $1
+             ORDER BY u.username",
+            organization_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Роль пользователя в организации
+    pub async fn member_role(
+        organization_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query!(
+            "SELECT role FROM organization_members
+             WHERE organization_id = $1 AND user_id = $2",
+            organization_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(|row| row.role))
+    }
+
+    /// Добавляет участника или меняет его роль
+    pub async fn upsert_member(
+        organization_id: i64,
+        user_id: i64,
+        role: &str,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO organization_members (organization_id, user_id, role)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (organization_id, user_id) DO UPDATE SET role = $3",
+            organization_id,
+            user_id,
+            role
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Исключает участника; возвращает false, если его не было
+    pub async fn delete_member(
+        organization_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+            organization_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Число владельцев организации
+    pub async fn count_owners(organization_id: i64, pool: &PgPool) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT COUNT(*) AS "count!" FROM organization_members
+               WHERE organization_id = $1 AND role = 'owner'"#,
+            organization_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.count)
+    }
+
+    /// Проверяет, занято ли имя пользователем или организацией
+    pub async fn is_name_taken(name: &str, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT EXISTS (SELECT 1 FROM users WHERE username = $1)
+                   OR EXISTS (SELECT 1 FROM organizations WHERE name = $1) AS "taken!""#,
+            name
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.taken)
+    }
+}
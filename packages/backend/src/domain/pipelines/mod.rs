@@ -0,0 +1,277 @@
+//! Доменная модель запусков встроенных пайплайнов и их шагов
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Статусы запуска
+pub const RUN_QUEUED: &str = "queued";
+pub const RUN_RUNNING: &str = "running";
+pub const RUN_SUCCESS: &str = "success";
+pub const RUN_FAILED: &str = "failed";
+/// Запуск не состоялся: нет конфигурации шагов, ошибка checkout и т.п.
+pub const RUN_ERROR: &str = "error";
+
+/// Статусы шага
+pub const STEP_RUNNING: &str = "running";
+pub const STEP_SUCCESS: &str = "success";
+pub const STEP_FAILED: &str = "failed";
+pub const STEP_TIMED_OUT: &str = "timed_out";
+pub const STEP_SKIPPED: &str = "skipped";
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Запуск пайплайна для коммита, попавшего в ветку при push
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineRun {
+    pub id: i64,
+    #[serde(skip_serializing)]
+    pub repository_id: i64,
+    pub commit_sha: String,
+    pub ref_name: String,
+    /// queued, running, success, failed или error
+    pub status: String,
+    pub trigger_user_id: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Шаг запуска с кодом возврата и логом
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineStep {
+    pub id: i64,
+    pub position: i32,
+    pub name: String,
+    pub command: String,
+    pub timeout_secs: i32,
+    /// pending, running, success, failed, timed_out или skipped
+    pub status: String,
+    pub exit_code: Option<i32>,
+    /// Объединённый stdout и stderr
+    pub log: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl PipelineRun {
+    /// Создаёт запуск в очереди
+    pub async fn create(
+        repository_id: i64,
+        commit_sha: &str,
+        ref_name: &str,
+        trigger_user_id: Option<i64>,
+        pool: &PgPool,
+    ) -> Result<PipelineRun, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRun,
+            "INSERT INTO pipeline_runs (repository_id, commit_sha, ref_name, trigger_user_id)
+             VALUES ($1, $2, $3, $4)
+             RETURNING id, repository_id, commit_sha, ref_name, status, trigger_user_id, error,
+                       created_at, started_at, finished_at",
+            repository_id,
+            commit_sha,
+            ref_name,
+            trigger_user_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(
+        id: i64,
+        repository_id: i64,
+        pool: &PgPool,
+    ) -> Result<Option<PipelineRun>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRun,
+            "SELECT id, repository_id, commit_sha, ref_name, status, trigger_user_id, error,
+                    created_at, started_at, finished_at
+             FROM pipeline_runs
+             WHERE id = $1 AND repository_id = $2",
+            id,
+            repository_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Запуски репозитория, новые первыми
+    pub async fn find_by_repository(
+        repository_id: i64,
+        limit: i64,
+        offset: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<PipelineRun>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRun,
+            "SELECT id, repository_id, commit_sha, ref_name, status, trigger_user_id, error,
+                    created_at, started_at, finished_at
+             FROM pipeline_runs
+             WHERE repository_id = $1
+             ORDER BY id DESC
+             LIMIT $2 OFFSET $3",
+            repository_id,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_running(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE pipeline_runs SET status = $2, started_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id,
+            RUN_RUNNING
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn finish(
+        id: i64,
+        status: &str,
+        error: Option<&str>,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE pipeline_runs
+             SET status = $2, error = $3, finished_at = CURRENT_TIMESTAMP
+             WHERE id = $1",
+            id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Помечает незавершённые запуски как прерванные (после перезапуска сервера)
+    pub async fn abort_unfinished(error: &str, pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE pipeline_runs
+             SET status = $1, error = $2, finished_at = CURRENT_TIMESTAMP
+             WHERE status IN ($3, $4)",
+            RUN_ERROR,
+            error,
+            RUN_QUEUED,
+            RUN_RUNNING
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE pipeline_steps SET status = $1, finished_at = CURRENT_TIMESTAMP
+             WHERE status IN ('pending', 'running')",
+            STEP_SKIPPED
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+impl PipelineStep {
+    /// Создаёт шаги запуска в статусе pending
+    pub async fn create_all(
+        run_id: i64,
+        steps: &[(String, String, i32)],
+        pool: &PgPool,
+    ) -> Result<Vec<PipelineStep>, sqlx::Error> {
+        let mut created = Vec::with_capacity(steps.len());
+        for (position, (name, command, timeout_secs)) in steps.iter().enumerate() {
+            let step = sqlx::query_as!(
+                PipelineStep,
+                "INSERT INTO pipeline_steps (run_id, position, name, command, timeout_secs)
+                 VALUES ($1, $2, $3, $4, $5)
+                 RETURNING id, position, name, command, timeout_secs, status, exit_code,
+                           log, started_at, finished_at",
+                run_id,
+                position as i32,
+                name,
+                command,
+                timeout_secs
+            )
+            .fetch_one(pool)
+            .await?;
+            created.push(step);
+        }
+
+        Ok(created)
+    }
+
+    /// Шаги запуска по порядку
+    pub async fn find_by_run(run_id: i64, pool: &PgPool) -> Result<Vec<PipelineStep>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineStep,
+            "SELECT id, position, name, command, timeout_secs, status, exit_code,
+                    log, started_at, finished_at
+             FROM pipeline_steps
+             WHERE run_id = $1
+             ORDER BY position",
+            run_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_running(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE pipeline_steps SET status = $2, started_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id,
+            STEP_RUNNING
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn finish(
+        id: i64,
+        status: &str,
+        exit_code: Option<i32>,
+        log: &str,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE pipeline_steps
+             SET status = $2, exit_code = $3, log = $4, finished_at = CURRENT_TIMESTAMP
+             WHERE id = $1",
+            id,
+            status,
+            exit_code,
+            log
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Пропускает оставшиеся шаги запуска после неудачного шага
+    pub async fn skip_pending(run_id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE pipeline_steps SET status = $2 WHERE run_id = $1 AND status = 'pending'",
+            run_id,
+            STEP_SKIPPED
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 //! Доменная модель проекта
 
+use crate::utils::helpers;
 use chrono::{DateTime, Utc};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
@@ -8,24 +10,264 @@ use sqlx::PgPool;
 pub struct Project {
     pub id: Option<i64>,
     pub name: String,
+    /// Владелец личного проекта или создатель проекта организации
     pub owner_id: i64,
+    /// Организация-владелец; `None` у личных проектов
+    #[serde(default)]
+    pub organization_id: Option<i64>,
     pub description: Option<String>,
     pub is_public: bool,
     pub created_at: Option<DateTime<Utc>>,
+    /// Архивный проект доступен только для чтения
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Удалённый проект скрыт и окончательно удаляется после срока хранения
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+/// Конфигурация проекта
+///
+/// Все разделы необязательны: отсутствующие поля получают значения по
+/// умолчанию, поэтому старые сохранённые конфигурации читаются без миграций.
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProjectConfig {
+    #[serde(default)]
     pub metadata: ProjectMetadata,
+    #[serde(default)]
+    pub security: ProjectSecurity,
+    /// Ветка, на которую указывает HEAD новых репозиториев
+    #[serde(default = "default_branch")]
+    pub default_branch: String,
+    #[serde(default)]
+    pub visibility: VisibilityDefaults,
+    /// Правила защиты веток; к ветке применяется первое подходящее
+    #[serde(default)]
+    pub branch_rules: Vec<BranchRule>,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    #[serde(default)]
+    pub spark: SparkPublishing,
+    #[serde(default)]
+    pub pipelines: PipelineSettings,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct ProjectMetadata {
+    #[serde(default)]
     pub tags: Vec<String>,
 }
 
+/// Настройки безопасности проекта
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProjectSecurity {
+    #[serde(default)]
+    pub secret_scanning: SecretScanningMode,
+}
+
+/// Реакция на секреты в отправленных блобах
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretScanningMode {
+    /// Не проверять push
+    Off,
+    /// Принять push и уведомить владельца проекта
+    #[default]
+    Notify,
+    /// Отклонить push с объяснением
+    Reject,
+}
+
+/// Видимость, которую получают новые объекты проекта
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VisibilityDefaults {
+    /// Видимость репозитория, если при создании она не указана
+    #[serde(default)]
+    pub repositories: RepositoryVisibility,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RepositoryVisibility {
+    /// Как у самого проекта
+    #[default]
+    Inherit,
+    Public,
+    Private,
+}
+
+/// Защита веток, имена которых подходят под glob-шаблон
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BranchRule {
+    /// Шаблон имени ветки, например `main` или `release/*`
+    pub pattern: String,
+    /// Разрешить push, переписывающий историю
+    #[serde(default)]
+    pub allow_force_push: bool,
+    /// Разрешить удаление ветки
+    #[serde(default)]
+    pub allow_deletion: bool,
+    /// Минимальный уровень доступа для push в ветку
+    #[serde(default)]
+    pub push_access: PushAccess,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PushAccess {
+    #[default]
+    Write,
+    Admin,
+}
+
+/// Кому и о чём проект отправляет уведомления
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationSettings {
+    /// Уведомлять о секретах, найденных в принятом push
+    #[serde(default = "enabled")]
+    pub secret_findings: bool,
+    /// Пользователи, которые получают уведомления вместе с владельцем проекта
+    #[serde(default)]
+    pub recipients: Vec<String>,
+}
+
+/// Публикация релизов проекта в реестр Spark
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SparkPublishing {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Имя пакета в реестре; по умолчанию совпадает с именем проекта
+    #[serde(default)]
+    pub package: Option<String>,
+    /// Glob-шаблон тегов, из которых публикуются версии
+    #[serde(default = "default_tag_pattern")]
+    pub tag_pattern: String,
+}
+
+/// Встроенные пайплайны проекта
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PipelineSettings {
+    /// Запускать `.netbit/pipeline.toml` при push; по умолчанию выключено
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Ревизия конфигурации проекта
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigRevision {
+    pub revision: i32,
+    /// Автор изменения; `None` для ревизий, перенесённых миграцией
+    pub author: Option<String>,
+    /// Ревизия, к которой откатили конфигурацию
+    pub restored_from: Option<i32>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+fn default_branch() -> String {
+    "main".to_string()
+}
+
+fn default_tag_pattern() -> String {
+    "v*".to_string()
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        ProjectConfig {
+            metadata: ProjectMetadata::default(),
+            security: ProjectSecurity::default(),
+            default_branch: default_branch(),
+            visibility: VisibilityDefaults::default(),
+            branch_rules: Vec::new(),
+            notifications: NotificationSettings::default(),
+            spark: SparkPublishing::default(),
+            pipelines: PipelineSettings::default(),
+        }
+    }
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        NotificationSettings {
+            secret_findings: true,
+            recipients: Vec::new(),
+        }
+    }
+}
+
+impl Default for SparkPublishing {
+    fn default() -> Self {
+        SparkPublishing {
+            enabled: false,
+            package: None,
+            tag_pattern: default_tag_pattern(),
+        }
+    }
+}
+
+impl VisibilityDefaults {
+    /// Видимость нового репозитория в проекте с видимостью `project_public`
+    pub fn repository_public(&self, project_public: bool) -> bool {
+        match self.repositories {
+            RepositoryVisibility::Inherit => project_public,
+            RepositoryVisibility::Public => true,
+            RepositoryVisibility::Private => false,
+        }
+    }
+}
+
+impl SparkPublishing {
+    /// Можно ли опубликовать версию `version` пакета `package`, связанного с проектом
+    ///
+    /// Версия `1.2.0` соответствует тегу `1.2.0` или `v1.2.0`; хотя бы один из
+    /// них должен подходить под `tag_pattern`. Возвращает причину отказа.
+    pub fn check_version(&self, package: &str, version: &str) -> Result<(), String> {
+        if !self.enabled {
+            return Err("Spark publishing is disabled in the project configuration".to_string());
+        }
+        if let Some(expected) = self.package.as_deref().filter(|name| *name != package) {
+            return Err(format!("The project publishes the package {}", expected));
+        }
+        let tag_matches = Regex::new(&helpers::glob_to_regex(&self.tag_pattern))
+            .is_ok_and(|regex| regex.is_match(version) || regex.is_match(&format!("v{}", version)));
+        if !tag_matches {
+            return Err(format!(
+                "Version {} does not match the tag pattern {}",
+                version, self.tag_pattern
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl BranchRule {
+    /// Подходит ли правило к ветке (имя без `refs/heads/`)
+    pub fn matches(&self, branch: &str) -> bool {
+        Regex::new(&helpers::glob_to_regex(&self.pattern)).is_ok_and(|regex| regex.is_match(branch))
+    }
+}
+
 impl ProjectConfig {
-    pub async fn save(&self, project_id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+    /// Правило защиты ветки, если оно есть
+    pub fn branch_rule(&self, branch: &str) -> Option<&BranchRule> {
+        self.branch_rules.iter().find(|rule| rule.matches(branch))
+    }
+
+    /// Сохраняет конфигурацию и записывает её новой ревизией
+    ///
+    /// Возвращает номер ревизии. Строка `project_configs` блокируется на время
+    /// транзакции, поэтому параллельные изменения получают разные номера.
+    pub async fn save_revision(
+        &self,
+        project_id: i64,
+        author_id: Option<i64>,
+        restored_from: Option<i32>,
+        pool: &PgPool,
+    ) -> Result<i32, sqlx::Error> {
         let config_json = serde_json::to_string(self).map_err(|_| {
             sqlx::Error::Decode(Box::new(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
@@ -33,6 +275,7 @@ impl ProjectConfig {
             )))
         })?;
 
+        let mut tx = pool.begin().await?;
         sqlx::query!(
             "INSERT INTO project_configs (project_id, config_json)
              VALUES ($1, $2)
@@ -40,10 +283,24 @@ impl ProjectConfig {
             project_id,
             config_json
         )
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+        let row = sqlx::query!(
+            r#"INSERT INTO project_config_revisions
+                   (project_id, revision, config_json, author_id, restored_from)
+               SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3, $4
+               FROM project_config_revisions WHERE project_id = $1
+               RETURNING revision"#,
+            project_id,
+            config_json,
+            author_id,
+            restored_from
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
 
-        Ok(())
+        Ok(row.revision)
     }
 
     pub async fn load(project_id: i64, pool: &PgPool) -> Result<ProjectConfig, sqlx::Error> {
@@ -55,25 +312,87 @@ impl ProjectConfig {
         .await?;
 
         if let Some(row) = result {
-            serde_json::from_str(&row.config_json).map_err(|_| {
-                sqlx::Error::Decode(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    "Failed to deserialize",
-                )))
-            })
+            parse_config(&row.config_json)
         } else {
             Ok(ProjectConfig::default())
         }
     }
+
+    /// Конфигурация, сохранённая в ревизии
+    pub async fn load_revision(
+        project_id: i64,
+        revision: i32,
+        pool: &PgPool,
+    ) -> Result<Option<ProjectConfig>, sqlx::Error> {
+        let result = sqlx::query!(
+            "SELECT config_json FROM project_config_revisions
+             WHERE project_id = $1 AND revision = $2",
+            project_id,
+            revision
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        result.map(|row| parse_config(&row.config_json)).transpose()
+    }
+}
+
+impl ConfigRevision {
+    /// Ревизии конфигурации проекта, новые первыми
+    pub async fn find_by_project(
+        project_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ConfigRevision>, sqlx::Error> {
+        sqlx::query_as!(
+            ConfigRevision,
+            r#"SELECT r.revision, u.username AS "author?", r.restored_from, r.created_at
+               FROM project_config_revisions r
+               LEFT JOIN users u ON u.id = r.author_id
+               WHERE r.project_id = $1
+               ORDER BY r.revision DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Ревизия по номеру
+    pub async fn find(
+        project_id: i64,
+        revision: i32,
+        pool: &PgPool,
+    ) -> Result<Option<ConfigRevision>, sqlx::Error> {
+        sqlx::query_as!(
+            ConfigRevision,
+            r#"SELECT r.revision, u.username AS "author?", r.restored_from, r.created_at
+               FROM project_config_revisions r
+               LEFT JOIN users u ON u.id = r.author_id
+               WHERE r.project_id = $1 AND r.revision = $2"#,
+            project_id,
+            revision
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
+
+fn parse_config(config_json: &str) -> Result<ProjectConfig, sqlx::Error> {
+    serde_json::from_str(config_json).map_err(|_| {
+        sqlx::Error::Decode(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Failed to deserialize",
+        )))
+    })
 }
 
 impl Project {
     pub async fn create(&self, pool: &PgPool) -> Result<i64, sqlx::Error> {
         let result = sqlx::query!(
-            "INSERT INTO projects (name, owner_id, description, is_public)
-             VALUES ($1, $2, $3, $4) RETURNING id",
+            "INSERT INTO projects (name, owner_id, organization_id, description, is_public)
+             VALUES ($1, $2, $3, $4, $5) RETURNING id",
             self.name,
             self.owner_id,
+            self.organization_id,
             self.description,
             self.is_public
         )
@@ -83,6 +402,19 @@ impl Project {
         Ok(result.id)
     }
 
+    /// Владелец личного проекта; у проектов организаций его нет
+    pub fn personal_owner_id(&self) -> Option<i64> {
+        self.organization_id.is_none().then_some(self.owner_id)
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
     pub async fn get_config(&self, pool: &PgPool) -> Result<ProjectConfig, sqlx::Error> {
         if let Some(project_id) = self.id {
             ProjectConfig::load(project_id, pool).await
@@ -91,22 +423,11 @@ impl Project {
         }
     }
 
-    pub async fn update_config(
-        &self,
-        config: &ProjectConfig,
-        pool: &PgPool,
-    ) -> Result<(), sqlx::Error> {
-        if let Some(project_id) = self.id {
-            config.save(project_id, pool).await
-        } else {
-            Err(sqlx::Error::RowNotFound)
-        }
-    }
-
     pub async fn find_by_owner(owner_id: i64, pool: &PgPool) -> Result<Vec<Project>, sqlx::Error> {
         let projects = sqlx::query!(
-            "SELECT id, name, owner_id, description, is_public, created_at
-             FROM projects WHERE owner_id = $1",
+            "SELECT id, name, owner_id, organization_id, description, is_public, created_at,
+                    archived_at, deleted_at
+             FROM projects WHERE owner_id = $1 AND deleted_at IS NULL",
             owner_id
         )
         .fetch_all(pool)
@@ -116,18 +437,130 @@ impl Project {
             id: Some(row.id),
             name: row.name,
             owner_id: row.owner_id,
+            organization_id: row.organization_id,
+            description: row.description,
+            is_public: row.is_public,
+            created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
+        })
+        .collect();
+
+        Ok(projects)
+    }
+
+    /// Проекты, которыми пользователь владеет или в которых участвует сам,
+    /// через команду или через организацию; удалённые не возвращаются
+    pub async fn find_for_member(user_id: i64, pool: &PgPool) -> Result<Vec<Project>, sqlx::Error> {
+        let projects = sqlx::query!(
+            "SELECT p.id, p.name, p.owner_id, p.organization_id, p.description, p.is_public, p.created_at,
+                    p.archived_at, p.deleted_at
+             FROM projects p
+             WHERE p.deleted_at IS NULL
+               AND (p.owner_id = $1
+                OR EXISTS (
+                    SELECT 1 FROM project_members m
+                    WHERE m.project_id = p.id AND m.user_id = $1
+                )
+                OR EXISTS (
+                    SELECT 1 FROM project_teams pt
+                    JOIN team_members tm ON tm.team_id = pt.team_id
+                    WHERE pt.project_id = p.id AND tm.user_id = $1
+                )
+                OR EXISTS (
+                    SELECT 1 FROM organization_members om
+                    WHERE om.organization_id = p.organization_id AND om.user_id = $1
+                ))
+             ORDER BY p.created_at, p.id",
+            user_id
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| Project {
+            id: Some(row.id),
+            name: row.name,
+            owner_id: row.owner_id,
+            organization_id: row.organization_id,
+            description: row.description,
+            is_public: row.is_public,
+            created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
+        })
+        .collect();
+
+        Ok(projects)
+    }
+
+    /// Проекты, к которым у пользователя есть доступ помимо публичного:
+    /// участники проекта (см. представление `project_participants`) и соавторы
+    /// отдельных репозиториев; удалённые не возвращаются
+    pub async fn find_accessible(user_id: i64, pool: &PgPool) -> Result<Vec<Project>, sqlx::Error> {
+        let projects = sqlx::query!(
+            "SELECT p.id, p.name, p.owner_id, p.organization_id, p.description, p.is_public, p.created_at,
+                    p.archived_at, p.deleted_at
+             FROM projects p
+             WHERE p.deleted_at IS NULL
+               AND (EXISTS (
+                    SELECT 1 FROM project_participants pp
+                    WHERE pp.project_id = p.id AND pp.user_id = $1
+                )
+                OR EXISTS (
+                    SELECT 1 FROM repository_collaborators rc
+                    JOIN repositories r ON r.id = rc.repository_id
+                    WHERE r.project_id = p.id AND rc.user_id = $1
+                ))
+             ORDER BY p.id",
+            user_id
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| Project {
+            id: Some(row.id),
+            name: row.name,
+            owner_id: row.owner_id,
+            organization_id: row.organization_id,
             description: row.description,
             is_public: row.is_public,
             created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
         })
         .collect();
 
         Ok(projects)
     }
 
+    pub async fn find_by_id(id: i64, pool: &PgPool) -> Result<Option<Project>, sqlx::Error> {
+        let project = sqlx::query!(
+            "SELECT id, name, owner_id, organization_id, description, is_public, created_at,
+                    archived_at, deleted_at
+             FROM projects WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .map(|row| Project {
+            id: Some(row.id),
+            name: row.name,
+            owner_id: row.owner_id,
+            organization_id: row.organization_id,
+            description: row.description,
+            is_public: row.is_public,
+            created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
+        });
+
+        Ok(project)
+    }
+
     pub async fn find_by_name(name: &str, pool: &PgPool) -> Result<Option<Project>, sqlx::Error> {
         let project = sqlx::query!(
-            "SELECT id, name, owner_id, description, is_public, created_at
+            "SELECT id, name, owner_id, organization_id, description, is_public, created_at,
+                    archived_at, deleted_at
              FROM projects WHERE name = $1",
             name
         )
@@ -137,22 +570,27 @@ impl Project {
             id: Some(row.id),
             name: row.name,
             owner_id: row.owner_id,
+            organization_id: row.organization_id,
             description: row.description,
             is_public: row.is_public,
             created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
         });
 
         Ok(project)
     }
 
+    /// Личный проект пользователя по имени, в том числе удалённый
     pub async fn find_by_name_and_owner(
         name: &str,
         owner_id: i64,
         pool: &PgPool,
     ) -> Result<Option<Project>, sqlx::Error> {
         let project = sqlx::query!(
-            "SELECT id, name, owner_id, description, is_public, created_at
-             FROM projects WHERE name = $1 AND owner_id = $2",
+            "SELECT id, name, owner_id, organization_id, description, is_public, created_at,
+                    archived_at, deleted_at
+             FROM projects WHERE name = $1 AND owner_id = $2 AND organization_id IS NULL",
             name,
             owner_id
         )
@@ -162,18 +600,168 @@ impl Project {
             id: Some(row.id),
             name: row.name,
             owner_id: row.owner_id,
+            organization_id: row.organization_id,
+            description: row.description,
+            is_public: row.is_public,
+            created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
+        });
+
+        Ok(project)
+    }
+
+    /// Проект организации по имени, в том числе удалённый
+    pub async fn find_by_name_and_organization(
+        name: &str,
+        organization_id: i64,
+        pool: &PgPool,
+    ) -> Result<Option<Project>, sqlx::Error> {
+        let project = sqlx::query!(
+            "SELECT id, name, owner_id, organization_id, description, is_public, created_at,
+                    archived_at, deleted_at
+             FROM projects WHERE name = $1 AND organization_id = $2",
+            name,
+            organization_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .map(|row| Project {
+            id: Some(row.id),
+            name: row.name,
+            owner_id: row.owner_id,
+            organization_id: row.organization_id,
             description: row.description,
             is_public: row.is_public,
             created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
         });
 
         Ok(project)
     }
 
+    /// Проекты организации
+    pub async fn find_by_organization(
+        organization_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<Project>, sqlx::Error> {
+        let projects = sqlx::query!(
+            "SELECT id, name, owner_id, organization_id, description, is_public, created_at,
+                    archived_at, deleted_at
+             FROM projects WHERE organization_id = $1 AND deleted_at IS NULL
+             ORDER BY name",
+            organization_id
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| Project {
+            id: Some(row.id),
+            name: row.name,
+            owner_id: row.owner_id,
+            organization_id: row.organization_id,
+            description: row.description,
+            is_public: row.is_public,
+            created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
+        })
+        .collect();
+
+        Ok(projects)
+    }
+
+    /// Имя пространства проекта в URL: организация или пользователь-владелец
+    pub async fn namespace(&self, pool: &PgPool) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT COALESCE(
+                   (SELECT name FROM organizations WHERE id = $2),
+                   (SELECT username FROM users WHERE id = $1)
+               ) AS namespace"#,
+            self.owner_id,
+            self.organization_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.namespace)
+    }
+
     pub async fn find_public(pool: &PgPool) -> Result<Vec<Project>, sqlx::Error> {
         let projects = sqlx::query!(
-            "SELECT id, name, owner_id, description, is_public, created_at
-             FROM projects WHERE is_public = true"
+            "SELECT id, name, owner_id, organization_id, description, is_public, created_at,
+                    archived_at, deleted_at
+             FROM projects WHERE is_public = true AND deleted_at IS NULL"
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| Project {
+            id: Some(row.id),
+            name: row.name,
+            owner_id: row.owner_id,
+            organization_id: row.organization_id,
+            description: row.description,
+            is_public: row.is_public,
+            created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
+        })
+        .collect();
+
+        Ok(projects)
+    }
+
+    /// Удалённые проекты, которые пользователь может восстановить: личные и
+    /// проекты организаций, где он владелец или администратор
+    pub async fn find_deleted_for_user(
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<Project>, sqlx::Error> {
+        let projects = sqlx::query!(
+            "SELECT p.id, p.name, p.owner_id, p.organization_id, p.description, p.is_public, p.created_at,
+                    p.archived_at, p.deleted_at
+             FROM projects p
+             WHERE p.deleted_at IS NOT NULL
+               AND ((p.organization_id IS NULL AND p.owner_id = $1)
+                OR EXISTS (
+                    SELECT 1 FROM organization_members om
+                    WHERE om.organization_id = p.organization_id AND om.user_id = $1
+                      AND om.role IN ('owner', 'admin')
+                ))
+             ORDER BY p.deleted_at DESC",
+            user_id
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| Project {
+            id: Some(row.id),
+            name: row.name,
+            owner_id: row.owner_id,
+            organization_id: row.organization_id,
+            description: row.description,
+            is_public: row.is_public,
+            created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
+        })
+        .collect();
+
+        Ok(projects)
+    }
+
+    /// Проекты, удалённые раньше `before`
+    pub async fn find_deleted_before(
+        before: DateTime<Utc>,
+        pool: &PgPool,
+    ) -> Result<Vec<Project>, sqlx::Error> {
+        let projects = sqlx::query!(
+            "SELECT id, name, owner_id, organization_id, description, is_public, created_at,
+                    archived_at, deleted_at
+             FROM projects WHERE deleted_at < $1",
+            before
         )
         .fetch_all(pool)
         .await?
@@ -182,15 +770,112 @@ impl Project {
             id: Some(row.id),
             name: row.name,
             owner_id: row.owner_id,
+            organization_id: row.organization_id,
             description: row.description,
             is_public: row.is_public,
             created_at: row.created_at,
+            archived_at: row.archived_at,
+            deleted_at: row.deleted_at,
         })
         .collect();
 
         Ok(projects)
     }
 
+    /// Архивирует проект или возвращает его из архива
+    pub async fn set_archived(id: i64, archived: bool, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET archived_at = CASE WHEN $2 THEN CURRENT_TIMESTAMP END
+             WHERE id = $1",
+            id,
+            archived
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Помечает проект удалённым или восстанавливает его
+    pub async fn set_deleted(id: i64, deleted: bool, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET deleted_at = CASE WHEN $2 THEN CURRENT_TIMESTAMP END
+             WHERE id = $1",
+            id,
+            deleted
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Передаёт проект пользователю (`organization_id = None`) или организации
+    ///
+    /// Репозитории переходят к тому же владельцу. Прежние участники с ролью
+    /// owner теряют членство в проекте, новый личный владелец получает роль
+    /// owner. Запрос на передачу проекта удаляется.
+    pub async fn transfer(
+        id: i64,
+        owner_id: i64,
+        organization_id: Option<i64>,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "UPDATE projects SET owner_id = $2, organization_id = $3 WHERE id = $1",
+            id,
+            owner_id,
+            organization_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE repositories SET owner_id = $2 WHERE project_id = $1",
+            id,
+            owner_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM project_members WHERE project_id = $1 AND role = 'owner'",
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if organization_id.is_none() {
+            sqlx::query!(
+                "INSERT INTO project_members (project_id, user_id, role)
+                 VALUES ($1, $2, 'owner')
+                 ON CONFLICT (project_id, user_id) DO UPDATE SET role = 'owner'",
+                id,
+                owner_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!("DELETE FROM project_transfers WHERE project_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Окончательно удаляет проект с его репозиториями и всеми их данными
+    pub async fn delete(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!("DELETE FROM repositories WHERE project_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM projects WHERE id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
     pub fn new(
         name: String,
         owner_id: i64,
@@ -213,9 +898,38 @@ impl Project {
             id: None,
             name,
             owner_id,
+            organization_id: None,
             description,
             is_public,
             created_at: None,
+            archived_at: None,
+            deleted_at: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spark_check_version() {
+        let spark = SparkPublishing {
+            enabled: true,
+            ..SparkPublishing::default()
+        };
+        assert!(spark.check_version("tool", "1.2.0").is_ok());
+        assert!(SparkPublishing::default()
+            .check_version("tool", "1.2.0")
+            .is_err());
+
+        let spark = SparkPublishing {
+            enabled: true,
+            package: Some("tool".to_string()),
+            tag_pattern: "release-*".to_string(),
+        };
+        assert!(spark.check_version("tool", "release-3").is_ok());
+        assert!(spark.check_version("tool", "1.2.0").is_err());
+        assert!(spark.check_version("other", "release-3").is_err());
+    }
+}
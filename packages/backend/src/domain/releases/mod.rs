@@ -0,0 +1,254 @@
+//! Доменная модель релизов и прикреплённых к ним файлов
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Релиз, привязанный к git-тегу
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Release {
+    pub id: Option<i64>,
+    pub repository_id: i64,
+    /// Имя тега, на который указывает релиз
+    pub tag_name: String,
+    /// SHA коммита тега на момент создания релиза
+    pub target_sha: String,
+    pub title: String,
+    /// Описание релиза в формате Markdown
+    pub notes: Option<String>,
+    pub is_draft: bool,
+    pub is_prerelease: bool,
+    pub author_id: i64,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Дата публикации (None для черновиков)
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Файл, прикреплённый к релизу
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseAsset {
+    pub id: Option<i64>,
+    pub release_id: i64,
+    pub file_name: String,
+    pub content_type: String,
+    /// Размер файла в байтах
+    pub size: i64,
+    /// Контрольная сумма SHA-256 (hex)
+    pub sha256: String,
+    /// Путь к файлу относительно ASSETS_PATH
+    #[serde(skip_serializing)]
+    pub storage_path: String,
+    pub download_count: i64,
+    pub uploader_id: i64,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl Release {
+    /// Создаёт релиз; опубликованный релиз сразу получает дату публикации
+    pub async fn create(&self, pool: &PgPool) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query!(
+            "INSERT INTO releases
+                 (repository_id, tag_name, target_sha, title, notes, is_draft, is_prerelease,
+                  author_id, published_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8,
+                     CASE WHEN $6 THEN NULL ELSE CURRENT_TIMESTAMP END)
+             RETURNING id",
+            self.repository_id,
+            self.tag_name,
+            self.target_sha,
+            self.title,
+            self.notes,
+            self.is_draft,
+            self.is_prerelease,
+            self.author_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result.id)
+    }
+
+    /// Возвращает релизы репозитория, начиная с самых новых
+    pub async fn find_by_repository(
+        repository_id: i64,
+        include_drafts: bool,
+        pool: &PgPool,
+    ) -> Result<Vec<Release>, sqlx::Error> {
+        let releases = sqlx::query_as!(
+            Release,
+            "SELECT id, repository_id, tag_name, target_sha, title, notes, is_draft,
+                    is_prerelease, author_id, created_at, published_at
+             FROM releases
+             WHERE repository_id = $1 AND ($2 OR NOT is_draft)
+             ORDER BY COALESCE(published_at, created_at) DESC, id DESC",
+            repository_id,
+            include_drafts
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(releases)
+    }
+
+    /// Опубликованные релизы автора во всех репозиториях, начиная с новых
+    pub async fn find_published_by_author(
+        author_id: i64,
+        limit: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<Release>, sqlx::Error> {
+        sqlx::query_as!(
+            Release,
+            "SELECT id, repository_id, tag_name, target_sha, title, notes, is_draft,
+                    is_prerelease, author_id, created_at, published_at
+             FROM releases
+             WHERE author_id = $1 AND NOT is_draft
+             ORDER BY COALESCE(published_at, created_at) DESC, id DESC
+             LIMIT $2",
+            author_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Находит релиз по имени тега
+    pub async fn find_by_tag(
+        repository_id: i64,
+        tag_name: &str,
+        pool: &PgPool,
+    ) -> Result<Option<Release>, sqlx::Error> {
+        let release = sqlx::query_as!(
+            Release,
+            "SELECT id, repository_id, tag_name, target_sha, title, notes, is_draft,
+                    is_prerelease, author_id, created_at, published_at
+             FROM releases WHERE repository_id = $1 AND tag_name = $2",
+            repository_id,
+            tag_name
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(release)
+    }
+
+    /// Обновляет описание и флаги релиза
+    ///
+    /// При публикации черновика выставляется дата публикации.
+    pub async fn update(&self, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE releases
+             SET title = $1, notes = $2, is_draft = $3, is_prerelease = $4,
+                 published_at = CASE WHEN $3 THEN NULL
+                                     ELSE COALESCE(published_at, CURRENT_TIMESTAMP) END
+             WHERE id = $5",
+            self.title,
+            self.notes,
+            self.is_draft,
+            self.is_prerelease,
+            self.id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Удаляет релиз вместе с записями о файлах
+    pub async fn delete(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM releases WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl ReleaseAsset {
+    /// Сохраняет запись о загруженном файле
+    pub async fn create(&self, pool: &PgPool) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query!(
+            "INSERT INTO release_assets
+                 (release_id, file_name, content_type, size, sha256, storage_path, uploader_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING id",
+            self.release_id,
+            self.file_name,
+            self.content_type,
+            self.size,
+            self.sha256,
+            self.storage_path,
+            self.uploader_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result.id)
+    }
+
+    /// Возвращает файлы релиза
+    pub async fn find_by_release(
+        release_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ReleaseAsset>, sqlx::Error> {
+        let assets = sqlx::query_as!(
+            ReleaseAsset,
+            "SELECT id, release_id, file_name, content_type, size, sha256, storage_path,
+                    download_count, uploader_id, created_at
+             FROM release_assets WHERE release_id = $1 ORDER BY file_name",
+            release_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(assets)
+    }
+
+    /// Находит файл релиза по имени
+    pub async fn find_by_name(
+        release_id: i64,
+        file_name: &str,
+        pool: &PgPool,
+    ) -> Result<Option<ReleaseAsset>, sqlx::Error> {
+        let asset = sqlx::query_as!(
+            ReleaseAsset,
+            "SELECT id, release_id, file_name, content_type, size, sha256, storage_path,
+                    download_count, uploader_id, created_at
+             FROM release_assets WHERE release_id = $1 AND file_name = $2",
+            release_id,
+            file_name
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(asset)
+    }
+
+    /// Увеличивает счётчик скачиваний
+    pub async fn increment_download_count(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE release_assets SET download_count = download_count + 1 WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Удаляет запись о файле
+    pub async fn delete(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM release_assets WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
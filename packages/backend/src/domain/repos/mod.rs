@@ -25,6 +25,8 @@ pub struct Repository {
     pub description: Option<String>,
     /// Флаг публичности репозитория
     pub is_public: bool,
+    /// Репозиторий может служить шаблоном для новых репозиториев
+    pub is_template: bool,
     /// Дата создания репозитория
     pub created_at: Option<DateTime<Utc>>,
 }
@@ -46,13 +48,14 @@ impl Repository {
     pub async fn create(&self, pool: &PgPool) -> Result<i64, sqlx::Error> {
         // Добавляем репозиторий в базу данных
         let result = sqlx::query!(
-            "INSERT INTO repositories (name, project_id, owner_id, description, is_public) 
-             VALUES ($1, $2, $3, $4, $5) RETURNING id",
+            "INSERT INTO repositories (name, project_id, owner_id, description, is_public, is_template) 
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
             self.name,
             self.project_id,
             self.owner_id,
             self.description,
-            self.is_public
+            self.is_public,
+            self.is_template
         )
         .fetch_one(pool)
         .await?;
@@ -60,7 +63,7 @@ impl Repository {
         let repo_id = result.id;
 
         // Создаём репозиторий на диске
-        let repo_path = format!("repositories/{}.git", self.name);
+        let repo_path = git::repository_path(&self.name);
 
         // Используем функцию из utils::git для создания репозитория
         if let Err(e) = git::create_bare_repository(&repo_path) {
@@ -71,7 +74,7 @@ impl Repository {
         Ok(repo_id)
     }
 
-    /// Получает список репозиториев пользователя
+    /// Получает список репозиториев пользователя, кроме репозиториев удалённых проектов
     ///
     /// # Параметры
     ///
@@ -87,8 +90,10 @@ impl Repository {
     ) -> Result<Vec<Repository>, sqlx::Error> {
         let repos = sqlx::query_as!(
             Repository,
-            "SELECT id, name, project_id, owner_id, description, is_public, created_at 
-             FROM repositories WHERE owner_id = $1",
+            "SELECT id, name, project_id, owner_id, description, is_public, is_template, created_at 
+             FROM repositories
+             WHERE owner_id = $1
+               AND project_id IN (SELECT id FROM projects WHERE deleted_at IS NULL)",
             owner_id
         )
         .fetch_all(pool)
@@ -113,7 +118,7 @@ impl Repository {
     ) -> Result<Option<Repository>, sqlx::Error> {
         let repo = sqlx::query_as!(
             Repository,
-            "SELECT id, name, project_id, owner_id, description, is_public, created_at 
+            "SELECT id, name, project_id, owner_id, description, is_public, is_template, created_at 
              FROM repositories WHERE name = $1",
             name
         )
@@ -123,6 +128,18 @@ impl Repository {
         Ok(repo)
     }
 
+    /// Находит репозиторий по ID
+    pub async fn find_by_id(id: i64, pool: &PgPool) -> Result<Option<Repository>, sqlx::Error> {
+        sqlx::query_as!(
+            Repository,
+            "SELECT id, name, project_id, owner_id, description, is_public, is_template, created_at
+             FROM repositories WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     /// Получает список репозиториев проекта
     ///
     /// # Параметры
@@ -139,7 +156,7 @@ impl Repository {
     ) -> Result<Vec<Repository>, sqlx::Error> {
         let repos = sqlx::query_as!(
             Repository,
-            "SELECT id, name, project_id, owner_id, description, is_public, created_at 
+            "SELECT id, name, project_id, owner_id, description, is_public, is_template, created_at 
              FROM repositories WHERE project_id = $1",
             project_id
         )
@@ -167,7 +184,7 @@ impl Repository {
     ) -> Result<Option<Repository>, sqlx::Error> {
         let repo = sqlx::query_as!(
             Repository,
-            "SELECT id, name, project_id, owner_id, description, is_public, created_at 
+            "SELECT id, name, project_id, owner_id, description, is_public, is_template, created_at 
              FROM repositories WHERE name = $1 AND project_id = $2",
             name,
             project_id
@@ -178,6 +195,32 @@ impl Repository {
         Ok(repo)
     }
 
+    /// Помечает репозиторий как шаблон или снимает отметку
+    pub async fn set_template(
+        id: i64,
+        is_template: bool,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE repositories SET is_template = $2 WHERE id = $1",
+            id,
+            is_template
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Удаляет запись репозитория
+    pub async fn delete(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM repositories WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
     /// Создает новый репозиторий с валидацией
     pub fn new(
         name: String,
@@ -213,6 +256,7 @@ impl Repository {
             owner_id,
             description,
             is_public,
+            is_template: false,
             created_at: None,
         })
     }
@@ -0,0 +1,152 @@
+//! Доменная модель полнотекстового индекса кода
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Проиндексированный файл репозитория
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CodeDocument {
+    /// Путь к файлу относительно корня репозитория
+    pub path: String,
+    /// Язык программирования (определяется по расширению)
+    pub language: Option<String>,
+    /// Текстовое содержимое файла
+    pub content: String,
+}
+
+/// Фильтры поиска по коду
+#[derive(Debug, Default)]
+pub struct CodeSearchFilters {
+    /// Имя проекта
+    pub project: Option<String>,
+    /// Имя репозитория
+    pub repository: Option<String>,
+    /// Регулярное выражение для пути (см. `helpers::glob_to_regex`)
+    pub path_regex: Option<String>,
+    /// Язык файла
+    pub language: Option<String>,
+}
+
+/// Найденный документ вместе с контекстом репозитория
+#[derive(Debug, Clone)]
+pub struct CodeSearchRow {
+    pub repository_name: String,
+    pub project_name: String,
+    pub owner_username: String,
+    pub path: String,
+    pub language: Option<String>,
+    pub commit_sha: String,
+    pub content: String,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl CodeDocument {
+    /// Заменяет индекс репозитория новым набором документов (в одной транзакции)
+    pub async fn replace_for_repository(
+        repository_id: i64,
+        commit_sha: &str,
+        documents: &[CodeDocument],
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM code_search_documents WHERE repository_id = $1",
+            repository_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for document in documents {
+            sqlx::query!(
+                "INSERT INTO code_search_documents (repository_id, commit_sha, path, language, content)
+                 VALUES ($1, $2, $3, $4, $5)",
+                repository_id,
+                commit_sha,
+                document.path,
+                document.language,
+                document.content
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// Возвращает SHA коммита, по которому построен индекс репозитория
+    pub async fn indexed_commit(
+        repository_id: i64,
+        pool: &PgPool,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let result = sqlx::query!(
+            "SELECT commit_sha FROM code_search_documents WHERE repository_id = $1 LIMIT 1",
+            repository_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result.map(|row| row.commit_sha))
+    }
+
+    /// Полнотекстовый поиск по публичным репозиториям публичных проектов и
+    /// по `readable_ids` — репозиториям, которые зритель может читать
+    pub async fn search(
+        query: &str,
+        filters: &CodeSearchFilters,
+        readable_ids: &[i64],
+        limit: i64,
+        offset: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<CodeSearchRow>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT r.name AS repository_name, p.name AS project_name,
+                    COALESCE(o.name, u.username) AS "owner_username!", d.path, d.language,
+                    d.commit_sha, d.content
+             FROM code_search_documents d
+             JOIN repositories r ON r.id = d.repository_id
+             JOIN projects p ON p.id = r.project_id
+             JOIN users u ON u.id = p.owner_id
+             LEFT JOIN organizations o ON o.id = p.organization_id
+             WHERE d.content_tsv @@ plainto_tsquery('simple', $1)
+               AND p.deleted_at IS NULL
+               AND ($2::TEXT IS NULL OR p.name = $2)
+               AND ($3::TEXT IS NULL OR r.name = $3)
+               AND ($4::TEXT IS NULL OR d.path ~ $4)
+               AND ($5::TEXT IS NULL OR LOWER(d.language) = LOWER($5))
+               AND ((p.is_public AND r.is_public) OR r.id = ANY($6))
+             ORDER BY ts_rank(d.content_tsv, plainto_tsquery('simple', $1)) DESC, d.path
+             LIMIT $7 OFFSET $8"#,
+            query,
+            filters.project,
+            filters.repository,
+            filters.path_regex,
+            filters.language,
+            readable_ids,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| CodeSearchRow {
+            repository_name: row.repository_name,
+            project_name: row.project_name,
+            owner_username: row.owner_username,
+            path: row.path,
+            language: row.language,
+            commit_sha: row.commit_sha,
+            content: row.content,
+        })
+        .collect();
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,131 @@
+//! Доменная модель найденных в push секретов
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Состояния находки
+pub const STATE_OPEN: &str = "open";
+pub const STATE_RESOLVED: &str = "resolved";
+pub const STATE_IGNORED: &str = "ignored";
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Секрет, найденный в блобе, принятом в репозиторий
+///
+/// Сам секрет не хранится — только его маскированная запись и место в
+/// репозитории. Находка одного правила в той же строке того же блоба
+/// записывается один раз.
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretFinding {
+    pub id: i64,
+    pub rule: String,
+    pub blob_sha: String,
+    pub path: String,
+    pub line: i32,
+    pub redacted: String,
+    /// Вершина ссылки, с которой пришёл блоб
+    pub commit_sha: String,
+    pub ref_name: String,
+    /// open, resolved или ignored
+    pub state: String,
+    pub pusher_id: Option<i64>,
+    pub resolved_by: Option<i64>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Данные новой находки
+#[derive(Debug)]
+pub struct NewSecretFinding {
+    pub repository_id: i64,
+    pub rule: String,
+    pub blob_sha: String,
+    pub path: String,
+    pub line: i32,
+    pub redacted: String,
+    pub commit_sha: String,
+    pub ref_name: String,
+    pub pusher_id: Option<i64>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl SecretFinding {
+    /// Сохраняет находку; `None`, если такая уже записана
+    pub async fn create(
+        finding: &NewSecretFinding,
+        pool: &PgPool,
+    ) -> Result<Option<SecretFinding>, sqlx::Error> {
+        sqlx::query_as!(
+            SecretFinding,
+            "INSERT INTO secret_findings (repository_id, rule, blob_sha, path, line, redacted,
+                                          commit_sha, ref_name, pusher_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (repository_id, blob_sha, rule, line) DO NOTHING
+             RETURNING id, rule, blob_sha, path, line, redacted, commit_sha, ref_name, state,
+                       pusher_id, resolved_by, resolved_at, created_at",
+            finding.repository_id,
+            finding.rule,
+            finding.blob_sha,
+            finding.path,
+            finding.line,
+            finding.redacted,
+            finding.commit_sha,
+            finding.ref_name,
+            finding.pusher_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Находки репозитория, новые первыми
+    pub async fn find_by_repository(
+        repository_id: i64,
+        state: Option<&str>,
+        pool: &PgPool,
+    ) -> Result<Vec<SecretFinding>, sqlx::Error> {
+        sqlx::query_as!(
+            SecretFinding,
+            "SELECT id, rule, blob_sha, path, line, redacted, commit_sha, ref_name, state,
+                    pusher_id, resolved_by, resolved_at, created_at
+             FROM secret_findings
+             WHERE repository_id = $1 AND ($2::TEXT IS NULL OR state = $2)
+             ORDER BY id DESC",
+            repository_id,
+            state
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Меняет состояние находки; для `open` сбрасывает автора решения
+    pub async fn update_state(
+        id: i64,
+        repository_id: i64,
+        state: &str,
+        user_id: Option<i64>,
+        pool: &PgPool,
+    ) -> Result<Option<SecretFinding>, sqlx::Error> {
+        sqlx::query_as!(
+            SecretFinding,
+            "UPDATE secret_findings
+             SET state = $3::TEXT,
+                 resolved_by = CASE WHEN $3 = 'open' THEN NULL ELSE $4::BIGINT END,
+                 resolved_at = CASE WHEN $3 = 'open' THEN NULL ELSE CURRENT_TIMESTAMP END
+             WHERE id = $1 AND repository_id = $2
+             RETURNING id, rule, blob_sha, path, line, redacted, commit_sha, ref_name, state,
+                       pusher_id, resolved_by, resolved_at, created_at",
+            id,
+            repository_id,
+            state,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -0,0 +1,134 @@
+//! Доменная модель ключей подписи коммитов
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Публичный ключ пользователя для проверки подписей (GPG или SSH)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningKey {
+    pub id: Option<i64>,
+    pub user_id: i64,
+    /// Тип ключа: gpg или ssh
+    pub key_type: String,
+    /// Название ключа, заданное пользователем
+    pub title: String,
+    /// Публичный ключ (ASCII-armored для GPG, `<type> <base64>` для SSH)
+    pub public_key: String,
+    /// Отпечаток ключа (основного ключа для GPG, SHA256 для SSH)
+    pub fingerprint: String,
+    /// Отпечатки подключей GPG; у SSH ключей пусто
+    pub subkey_fingerprints: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Зарегистрированный ключ, которым могла быть сделана подпись, и его владелец
+#[derive(Debug, Clone)]
+pub struct SignerKey {
+    pub username: String,
+    pub public_key: String,
+    pub fingerprint: String,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl SigningKey {
+    /// Сохраняет ключ пользователя
+    pub async fn create(&self, pool: &PgPool) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query!(
+            "INSERT INTO user_signing_keys
+                 (user_id, key_type, title, public_key, fingerprint, subkey_fingerprints)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id",
+            self.user_id,
+            self.key_type,
+            self.title,
+            self.public_key,
+            self.fingerprint,
+            &self.subkey_fingerprints
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result.id)
+    }
+
+    /// Возвращает все ключи пользователя
+    pub async fn find_by_user(user_id: i64, pool: &PgPool) -> Result<Vec<SigningKey>, sqlx::Error> {
+        let keys = sqlx::query_as!(
+            SigningKey,
+            "SELECT id, user_id, key_type, title, public_key, fingerprint, subkey_fingerprints,
+                    created_at
+             FROM user_signing_keys WHERE user_id = $1 ORDER BY created_at",
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    /// Находит ключ по отпечатку; ключ принадлежит не более чем одному пользователю
+    pub async fn find_by_fingerprint(
+        fingerprint: &str,
+        pool: &PgPool,
+    ) -> Result<Option<SigningKey>, sqlx::Error> {
+        let key = sqlx::query_as!(
+            SigningKey,
+            "SELECT id, user_id, key_type, title, public_key, fingerprint, subkey_fingerprints,
+                    created_at
+             FROM user_signing_keys WHERE fingerprint = $1",
+            fingerprint
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(key)
+    }
+
+    /// Ключи, которыми могла быть сделана подпись
+    ///
+    /// `key_id` — SHA256 отпечаток для SSH; для GPG — длинный ID (последние
+    /// 16 символов отпечатка) основного ключа или подключа.
+    pub async fn find_signers(
+        key_type: &str,
+        key_id: &str,
+        pool: &PgPool,
+    ) -> Result<Vec<SignerKey>, sqlx::Error> {
+        sqlx::query_as!(
+            SignerKey,
+            "SELECT u.username, k.public_key, k.fingerprint
+             FROM user_signing_keys k
+             JOIN users u ON u.id = k.user_id
+             WHERE k.key_type = $1
+               AND (k.fingerprint = $2
+                    OR ($1 = 'gpg' AND EXISTS (
+                        SELECT 1 FROM unnest(k.fingerprint || k.subkey_fingerprints) f
+                        WHERE RIGHT(f, 16) = $2
+                    )))",
+            key_type,
+            key_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Удаляет ключ пользователя; возвращает false, если ключ не найден
+    pub async fn delete(id: i64, user_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM user_signing_keys WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,81 @@
+//! Доменная модель статусов коммитов (результаты внешних CI)
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Допустимые состояния статуса
+pub const STATES: [&str; 4] = ["pending", "success", "failure", "error"];
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Статус коммита в рамках одного контекста (например, `ci/build`)
+///
+/// Повторная отправка статуса с тем же контекстом заменяет предыдущий.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitStatus {
+    pub id: Option<i64>,
+    #[serde(skip_serializing)]
+    pub repository_id: i64,
+    pub commit_sha: String,
+    /// pending, success, failure или error
+    pub state: String,
+    pub context: String,
+    pub description: Option<String>,
+    pub target_url: Option<String>,
+    pub creator_id: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl CommitStatus {
+    /// Создаёт статус или заменяет существующий с тем же контекстом
+    pub async fn upsert(&self, pool: &PgPool) -> Result<CommitStatus, sqlx::Error> {
+        sqlx::query_as!(
+            CommitStatus,
+            "INSERT INTO commit_statuses
+                 (repository_id, commit_sha, state, context, description, target_url, creator_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (repository_id, commit_sha, context) DO UPDATE
+             SET state = $3, description = $5, target_url = $6, creator_id = $7,
+                 updated_at = CURRENT_TIMESTAMP
+             RETURNING id, repository_id, commit_sha, state, context, description, target_url,
+                       creator_id, created_at, updated_at",
+            self.repository_id,
+            self.commit_sha,
+            self.state,
+            self.context,
+            self.description,
+            self.target_url,
+            self.creator_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Статусы коммита, отсортированные по контексту
+    pub async fn find_by_commit(
+        repository_id: i64,
+        commit_sha: &str,
+        pool: &PgPool,
+    ) -> Result<Vec<CommitStatus>, sqlx::Error> {
+        sqlx::query_as!(
+            CommitStatus,
+            "SELECT id, repository_id, commit_sha, state, context, description, target_url,
+                    creator_id, created_at, updated_at
+             FROM commit_statuses
+             WHERE repository_id = $1 AND commit_sha = $2
+             ORDER BY context",
+            repository_id,
+            commit_sha
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,266 @@
+//! Доменная модель команд: группы пользователей с доступом к нескольким проектам
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Роли, которые команда может получить в проекте (владельцем команда не бывает)
+pub const TEAM_ROLES: [&str; 4] = ["maintainer", "developer", "reporter", "guest"];
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Команда пользователя
+#[derive(Debug, Clone, Serialize)]
+pub struct Team {
+    pub id: i64,
+    pub owner_id: i64,
+    /// Имя владельца команды
+    pub owner: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Участник команды
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamMember {
+    pub user_id: i64,
+    pub username: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Доступ команды к проекту
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectTeam {
+    pub team_id: i64,
+    /// Владелец команды
+    pub owner: String,
+    pub name: String,
+    pub project_id: i64,
+    /// Полное имя проекта `{owner}/{project}`
+    pub project: String,
+    /// maintainer, developer, reporter или guest
+    pub role: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl Team {
+    /// Создаёт команду
+    pub async fn create(
+        owner_id: i64,
+        name: &str,
+        description: Option<&str>,
+        pool: &PgPool,
+    ) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query!(
+            "INSERT INTO teams (owner_id, name, description)
+             VALUES ($1, $2, $3) RETURNING id",
+            owner_id,
+            name,
+            description
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.id)
+    }
+
+    /// Команда по владельцу и имени
+    pub async fn find_by_owner_and_name(
+        owner_id: i64,
+        name: &str,
+        pool: &PgPool,
+    ) -> Result<Option<Team>, sqlx::Error> {
+        sqlx::query_as!(
+            Team,
+            "SELECT t.id, t.owner_id, u.username AS owner, t.name, t.description, t.created_at
+             FROM teams t
+             JOIN users u ON u.id = t.owner_id
+             WHERE t.owner_id = $1 AND t.name = $2",
+            owner_id,
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Команды, которыми пользователь владеет или в которых состоит
+    pub async fn find_for_user(user_id: i64, pool: &PgPool) -> Result<Vec<Team>, sqlx::Error> {
+        sqlx::query_as!(
+            Team,
+            "SELECT t.id, t.owner_id, u.username AS owner, t.name, t.description, t.created_at
+             FROM teams t
+             JOIN users u ON u.id = t.owner_id
+             WHERE t.owner_id = $1
+                OR EXISTS (SELECT 1 FROM team_members m WHERE m.team_id = t.id AND m.user_id = $1)
+             ORDER BY u.username, t.name",
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Удаляет команду вместе с её доступом к проектам
+    pub async fn delete(id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM teams WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Участники команды
+    pub async fn members(team_id: i64, pool: &PgPool) -> Result<Vec<TeamMember>, sqlx::Error> {
+        sqlx::query_as!(
+            TeamMember,
+            "SELECT m.user_id, u.username, m.created_at
+             FROM team_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.team_id = $1
+             ORDER BY u.username",
+            team_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Проверяет, состоит ли пользователь в команде
+    pub async fn has_member(
+        team_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT EXISTS (
+                   SELECT 1 FROM team_members WHERE team_id = $1 AND user_id = $2
+               ) AS "exists!""#,
+            team_id,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.exists)
+    }
+
+    /// Добавляет пользователя в команду (повторное добавление ничего не меняет)
+    pub async fn add_member(team_id: i64, user_id: i64, pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO team_members (team_id, user_id) VALUES ($1, $2)
+             ON CONFLICT (team_id, user_id) DO NOTHING",
+            team_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Исключает пользователя из команды; возвращает false, если его не было
+    pub async fn remove_member(
+        team_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM team_members WHERE team_id = $1 AND user_id = $2",
+            team_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl ProjectTeam {
+    /// Команды с доступом к проекту
+    pub async fn find_by_project(
+        project_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ProjectTeam>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectTeam,
+            r#"SELECT t.id AS team_id, u.username AS owner, t.name, p.id AS project_id,
+                      COALESCE(o.name, pu.username) || '/' || p.name AS "project!",
+                      pt.role, pt.created_at
+               FROM project_teams pt
+               JOIN teams t ON t.id = pt.team_id
+               JOIN users u ON u.id = t.owner_id
+               JOIN projects p ON p.id = pt.project_id
+               JOIN users pu ON pu.id = p.owner_id
+               LEFT JOIN organizations o ON o.id = p.organization_id
+               WHERE pt.project_id = $1
+               ORDER BY u.username, t.name"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Проекты, к которым у команды есть доступ
+    pub async fn find_by_team(
+        team_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ProjectTeam>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectTeam,
+            r#"SELECT t.id AS team_id, u.username AS owner, t.name, p.id AS project_id,
+                      COALESCE(o.name, pu.username) || '/' || p.name AS "project!",
+                      pt.role, pt.created_at
+               FROM project_teams pt
+               JOIN teams t ON t.id = pt.team_id
+               JOIN users u ON u.id = t.owner_id
+               JOIN projects p ON p.id = pt.project_id
+               JOIN users pu ON pu.id = p.owner_id
+               LEFT JOIN organizations o ON o.id = p.organization_id
+               WHERE pt.team_id = $1
+               ORDER BY 5"#,
+            team_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Выдаёт команде роль в проекте или меняет существующую
+    pub async fn upsert(
+        project_id: i64,
+        team_id: i64,
+        role: &str,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO project_teams (project_id, team_id, role)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (project_id, team_id) DO UPDATE SET role = $3",
+            project_id,
+            team_id,
+            role
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Отзывает доступ команды; возвращает false, если его не было
+    pub async fn delete(project_id: i64, team_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_teams WHERE project_id = $1 AND team_id = $2",
+            project_id,
+            team_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,194 @@
+//! Доменная модель статистики посещений репозиториев
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::PgPool;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Вид обращения к репозиторию
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrafficKind {
+    /// Запрос к API репозитория
+    View,
+    /// Полная загрузка репозитория через git
+    Clone,
+    /// Догрузка изменений в существующий клон
+    Fetch,
+}
+
+impl TrafficKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrafficKind::View => "view",
+            TrafficKind::Clone => "clone",
+            TrafficKind::Fetch => "fetch",
+        }
+    }
+}
+
+/// Счётчик обращений одного вида за день
+#[derive(Debug, Clone)]
+pub struct TrafficCount {
+    pub day: NaiveDate,
+    pub kind: String,
+    pub count: i64,
+    /// Число различных посетителей за день
+    pub uniques: i64,
+}
+
+/// Путь, с которого переходили к репозиторию
+#[derive(Debug, Clone, Serialize)]
+pub struct TrafficReferrer {
+    pub path: String,
+    pub count: i64,
+}
+
+/// Счётчики посещений репозиториев по дням
+pub struct RepositoryTraffic;
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl RepositoryTraffic {
+    /// Учитывает обращение посетителя `visitor` за текущий день
+    pub async fn record(
+        repository_id: i64,
+        kind: TrafficKind,
+        visitor: &str,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "WITH visitor AS (
+                 INSERT INTO repository_traffic_visitors (repository_id, day, kind, visitor)
+                 VALUES ($1, CURRENT_DATE, $2, $3)
+                 ON CONFLICT DO NOTHING
+             )
+             INSERT INTO repository_traffic (repository_id, day, kind, count)
+             VALUES ($1, CURRENT_DATE, $2, 1)
+             ON CONFLICT (repository_id, day, kind)
+             DO UPDATE SET count = repository_traffic.count + 1",
+            repository_id,
+            kind.as_str(),
+            visitor
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Учитывает переход к репозиторию с пути `path` за текущий день
+    pub async fn record_referrer(
+        repository_id: i64,
+        path: &str,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO repository_traffic_referrers (repository_id, day, path, count)
+             VALUES ($1, CURRENT_DATE, $2, 1)
+             ON CONFLICT (repository_id, day, path)
+             DO UPDATE SET count = repository_traffic_referrers.count + 1",
+            repository_id,
+            path
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Счётчики по дням начиная с `since`
+    pub async fn daily(
+        repository_id: i64,
+        since: NaiveDate,
+        pool: &PgPool,
+    ) -> Result<Vec<TrafficCount>, sqlx::Error> {
+        sqlx::query_as!(
+            TrafficCount,
+            r#"SELECT t.day, t.kind, t.count,
+                      (SELECT COUNT(*) FROM repository_traffic_visitors v
+                       WHERE v.repository_id = t.repository_id AND v.day = t.day
+                         AND v.kind = t.kind) AS "uniques!"
+               FROM repository_traffic t
+               WHERE t.repository_id = $1 AND t.day >= $2
+               ORDER BY t.day"#,
+            repository_id,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Число различных посетителей каждого вида за весь период с `since`
+    pub async fn uniques(
+        repository_id: i64,
+        since: NaiveDate,
+        pool: &PgPool,
+    ) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT kind, COUNT(DISTINCT visitor) AS "uniques!"
+               FROM repository_traffic_visitors
+               WHERE repository_id = $1 AND day >= $2
+               GROUP BY kind"#,
+            repository_id,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.kind, row.uniques))
+            .collect())
+    }
+
+    /// Самые частые пути переходов за период с `since`
+    pub async fn top_referrers(
+        repository_id: i64,
+        since: NaiveDate,
+        limit: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<TrafficReferrer>, sqlx::Error> {
+        sqlx::query_as!(
+            TrafficReferrer,
+            r#"SELECT path, SUM(count)::BIGINT AS "count!"
+               FROM repository_traffic_referrers
+               WHERE repository_id = $1 AND day >= $2
+               GROUP BY path
+               ORDER BY 2 DESC, path
+               LIMIT $3"#,
+            repository_id,
+            since,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Удаляет статистику за дни раньше `before`
+    pub async fn prune(before: NaiveDate, pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let mut removed = sqlx::query!("DELETE FROM repository_traffic WHERE day < $1", before)
+            .execute(pool)
+            .await?
+            .rows_affected();
+        removed += sqlx::query!(
+            "DELETE FROM repository_traffic_visitors WHERE day < $1",
+            before
+        )
+        .execute(pool)
+        .await?
+        .rows_affected();
+        removed += sqlx::query!(
+            "DELETE FROM repository_traffic_referrers WHERE day < $1",
+            before
+        )
+        .execute(pool)
+        .await?
+        .rows_affected();
+        Ok(removed)
+    }
+}
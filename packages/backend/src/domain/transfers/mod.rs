@@ -0,0 +1,130 @@
+//! Доменная модель запросов на передачу проектов
+//!
+//! Передача выполняется только после того, как новый владелец примет запрос;
+//! у проекта может быть один запрос, новый заменяет прежний.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Ожидающий запрос на передачу проекта
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectTransfer {
+    pub project_id: i64,
+    pub project_name: String,
+    /// Текущее пространство имён проекта
+    pub namespace: Option<String>,
+    /// Новый владелец: пользователь или организация
+    pub target_user_id: Option<i64>,
+    pub target_organization_id: Option<i64>,
+    /// Имя нового владельца
+    pub target: Option<String>,
+    pub requested_by: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl ProjectTransfer {
+    /// Создаёт запрос или заменяет прежний запрос проекта
+    pub async fn request(
+        project_id: i64,
+        target_user_id: Option<i64>,
+        target_organization_id: Option<i64>,
+        requested_by: i64,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO project_transfers
+                 (project_id, target_user_id, target_organization_id, requested_by)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (project_id) DO UPDATE SET
+                 target_user_id = $2,
+                 target_organization_id = $3,
+                 requested_by = $4,
+                 created_at = CURRENT_TIMESTAMP",
+            project_id,
+            target_user_id,
+            target_organization_id,
+            requested_by
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Запрос на передачу проекта, если он есть
+    pub async fn find_by_project(
+        project_id: i64,
+        pool: &PgPool,
+    ) -> Result<Option<ProjectTransfer>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectTransfer,
+            r#"SELECT t.project_id, p.name AS project_name,
+                      COALESCE(po.name, pu.username) AS namespace,
+                      t.target_user_id, t.target_organization_id,
+                      COALESCE(tu.username, tor.name) AS target,
+                      t.requested_by, t.created_at
+               FROM project_transfers t
+               JOIN projects p ON p.id = t.project_id
+               JOIN users pu ON pu.id = p.owner_id
+               LEFT JOIN organizations po ON po.id = p.organization_id
+               LEFT JOIN users tu ON tu.id = t.target_user_id
+               LEFT JOIN organizations tor ON tor.id = t.target_organization_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Запросы, которые пользователь может принять: адресованные ему и
+    /// организациям, где он владелец или администратор
+    pub async fn find_for_user(
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ProjectTransfer>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectTransfer,
+            r#"SELECT t.project_id, p.name AS project_name,
+                      COALESCE(po.name, pu.username) AS namespace,
+                      t.target_user_id, t.target_organization_id,
+                      COALESCE(tu.username, tor.name) AS target,
+                      t.requested_by, t.created_at
+               FROM project_transfers t
+               JOIN projects p ON p.id = t.project_id
+               JOIN users pu ON pu.id = p.owner_id
+               LEFT JOIN organizations po ON po.id = p.organization_id
+               LEFT JOIN users tu ON tu.id = t.target_user_id
+               LEFT JOIN organizations tor ON tor.id = t.target_organization_id
+               WHERE p.deleted_at IS NULL
+                 AND (t.target_user_id = $1
+                      OR t.target_organization_id IN (
+                          SELECT organization_id FROM organization_members
+                          WHERE user_id = $1 AND role IN ('owner', 'admin')))
+               ORDER BY t.created_at DESC"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Удаляет запрос; возвращает false, если его не было
+    pub async fn delete(project_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_transfers WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
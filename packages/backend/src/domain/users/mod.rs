@@ -23,6 +23,8 @@ pub struct User {
     pub email: Option<String>,
     /// Дата создания пользователя
     pub created_at: Option<DateTime<Utc>>,
+    /// Флаг администратора сервера
+    pub is_admin: bool,
 }
 
 // ============================================================================
@@ -51,7 +53,7 @@ impl User {
     ) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as!(
             User,
-            "SELECT id, username, password_hash, email, created_at FROM users WHERE username = $1",
+            "SELECT id, username, password_hash, email, created_at, is_admin FROM users WHERE username = $1",
             username
         )
         .fetch_optional(pool)
@@ -82,7 +84,7 @@ impl User {
     pub async fn find_by_id(user_id: i64, pool: &PgPool) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as!(
             User,
-            "SELECT id, username, password_hash, email, created_at FROM users WHERE id = $1",
+            "SELECT id, username, password_hash, email, created_at, is_admin FROM users WHERE id = $1",
             user_id
         )
         .fetch_optional(pool)
@@ -121,7 +123,7 @@ impl User {
     pub async fn find_all(pool: &PgPool) -> Result<Vec<User>, sqlx::Error> {
         let users = sqlx::query_as!(
             User,
-            "SELECT id, username, password_hash, email, created_at FROM users ORDER BY created_at DESC"
+            "SELECT id, username, password_hash, email, created_at, is_admin FROM users ORDER BY created_at DESC"
         )
         .fetch_all(pool)
         .await?;
@@ -185,15 +187,10 @@ impl User {
             password_hash,
             email,
             created_at: None,
+            is_admin: false,
         })
     }
 
-    /// Проверяет права администратора (заглушка)
-    pub fn is_admin(&self) -> bool {
-        // В будущем можно добавить поле role в базу данных
-        false
-    }
-
     /// Получает публичную информацию о пользователе (без пароля)
     pub fn public_info(&self) -> PublicUser {
         PublicUser {
@@ -0,0 +1,229 @@
+//! Доменная модель звёзд и подписок на проекты
+//!
+//! Участники проекта следят за ним на уровне `all`, пока не выберут другой
+//! уровень; остальные пользователи подписываются явно. Участники — все, у кого
+//! есть доступ ко всему проекту (представление `project_participants`):
+//! личный владелец, участники и соавторы проекта, члены его команд и члены
+//! организации-владельца.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Уровни подписки
+pub const WATCH_ALL: &str = "all";
+pub const WATCH_RELEASES: &str = "releases";
+pub const WATCH_IGNORE: &str = "ignore";
+pub const WATCH_LEVELS: [&str; 3] = [WATCH_ALL, WATCH_RELEASES, WATCH_IGNORE];
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Пользователь, отметивший проект звездой
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectStar {
+    pub user_id: i64,
+    pub username: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Действующая подписка пользователя на проект
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectWatch {
+    pub project_id: i64,
+    pub user_id: i64,
+    /// all, releases или ignore
+    pub level: String,
+    /// `false`, если уровень достался участнику проекта по умолчанию
+    pub explicit: bool,
+}
+
+// ============================================================================
+// РЕАЛИЗАЦИЯ МЕТОДОВ
+// ============================================================================
+
+impl ProjectStar {
+    /// Ставит звезду; возвращает false, если она уже стояла
+    pub async fn add(project_id: i64, user_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "INSERT INTO project_stars (project_id, user_id) VALUES ($1, $2)
+             ON CONFLICT (project_id, user_id) DO NOTHING",
+            project_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Снимает звезду; возвращает false, если её не было
+    pub async fn remove(project_id: i64, user_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_stars WHERE project_id = $1 AND user_id = $2",
+            project_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Проверяет, отметил ли пользователь проект
+    pub async fn exists(project_id: i64, user_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT EXISTS (
+                   SELECT 1 FROM project_stars WHERE project_id = $1 AND user_id = $2
+               ) AS "exists!""#,
+            project_id,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.exists)
+    }
+
+    /// Пользователи, отметившие проект, новые первыми
+    pub async fn find_by_project(
+        project_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ProjectStar>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectStar,
+            "SELECT s.user_id, u.username, s.created_at
+             FROM project_stars s
+             JOIN users u ON u.id = s.user_id
+             WHERE s.project_id = $1
+             ORDER BY s.created_at DESC, u.username",
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Число звёзд у каждого из проектов; проекты без звёзд не возвращаются
+    pub async fn counts(
+        project_ids: &[i64],
+        pool: &PgPool,
+    ) -> Result<Vec<(i64, i64)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT project_id, COUNT(*) AS "stars!"
+               FROM project_stars
+               WHERE project_id = ANY($1)
+               GROUP BY project_id"#,
+            project_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.project_id, row.stars))
+            .collect())
+    }
+}
+
+impl ProjectWatch {
+    /// Подписка пользователя на проект, в том числе подписка участника по умолчанию
+    pub async fn find(
+        project_id: i64,
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Option<ProjectWatch>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWatch,
+            r#"SELECT COALESCE(w.project_id, m.project_id) AS "project_id!",
+                      COALESCE(w.user_id, m.user_id) AS "user_id!",
+                      COALESCE(w.level, 'all') AS "level!",
+                      w.level IS NOT NULL AS "explicit!"
+               FROM project_watches w
+               FULL JOIN project_participants m ON m.project_id = w.project_id AND m.user_id = w.user_id
+               WHERE COALESCE(w.project_id, m.project_id) = $1
+                 AND COALESCE(w.user_id, m.user_id) = $2"#,
+            project_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Подписчики проекта, кроме тех, кто выбрал ignore
+    pub async fn find_by_project(
+        project_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ProjectWatch>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWatch,
+            r#"SELECT COALESCE(w.project_id, m.project_id) AS "project_id!",
+                      COALESCE(w.user_id, m.user_id) AS "user_id!",
+                      COALESCE(w.level, 'all') AS "level!",
+                      w.level IS NOT NULL AS "explicit!"
+               FROM project_watches w
+               FULL JOIN project_participants m ON m.project_id = w.project_id AND m.user_id = w.user_id
+               WHERE COALESCE(w.project_id, m.project_id) = $1
+                 AND COALESCE(w.level, 'all') <> 'ignore'"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Проекты, за которыми следит пользователь, кроме выбранных с ignore
+    pub async fn find_by_user(
+        user_id: i64,
+        pool: &PgPool,
+    ) -> Result<Vec<ProjectWatch>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWatch,
+            r#"SELECT COALESCE(w.project_id, m.project_id) AS "project_id!",
+                      COALESCE(w.user_id, m.user_id) AS "user_id!",
+                      COALESCE(w.level, 'all') AS "level!",
+                      w.level IS NOT NULL AS "explicit!"
+               FROM project_watches w
+               FULL JOIN project_participants m ON m.project_id = w.project_id AND m.user_id = w.user_id
+               WHERE COALESCE(w.user_id, m.user_id) = $1
+                 AND COALESCE(w.level, 'all') <> 'ignore'"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Задаёт уровень подписки
+    pub async fn upsert(
+        project_id: i64,
+        user_id: i64,
+        level: &str,
+        pool: &PgPool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO project_watches (project_id, user_id, level)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (project_id, user_id)
+             DO UPDATE SET level = $3, updated_at = CURRENT_TIMESTAMP",
+            project_id,
+            user_id,
+            level
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Удаляет явную подписку; возвращает false, если её не было
+    pub async fn delete(project_id: i64, user_id: i64, pool: &PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_watches WHERE project_id = $1 AND user_id = $2",
+            project_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
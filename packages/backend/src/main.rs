@@ -68,6 +68,21 @@ async fn main() -> std::io::Result<()> {
         std::process::exit(1);
     }
 
+    // Фоновое обслуживание репозиториев
+    services::maintenance::start_scheduler(
+        database.clone(),
+        config.maintenance_interval,
+        config.maintenance_concurrency,
+    );
+
+    // Встроенные пайплайны, запускаемые при push
+    services::pipelines::init(
+        database.clone(),
+        config.pipeline_concurrency,
+        &config.pipeline_user,
+    )
+    .await;
+
     let bind_address = format!("{}:{}", config.host, config.port);
     println!("Starting server at http://{}", bind_address);
 
@@ -112,10 +127,53 @@ fn configure_routes(cfg: &mut web::ServiceConfig) {
                 .route("/logout", web::post().to(transports::http::users::logout)),
         )
         // API маршруты для пользователей
-        .service(web::scope("/api/user").route(
-            "/profile",
-            web::get().to(transports::http::users::user_profile),
-        ))
+        .service(
+            web::scope("/api/user")
+                .route(
+                    "/profile",
+                    web::get().to(transports::http::users::user_profile),
+                )
+                .route(
+                    "/keys",
+                    web::get().to(transports::http::signing_keys::list_keys),
+                )
+                .route(
+                    "/keys",
+                    web::post().to(transports::http::signing_keys::add_key),
+                )
+                .route(
+                    "/keys/{id}",
+                    web::delete().to(transports::http::signing_keys::delete_key),
+                )
+                .route(
+                    "/tokens",
+                    web::get().to(transports::http::api_tokens::list_tokens),
+                )
+                .route(
+                    "/tokens",
+                    web::post().to(transports::http::api_tokens::create_token),
+                )
+                .route(
+                    "/tokens/{id}",
+                    web::delete().to(transports::http::api_tokens::delete_token),
+                )
+                .route(
+                    "/dashboard",
+                    web::get().to(transports::http::activity::dashboard),
+                )
+                .route(
+                    "/transfers",
+                    web::get().to(transports::http::projects::list_transfers),
+                )
+                .route(
+                    "/transfers/{project_id}/accept",
+                    web::post().to(transports::http::projects::accept_transfer),
+                )
+                .route(
+                    "/transfers/{project_id}",
+                    web::delete().to(transports::http::projects::decline_transfer),
+                ),
+        )
         // API маршруты для проектов
         .service(
             web::scope("/api/projects")
@@ -128,10 +186,70 @@ fn configure_routes(cfg: &mut web::ServiceConfig) {
                     "/create",
                     web::post().to(transports::http::projects::create_project),
                 )
+                .route(
+                    "/config-schema",
+                    web::get().to(transports::http::projects::get_config_schema),
+                )
+                .route(
+                    "/deleted",
+                    web::get().to(transports::http::projects::list_deleted_projects),
+                )
                 .route(
                     "/{user}/{project}",
                     web::get().to(transports::http::projects::get_project),
                 )
+                .route(
+                    "/{user}/{project}",
+                    web::delete().to(transports::http::projects::delete_project),
+                )
+                .route(
+                    "/{user}/{project}/restore",
+                    web::post().to(transports::http::projects::restore_project),
+                )
+                .route(
+                    "/{user}/{project}/archive",
+                    web::post().to(transports::http::projects::archive_project),
+                )
+                .route(
+                    "/{user}/{project}/unarchive",
+                    web::post().to(transports::http::projects::unarchive_project),
+                )
+                .route(
+                    "/{user}/{project}/transfer",
+                    web::post().to(transports::http::projects::transfer_project),
+                )
+                .route(
+                    "/{user}/{project}/transfer",
+                    web::delete().to(transports::http::projects::cancel_transfer),
+                )
+                .route(
+                    "/{user}/{project}/activity",
+                    web::get().to(transports::http::activity::project_activity),
+                )
+                .route(
+                    "/{user}/{project}/star",
+                    web::put().to(transports::http::watches::star_project),
+                )
+                .route(
+                    "/{user}/{project}/star",
+                    web::delete().to(transports::http::watches::unstar_project),
+                )
+                .route(
+                    "/{user}/{project}/stargazers",
+                    web::get().to(transports::http::watches::list_stargazers),
+                )
+                .route(
+                    "/{user}/{project}/watch",
+                    web::get().to(transports::http::watches::get_watch),
+                )
+                .route(
+                    "/{user}/{project}/watch",
+                    web::put().to(transports::http::watches::set_watch),
+                )
+                .route(
+                    "/{user}/{project}/watch",
+                    web::delete().to(transports::http::watches::reset_watch),
+                )
                 .route(
                     "/{user}/{project}/config",
                     web::get().to(transports::http::projects::get_project_config),
@@ -140,14 +258,82 @@ fn configure_routes(cfg: &mut web::ServiceConfig) {
                     "/{user}/{project}/config",
                     web::put().to(transports::http::projects::update_project_config),
                 )
+                .route(
+                    "/{user}/{project}/config/revisions",
+                    web::get().to(transports::http::projects::list_config_revisions),
+                )
+                .route(
+                    "/{user}/{project}/config/diff",
+                    web::get().to(transports::http::projects::diff_config_revisions),
+                )
+                .route(
+                    "/{user}/{project}/config/revisions/{revision}",
+                    web::get().to(transports::http::projects::get_config_revision),
+                )
+                .route(
+                    "/{user}/{project}/config/revisions/{revision}/rollback",
+                    web::post().to(transports::http::projects::rollback_config),
+                )
                 .route(
                     "/{user}/{project}/repos/create",
                     web::post().to(transports::http::projects::create_repo_in_project),
                 )
+                .route(
+                    "/{user}/{project}/collaborators",
+                    web::get().to(transports::http::collaborators::list_project_collaborators),
+                )
+                .route(
+                    "/{user}/{project}/collaborators/{username}",
+                    web::put().to(transports::http::collaborators::grant_project_collaborator),
+                )
+                .route(
+                    "/{user}/{project}/collaborators/{username}",
+                    web::delete().to(transports::http::collaborators::revoke_project_collaborator),
+                )
+                .route(
+                    "/{user}/{project}/members",
+                    web::get().to(transports::http::members::list_members),
+                )
+                .route(
+                    "/{user}/{project}/members/{username}",
+                    web::put().to(transports::http::members::set_member),
+                )
+                .route(
+                    "/{user}/{project}/members/{username}",
+                    web::delete().to(transports::http::members::remove_member),
+                )
+                .route(
+                    "/{user}/{project}/teams",
+                    web::get().to(transports::http::members::list_project_teams),
+                )
+                .route(
+                    "/{user}/{project}/teams/{owner}/{team}",
+                    web::put().to(transports::http::members::grant_team),
+                )
+                .route(
+                    "/{user}/{project}/teams/{owner}/{team}",
+                    web::delete().to(transports::http::members::revoke_team),
+                )
                 .route(
                     "/{user}/{project}/{repo}",
                     web::get().to(transports::http::repositories::get_repo_in_project),
                 )
+                .route(
+                    "/{user}/{project}/{repo}/collaborators",
+                    web::get().to(transports::http::collaborators::list_repo_collaborators),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/collaborators/{username}",
+                    web::put().to(transports::http::collaborators::grant_repo_collaborator),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/collaborators/{username}",
+                    web::delete().to(transports::http::collaborators::revoke_repo_collaborator),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/template",
+                    web::put().to(transports::http::repositories::update_repo_template),
+                )
                 .route(
                     "/{user}/{project}/{repo}/contents",
                     web::get().to(transports::http::repositories::get_repo_contents),
@@ -156,6 +342,92 @@ fn configure_routes(cfg: &mut web::ServiceConfig) {
                     "/{user}/{project}/{repo}/commits",
                     web::get().to(transports::http::repositories::get_repo_commits),
                 )
+                .route(
+                    "/{user}/{project}/{repo}/commits/graph",
+                    web::get().to(transports::http::repositories::get_commit_graph),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/commits/{sha}",
+                    web::get().to(transports::http::repositories::get_repo_commit),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/commits/{reference}/statuses",
+                    web::get().to(transports::http::statuses::list_statuses),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/commits/{reference}/status",
+                    web::get().to(transports::http::statuses::get_combined_status),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/statuses/{sha}",
+                    web::post().to(transports::http::statuses::create_status),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/pipelines",
+                    web::get().to(transports::http::pipelines::list_pipelines),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/pipelines/{id}",
+                    web::get().to(transports::http::pipelines::get_pipeline),
+                )
+                .service(
+                    web::resource("/{user}/{project}/{repo}/files/{path:.*}")
+                        .app_data(
+                            web::JsonConfig::default()
+                                .limit(transports::http::files::MAX_REQUEST_SIZE),
+                        )
+                        .route(web::get().to(transports::http::files::get_file))
+                        .route(web::put().to(transports::http::files::write_file))
+                        .route(web::delete().to(transports::http::files::delete_file)),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/deploy-keys",
+                    web::get().to(transports::http::deploy_keys::list_deploy_keys),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/deploy-keys",
+                    web::post().to(transports::http::deploy_keys::add_deploy_key),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/deploy-keys/{id}",
+                    web::delete().to(transports::http::deploy_keys::delete_deploy_key),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/traffic",
+                    web::get().to(transports::http::traffic::get_traffic),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/secret-findings",
+                    web::get().to(transports::http::secret_findings::list_secret_findings),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/secret-findings/{id}",
+                    web::put().to(transports::http::secret_findings::update_secret_finding),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/wiki",
+                    web::get().to(transports::http::wiki::list_pages),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/wiki/{page}",
+                    web::get().to(transports::http::wiki::get_page),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/wiki/{page}",
+                    web::put().to(transports::http::wiki::save_page),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/wiki/{page}",
+                    web::delete().to(transports::http::wiki::delete_page),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/wiki/{page}/history",
+                    web::get().to(transports::http::wiki::get_page_history),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/wiki/{page}/revert",
+                    web::post().to(transports::http::wiki::revert_page),
+                )
                 .route(
                     "/{user}/{project}/{repo}/branches",
                     web::get().to(transports::http::repositories::get_repo_branches),
@@ -163,6 +435,134 @@ fn configure_routes(cfg: &mut web::ServiceConfig) {
                 .route(
                     "/{user}/{project}/{repo}/readme",
                     web::get().to(transports::http::repositories::get_repo_readme),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/insights/languages",
+                    web::get().to(transports::http::insights::get_languages),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/insights/contributors",
+                    web::get().to(transports::http::insights::get_contributors),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/insights/activity",
+                    web::get().to(transports::http::insights::get_activity),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/insights/dependencies",
+                    web::get().to(transports::http::insights::get_dependencies),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/insights/dependencies/sbom",
+                    web::get().to(transports::http::insights::export_sbom),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/feeds/commits",
+                    web::get().to(transports::http::feeds::commits_feed),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/feeds/releases",
+                    web::get().to(transports::http::feeds::releases_feed),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/releases",
+                    web::get().to(transports::http::releases::list_releases),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/releases",
+                    web::post().to(transports::http::releases::create_release),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/releases/{tag}",
+                    web::get().to(transports::http::releases::get_release),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/releases/{tag}",
+                    web::put().to(transports::http::releases::update_release),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/releases/{tag}",
+                    web::delete().to(transports::http::releases::delete_release),
+                )
+                .service(
+                    web::resource("/{user}/{project}/{repo}/releases/{tag}/assets")
+                        .app_data(web::PayloadConfig::new(
+                            transports::http::releases::MAX_ASSET_SIZE,
+                        ))
+                        .route(web::post().to(transports::http::releases::upload_asset)),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/releases/{tag}/assets/{name}",
+                    web::get().to(transports::http::releases::download_asset),
+                )
+                .route(
+                    "/{user}/{project}/{repo}/releases/{tag}/assets/{name}",
+                    web::delete().to(transports::http::releases::delete_asset),
+                ),
+        )
+        // API маршруты для поиска по коду
+        .service(
+            web::scope("/api/search")
+                .route("/code", web::get().to(transports::http::search::search_code)),
+        )
+        // API маршруты администрирования
+        .service(web::scope("/api/admin").route(
+            "/maintenance",
+            web::get().to(transports::http::admin::get_maintenance),
+        ))
+        // Публичные страницы пользователей
+        .service(web::scope("/api/users").route(
+            "/{username}/feed",
+            web::get().to(transports::http::feeds::user_feed),
+        ))
+        // API маршруты для организаций
+        .service(
+            web::scope("/api/orgs")
+                .route(
+                    "",
+                    web::get().to(transports::http::organizations::list_organizations),
+                )
+                .route(
+                    "",
+                    web::post().to(transports::http::organizations::create_organization),
+                )
+                .route(
+                    "/{org}",
+                    web::get().to(transports::http::organizations::get_organization),
+                )
+                .route(
+                    "/{org}/members",
+                    web::get().to(transports::http::organizations::list_members),
+                )
+                .route(
+                    "/{org}/members/{username}",
+                    web::put().to(transports::http::organizations::set_member),
+                )
+                .route(
+                    "/{org}/members/{username}",
+                    web::delete().to(transports::http::organizations::remove_member),
+                ),
+        )
+        // API маршруты для команд
+        .service(
+            web::scope("/api/teams")
+                .route("", web::get().to(transports::http::teams::list_teams))
+                .route("", web::post().to(transports::http::teams::create_team))
+                .route(
+                    "/{owner}/{team}",
+                    web::get().to(transports::http::teams::get_team),
+                )
+                .route(
+                    "/{owner}/{team}",
+                    web::delete().to(transports::http::teams::delete_team),
+                )
+                .route(
+                    "/{owner}/{team}/members/{username}",
+                    web::put().to(transports::http::teams::add_team_member),
+                )
+                .route(
+                    "/{owner}/{team}/members/{username}",
+                    web::delete().to(transports::http::teams::remove_team_member),
                 ),
         )
         // API маршруты для уведомлений
@@ -176,6 +576,10 @@ fn configure_routes(cfg: &mut web::ServiceConfig) {
                     "",
                     web::post().to(transports::http::notifications::create_notification),
                 )
+                .route(
+                    "/{id}",
+                    web::get().to(transports::http::notifications::get_notification),
+                )
                 .route(
                     "/{id}",
                     web::put().to(transports::http::notifications::update_notification),
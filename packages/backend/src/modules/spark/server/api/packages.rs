@@ -3,6 +3,11 @@ use sqlx::PgPool;
 use serde::{Deserialize, Serialize};
 
 use crate::core::auth::verify_token;
+use crate::core::database::Database;
+use crate::domain::events::EVENT_PACKAGE_PUBLISHED;
+use crate::domain::organizations::Organization;
+use crate::domain::projects::ProjectConfig;
+use crate::services::activity as activity_service;
 use crate::modules::spark::server::domain::{
     SparkPackage, CreatePackageRequest, CreateVersionRequest, SparkPackageVersion
 };
@@ -126,7 +131,16 @@ pub async fn create_package(
         }
     }
 
-    match SparkPackage::create(&pool, user_id, body.into_inner()).await {
+    // Publishing on behalf of an organization requires owner or admin role
+    let organization_id = match &body.organization {
+        Some(name) => match organization_publisher(&pool, name, user_id).await {
+            Ok(id) => Some(id),
+            Err(response) => return response,
+        },
+        None => None,
+    };
+
+    match SparkPackage::create(&pool, user_id, organization_id, body.into_inner()).await {
         Ok(package) => HttpResponse::Created().json(package),
         Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("Failed to create package: {}", e)
@@ -134,6 +148,37 @@ pub async fn create_package(
     }
 }
 
+// Resolves an organization the user may publish packages for
+async fn organization_publisher(
+    pool: &PgPool,
+    name: &str,
+    user_id: i64,
+) -> Result<i64, HttpResponse> {
+    let org = match Organization::find_by_name(name, pool).await {
+        Ok(Some(org)) => org,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Organization not found"
+            })));
+        }
+        Err(e) => {
+            return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to fetch organization: {}", e)
+            })));
+        }
+    };
+
+    match Organization::member_role(org.id, user_id, pool).await {
+        Ok(Some(role)) if role == "owner" || role == "admin" => Ok(org.id),
+        Ok(_) => Err(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Organization admin access is required to publish packages"
+        }))),
+        Err(e) => Err(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to check organization membership: {}", e)
+        }))),
+    }
+}
+
 // POST /api/spark/packages/:name/versions - Add new version
 pub async fn create_version(
     req: HttpRequest,
@@ -154,7 +199,14 @@ pub async fn create_version(
     // Get package and verify ownership
     let package = match SparkPackage::find_by_name(&pool, &name).await {
         Ok(Some(pkg)) => {
-            if pkg.author_id != user_id {
+            let org_admin = match pkg.organization_id {
+                Some(org_id) => matches!(
+                    Organization::member_role(org_id, user_id, &pool).await,
+                    Ok(Some(role)) if role == "owner" || role == "admin"
+                ),
+                None => false,
+            };
+            if pkg.author_id != user_id && !org_admin {
                 return HttpResponse::Forbidden().json(serde_json::json!({
                     "error": "You don't have permission to add versions to this package"
                 }));
@@ -173,8 +225,43 @@ pub async fn create_version(
         }
     };
 
+    // Packages linked to a project follow the spark section of its configuration
+    if let Some(project_id) = package.project_id {
+        let config = match ProjectConfig::load(project_id, &pool).await {
+            Ok(config) => config,
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to load project configuration: {}", e)
+                }));
+            }
+        };
+        if let Err(reason) = config.spark.check_version(&package.name, &body.version) {
+            return HttpResponse::Forbidden().json(serde_json::json!({ "error": reason }));
+        }
+    }
+
     match SparkPackageVersion::create(&pool, package.id, body.into_inner()).await {
-        Ok(version) => HttpResponse::Created().json(version),
+        Ok(version) => {
+            // Publishing shows up in the activity log of the linked project
+            // and notifies its watchers
+            if let Some(project_id) = package.project_id {
+                let db = Database { pool: pool.get_ref().clone() };
+                let payload = serde_json::json!({
+                    "package": package.name,
+                    "version": version.version
+                });
+                activity_service::record(
+                    project_id,
+                    None,
+                    Some(user_id),
+                    EVENT_PACKAGE_PUBLISHED,
+                    payload,
+                    &db,
+                )
+                .await;
+            }
+            HttpResponse::Created().json(version)
+        }
         Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("Failed to create version: {}", e)
         })),
@@ -10,6 +10,8 @@ pub struct SparkPackage {
     pub description: Option<String>,
     pub author_id: i64,
     pub project_id: Option<i64>,
+    /// Организация-владелец пакета
+    pub organization_id: Option<i64>,
     pub repository: Option<String>,
     pub category: Option<String>,
     pub is_public: bool,
@@ -47,6 +49,9 @@ pub struct CreatePackageRequest {
     pub repository: Option<String>,
     pub category: Option<String>,
     pub is_public: bool,
+    /// Организация, от имени которой публикуется пакет
+    #[serde(default)]
+    pub organization: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,19 +66,21 @@ impl SparkPackage {
     pub async fn create(
         pool: &PgPool,
         author_id: i64,
+        organization_id: Option<i64>,
         req: CreatePackageRequest,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             SparkPackage,
             r#"
-            INSERT INTO spark_packages (name, display_name, description, author_id, repository, category, is_public)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO spark_packages (name, display_name, description, author_id, organization_id, repository, category, is_public)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             RETURNING *
             "#,
             req.name,
             req.display_name,
             req.description,
             author_id,
+            organization_id,
             req.repository,
             req.category,
             req.is_public
@@ -0,0 +1,203 @@
+//! Журнал событий проектов и лента активности
+//!
+//! События записываются попутно с основным действием: сбой записи только
+//! логируется и не отменяет действие. При чтении события репозиториев, которые
+//! зрителю недоступны, отфильтровываются в запросе, поэтому страницы не
+//! «проседают». Лента пользователя собирает события проектов, за которыми он
+//! следит (см. `services::watches`); подписчики получают и уведомления.
+
+use log::error;
+use serde_json::{json, Value};
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::events::{ProjectEvent, EVENT_PUSH};
+use crate::domain::projects::Project;
+use crate::domain::repos::Repository;
+use crate::domain::users::User;
+use crate::domain::watches::{WATCH_ALL, WATCH_RELEASES};
+use crate::services::permissions::{self as permission_service, Permission};
+use crate::services::watches as watch_service;
+use crate::utils::git::{self, RefUpdate};
+
+// ============================================================================
+// ЗАПИСЬ
+// ============================================================================
+
+/// Записывает событие проекта и в фоне уведомляет подписчиков; ошибка
+/// только логируется
+pub async fn record(
+    project_id: i64,
+    repository_id: Option<i64>,
+    actor_id: Option<i64>,
+    kind: &str,
+    payload: Value,
+    db: &Database,
+) {
+    match ProjectEvent::create(
+        project_id,
+        repository_id,
+        actor_id,
+        kind,
+        &payload,
+        db.get_pool(),
+    )
+    .await
+    {
+        Ok(event_id) => {
+            let db = db.clone();
+            tokio::spawn(async move { watch_service::notify_watchers(event_id, &db).await });
+        }
+        Err(e) => error!(
+            "Failed to record {} event for project {}: {}",
+            kind, project_id, e
+        ),
+    }
+}
+
+/// Записывает push в репозиторий `repo_name` с обновлёнными ссылками
+pub async fn record_push(
+    repo_name: &str,
+    updates: &[RefUpdate],
+    pusher_id: Option<i64>,
+    db: &Database,
+) {
+    if updates.is_empty() {
+        return;
+    }
+    let repo = match Repository::find_by_name(repo_name, db.get_pool()).await {
+        Ok(Some(repo)) => repo,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to find repository {}: {}", repo_name, e);
+            return;
+        }
+    };
+
+    // Удалённая ссылка записывается с `sha: null`
+    let refs: Vec<Value> = updates
+        .iter()
+        .map(|update| {
+            let sha = (update.new_sha != git::ZERO_SHA).then_some(&update.new_sha);
+            json!({"ref": update.ref_name, "sha": sha})
+        })
+        .collect();
+    record(
+        repo.project_id,
+        repo.id,
+        pusher_id,
+        EVENT_PUSH,
+        json!({"refs": refs}),
+        db,
+    )
+    .await;
+}
+
+// ============================================================================
+// ЧТЕНИЕ
+// ============================================================================
+
+/// События проекта, новые первыми
+pub async fn project_activity(
+    project: &Project,
+    viewer: Option<&User>,
+    page: i64,
+    per_page: i64,
+    db: &Database,
+) -> Result<Vec<ProjectEvent>, AppError> {
+    let project_id = project
+        .id
+        .ok_or_else(|| internal_error("Project must have id"))?;
+    let repository_ids = visible_repositories(project, viewer, db).await?;
+
+    ProjectEvent::find_for_projects(
+        &[project_id],
+        &[],
+        &repository_ids,
+        per_page,
+        (page - 1) * per_page,
+        db.get_pool(),
+    )
+    .await
+    .map_err(database_error)
+}
+
+/// Лента пользователя: события проектов, за которыми он следит
+///
+/// Из проектов с подпиской `releases` в ленту попадают только релизы.
+pub async fn dashboard(
+    user: &User,
+    page: i64,
+    per_page: i64,
+    db: &Database,
+) -> Result<Vec<ProjectEvent>, AppError> {
+    let mut project_ids = Vec::new();
+    let mut release_project_ids = Vec::new();
+    let mut repository_ids = Vec::new();
+    for watched in watch_service::watched_projects(user, db).await? {
+        let project = &watched.project;
+        let permission = permission_service::project_permission(project, Some(user), db)
+            .await
+            .map_err(database_error)?;
+        let Some(project_id) = project.id.filter(|_| permission >= Permission::Read) else {
+            continue;
+        };
+        match watched.level.as_str() {
+            WATCH_ALL => project_ids.push(project_id),
+            WATCH_RELEASES => release_project_ids.push(project_id),
+            _ => continue,
+        }
+        repository_ids.extend(visible_repositories(project, Some(user), db).await?);
+    }
+    if project_ids.is_empty() && release_project_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    ProjectEvent::find_for_projects(
+        &project_ids,
+        &release_project_ids,
+        &repository_ids,
+        per_page,
+        (page - 1) * per_page,
+        db.get_pool(),
+    )
+    .await
+    .map_err(database_error)
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Репозитории проекта, которые зритель может читать
+async fn visible_repositories(
+    project: &Project,
+    viewer: Option<&User>,
+    db: &Database,
+) -> Result<Vec<i64>, AppError> {
+    let Some(project_id) = project.id else {
+        return Ok(Vec::new());
+    };
+    let repositories = Repository::find_by_project(project_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+
+    let mut visible = Vec::new();
+    for repo in repositories {
+        let permission = permission_service::repository_permission(project, &repo, viewer, db)
+            .await
+            .map_err(database_error)?;
+        if let Some(id) = repo.id.filter(|_| permission >= Permission::Read) {
+            visible.push(id);
+        }
+    }
+    Ok(visible)
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
@@ -10,10 +10,17 @@ use serde::Serialize;
 use sha2::{Digest, Sha256};
 
 use crate::core::{database::Database, security::jwt::Claims};
-use crate::domain::{auth::RefreshToken, users::User};
+use crate::domain::{
+    auth::{ApiToken, RefreshToken},
+    users::User,
+};
 
 const ACCESS_TOKEN_TTL_HOURS: i64 = 1;
 const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+/// Префикс персональных API токенов (отличает их от JWT)
+pub const API_TOKEN_PREFIX: &str = "nbt_";
+/// Префикс токенов ключей развёртывания (доступ к одному репозиторию)
+pub const DEPLOY_TOKEN_PREFIX: &str = "nbd_";
 
 #[derive(Serialize, Clone)]
 pub struct TokenPair {
@@ -29,7 +36,21 @@ pub struct AuthResponse {
     pub user: User,
 }
 
+/// Созданный API токен; значение токена показывается только один раз
+#[derive(Serialize)]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    pub info: ApiToken,
+    pub token: String,
+}
+
 pub async fn authenticate_request(req: &HttpRequest, db: &Database) -> Option<User> {
+    if let Some(token) = extract_token_from_header(req) {
+        if token.starts_with(API_TOKEN_PREFIX) {
+            return verify_api_token(&token, db).await;
+        }
+    }
+
     if let Some(user) = verify_jwt_token(req, db).await {
         return Some(user);
     }
@@ -57,7 +78,7 @@ pub async fn issue_tokens(
     })?;
 
     let refresh_raw = generate_refresh_token();
-    let refresh_hash = hash_refresh_token(&refresh_raw);
+    let refresh_hash = hash_token(&refresh_raw);
     let now = Utc::now();
     let refresh = RefreshToken {
         id: uuid::Uuid::new_v4(),
@@ -90,7 +111,7 @@ pub async fn rotate_refresh_token(
     ip: Option<String>,
 ) -> Result<(User, TokenPair), String> {
     let pool = db.get_pool();
-    let token_hash = hash_refresh_token(refresh_token);
+    let token_hash = hash_token(refresh_token);
 
     let stored = RefreshToken::find_active_by_hash(&token_hash, pool)
         .await
@@ -129,7 +150,7 @@ pub async fn revoke_all_sessions(user_id: i64, db: &Database) -> Result<(), Stri
 
 pub async fn revoke_single_session(refresh_token: &str, db: &Database) -> Result<(), String> {
     let pool = db.get_pool();
-    let token_hash = hash_refresh_token(refresh_token);
+    let token_hash = hash_token(refresh_token);
 
     if let Some(stored) = RefreshToken::find_active_by_hash(&token_hash, pool)
         .await
@@ -184,18 +205,113 @@ pub async fn check_basic_auth(req: &HttpRequest, db: &Database) -> Option<User>
     let username = parts.next()?;
     let password = parts.next()?;
 
+    // Токен ключа развёртывания не принадлежит пользователю
+    if password.starts_with(DEPLOY_TOKEN_PREFIX) {
+        return None;
+    }
+
+    // Git клиенты передают API токен вместо пароля
+    if password.starts_with(API_TOKEN_PREFIX) {
+        return verify_api_token(password, db)
+            .await
+            .filter(|user| user.username == username);
+    }
+
     User::authenticate(username, password, db.get_pool())
         .await
         .ok()
         .flatten()
 }
 
+/// Токен ключа развёртывания из запроса: `Bearer` или пароль Basic авторизации
+///
+/// Имя пользователя в Basic авторизации не проверяется, git клиенту подойдёт любое.
+pub fn extract_deploy_token(req: &HttpRequest) -> Option<String> {
+    let auth_str = req.headers().get("Authorization")?.to_str().ok()?;
+
+    let token = match auth_str.strip_prefix("Basic ") {
+        Some(encoded) => {
+            let decoded = general_purpose::STANDARD.decode(encoded).ok()?;
+            let decoded = String::from_utf8(decoded).ok()?;
+            decoded.split_once(':')?.1.to_string()
+        }
+        None => auth_str.strip_prefix("Bearer ")?.to_string(),
+    };
+
+    token.starts_with(DEPLOY_TOKEN_PREFIX).then_some(token)
+}
+
+/// Генерирует токен ключа развёртывания и его хэш для хранения
+pub fn generate_deploy_token() -> (String, String) {
+    let raw = format!("{}{}", DEPLOY_TOKEN_PREFIX, generate_api_token());
+    let hash = hash_token(&raw);
+    (raw, hash)
+}
+
+/// Создаёт персональный API токен пользователя
+pub async fn create_api_token(
+    user_id: i64,
+    name: &str,
+    expires_in_days: Option<i64>,
+    db: &Database,
+) -> Result<CreatedApiToken, String> {
+    let raw = format!("{}{}", API_TOKEN_PREFIX, generate_api_token());
+    let token = ApiToken {
+        id: None,
+        user_id,
+        name: name.to_string(),
+        token_hash: hash_token(&raw),
+        token_prefix: raw[..API_TOKEN_PREFIX.len() + 6].to_string(),
+        expires_at: expires_in_days.map(|days| Utc::now() + Duration::days(days)),
+        last_used_at: None,
+        created_at: None,
+    };
+
+    let pool = db.get_pool();
+    let id = token.create(pool).await.map_err(|e| {
+        error!("Failed to persist API token: {}", e);
+        "Failed to create token".to_string()
+    })?;
+
+    let info = ApiToken::find_by_id(id, user_id, pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch API token: {}", e);
+            "Failed to create token".to_string()
+        })?
+        .ok_or_else(|| "Failed to create token".to_string())?;
+
+    Ok(CreatedApiToken { info, token: raw })
+}
+
+/// Проверяет API токен и возвращает его владельца
+pub async fn verify_api_token(token: &str, db: &Database) -> Option<User> {
+    let pool = db.get_pool();
+    let stored = ApiToken::find_active_by_hash(&hash_token(token), pool)
+        .await
+        .ok()
+        .flatten()?;
+
+    if let Some(id) = stored.id {
+        if let Err(e) = ApiToken::touch(id, pool).await {
+            error!("Failed to update API token usage: {}", e);
+        }
+    }
+
+    User::find_by_id(stored.user_id, pool).await.ok().flatten()
+}
+
 fn generate_refresh_token() -> String {
     let mut rng = rng();
     Alphanumeric.sample_string(&mut rng, 64)
 }
 
-fn hash_refresh_token(token: &str) -> String {
+fn generate_api_token() -> String {
+    let mut rng = rng();
+    Alphanumeric.sample_string(&mut rng, 40)
+}
+
+pub fn hash_token(token: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(token.as_bytes());
     format!("{:x}", hasher.finalize())
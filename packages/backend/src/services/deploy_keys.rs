@@ -0,0 +1,155 @@
+//! Ключи развёртывания: доступ машин к одному репозиторию без учётной записи
+//!
+//! Ключ — токен `nbd_...`, который git клиент передаёт паролем Basic
+//! авторизации (имя пользователя любое). SSH ключи не принимаются: SSH
+//! транспорта у сервера нет. Ключ даёт только чтение либо чтение и push;
+//! в вики репозитория ключ только читает.
+
+use log::error;
+use serde::Serialize;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::deploy_keys::{DeployKey, NewDeployKey, KEY_TYPE_HTTP};
+use crate::domain::repos::Repository;
+use crate::services::auth as auth_service;
+
+const MAX_TITLE_LENGTH: usize = 255;
+/// Длина видимого начала HTTP токена
+const TOKEN_PREFIX_LENGTH: usize = auth_service::DEPLOY_TOKEN_PREFIX.len() + 6;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Параметры нового ключа
+#[derive(Debug)]
+pub struct NewKeyInput {
+    pub title: String,
+    /// Публичный SSH ключ; не поддерживается, запрос с ним отклоняется
+    pub public_key: Option<String>,
+    pub read_only: bool,
+}
+
+/// Созданный ключ; токен показывается только один раз
+#[derive(Serialize)]
+pub struct CreatedDeployKey {
+    #[serde(flatten)]
+    pub key: DeployKey,
+    pub token: Option<String>,
+}
+
+// ============================================================================
+// УПРАВЛЕНИЕ КЛЮЧАМИ
+// ============================================================================
+
+pub async fn list_keys(repo: &Repository, db: &Database) -> Result<Vec<DeployKey>, AppError> {
+    DeployKey::find_by_repository(repository_id(repo)?, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Добавляет ключ развёртывания в репозиторий
+pub async fn add_key(
+    repo: &Repository,
+    input: NewKeyInput,
+    creator_id: Option<i64>,
+    db: &Database,
+) -> Result<CreatedDeployKey, AppError> {
+    let repository_id = repository_id(repo)?;
+    let title = input.title.trim().to_string();
+    if title.is_empty() || title.len() > MAX_TITLE_LENGTH {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Title is required and must be at most 255 characters",
+        ));
+    }
+
+    // SSH транспорта у сервера нет, ключ с публичным ключом был бы бесполезен
+    if input.public_key.is_some() {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "SSH deploy keys are not supported, omit public_key to get an HTTP token",
+        ));
+    }
+
+    let pool = db.get_pool();
+    let (token, token_hash) = auth_service::generate_deploy_token();
+    let new_key = NewDeployKey {
+        repository_id,
+        title,
+        key_type: KEY_TYPE_HTTP,
+        public_key: None,
+        fingerprint: None,
+        token_hash: Some(token_hash),
+        token_prefix: Some(token[..TOKEN_PREFIX_LENGTH].to_string()),
+        read_only: input.read_only,
+        creator_id,
+    };
+
+    let key = DeployKey::create(&new_key, pool)
+        .await
+        .map_err(database_error)?;
+    Ok(CreatedDeployKey {
+        key,
+        token: Some(token),
+    })
+}
+
+pub async fn delete_key(repo: &Repository, id: i64, db: &Database) -> Result<(), AppError> {
+    let deleted = DeployKey::delete(id, repository_id(repo)?, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    if deleted {
+        Ok(())
+    } else {
+        Err(AppError::new(
+            ErrorType::NotFoundError,
+            "Deploy key not found",
+        ))
+    }
+}
+
+// ============================================================================
+// АУТЕНТИФИКАЦИЯ
+// ============================================================================
+
+/// Находит HTTP ключ репозитория по токену и отмечает его использование
+pub async fn authenticate(
+    repo: &Repository,
+    token: &str,
+    db: &Database,
+) -> Result<Option<DeployKey>, sqlx::Error> {
+    let Some(repository_id) = repo.id else {
+        return Ok(None);
+    };
+
+    let pool = db.get_pool();
+    let key = DeployKey::find_by_token_hash(repository_id, &auth_service::hash_token(token), pool)
+        .await?;
+
+    if let Some(key) = &key {
+        if let Err(e) = DeployKey::touch(key.id, pool).await {
+            error!("Failed to update deploy key usage: {}", e);
+        }
+    }
+
+    Ok(key)
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn repository_id(repo: &Repository) -> Result<i64, AppError> {
+    repo.id
+        .ok_or_else(|| internal_error("Repository must have id"))
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
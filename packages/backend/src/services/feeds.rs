@@ -0,0 +1,364 @@
+//! Ленты Atom/RSS: коммиты ветки, релизы и теги репозитория, активность
+//! пользователя
+//!
+//! Ленты репозитория отдаются тем, кто может его читать. В ленте
+//! пользователя остаются только проекты и репозитории, доступные читателю,
+//! так что анонимный подписчик видит лишь публичную активность.
+
+use chrono::{DateTime, TimeZone, Utc};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::projects::Project;
+use crate::domain::releases::Release;
+use crate::domain::repos::Repository;
+use crate::domain::users::User;
+use crate::services::permissions::{self as permission_service, Permission};
+use crate::utils::feed::{Feed, FeedEntry};
+use crate::utils::git;
+
+/// Сколько записей попадает в ленту
+const FEED_SIZE: usize = 30;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Адреса, из которых строятся ссылки ленты
+pub struct FeedLinks<'a> {
+    /// Адрес запрошенной ленты
+    pub url: &'a str,
+    /// Схема и хост сервера, например `https://netbit.example`
+    pub base_url: &'a str,
+}
+
+// ============================================================================
+// ЛЕНТЫ РЕПОЗИТОРИЯ
+// ============================================================================
+
+/// Последние коммиты ветки (по умолчанию — ветки по умолчанию)
+pub async fn commits_feed(
+    owner: &str,
+    project: &Project,
+    repo: &Repository,
+    branch: Option<String>,
+    links: &FeedLinks<'_>,
+) -> Result<Feed, AppError> {
+    let repo_path = git::repository_path(&repo.name);
+    let (branch, commits) = tokio::task::spawn_blocking(move || {
+        let branch = match branch {
+            Some(branch) => branch,
+            None => match git::resolve_default_head(&repo_path) {
+                Some((branch, _)) => branch,
+                // Пустой репозиторий даёт пустую ленту
+                None => return Ok((String::new(), Vec::new())),
+            },
+        };
+        let sha = git::resolve_revision(&repo_path, &branch)
+            .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Branch not found"))?;
+        let commits = git::get_commits(&repo_path, &sha, Some(FEED_SIZE), 0)
+            .map_err(|e| internal_error(&e))?;
+        Ok((branch, commits))
+    })
+    .await
+    .map_err(|e| internal_error(&format!("Feed task failed: {}", e)))??;
+
+    let repo_url = repository_url(links, owner, project, repo);
+    let entries = commits
+        .into_iter()
+        .map(|commit| {
+            let link = format!("{}/commits/{}", repo_url, commit.sha);
+            FeedEntry {
+                id: link.clone(),
+                title: commit.subject,
+                link,
+                updated: timestamp(commit.committed_at),
+                author: Some(commit.author_name),
+                summary: Some(format!("Commit {}", commit.sha)),
+            }
+        })
+        .collect();
+
+    let full_name = format!("{}/{}/{}", owner, project.name, repo.name);
+    Ok(Feed {
+        url: links.url.to_string(),
+        title: format!("{}: commits on {}", full_name, branch),
+        link: format!("{}/commits?branch={}", repo_url, branch),
+        description: format!("Recent commits to {} on branch {}", full_name, branch),
+        author: owner.to_string(),
+        entries,
+    })
+}
+
+/// Опубликованные релизы и теги без релиза
+pub async fn releases_feed(
+    owner: &str,
+    project: &Project,
+    repo: &Repository,
+    links: &FeedLinks<'_>,
+    db: &Database,
+) -> Result<Feed, AppError> {
+    let pool = db.get_pool();
+    let releases = Release::find_by_repository(repo.id.unwrap(), false, pool)
+        .await
+        .map_err(database_error)?;
+
+    let repo_path = git::repository_path(&repo.name);
+    let tags = tokio::task::spawn_blocking(move || git::list_tags(&repo_path))
+        .await
+        .map_err(|e| internal_error(&format!("Feed task failed: {}", e)))?
+        .map_err(|e| internal_error(&e))?;
+
+    let repo_url = repository_url(links, owner, project, repo);
+    let mut authors = UsernameCache::default();
+    let mut entries = Vec::new();
+    for release in &releases {
+        let link = format!("{}/releases/{}", repo_url, release.tag_name);
+        let prerelease = if release.is_prerelease {
+            " (pre-release)"
+        } else {
+            ""
+        };
+        entries.push(FeedEntry {
+            id: link.clone(),
+            title: format!("{}{}", release.title, prerelease),
+            link,
+            updated: release
+                .published_at
+                .or(release.created_at)
+                .unwrap_or_else(Utc::now),
+            author: authors.get(release.author_id, db).await?,
+            summary: release.notes.clone(),
+        });
+    }
+
+    let released: Vec<&str> = releases.iter().map(|r| r.tag_name.as_str()).collect();
+    for tag in tags
+        .into_iter()
+        .filter(|tag| !released.contains(&tag.name.as_str()))
+    {
+        let link = format!("{}/commits/{}", repo_url, tag.target_sha);
+        entries.push(FeedEntry {
+            id: format!("{}/tags/{}", repo_url, tag.name),
+            title: tag.name,
+            link,
+            updated: timestamp(tag.created_at),
+            author: None,
+            summary: (!tag.subject.is_empty()).then_some(tag.subject),
+        });
+    }
+    entries.sort_by_key(|entry| Reverse(entry.updated));
+    entries.truncate(FEED_SIZE);
+
+    let full_name = format!("{}/{}/{}", owner, project.name, repo.name);
+    Ok(Feed {
+        url: links.url.to_string(),
+        title: format!("{}: releases and tags", full_name),
+        link: format!("{}/releases", repo_url),
+        description: format!("Releases and tags of {}", full_name),
+        author: owner.to_string(),
+        entries,
+    })
+}
+
+// ============================================================================
+// ЛЕНТА ПОЛЬЗОВАТЕЛЯ
+// ============================================================================
+
+/// Созданные проекты и репозитории и опубликованные релизы пользователя
+pub async fn user_feed(
+    user: &User,
+    viewer: Option<&User>,
+    links: &FeedLinks<'_>,
+    db: &Database,
+) -> Result<Feed, AppError> {
+    let pool = db.get_pool();
+    let user_id = user.id.unwrap();
+    let mut entries = Vec::new();
+
+    // Проекты пользователя заодно служат кэшем для его репозиториев
+    let mut projects: HashMap<i64, Option<Project>> = HashMap::new();
+    for project in Project::find_by_owner(user_id, pool)
+        .await
+        .map_err(database_error)?
+    {
+        let permission = permission_service::project_permission(&project, viewer, db)
+            .await
+            .map_err(database_error)?;
+        let project_id = project.id.unwrap();
+        if permission >= Permission::Read {
+            // Проект организации живёт в её пространстве имён
+            let namespace = project
+                .namespace(pool)
+                .await
+                .map_err(database_error)?
+                .unwrap_or_else(|| user.username.clone());
+            let link = format!(
+                "{}/api/projects/{}/{}",
+                links.base_url, namespace, project.name
+            );
+            entries.push(FeedEntry {
+                id: link.clone(),
+                title: format!("{} created project {}", user.username, project.name),
+                link,
+                updated: project.created_at.unwrap_or_else(Utc::now),
+                author: None,
+                summary: project.description.clone(),
+            });
+        }
+        projects.insert(project_id, Some(project));
+    }
+
+    for repo in Repository::find_by_owner(user_id, pool)
+        .await
+        .map_err(database_error)?
+    {
+        let Some((owner, project)) = visible_project(&repo, viewer, &mut projects, db).await?
+        else {
+            continue;
+        };
+        let link = repository_url(links, &owner, &project, &repo);
+        entries.push(FeedEntry {
+            id: link.clone(),
+            title: format!(
+                "{} created repository {}/{}",
+                user.username, project.name, repo.name
+            ),
+            link,
+            updated: repo.created_at.unwrap_or_else(Utc::now),
+            author: None,
+            summary: repo.description.clone(),
+        });
+    }
+
+    for release in Release::find_published_by_author(user_id, FEED_SIZE as i64, pool)
+        .await
+        .map_err(database_error)?
+    {
+        let Some(repo) = Repository::find_by_id(release.repository_id, pool)
+            .await
+            .map_err(database_error)?
+        else {
+            continue;
+        };
+        let Some((owner, project)) = visible_project(&repo, viewer, &mut projects, db).await?
+        else {
+            continue;
+        };
+        let link = format!(
+            "{}/releases/{}",
+            repository_url(links, &owner, &project, &repo),
+            release.tag_name
+        );
+        entries.push(FeedEntry {
+            id: link.clone(),
+            title: format!(
+                "{} published {} in {}/{}",
+                user.username, release.title, project.name, repo.name
+            ),
+            link,
+            updated: release
+                .published_at
+                .or(release.created_at)
+                .unwrap_or_else(Utc::now),
+            author: None,
+            summary: release.notes,
+        });
+    }
+
+    entries.sort_by_key(|entry| Reverse(entry.updated));
+    entries.truncate(FEED_SIZE);
+
+    Ok(Feed {
+        url: links.url.to_string(),
+        title: format!("{}: public activity", user.username),
+        link: format!("{}/api/users/{}/feed", links.base_url, user.username),
+        description: format!("Recent activity of {}", user.username),
+        author: user.username.clone(),
+        entries,
+    })
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Имена авторов, загруженные по мере надобности
+#[derive(Default)]
+struct UsernameCache(HashMap<i64, Option<String>>);
+
+impl UsernameCache {
+    async fn get(&mut self, user_id: i64, db: &Database) -> Result<Option<String>, AppError> {
+        if let Some(name) = self.0.get(&user_id) {
+            return Ok(name.clone());
+        }
+        let name = User::find_by_id(user_id, db.get_pool())
+            .await
+            .map_err(database_error)?
+            .map(|user| user.username);
+        self.0.insert(user_id, name.clone());
+        Ok(name)
+    }
+}
+
+/// Проект репозитория и его пространство имён, если читатель видит репозиторий
+async fn visible_project(
+    repo: &Repository,
+    viewer: Option<&User>,
+    projects: &mut HashMap<i64, Option<Project>>,
+    db: &Database,
+) -> Result<Option<(String, Project)>, AppError> {
+    let pool = db.get_pool();
+    let project = match projects.get(&repo.project_id) {
+        Some(project) => project.clone(),
+        None => {
+            let project = Project::find_by_id(repo.project_id, pool)
+                .await
+                .map_err(database_error)?
+                .filter(|project| !project.is_deleted());
+            projects.insert(repo.project_id, project.clone());
+            project
+        }
+    };
+    let Some(project) = project else {
+        return Ok(None);
+    };
+
+    let permission = permission_service::repository_permission(&project, repo, viewer, db)
+        .await
+        .map_err(database_error)?;
+    if permission < Permission::Read {
+        return Ok(None);
+    }
+
+    let owner = project.namespace(pool).await.map_err(database_error)?;
+    Ok(owner.map(|owner| (owner, project)))
+}
+
+fn repository_url(
+    links: &FeedLinks<'_>,
+    owner: &str,
+    project: &Project,
+    repo: &Repository,
+) -> String {
+    format!(
+        "{}/api/projects/{}/{}/{}",
+        links.base_url, owner, project.name, repo.name
+    )
+}
+
+fn timestamp(seconds: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(seconds, 0)
+        .single()
+        .unwrap_or_else(Utc::now)
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
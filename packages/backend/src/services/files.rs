@@ -0,0 +1,560 @@
+//! Редактирование файлов через API: каждое изменение — новый коммит в ветке
+//!
+//! Коммит собирается во временном индексе, ветка переводится на него через
+//! `update-ref` с проверкой старого значения. Конкурентные изменения файла
+//! отсекаются сравнением SHA блоба, который видел клиент.
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::repos::Repository;
+use crate::domain::users::User;
+use crate::utils::git::{self, CommitError, FileChange, TreeEntry};
+use crate::utils::highlight;
+use crate::utils::languages;
+
+/// Режим обычного файла для новых файлов
+const REGULAR_FILE_MODE: &str = "100644";
+/// Ветка для первого коммита, если у пустого репозитория нет HEAD
+const DEFAULT_BRANCH: &str = "main";
+/// Файлы крупнее отдаются в режиме отрисовки без разметки
+const MAX_RENDER_SIZE: usize = 5 * 1024 * 1024;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Изменение одного файла
+#[derive(Debug)]
+pub enum FileOperation {
+    /// Создание (`expected_sha = None`) или обновление файла
+    ///
+    /// С `from_path` файл переносится; без `content` сохраняется прежнее содержимое.
+    Write {
+        path: String,
+        content: Option<Vec<u8>>,
+        expected_sha: Option<String>,
+        from_path: Option<String>,
+    },
+    Delete {
+        path: String,
+        expected_sha: String,
+    },
+}
+
+/// Куда и с каким сообщением записать коммит
+#[derive(Debug)]
+pub struct CommitOptions {
+    /// Ветка-основа (по умолчанию — ветка по умолчанию)
+    pub branch: Option<String>,
+    /// Новая ветка для изменения; создаётся от `branch`
+    pub new_branch: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Результат изменения файла
+#[derive(Debug, Serialize)]
+pub struct FileCommit {
+    pub commit_sha: String,
+    pub branch: String,
+    pub path: String,
+    /// SHA блоба файла после изменения (`None` после удаления)
+    pub content_sha: Option<String>,
+}
+
+/// Файл ревизии
+#[derive(Debug)]
+pub struct FileBlob {
+    pub path: String,
+    pub sha: String,
+    pub content: Vec<u8>,
+}
+
+/// Содержимое файла как есть
+#[derive(Debug, Serialize)]
+pub struct FileContent {
+    pub path: String,
+    pub sha: String,
+    pub size: usize,
+    /// `utf-8` для текста, `base64` для бинарных файлов
+    pub encoding: String,
+    pub content: String,
+}
+
+/// Файл, подготовленный к показу
+#[derive(Debug, Serialize)]
+pub struct RenderedFile {
+    pub path: String,
+    pub sha: String,
+    pub size: usize,
+    /// code, image или binary
+    pub kind: String,
+    pub language: Option<String>,
+    pub lines: Option<usize>,
+    /// Подсвеченный код с якорями строк или превью изображения;
+    /// `None` для прочих бинарных и слишком больших файлов
+    pub html: Option<String>,
+}
+
+impl FileBlob {
+    pub fn into_content(self) -> FileContent {
+        let size = self.content.len();
+        let (encoding, content) = match String::from_utf8(self.content) {
+            Ok(text) if !languages::is_binary(text.as_bytes()) => ("utf-8", text),
+            Ok(text) => ("base64", general_purpose::STANDARD.encode(text)),
+            Err(e) => ("base64", general_purpose::STANDARD.encode(e.into_bytes())),
+        };
+        FileContent {
+            path: self.path,
+            sha: self.sha,
+            size,
+            encoding: encoding.to_string(),
+            content,
+        }
+    }
+}
+
+// ============================================================================
+// ЧТЕНИЕ ФАЙЛОВ
+// ============================================================================
+
+/// Читает файл из ревизии (по умолчанию — из ветки по умолчанию)
+pub async fn read_file(
+    repo: &Repository,
+    path: &str,
+    revision: Option<String>,
+) -> Result<FileBlob, AppError> {
+    let path = normalize_path(path)?;
+    let repo_path = git::repository_path(&repo.name);
+
+    tokio::task::spawn_blocking(move || {
+        let revision = match revision {
+            Some(revision) => git::resolve_revision(&repo_path, &revision)
+                .ok_or_else(|| not_found("Revision not found"))?,
+            None => {
+                git::resolve_default_head(&repo_path)
+                    .ok_or_else(|| not_found("Repository is empty"))?
+                    .1
+            }
+        };
+
+        let entry = git::tree_entry(&repo_path, &revision, &path)
+            .map_err(|e| internal_error(&e))?
+            .ok_or_else(|| not_found("File not found"))?;
+        if entry.object_type != "blob" {
+            return Err(validation_error("Path is not a file"));
+        }
+
+        let content = read_blob(&repo_path, &entry.hash)?;
+        Ok(FileBlob {
+            path,
+            sha: entry.hash,
+            content,
+        })
+    })
+    .await
+    .map_err(|e| internal_error(&format!("File task failed: {}", e)))?
+}
+
+/// Размечает файл: подсветка кода по расширению и shebang, превью изображений
+pub async fn render_file(blob: FileBlob) -> Result<RenderedFile, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let size = blob.content.len();
+        let mut rendered = RenderedFile {
+            path: blob.path,
+            sha: blob.sha,
+            size,
+            kind: "binary".to_string(),
+            language: None,
+            lines: None,
+            html: None,
+        };
+
+        let text = std::str::from_utf8(&blob.content)
+            .ok()
+            .filter(|text| !languages::is_binary(text.as_bytes()));
+        match text {
+            Some(text) => {
+                rendered.kind = "code".to_string();
+                if size <= MAX_RENDER_SIZE {
+                    let code = highlight::highlight(&rendered.path, text);
+                    rendered.language = Some(code.language);
+                    rendered.lines = Some(code.lines);
+                    rendered.html = Some(code.html);
+                }
+            }
+            None => {
+                if let Some(mime) = highlight::image_mime_type(&blob.content) {
+                    rendered.kind = "image".to_string();
+                    if size <= MAX_RENDER_SIZE {
+                        rendered.html = Some(highlight::image_preview(
+                            &rendered.path,
+                            mime,
+                            &blob.content,
+                        ));
+                    }
+                }
+            }
+        }
+        rendered
+    })
+    .await
+    .map_err(|e| internal_error(&format!("Render task failed: {}", e)))
+}
+
+// ============================================================================
+// ИЗМЕНЕНИЕ ФАЙЛОВ
+// ============================================================================
+
+/// Применяет изменение файла новым коммитом от имени `author`
+pub async fn commit_file(
+    repo: &Repository,
+    operation: FileOperation,
+    options: CommitOptions,
+    author: &User,
+) -> Result<FileCommit, AppError> {
+    commit_file_at(git::repository_path(&repo.name), operation, options, author).await
+}
+
+/// То же, что `commit_file`, для bare-репозитория по пути (например, вики)
+pub async fn commit_file_at(
+    repo_path: String,
+    operation: FileOperation,
+    options: CommitOptions,
+    author: &User,
+) -> Result<FileCommit, AppError> {
+    let author_name = author.username.clone();
+    let author_email = author
+        .email
+        .clone()
+        .unwrap_or_else(|| format!("{}@users.noreply.netbit", author.username));
+
+    tokio::task::spawn_blocking(move || {
+        apply_operation(&repo_path, operation, options, &author_name, &author_email)
+    })
+    .await
+    .map_err(|e| internal_error(&format!("File task failed: {}", e)))?
+}
+
+/// Ветка, в которую попадёт коммит с такими параметрами
+pub async fn target_branch(repo: &Repository, options: &CommitOptions) -> Result<String, AppError> {
+    if let Some(new_branch) = &options.new_branch {
+        return Ok(new_branch.clone());
+    }
+    let repo_path = git::repository_path(&repo.name);
+    let branch = options.branch.clone();
+    tokio::task::spawn_blocking(move || base_branch(git::open_reader(&repo_path).as_ref(), branch))
+        .await
+        .map_err(|e| internal_error(&format!("File task failed: {}", e)))
+}
+
+/// Ветка, от которой строится коммит (по умолчанию — ветка по умолчанию)
+fn base_branch(reader: &dyn git::GitReader, branch: Option<String>) -> String {
+    branch
+        .or_else(|| reader.default_branch())
+        .unwrap_or_else(|| DEFAULT_BRANCH.to_string())
+}
+
+fn apply_operation(
+    repo_path: &str,
+    operation: FileOperation,
+    options: CommitOptions,
+    author_name: &str,
+    author_email: &str,
+) -> Result<FileCommit, AppError> {
+    let reader = git::open_reader(repo_path);
+    let branch = base_branch(reader.as_ref(), options.branch);
+    if !git::is_valid_ref_name("heads", &branch) {
+        return Err(validation_error("Invalid branch name"));
+    }
+
+    let base = reader.resolve_revision(&format!("refs/heads/{}", branch));
+    if base.is_none() {
+        // В пустой репозиторий можно только добавить первый файл
+        let is_create = matches!(
+            &operation,
+            FileOperation::Write {
+                expected_sha: None,
+                from_path: None,
+                ..
+            }
+        );
+        if !is_create || !reader.branches().unwrap_or_default().is_empty() {
+            return Err(not_found("Branch not found"));
+        }
+    }
+
+    let target_branch = match options.new_branch {
+        Some(new_branch) => {
+            if !git::is_valid_ref_name("heads", &new_branch) {
+                return Err(validation_error("Invalid branch name"));
+            }
+            if reader
+                .resolve_revision(&format!("refs/heads/{}", new_branch))
+                .is_some()
+            {
+                return Err(conflict("Branch already exists"));
+            }
+            Some(new_branch)
+        }
+        None => None,
+    };
+
+    let lookup = |path: &str| -> Result<Option<TreeEntry>, AppError> {
+        match &base {
+            Some(base) => git::tree_entry(repo_path, base, path).map_err(|e| internal_error(&e)),
+            None => Ok(None),
+        }
+    };
+
+    let (changes, path, default_message) = match operation {
+        FileOperation::Write {
+            path,
+            content,
+            expected_sha,
+            from_path,
+        } => {
+            let path = normalize_path(&path)?;
+            let existing = lookup(&path)?;
+            let mut changes = Vec::new();
+
+            let (mode, content, message) = match from_path {
+                Some(from_path) => {
+                    let from_path = normalize_path(&from_path)?;
+                    let source = expect_file(lookup(&from_path)?, expected_sha.as_deref())?;
+                    if from_path != path {
+                        if existing.is_some() {
+                            return Err(conflict("Destination path already exists"));
+                        }
+                        ensure_parents_are_directories(&path, &lookup)?;
+                        changes.push(FileChange::Delete {
+                            path: from_path.clone(),
+                        });
+                    }
+                    let content = match content {
+                        Some(content) => content,
+                        None => read_blob(repo_path, &source.hash)?,
+                    };
+                    (
+                        source.mode,
+                        content,
+                        format!("Move {} to {}", from_path, path),
+                    )
+                }
+                None => {
+                    let content =
+                        content.ok_or_else(|| validation_error("File content is required"))?;
+                    match (existing, expected_sha) {
+                        (None, None) => {
+                            ensure_parents_are_directories(&path, &lookup)?;
+                            let mode = REGULAR_FILE_MODE.to_string();
+                            (mode, content, format!("Create {}", path))
+                        }
+                        (Some(_), None) => {
+                            return Err(conflict("File already exists, pass its sha to update it"))
+                        }
+                        (existing, Some(sha)) => {
+                            let existing = expect_file(existing, Some(&sha))?;
+                            (existing.mode, content, format!("Update {}", path))
+                        }
+                    }
+                }
+            };
+
+            changes.push(FileChange::Write {
+                path: path.clone(),
+                mode,
+                content,
+            });
+            (changes, path, message)
+        }
+        FileOperation::Delete { path, expected_sha } => {
+            let path = normalize_path(&path)?;
+            expect_file(lookup(&path)?, Some(&expected_sha))?;
+            let message = format!("Delete {}", path);
+            (
+                vec![FileChange::Delete { path: path.clone() }],
+                path,
+                message,
+            )
+        }
+    };
+
+    let message = options
+        .message
+        .map(|message| message.trim().to_string())
+        .filter(|message| !message.is_empty())
+        .unwrap_or(default_message);
+    let commit_sha = git::commit_changes(
+        repo_path,
+        base.as_deref(),
+        &changes,
+        author_name,
+        author_email,
+        &message,
+    )
+    .map_err(|e| match e {
+        CommitError::PathConflict => {
+            conflict("The path conflicts with an existing file or directory")
+        }
+        CommitError::Failed(e) => internal_error(&e),
+    })?;
+
+    // Ветка могла сдвинуться, пока собирался коммит
+    let (branch, old_sha) = match target_branch {
+        Some(new_branch) => (new_branch, git::ZERO_SHA.to_string()),
+        None => (branch, base.unwrap_or_else(|| git::ZERO_SHA.to_string())),
+    };
+    git::update_ref(
+        repo_path,
+        &format!("refs/heads/{}", branch),
+        &commit_sha,
+        &old_sha,
+    )
+    .map_err(|_| conflict("Branch was updated concurrently, retry the change"))?;
+
+    let content_sha = match changes.last() {
+        Some(FileChange::Write { .. }) => git::tree_entry(repo_path, &commit_sha, &path)
+            .map_err(|e| internal_error(&e))?
+            .map(|entry| entry.hash),
+        _ => None,
+    };
+
+    Ok(FileCommit {
+        commit_sha,
+        branch,
+        path,
+        content_sha,
+    })
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Убирает крайние `/` и проверяет, что путь остаётся внутри дерева
+fn normalize_path(path: &str) -> Result<String, AppError> {
+    let path = path.trim_matches('/');
+    if path.is_empty() || !git::is_safe_tree_path(path) {
+        return Err(validation_error("Invalid file path"));
+    }
+    Ok(path.to_string())
+}
+
+/// Проверяет, что по пути лежит файл с ожидаемым SHA блоба
+fn expect_file(
+    entry: Option<TreeEntry>,
+    expected_sha: Option<&str>,
+) -> Result<TreeEntry, AppError> {
+    let entry = entry.ok_or_else(|| not_found("File not found"))?;
+    if entry.object_type != "blob" {
+        return Err(validation_error("Path is not a file"));
+    }
+
+    let Some(expected_sha) = expected_sha else {
+        return Err(validation_error("The current file sha is required"));
+    };
+    if entry.hash != expected_sha {
+        return Err(conflict(
+            "File has been changed since the given sha, reload it and retry",
+        ));
+    }
+
+    Ok(entry)
+}
+
+/// Новый файл нельзя создать внутри пути, который уже занят файлом
+fn ensure_parents_are_directories(
+    path: &str,
+    lookup: &dyn Fn(&str) -> Result<Option<TreeEntry>, AppError>,
+) -> Result<(), AppError> {
+    let mut parent = path;
+    while let Some((dir, _)) = parent.rsplit_once('/') {
+        if let Some(entry) = lookup(dir)? {
+            if entry.object_type != "tree" {
+                return Err(conflict("A parent of the path is a file"));
+            }
+            // Выше существующей директории файлов быть не может
+            break;
+        }
+        parent = dir;
+    }
+    Ok(())
+}
+
+fn read_blob(repo_path: &str, hash: &str) -> Result<Vec<u8>, AppError> {
+    git::read_objects(repo_path, &[hash.to_string()], "blob")
+        .map_err(|e| internal_error(&e))?
+        .pop()
+        .flatten()
+        .ok_or_else(|| internal_error(&format!("Blob {} is missing", hash)))
+}
+
+fn validation_error(message: &str) -> AppError {
+    AppError::new(ErrorType::ValidationError, message)
+}
+
+fn not_found(message: &str) -> AppError {
+    AppError::new(ErrorType::NotFoundError, message)
+}
+
+fn conflict(message: &str) -> AppError {
+    AppError::new(ErrorType::ConflictError, message)
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(object_type: &str, hash: &str) -> TreeEntry {
+        TreeEntry {
+            mode: REGULAR_FILE_MODE.to_string(),
+            object_type: object_type.to_string(),
+            hash: hash.to_string(),
+            path: "src/main.rs".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("src/main.rs").unwrap(), "src/main.rs");
+        assert_eq!(normalize_path("/src/main.rs/").unwrap(), "src/main.rs");
+
+        for path in [
+            "",
+            "/",
+            "//",
+            "../etc/passwd",
+            "src/../../x",
+            "src//main.rs",
+            "./a",
+        ] {
+            assert!(normalize_path(path).is_err(), "{}", path);
+        }
+        assert!(normalize_path(".git/config").is_err());
+        assert!(normalize_path("a\0b").is_err());
+    }
+
+    #[test]
+    fn test_expect_file() {
+        let found = expect_file(Some(entry("blob", "abc")), Some("abc")).unwrap();
+        assert_eq!(found.hash, "abc");
+
+        let error = expect_file(None, Some("abc")).unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::NotFoundError));
+
+        let error = expect_file(Some(entry("tree", "abc")), Some("abc")).unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::ValidationError));
+
+        let error = expect_file(Some(entry("blob", "abc")), None).unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::ValidationError));
+
+        let error = expect_file(Some(entry("blob", "abc")), Some("def")).unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::ConflictError));
+    }
+}
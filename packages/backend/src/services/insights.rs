@@ -0,0 +1,457 @@
+//! Сервис статистики репозиториев: языки, участники, активность
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use log::error;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::core::database::Database;
+use crate::domain::insights::{InsightKind, InsightsCache};
+use crate::domain::repos::Repository;
+use crate::utils::dependencies::{self, DependencyGraph};
+use crate::utils::sbom::{self, SbomFormat, SbomSubject};
+use crate::utils::{git, languages};
+
+/// Манифесты больше этого размера не разбираются
+const MAX_MANIFEST_SIZE: u64 = 5 * 1024 * 1024;
+/// Сколько манифестов разбирается в одной ревизии
+const MAX_MANIFESTS: usize = 200;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Доля языка в репозитории
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LanguageStat {
+    pub language: String,
+    pub bytes: u64,
+    pub percentage: f64,
+}
+
+/// Участник репозитория и количество его коммитов
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContributorStat {
+    pub name: String,
+    pub email: String,
+    pub commits: u64,
+    pub first_commit_at: DateTime<Utc>,
+    pub last_commit_at: DateTime<Utc>,
+}
+
+/// Количество коммитов за неделю (неделя начинается в понедельник, UTC)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WeeklyActivity {
+    pub week_start: NaiveDate,
+    pub commits: u64,
+}
+
+// ============================================================================
+// ПУБЛИЧНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Распределение байтов по языкам в ветке по умолчанию
+pub async fn languages(repo: &Repository, db: &Database) -> Result<Vec<LanguageStat>, String> {
+    cached(repo, InsightKind::Languages, db, compute_languages).await
+}
+
+/// Рейтинг участников по числу коммитов
+pub async fn contributors(
+    repo: &Repository,
+    db: &Database,
+) -> Result<Vec<ContributorStat>, String> {
+    cached(repo, InsightKind::Contributors, db, compute_contributors).await
+}
+
+/// Понедельная активность за последние `weeks` недель (включая текущую)
+pub async fn activity(
+    repo: &Repository,
+    weeks: usize,
+    db: &Database,
+) -> Result<Vec<WeeklyActivity>, String> {
+    let history: Vec<WeeklyActivity> =
+        cached(repo, InsightKind::Activity, db, compute_activity).await?;
+    let counts: HashMap<NaiveDate, u64> = history
+        .into_iter()
+        .map(|week| (week.week_start, week.commits))
+        .collect();
+
+    // Кэш хранит историю до последнего коммита, поэтому окно достраивается до текущей недели
+    let current_week = week_start(Utc::now());
+    Ok((0..weeks)
+        .rev()
+        .map(|offset| {
+            let week_start = current_week - Duration::weeks(offset as i64);
+            WeeklyActivity {
+                week_start,
+                commits: counts.get(&week_start).copied().unwrap_or(0),
+            }
+        })
+        .collect())
+}
+
+/// Граф зависимостей по манифестам ветки по умолчанию
+pub async fn dependencies(repo: &Repository, db: &Database) -> Result<DependencyGraph, String> {
+    cached(repo, InsightKind::Dependencies, db, compute_dependencies).await
+}
+
+/// SBOM ветки по умолчанию; `None`, если репозиторий пуст
+///
+/// `full_name` — имя приложения в документе (`owner/project/repo`).
+pub async fn sbom(
+    repo: &Repository,
+    full_name: &str,
+    format: SbomFormat,
+    db: &Database,
+) -> Result<Option<Value>, String> {
+    let repo_path = git::repository_path(&repo.name);
+    let head = tokio::task::spawn_blocking(move || git::resolve_default_head(&repo_path))
+        .await
+        .map_err(|e| format!("Insights task failed: {}", e))?;
+    let Some((_, head)) = head else {
+        return Ok(None);
+    };
+
+    let graph = dependencies(repo, db).await?;
+    let subject = SbomSubject {
+        name: full_name,
+        revision: &head,
+    };
+    Ok(Some(sbom::render(format, &graph, &subject)))
+}
+
+// ============================================================================
+// КЭШИРОВАНИЕ
+// ============================================================================
+
+/// Возвращает статистику из кэша или вычисляет её для текущей головы ветки по умолчанию
+async fn cached<T, F>(
+    repo: &Repository,
+    kind: InsightKind,
+    db: &Database,
+    compute: F,
+) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned + Default + Send + 'static,
+    F: FnOnce(&str, &str) -> Result<T, String> + Send + 'static,
+{
+    let repository_id = repo.id.ok_or("Repository must have id")?;
+    let repo_path = git::repository_path(&repo.name);
+
+    let head = {
+        let repo_path = repo_path.clone();
+        tokio::task::spawn_blocking(move || git::resolve_default_head(&repo_path))
+            .await
+            .map_err(|e| format!("Insights task failed: {}", e))?
+    };
+
+    // В пустом репозитории статистики нет
+    let Some((_, head)) = head else {
+        return Ok(T::default());
+    };
+
+    let pool = db.get_pool();
+    match InsightsCache::load(repository_id, kind, &head, pool).await {
+        Ok(Some(data)) => return Ok(data),
+        Ok(None) => {}
+        Err(e) => error!("Failed to load insights cache: {}", e),
+    }
+
+    let data = {
+        let head = head.clone();
+        tokio::task::spawn_blocking(move || compute(&repo_path, &head))
+            .await
+            .map_err(|e| format!("Insights task failed: {}", e))??
+    };
+
+    if let Err(e) = InsightsCache::store(repository_id, kind, &head, &data, pool).await {
+        error!("Failed to store insights cache: {}", e);
+    }
+
+    Ok(data)
+}
+
+// ============================================================================
+// ВЫЧИСЛЕНИЯ
+// ============================================================================
+
+fn compute_languages(repo_path: &str, head: &str) -> Result<Vec<LanguageStat>, String> {
+    let mut bytes_by_language: HashMap<&'static str, u64> = HashMap::new();
+
+    for file in git::get_repository_tree(repo_path, head)? {
+        if file.file_type != "blob" {
+            continue;
+        }
+        if let Some(language) = languages::detect_language(&file.name) {
+            *bytes_by_language.entry(language).or_default() += file.size.unwrap_or(0);
+        }
+    }
+
+    let total: u64 = bytes_by_language.values().sum();
+    let mut stats: Vec<LanguageStat> = bytes_by_language
+        .into_iter()
+        .map(|(language, bytes)| LanguageStat {
+            language: language.to_string(),
+            bytes,
+            percentage: if total > 0 {
+                (bytes as f64 * 10000.0 / total as f64).round() / 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect();
+    stats.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.language.cmp(&b.language)));
+
+    Ok(stats)
+}
+
+fn compute_contributors(repo_path: &str, head: &str) -> Result<Vec<ContributorStat>, String> {
+    let mut by_email: HashMap<String, ContributorStat> = HashMap::new();
+
+    // История идёт от новых коммитов к старым: первое встреченное имя — самое свежее
+    for commit in git::get_commits(repo_path, head, None, 0)? {
+        let authored_at = timestamp(commit.authored_at);
+        by_email
+            .entry(commit.author_email.to_lowercase())
+            .and_modify(|stat| {
+                stat.commits += 1;
+                stat.first_commit_at = stat.first_commit_at.min(authored_at);
+                stat.last_commit_at = stat.last_commit_at.max(authored_at);
+            })
+            .or_insert(ContributorStat {
+                name: commit.author_name,
+                email: commit.author_email,
+                commits: 1,
+                first_commit_at: authored_at,
+                last_commit_at: authored_at,
+            });
+    }
+
+    let mut stats: Vec<ContributorStat> = by_email.into_values().collect();
+    stats.sort_by(|a, b| b.commits.cmp(&a.commits).then(a.name.cmp(&b.name)));
+
+    Ok(stats)
+}
+
+fn compute_activity(repo_path: &str, head: &str) -> Result<Vec<WeeklyActivity>, String> {
+    let mut counts: HashMap<NaiveDate, u64> = HashMap::new();
+    for commit in git::get_commits(repo_path, head, None, 0)? {
+        *counts
+            .entry(week_start(timestamp(commit.authored_at)))
+            .or_default() += 1;
+    }
+
+    let mut weeks: Vec<WeeklyActivity> = counts
+        .into_iter()
+        .map(|(week_start, commits)| WeeklyActivity {
+            week_start,
+            commits,
+        })
+        .collect();
+    weeks.sort_by_key(|week| week.week_start);
+
+    Ok(weeks)
+}
+
+fn compute_dependencies(repo_path: &str, head: &str) -> Result<DependencyGraph, String> {
+    let manifests: Vec<git::GitFile> = git::get_repository_tree(repo_path, head)?
+        .into_iter()
+        .filter(|file| {
+            file.file_type == "blob"
+                && file.size.unwrap_or(0) <= MAX_MANIFEST_SIZE
+                && dependencies::is_manifest(&file.name)
+        })
+        .take(MAX_MANIFESTS)
+        .collect();
+
+    let hashes: Vec<String> = manifests.iter().map(|file| file.hash.clone()).collect();
+    let files: Vec<(String, Vec<u8>)> = manifests
+        .into_iter()
+        .zip(git::read_objects(repo_path, &hashes, "blob")?)
+        .filter_map(|(file, content)| Some((file.name, content?)))
+        .collect();
+
+    Ok(dependencies::build_graph(&files))
+}
+
+fn timestamp(seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(seconds, 0).unwrap_or_default()
+}
+
+/// Понедельник недели, в которую попадает момент времени
+fn week_start(moment: DateTime<Utc>) -> NaiveDate {
+    let date = moment.date_naive();
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    // Воскресенье 2023-12-31 23:59 UTC и следующие за ним дни
+    const SUNDAY_NIGHT: i64 = 1_704_067_140;
+    const MONDAY: i64 = 1_704_067_200;
+    const TUESDAY: i64 = 1_704_153_600;
+    const WEDNESDAY: i64 = 1_704_240_000;
+
+    fn run_git(dir: &Path, args: &[&str], time: i64) {
+        let date = format!("{} +0000", time);
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .env("GIT_AUTHOR_DATE", &date)
+            .env("GIT_COMMITTER_DATE", &date)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("HOME", dir)
+            .output()
+            .expect("git is installed");
+        assert!(
+            output.status.success(),
+            "git {:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn commit(dir: &Path, files: &[(&str, String)], author: &str, time: i64) {
+        for (path, content) in files {
+            fs::write(dir.join(path), content).unwrap();
+            run_git(dir, &["add", path], time);
+        }
+        run_git(
+            dir,
+            &["commit", "-q", "--author", author, "-m", "Update"],
+            time,
+        );
+    }
+
+    /// Репозиторий с Rust, Markdown и Python, двумя адресами одного автора и
+    /// псевдонимом из `.mailmap`; возвращает путь к каталогу `.git`
+    fn fixture_repo() -> (TempDir, String) {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        run_git(dir, &["init", "-q", "-b", "main"], SUNDAY_NIGHT);
+
+        let filler = |size: usize| "x".repeat(size);
+        commit(
+            dir,
+            &[("README.md", filler(100))],
+            "Old Name <old@example.com>",
+            SUNDAY_NIGHT,
+        );
+        commit(
+            dir,
+            &[("main.rs", filler(400))],
+            "Alias <alias@example.com>",
+            MONDAY,
+        );
+        commit(
+            dir,
+            &[("tool.py", filler(100))],
+            "Old Name <OLD@example.com>",
+            TUESDAY,
+        );
+        commit(
+            dir,
+            &[
+                (
+                    ".mailmap",
+                    "Dev <dev@example.com> <alias@example.com>\n".to_string(),
+                ),
+                ("notes.txt", filler(1000)),
+            ],
+            "Dev <dev@example.com>",
+            WEDNESDAY,
+        );
+
+        let git_dir = dir.join(".git").to_str().unwrap().to_string();
+        (tmp, git_dir)
+    }
+
+    #[test]
+    fn test_compute_languages() {
+        let (_tmp, repo) = fixture_repo();
+        let stats = compute_languages(&repo, "main").unwrap();
+
+        // Файлы без распознанного языка (notes.txt, .mailmap) не учитываются
+        let stats: Vec<(&str, u64, f64)> = stats
+            .iter()
+            .map(|stat| (stat.language.as_str(), stat.bytes, stat.percentage))
+            .collect();
+        assert_eq!(
+            stats,
+            vec![
+                ("Rust", 400, 66.67),
+                ("Markdown", 100, 16.67),
+                ("Python", 100, 16.67)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_contributors_merges_identities() {
+        let (_tmp, repo) = fixture_repo();
+        let stats = compute_contributors(&repo, "main").unwrap();
+
+        // Псевдоним из .mailmap объединён с Dev, адреса Old Name — без учёта регистра
+        let summary: Vec<(&str, u64)> = stats
+            .iter()
+            .map(|stat| (stat.name.as_str(), stat.commits))
+            .collect();
+        assert_eq!(summary, vec![("Dev", 2), ("Old Name", 2)]);
+
+        let dev = &stats[0];
+        assert_eq!(dev.email, "dev@example.com");
+        assert_eq!(dev.first_commit_at, timestamp(MONDAY));
+        assert_eq!(dev.last_commit_at, timestamp(WEDNESDAY));
+        let old = &stats[1];
+        assert_eq!(old.first_commit_at, timestamp(SUNDAY_NIGHT));
+        assert_eq!(old.last_commit_at, timestamp(TUESDAY));
+    }
+
+    #[test]
+    fn test_compute_activity() {
+        let (_tmp, repo) = fixture_repo();
+        let weeks: Vec<(NaiveDate, u64)> = compute_activity(&repo, "main")
+            .unwrap()
+            .into_iter()
+            .map(|week| (week.week_start, week.commits))
+            .collect();
+
+        // Коммит в воскресенье относится к предыдущей неделе, с понедельника — новая
+        assert_eq!(
+            weeks,
+            vec![
+                (NaiveDate::from_ymd_opt(2023, 12, 25).unwrap(), 1),
+                (NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_week_start() {
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert_eq!(week_start(timestamp(MONDAY)), monday);
+        assert_eq!(week_start(timestamp(WEDNESDAY)), monday);
+        assert_eq!(
+            week_start(timestamp(SUNDAY_NIGHT)),
+            NaiveDate::from_ymd_opt(2023, 12, 25).unwrap()
+        );
+        assert_eq!(
+            week_start(timestamp(MONDAY - 1)),
+            week_start(timestamp(SUNDAY_NIGHT))
+        );
+
+        // Неделя, начавшаяся в прошлом году: суббота 2022-01-01
+        assert_eq!(
+            week_start(timestamp(1_641_038_400)),
+            NaiveDate::from_ymd_opt(2021, 12, 27).unwrap()
+        );
+    }
+}
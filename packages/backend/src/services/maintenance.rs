@@ -0,0 +1,257 @@
+//! Фоновое обслуживание bare-репозиториев (gc, repack, commit-graph)
+//!
+//! Планировщик периодически считает объекты во всех репозиториях и обслуживает
+//! те, которым это нужно, в порядке приоритета. Одновременно обслуживается не
+//! больше `MAINTENANCE_CONCURRENCY` репозиториев.
+
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use serde::Serialize;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::core::database::Database;
+use crate::domain::maintenance::{RepositoryMaintenance, STATUS_FAILED, STATUS_SUCCESS};
+use crate::services::projects::lifecycle;
+use crate::services::traffic as traffic_service;
+use crate::utils::git;
+
+/// Столько loose-объектов делает репозиторий кандидатом на обслуживание
+const LOOSE_OBJECTS_THRESHOLD: i64 = 256;
+/// Репозиторий с объектами обслуживается не реже, чем раз в этот период
+const MAX_MAINTENANCE_AGE_HOURS: i64 = 7 * 24;
+
+/// Текущее состояние планировщика (для админского API)
+static SCHEDULER: LazyLock<Mutex<SchedulerStatus>> =
+    LazyLock::new(|| Mutex::new(SchedulerStatus::default()));
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Состояние планировщика обслуживания
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SchedulerStatus {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    pub concurrency: usize,
+    /// Репозитории, обслуживаемые прямо сейчас
+    pub running: Vec<i64>,
+    /// Репозитории, ожидающие своей очереди в текущем цикле
+    pub queued: Vec<i64>,
+    pub last_cycle_started_at: Option<DateTime<Utc>>,
+    pub last_cycle_finished_at: Option<DateTime<Utc>>,
+}
+
+/// Репозиторий, отобранный для обслуживания
+struct Candidate {
+    repository_id: i64,
+    repo_path: String,
+    priority: i64,
+}
+
+// ============================================================================
+// ПЛАНИРОВЩИК
+// ============================================================================
+
+/// Запускает планировщик обслуживания; `interval_secs = 0` отключает его
+pub fn start_scheduler(db: Database, interval_secs: u64, concurrency: usize) {
+    {
+        let mut status = SCHEDULER.lock().unwrap();
+        status.enabled = interval_secs > 0;
+        status.interval_secs = interval_secs;
+        status.concurrency = concurrency;
+    }
+
+    if interval_secs == 0 {
+        info!("Repository maintenance is disabled");
+        return;
+    }
+
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        // Долгий цикл не должен приводить к серии запусков подряд
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            run_cycle(&db, &semaphore).await;
+        }
+    });
+}
+
+/// Возвращает текущее состояние планировщика
+pub fn scheduler_status() -> SchedulerStatus {
+    SCHEDULER.lock().unwrap().clone()
+}
+
+/// Один цикл: подсчёт объектов, выбор кандидатов и их обслуживание
+async fn run_cycle(db: &Database, semaphore: &Arc<Semaphore>) {
+    SCHEDULER.lock().unwrap().last_cycle_started_at = Some(Utc::now());
+
+    // Заодно удаляем устаревшую статистику посещений и проекты после срока хранения
+    traffic_service::prune(db).await;
+    lifecycle::purge_expired(db).await;
+
+    let candidates = match collect_candidates(db).await {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            error!("Failed to plan repository maintenance: {}", e);
+            Vec::new()
+        }
+    };
+    if !candidates.is_empty() {
+        info!("Maintaining {} repositories", candidates.len());
+    }
+
+    SCHEDULER.lock().unwrap().queued = candidates.iter().map(|c| c.repository_id).collect();
+
+    let mut tasks = JoinSet::new();
+    for candidate in candidates {
+        let Ok(permit) = semaphore.clone().acquire_owned().await else {
+            break;
+        };
+        let db = db.clone();
+        tasks.spawn(async move {
+            maintain_repository(candidate, &db).await;
+            drop(permit);
+        });
+    }
+    while tasks.join_next().await.is_some() {}
+
+    let mut status = SCHEDULER.lock().unwrap();
+    status.queued.clear();
+    status.last_cycle_finished_at = Some(Utc::now());
+}
+
+/// Считает объекты во всех репозиториях и возвращает нуждающиеся в обслуживании
+///
+/// Приоритет — число loose-объектов плюс часы с последнего обслуживания,
+/// так что давно не обслуживаемые репозитории тоже доходят до очереди.
+async fn collect_candidates(db: &Database) -> Result<Vec<Candidate>, String> {
+    let repositories = RepositoryMaintenance::find_all(db.get_pool())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut candidates = Vec::new();
+    for repo in repositories {
+        let repo_path = git::repository_path(&repo.repository_name);
+        if !git::repository_exists(&repo_path) {
+            continue;
+        }
+
+        let counts = {
+            let repo_path = repo_path.clone();
+            tokio::task::spawn_blocking(move || git::count_objects(&repo_path))
+                .await
+                .map_err(|e| format!("Maintenance task failed: {}", e))?
+        };
+        let counts = match counts {
+            Ok(counts) => counts,
+            Err(e) => {
+                warn!("Failed to count objects in {}: {}", repo_path, e);
+                continue;
+            }
+        };
+
+        if let Err(e) = RepositoryMaintenance::record_check(
+            repo.repository_id,
+            counts.loose_objects,
+            counts.packs,
+            db.get_pool(),
+        )
+        .await
+        {
+            error!("Failed to store maintenance check: {}", e);
+        }
+
+        // Пустой репозиторий обслуживать не нужно
+        if counts.loose_objects == 0 && counts.packs == 0 {
+            continue;
+        }
+
+        let hours_since_run = match repo.last_run_at {
+            Some(last_run_at) => (Utc::now() - last_run_at).num_hours(),
+            None => MAX_MAINTENANCE_AGE_HOURS,
+        };
+        if counts.loose_objects < LOOSE_OBJECTS_THRESHOLD
+            && hours_since_run < MAX_MAINTENANCE_AGE_HOURS
+        {
+            continue;
+        }
+
+        candidates.push(Candidate {
+            repository_id: repo.repository_id,
+            repo_path,
+            priority: counts.loose_objects + hours_since_run,
+        });
+    }
+
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.priority));
+    Ok(candidates)
+}
+
+/// Обслуживает один репозиторий и сохраняет результат
+async fn maintain_repository(candidate: Candidate, db: &Database) {
+    let repository_id = candidate.repository_id;
+    {
+        let mut status = SCHEDULER.lock().unwrap();
+        status.queued.retain(|id| *id != repository_id);
+        status.running.push(repository_id);
+    }
+
+    let started = Instant::now();
+    let repo_path = candidate.repo_path;
+    let result = tokio::task::spawn_blocking(move || {
+        let result = git::run_maintenance(&repo_path);
+        let counts = git::count_objects(&repo_path).unwrap_or_default();
+        (result, counts)
+    })
+    .await;
+    let duration_ms = started.elapsed().as_millis() as i64;
+
+    let (result, counts) = match result {
+        Ok(result) => result,
+        Err(e) => (
+            Err(format!("Maintenance task failed: {}", e)),
+            Default::default(),
+        ),
+    };
+
+    let (status, error) = match &result {
+        Ok(()) => {
+            info!(
+                "Repository {} maintained in {} ms",
+                repository_id, duration_ms
+            );
+            (STATUS_SUCCESS, None)
+        }
+        Err(e) => {
+            error!("Maintenance of repository {} failed: {}", repository_id, e);
+            (STATUS_FAILED, Some(e.as_str()))
+        }
+    };
+
+    if let Err(e) = RepositoryMaintenance::record_run(
+        repository_id,
+        status,
+        error,
+        duration_ms,
+        counts.loose_objects,
+        counts.packs,
+        db.get_pool(),
+    )
+    .await
+    {
+        error!("Failed to store maintenance result: {}", e);
+    }
+
+    SCHEDULER
+        .lock()
+        .unwrap()
+        .running
+        .retain(|id| *id != repository_id);
+}
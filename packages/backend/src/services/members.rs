@@ -0,0 +1,461 @@
+//! Участники проектов и команды
+//!
+//! Владелец личного проекта всегда остаётся участником с ролью owner: его
+//! нельзя исключить или понизить. Роль owner выдают и отнимают только
+//! владельцы; мейнтейнеры управляют остальными ролями. Команда принадлежит
+//! пользователю, и только он меняет её состав и удаляет её, а доступ команды
+//! к проекту выдают администраторы проекта.
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::events::EVENT_MEMBER_ADDED;
+use crate::domain::members::{ProjectMember, MEMBER_ROLES};
+use crate::domain::organizations::Organization;
+use crate::domain::projects::Project;
+use crate::domain::teams::{ProjectTeam, Team, TeamMember, TEAM_ROLES};
+use crate::domain::users::User;
+use crate::services::activity as activity_service;
+use crate::utils::helpers;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Команда с участниками и проектами
+#[derive(Debug, Serialize)]
+pub struct TeamDetails {
+    pub team: Team,
+    pub members: Vec<TeamMember>,
+    pub projects: Vec<ProjectTeam>,
+}
+
+// ============================================================================
+// УЧАСТНИКИ ПРОЕКТА
+// ============================================================================
+
+/// Участники проекта
+pub async fn list_members(
+    project: &Project,
+    db: &Database,
+) -> Result<Vec<ProjectMember>, AppError> {
+    ProjectMember::find_by_project(project_id(project)?, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Записывает создателя проекта его владельцем
+pub async fn add_owner(project_id: i64, user_id: i64, db: &Database) -> Result<(), AppError> {
+    ProjectMember::upsert(project_id, user_id, "owner", db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Добавляет участника проекта или меняет его роль
+pub async fn set_member(
+    project: &Project,
+    actor: &User,
+    username: &str,
+    role: &str,
+    db: &Database,
+) -> Result<ProjectMember, AppError> {
+    if !MEMBER_ROLES.contains(&role) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Role must be one of: owner, maintainer, developer, reporter, guest",
+        ));
+    }
+
+    let project_id = project_id(project)?;
+    let user_id = find_user_id(username, db).await?;
+    check_not_personal_owner(project, user_id)?;
+
+    let pool = db.get_pool();
+    let current = ProjectMember::find(project_id, user_id, pool)
+        .await
+        .map_err(database_error)?;
+    let is_new = current.is_none();
+    let current_role = current.as_ref().map(|member| member.role.as_str());
+    if touches_owner(role, current_role) && !is_owner(project, actor, db).await? {
+        return Err(owners_only_error());
+    }
+
+    ProjectMember::upsert(project_id, user_id, role, pool)
+        .await
+        .map_err(database_error)?;
+    if is_new {
+        activity_service::record(
+            project_id,
+            None,
+            actor.id,
+            EVENT_MEMBER_ADDED,
+            json!({"username": username, "role": role}),
+            db,
+        )
+        .await;
+    }
+    ProjectMember::find(project_id, user_id, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| internal_error("Member was not saved"))
+}
+
+/// Исключает участника проекта
+pub async fn remove_member(
+    project: &Project,
+    actor: &User,
+    username: &str,
+    db: &Database,
+) -> Result<(), AppError> {
+    let project_id = project_id(project)?;
+    let user_id = find_user_id(username, db).await?;
+    if project.personal_owner_id() == Some(user_id) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "The project owner cannot be removed",
+        ));
+    }
+
+    let pool = db.get_pool();
+    let member = ProjectMember::find(project_id, user_id, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Member not found"))?;
+    if member.role == "owner" && !is_owner(project, actor, db).await? {
+        return Err(owners_only_error());
+    }
+
+    ProjectMember::delete(project_id, user_id, pool)
+        .await
+        .map_err(database_error)?;
+    Ok(())
+}
+
+// ============================================================================
+// КОМАНДЫ ПРОЕКТА
+// ============================================================================
+
+/// Команды с доступом к проекту
+pub async fn list_project_teams(
+    project: &Project,
+    db: &Database,
+) -> Result<Vec<ProjectTeam>, AppError> {
+    ProjectTeam::find_by_project(project_id(project)?, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Выдаёт команде роль в проекте или меняет существующую
+pub async fn grant_team(
+    project: &Project,
+    team_owner: &str,
+    team_name: &str,
+    role: &str,
+    db: &Database,
+) -> Result<ProjectTeam, AppError> {
+    if !TEAM_ROLES.contains(&role) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Role must be one of: maintainer, developer, reporter, guest",
+        ));
+    }
+
+    let project_id = project_id(project)?;
+    let team = find_team(team_owner, team_name, db).await?;
+    let pool = db.get_pool();
+    ProjectTeam::upsert(project_id, team.id, role, pool)
+        .await
+        .map_err(database_error)?;
+
+    ProjectTeam::find_by_project(project_id, pool)
+        .await
+        .map_err(database_error)?
+        .into_iter()
+        .find(|grant| grant.team_id == team.id)
+        .ok_or_else(|| internal_error("Team access was not saved"))
+}
+
+/// Отзывает доступ команды к проекту
+pub async fn revoke_team(
+    project: &Project,
+    team_owner: &str,
+    team_name: &str,
+    db: &Database,
+) -> Result<(), AppError> {
+    let project_id = project_id(project)?;
+    let team = find_team(team_owner, team_name, db).await?;
+    let removed = ProjectTeam::delete(project_id, team.id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+
+    if removed {
+        Ok(())
+    } else {
+        Err(AppError::new(
+            ErrorType::NotFoundError,
+            "Team has no access to this project",
+        ))
+    }
+}
+
+// ============================================================================
+// КОМАНДЫ
+// ============================================================================
+
+/// Команды, которыми пользователь владеет или в которых состоит
+pub async fn list_teams(user: &User, db: &Database) -> Result<Vec<Team>, AppError> {
+    let user_id = user.id.ok_or_else(|| internal_error("User must have id"))?;
+    Team::find_for_user(user_id, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Создаёт команду пользователя
+pub async fn create_team(
+    owner: &User,
+    name: &str,
+    description: Option<&str>,
+    db: &Database,
+) -> Result<Team, AppError> {
+    if !helpers::is_valid_project_name(name) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Team name may contain letters, digits, '-', '_' and '.' (up to 100 characters)",
+        ));
+    }
+
+    let owner_id = owner
+        .id
+        .ok_or_else(|| internal_error("User must have id"))?;
+    let pool = db.get_pool();
+    if Team::find_by_owner_and_name(owner_id, name, pool)
+        .await
+        .map_err(database_error)?
+        .is_some()
+    {
+        return Err(AppError::new(
+            ErrorType::ConflictError,
+            "Team with this name already exists",
+        ));
+    }
+
+    Team::create(owner_id, name, description, pool)
+        .await
+        .map_err(database_error)?;
+    Team::find_by_owner_and_name(owner_id, name, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| internal_error("Team was not saved"))
+}
+
+/// Команда по имени владельца и своему имени
+pub async fn find_team(owner: &str, name: &str, db: &Database) -> Result<Team, AppError> {
+    let owner_id = find_user_id(owner, db).await?;
+    Team::find_by_owner_and_name(owner_id, name, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Team not found"))
+}
+
+/// Состав и проекты команды; видны её владельцу и участникам
+pub async fn team_details(
+    team: Team,
+    viewer: &User,
+    db: &Database,
+) -> Result<TeamDetails, AppError> {
+    let viewer_id = viewer
+        .id
+        .ok_or_else(|| internal_error("User must have id"))?;
+    let pool = db.get_pool();
+    let visible = team.owner_id == viewer_id
+        || Team::has_member(team.id, viewer_id, pool)
+            .await
+            .map_err(database_error)?;
+    if !visible {
+        // Чужие команды неотличимы от несуществующих
+        return Err(AppError::new(ErrorType::NotFoundError, "Team not found"));
+    }
+
+    let members = Team::members(team.id, pool).await.map_err(database_error)?;
+    let projects = ProjectTeam::find_by_team(team.id, pool)
+        .await
+        .map_err(database_error)?;
+    Ok(TeamDetails {
+        team,
+        members,
+        projects,
+    })
+}
+
+/// Удаляет команду; доступ её участников через команду пропадает
+pub async fn delete_team(team: &Team, actor: &User, db: &Database) -> Result<(), AppError> {
+    check_team_owner(team, actor)?;
+    Team::delete(team.id, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Добавляет пользователя в команду
+pub async fn add_team_member(
+    team: &Team,
+    actor: &User,
+    username: &str,
+    db: &Database,
+) -> Result<Vec<TeamMember>, AppError> {
+    check_team_owner(team, actor)?;
+    let user_id = find_user_id(username, db).await?;
+    let pool = db.get_pool();
+    Team::add_member(team.id, user_id, pool)
+        .await
+        .map_err(database_error)?;
+    Team::members(team.id, pool).await.map_err(database_error)
+}
+
+/// Исключает пользователя из команды
+pub async fn remove_team_member(
+    team: &Team,
+    actor: &User,
+    username: &str,
+    db: &Database,
+) -> Result<(), AppError> {
+    check_team_owner(team, actor)?;
+    let user_id = find_user_id(username, db).await?;
+    let removed = Team::remove_member(team.id, user_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+
+    if removed {
+        Ok(())
+    } else {
+        Err(AppError::new(
+            ErrorType::NotFoundError,
+            "Team member not found",
+        ))
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Владелец проекта, участник с ролью owner или владелец либо администратор
+/// организации проекта
+async fn is_owner(project: &Project, user: &User, db: &Database) -> Result<bool, AppError> {
+    let Some(user_id) = user.id else {
+        return Ok(false);
+    };
+    if project.personal_owner_id() == Some(user_id) {
+        return Ok(true);
+    }
+    if let Some(organization_id) = project.organization_id {
+        let role = Organization::member_role(organization_id, user_id, db.get_pool())
+            .await
+            .map_err(database_error)?;
+        if matches!(role.as_deref(), Some("owner" | "admin")) {
+            return Ok(true);
+        }
+    }
+    let member = ProjectMember::find(project_id(project)?, user_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    Ok(member.is_some_and(|member| member.role == "owner"))
+}
+
+/// Роль владельца личного проекта не меняется
+fn check_not_personal_owner(project: &Project, user_id: i64) -> Result<(), AppError> {
+    if project.personal_owner_id() == Some(user_id) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "The role of the project owner cannot be changed",
+        ));
+    }
+    Ok(())
+}
+
+/// Изменение выдаёт или отнимает роль owner, и его может сделать только владелец
+fn touches_owner(role: &str, current_role: Option<&str>) -> bool {
+    role == "owner" || current_role == Some("owner")
+}
+
+fn check_team_owner(team: &Team, actor: &User) -> Result<(), AppError> {
+    if actor.id == Some(team.owner_id) {
+        Ok(())
+    } else {
+        Err(AppError::new(
+            ErrorType::AuthorizationError,
+            "Only the team owner can manage the team",
+        ))
+    }
+}
+
+async fn find_user_id(username: &str, db: &Database) -> Result<i64, AppError> {
+    let user = User::find_by_username(username, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "User not found"))?;
+    user.id.ok_or_else(|| internal_error("User must have id"))
+}
+
+fn project_id(project: &Project) -> Result<i64, AppError> {
+    project
+        .id
+        .ok_or_else(|| internal_error("Project must have id"))
+}
+
+fn owners_only_error() -> AppError {
+    AppError::new(
+        ErrorType::AuthorizationError,
+        "Only project owners can manage owners",
+    )
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(organization_id: Option<i64>) -> Project {
+        Project {
+            id: Some(1),
+            name: "demo".to_string(),
+            owner_id: 10,
+            organization_id,
+            description: None,
+            is_public: true,
+            created_at: None,
+            archived_at: None,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_check_not_personal_owner() {
+        let error = check_not_personal_owner(&project(None), 10).unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::ValidationError));
+        assert!(check_not_personal_owner(&project(None), 11).is_ok());
+        // У проекта организации нет личного владельца
+        assert!(check_not_personal_owner(&project(Some(5)), 10).is_ok());
+    }
+
+    #[test]
+    fn test_touches_owner() {
+        // Выдача роли owner
+        assert!(touches_owner("owner", None));
+        assert!(touches_owner("owner", Some("developer")));
+        // Понижение владельца
+        assert!(touches_owner("maintainer", Some("owner")));
+        assert!(touches_owner("owner", Some("owner")));
+
+        assert!(!touches_owner("developer", None));
+        assert!(!touches_owner("guest", Some("maintainer")));
+    }
+}
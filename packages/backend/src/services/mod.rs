@@ -1,4 +1,23 @@
 //! Сервисный слой — orchestration по подсистемам
 
+pub mod activity;
 pub mod auth;
+pub mod deploy_keys;
+pub mod feeds;
+pub mod files;
+pub mod insights;
+pub mod maintenance;
+pub mod members;
+pub mod organizations;
+pub mod permissions;
+pub mod pipelines;
 pub mod projects;
+pub mod releases;
+pub mod search;
+pub mod secret_scanning;
+pub mod signatures;
+pub mod statuses;
+pub mod templates;
+pub mod traffic;
+pub mod watches;
+pub mod wiki;
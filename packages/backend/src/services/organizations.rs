@@ -0,0 +1,321 @@
+//! Организации: создание, участники и проекты
+//!
+//! Имя организации живёт в том же пространстве URL, что и имена
+//! пользователей, поэтому при создании проверяются обе таблицы. Владельцы и
+//! администраторы организации управляют её проектами и участниками; роль
+//! owner выдают и отнимают только владельцы, и последний владелец не может
+//! уйти или быть понижен.
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::organizations::{Organization, OrganizationMember, ORGANIZATION_ROLES};
+use crate::domain::projects::Project;
+use crate::domain::users::User;
+use crate::services::permissions::{self as permission_service, Permission};
+use crate::utils::helpers;
+
+// ============================================================================
+// ОРГАНИЗАЦИИ
+// ============================================================================
+
+/// Создаёт организацию; создатель становится её владельцем
+pub async fn create(
+    creator: &User,
+    name: &str,
+    display_name: Option<&str>,
+    description: Option<&str>,
+    db: &Database,
+) -> Result<Organization, AppError> {
+    if !helpers::is_valid_username(name) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Organization name must be 3-50 characters: letters, digits, '-' and '_'",
+        ));
+    }
+
+    let creator_id = user_id(creator)?;
+    let pool = db.get_pool();
+    if Organization::is_name_taken(name, pool)
+        .await
+        .map_err(database_error)?
+    {
+        return Err(AppError::new(
+            ErrorType::ConflictError,
+            "Name is already taken by a user or organization",
+        ));
+    }
+
+    let id = Organization::create(name, display_name, description, creator_id, pool)
+        .await
+        .map_err(database_error)?;
+    Organization::find_by_id(id, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| internal_error("Organization was not saved"))
+}
+
+/// Организация по имени
+pub async fn find(name: &str, db: &Database) -> Result<Organization, AppError> {
+    Organization::find_by_name(name, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Organization not found"))
+}
+
+/// Организации, в которых состоит пользователь
+pub async fn list_for_user(user: &User, db: &Database) -> Result<Vec<Organization>, AppError> {
+    Organization::find_for_user(user_id(user)?, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Роль пользователя в организации
+pub async fn member_role(
+    org: &Organization,
+    user: &User,
+    db: &Database,
+) -> Result<Option<String>, AppError> {
+    Organization::member_role(org.id, user_id(user)?, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Может ли пользователь создавать проекты и пакеты от имени организации
+pub async fn can_manage(org: &Organization, user: &User, db: &Database) -> Result<bool, AppError> {
+    let role = member_role(org, user, db).await?;
+    Ok(matches!(role.as_deref(), Some("owner" | "admin")))
+}
+
+/// Проекты организации, видимые читателю
+pub async fn projects(
+    org: &Organization,
+    viewer: Option<&User>,
+    db: &Database,
+) -> Result<Vec<Project>, AppError> {
+    let mut visible = Vec::new();
+    for project in Project::find_by_organization(org.id, db.get_pool())
+        .await
+        .map_err(database_error)?
+    {
+        let permission = permission_service::project_permission(&project, viewer, db)
+            .await
+            .map_err(database_error)?;
+        if permission >= Permission::Read {
+            visible.push(project);
+        }
+    }
+    Ok(visible)
+}
+
+// ============================================================================
+// УЧАСТНИКИ
+// ============================================================================
+
+/// Участники организации
+pub async fn members(
+    org: &Organization,
+    db: &Database,
+) -> Result<Vec<OrganizationMember>, AppError> {
+    Organization::members(org.id, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Добавляет участника организации или меняет его роль
+pub async fn set_member(
+    org: &Organization,
+    actor: &User,
+    username: &str,
+    role: &str,
+    db: &Database,
+) -> Result<OrganizationMember, AppError> {
+    if !ORGANIZATION_ROLES.contains(&role) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Role must be one of: owner, admin, member",
+        ));
+    }
+
+    let actor_role = member_role(org, actor, db).await?;
+    let user_id = find_user_id(username, db).await?;
+    let pool = db.get_pool();
+    let current = Organization::member_role(org.id, user_id, pool)
+        .await
+        .map_err(database_error)?;
+    check_manage(
+        actor_role.as_deref(),
+        touches_owner(Some(role), current.as_deref()),
+    )?;
+    if demotes_owner(Some(role), current.as_deref()) {
+        check_not_last_owner(org, db).await?;
+    }
+
+    Organization::upsert_member(org.id, user_id, role, pool)
+        .await
+        .map_err(database_error)?;
+    members(org, db)
+        .await?
+        .into_iter()
+        .find(|member| member.user_id == user_id)
+        .ok_or_else(|| internal_error("Member was not saved"))
+}
+
+/// Исключает участника; любой участник может покинуть организацию сам
+pub async fn remove_member(
+    org: &Organization,
+    actor: &User,
+    username: &str,
+    db: &Database,
+) -> Result<(), AppError> {
+    let user_id = find_user_id(username, db).await?;
+    let pool = db.get_pool();
+    let current = Organization::member_role(org.id, user_id, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Member not found"))?;
+
+    if actor.id != Some(user_id) {
+        let actor_role = member_role(org, actor, db).await?;
+        check_manage(actor_role.as_deref(), touches_owner(None, Some(&current)))?;
+    }
+    if demotes_owner(None, Some(&current)) {
+        check_not_last_owner(org, db).await?;
+    }
+
+    Organization::delete_member(org.id, user_id, pool)
+        .await
+        .map_err(database_error)?;
+    Ok(())
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Участниками управляют владельцы и администраторы, владельцами — только владельцы
+fn check_manage(actor_role: Option<&str>, touches_owner: bool) -> Result<(), AppError> {
+    match actor_role {
+        Some("owner") => Ok(()),
+        Some("admin") if !touches_owner => Ok(()),
+        Some("admin") => Err(AppError::new(
+            ErrorType::AuthorizationError,
+            "Only organization owners can manage owners",
+        )),
+        _ => Err(AppError::new(
+            ErrorType::AuthorizationError,
+            "Admin access is required to manage organization members",
+        )),
+    }
+}
+
+/// Затрагивает ли изменение владельца: выдачу роли owner, её снятие или
+/// исключение владельца (`role = None`)
+fn touches_owner(role: Option<&str>, current: Option<&str>) -> bool {
+    role == Some("owner") || current == Some("owner")
+}
+
+/// Лишается ли владелец роли owner: при понижении или исключении (`role = None`)
+fn demotes_owner(role: Option<&str>, current: Option<&str>) -> bool {
+    current == Some("owner") && role != Some("owner")
+}
+
+async fn check_not_last_owner(org: &Organization, db: &Database) -> Result<(), AppError> {
+    let owners = Organization::count_owners(org.id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    check_owners_left(owners)
+}
+
+/// У организации остаётся хотя бы один владелец, если `owners` лишится одного
+fn check_owners_left(owners: i64) -> Result<(), AppError> {
+    if owners <= 1 {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "An organization must keep at least one owner",
+        ));
+    }
+    Ok(())
+}
+
+async fn find_user_id(username: &str, db: &Database) -> Result<i64, AppError> {
+    let user = User::find_by_username(username, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "User not found"))?;
+    user_id(&user)
+}
+
+fn user_id(user: &User) -> Result<i64, AppError> {
+    user.id.ok_or_else(|| internal_error("User must have id"))
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn denied(actor_role: Option<&str>, touches_owner: bool) -> bool {
+        match check_manage(actor_role, touches_owner) {
+            Ok(()) => false,
+            Err(e) => {
+                assert!(matches!(e.error_type, ErrorType::AuthorizationError));
+                true
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_manage() {
+        // Владелец управляет всеми, в том числе другими владельцами
+        assert!(!denied(Some("owner"), false));
+        assert!(!denied(Some("owner"), true));
+        // Администратор управляет всеми, кроме владельцев
+        assert!(!denied(Some("admin"), false));
+        assert!(denied(Some("admin"), true));
+        // Рядовые участники и посторонние не управляют никем
+        assert!(denied(Some("member"), false));
+        assert!(denied(Some("member"), true));
+        assert!(denied(None, false));
+        assert!(denied(None, true));
+    }
+
+    #[test]
+    fn test_touches_owner() {
+        // Выдача роли owner
+        assert!(touches_owner(Some("owner"), None));
+        assert!(touches_owner(Some("owner"), Some("member")));
+        // Понижение и исключение владельца
+        assert!(touches_owner(Some("admin"), Some("owner")));
+        assert!(touches_owner(None, Some("owner")));
+
+        assert!(!touches_owner(Some("admin"), Some("member")));
+        assert!(!touches_owner(Some("member"), Some("admin")));
+        assert!(!touches_owner(None, Some("admin")));
+    }
+
+    #[test]
+    fn test_demotes_owner() {
+        assert!(demotes_owner(Some("admin"), Some("owner")));
+        assert!(demotes_owner(Some("member"), Some("owner")));
+        assert!(demotes_owner(None, Some("owner")));
+
+        assert!(!demotes_owner(Some("owner"), Some("owner")));
+        assert!(!demotes_owner(Some("owner"), Some("admin")));
+        assert!(!demotes_owner(None, Some("member")));
+    }
+
+    #[test]
+    fn test_check_owners_left() {
+        let error = check_owners_left(1).unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::ValidationError));
+        assert!(check_owners_left(0).is_err());
+        assert!(check_owners_left(2).is_ok());
+    }
+}
@@ -0,0 +1,399 @@
+//! Единая проверка прав доступа к проектам и репозиториям
+//!
+//! Владелец личного проекта — администратор всех его репозиториев; в
+//! проектах организации администраторы — её владельцы и администраторы, а
+//! остальные участники организации могут читать. Соавторам роль
+//! выдаётся на весь проект или на отдельный репозиторий, участникам проекта и
+//! командам — на весь проект; действует наибольшая.
+//! Публичный репозиторий публичного проекта доступен на чтение всем.
+//! В архивном проекте никто не получает прав больше, чем на чтение.
+
+use serde::Serialize;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::collaborators::{Collaborator, ROLES};
+use crate::domain::deploy_keys::DeployKey;
+use crate::domain::members::ProjectMember;
+use crate::domain::organizations::Organization;
+use crate::domain::projects::Project;
+use crate::domain::repos::Repository;
+use crate::domain::users::User;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Уровень доступа; варианты упорядочены по возрастанию прав
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    None,
+    /// Просмотр и клонирование
+    Read,
+    /// Push, редактирование файлов, статусы и релизы
+    Write,
+    /// Настройки и управление соавторами
+    Admin,
+}
+
+impl Permission {
+    fn from_role(role: &str) -> Permission {
+        match role {
+            "admin" => Permission::Admin,
+            "write" => Permission::Write,
+            "read" => Permission::Read,
+            _ => Permission::None,
+        }
+    }
+
+    /// Права участника проекта или команды по его роли
+    fn from_member_role(role: &str) -> Permission {
+        match role {
+            "owner" | "maintainer" => Permission::Admin,
+            "developer" => Permission::Write,
+            "reporter" | "guest" => Permission::Read,
+            _ => Permission::None,
+        }
+    }
+}
+
+/// Где выдаётся роль соавтора
+#[derive(Debug, Clone, Copy)]
+pub enum CollaboratorScope {
+    Project(i64),
+    Repository(i64),
+}
+
+// ============================================================================
+// ПРОВЕРКА ПРАВ
+// ============================================================================
+
+/// Права пользователя (или анонима) на проект
+pub async fn project_permission(
+    project: &Project,
+    user: Option<&User>,
+    db: &Database,
+) -> Result<Permission, sqlx::Error> {
+    Ok(read_only_if_archived(
+        project,
+        resolve(project, None, user, db).await?,
+    ))
+}
+
+/// Права пользователя (или анонима) на репозиторий проекта
+pub async fn repository_permission(
+    project: &Project,
+    repo: &Repository,
+    user: Option<&User>,
+    db: &Database,
+) -> Result<Permission, sqlx::Error> {
+    Ok(read_only_if_archived(
+        project,
+        resolve(project, Some(repo), user, db).await?,
+    ))
+}
+
+/// Права на проект без ограничения архива: администраторы архивного проекта
+/// по-прежнему могут вернуть его из архива, удалить или передать
+pub async fn management_permission(
+    project: &Project,
+    user: Option<&User>,
+    db: &Database,
+) -> Result<Permission, sqlx::Error> {
+    resolve(project, None, user, db).await
+}
+
+/// Репозитории проекта, доступные пользователю (или анониму) на чтение
+///
+/// Результат тот же, что у `repository_permission` для каждого репозитория,
+/// но роли пользователя загружаются один раз на весь список.
+pub async fn readable_repositories(
+    project: &Project,
+    repositories: Vec<Repository>,
+    user: Option<&User>,
+    db: &Database,
+) -> Result<Vec<Repository>, sqlx::Error> {
+    if granted(project, None, user, db).await? >= Permission::Read {
+        return Ok(repositories);
+    }
+
+    let repository_roles = match (project.id, user.and_then(|user| user.id)) {
+        (Some(project_id), Some(user_id)) => {
+            Collaborator::repository_roles_for_user(project_id, user_id, db.get_pool()).await?
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(repositories
+        .into_iter()
+        .filter(|repo| {
+            (project.is_public && repo.is_public)
+                || repository_roles.iter().any(|(repository_id, role)| {
+                    repo.id == Some(*repository_id)
+                        && Permission::from_role(role) >= Permission::Read
+                })
+        })
+        .collect())
+}
+
+/// Права ключа развёртывания на его репозиторий
+pub fn deploy_key_permission(key: &DeployKey) -> Permission {
+    if key.read_only {
+        Permission::Read
+    } else {
+        Permission::Write
+    }
+}
+
+/// Архивный проект доступен только для чтения
+fn read_only_if_archived(project: &Project, permission: Permission) -> Permission {
+    if project.is_archived() {
+        permission.min(Permission::Read)
+    } else {
+        permission
+    }
+}
+
+async fn resolve(
+    project: &Project,
+    repo: Option<&Repository>,
+    user: Option<&User>,
+    db: &Database,
+) -> Result<Permission, sqlx::Error> {
+    let public = project.is_public && repo.is_none_or(|repo| repo.is_public);
+    let base = if public {
+        Permission::Read
+    } else {
+        Permission::None
+    };
+
+    let granted = granted(project, repo.and_then(|repo| repo.id), user, db).await?;
+    Ok(base.max(granted))
+}
+
+/// Права, выданные пользователю: владение, организация и роли в проекте
+/// (и в репозитории `repository_id`), без доступа публичного проекта
+async fn granted(
+    project: &Project,
+    repository_id: Option<i64>,
+    user: Option<&User>,
+    db: &Database,
+) -> Result<Permission, sqlx::Error> {
+    let base = Permission::None;
+    let Some(user_id) = user.and_then(|user| user.id) else {
+        return Ok(base);
+    };
+    if project.personal_owner_id() == Some(user_id) {
+        return Ok(Permission::Admin);
+    }
+    let Some(project_id) = project.id else {
+        return Ok(base);
+    };
+
+    let organization_role = match project.organization_id {
+        Some(organization_id) => {
+            Organization::member_role(organization_id, user_id, db.get_pool()).await?
+        }
+        None => None,
+    };
+    let roles =
+        Collaborator::roles_for_user(project_id, repository_id, user_id, db.get_pool()).await?;
+    let member_roles = ProjectMember::roles_for_user(project_id, user_id, db.get_pool()).await?;
+
+    Ok(granted_by_roles(
+        project,
+        user_id,
+        organization_role.as_deref(),
+        &roles,
+        &member_roles,
+    ))
+}
+
+/// Права по уже загруженным ролям пользователя: роли в организации проекта,
+/// ролям соавтора и ролям участника проекта или его команд
+pub(crate) fn granted_by_roles(
+    project: &Project,
+    user_id: i64,
+    organization_role: Option<&str>,
+    roles: &[String],
+    member_roles: &[String],
+) -> Permission {
+    if project.personal_owner_id() == Some(user_id) {
+        return Permission::Admin;
+    }
+
+    // Владельцы и администраторы организации управляют всеми её проектами,
+    // остальные участники их читают
+    let base = match (project.organization_id, organization_role) {
+        (Some(_), Some("owner" | "admin")) => return Permission::Admin,
+        (Some(_), Some(_)) => Permission::Read,
+        _ => Permission::None,
+    };
+
+    roles
+        .iter()
+        .map(|role| Permission::from_role(role))
+        .chain(
+            member_roles
+                .iter()
+                .map(|role| Permission::from_member_role(role)),
+        )
+        .fold(base, Permission::max)
+}
+
+// ============================================================================
+// СОАВТОРЫ
+// ============================================================================
+
+/// Соавторы проекта или репозитория
+pub async fn list_collaborators(
+    scope: CollaboratorScope,
+    db: &Database,
+) -> Result<Vec<Collaborator>, AppError> {
+    match scope {
+        CollaboratorScope::Project(id) => Collaborator::find_by_project(id, db.get_pool()).await,
+        CollaboratorScope::Repository(id) => {
+            Collaborator::find_by_repository(id, db.get_pool()).await
+        }
+    }
+    .map_err(database_error)
+}
+
+/// Выдаёт пользователю роль; владельцу проекта роль не нужна
+pub async fn grant(
+    scope: CollaboratorScope,
+    project: &Project,
+    username: &str,
+    role: &str,
+    db: &Database,
+) -> Result<Collaborator, AppError> {
+    if !ROLES.contains(&role) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Role must be one of: read, write, admin",
+        ));
+    }
+
+    let user = User::find_by_username(username, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "User not found"))?;
+    let user_id = user
+        .id
+        .ok_or_else(|| internal_error("User must have id"))?;
+    if project.personal_owner_id() == Some(user_id) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Project owner already has full access",
+        ));
+    }
+
+    match scope {
+        CollaboratorScope::Project(id) => {
+            Collaborator::upsert_for_project(id, user_id, role, db.get_pool()).await
+        }
+        CollaboratorScope::Repository(id) => {
+            Collaborator::upsert_for_repository(id, user_id, role, db.get_pool()).await
+        }
+    }
+    .map_err(database_error)?;
+
+    list_collaborators(scope, db)
+        .await?
+        .into_iter()
+        .find(|collaborator| collaborator.user_id == user_id)
+        .ok_or_else(|| internal_error("Collaborator was not saved"))
+}
+
+/// Отзывает роль пользователя
+pub async fn revoke(
+    scope: CollaboratorScope,
+    username: &str,
+    db: &Database,
+) -> Result<(), AppError> {
+    let user = User::find_by_username(username, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "User not found"))?;
+    let user_id = user
+        .id
+        .ok_or_else(|| internal_error("User must have id"))?;
+
+    let removed = match scope {
+        CollaboratorScope::Project(id) => {
+            Collaborator::delete_from_project(id, user_id, db.get_pool()).await
+        }
+        CollaboratorScope::Repository(id) => {
+            Collaborator::delete_from_repository(id, user_id, db.get_pool()).await
+        }
+    }
+    .map_err(database_error)?;
+
+    if removed {
+        Ok(())
+    } else {
+        Err(AppError::new(
+            ErrorType::NotFoundError,
+            "Collaborator not found",
+        ))
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_ordering() {
+        assert!(Permission::None < Permission::Read);
+        assert!(Permission::Read < Permission::Write);
+        assert!(Permission::Write < Permission::Admin);
+        assert_eq!(Permission::Read.max(Permission::Write), Permission::Write);
+        assert_eq!(Permission::Admin.min(Permission::Read), Permission::Read);
+    }
+
+    #[test]
+    fn test_from_role() {
+        assert_eq!(Permission::from_role("read"), Permission::Read);
+        assert_eq!(Permission::from_role("write"), Permission::Write);
+        assert_eq!(Permission::from_role("admin"), Permission::Admin);
+
+        for role in ["", "owner", "Admin", "WRITE", " read"] {
+            assert_eq!(Permission::from_role(role), Permission::None, "{:?}", role);
+        }
+        for role in ROLES {
+            assert!(Permission::from_role(role) > Permission::None, "{}", role);
+        }
+    }
+
+    #[test]
+    fn test_from_member_role() {
+        assert_eq!(Permission::from_member_role("owner"), Permission::Admin);
+        assert_eq!(Permission::from_member_role("maintainer"), Permission::Admin);
+        assert_eq!(Permission::from_member_role("developer"), Permission::Write);
+        assert_eq!(Permission::from_member_role("reporter"), Permission::Read);
+        assert_eq!(Permission::from_member_role("guest"), Permission::Read);
+
+        for role in ["", "admin", "write", "Owner", "guest "] {
+            assert_eq!(
+                Permission::from_member_role(role),
+                Permission::None,
+                "{:?}",
+                role
+            );
+        }
+    }
+}
@@ -0,0 +1,753 @@
+//! Встроенный раннер пайплайнов
+//!
+//! Если в конфигурации проекта включён раздел `pipelines`, при push в ветку
+//! пользователем с правом записи читает `.netbit/pipeline.toml` из нового
+//! коммита, создаёт временную рабочую копию и выполняет шаги локальными
+//! процессами по очереди от имени непривилегированного пользователя
+//! (`PIPELINE_USER`). Результат публикуется статусом коммита с контекстом
+//! `netbit/pipeline`.
+//!
+//! ```toml
+//! # Необязательно: ветки, для которых запускается пайплайн
+//! branches = ["main"]
+//!
+//! [[steps]]
+//! name = "test"
+//! run = "cargo test"
+//! timeout = 900 # секунды, по умолчанию 600
+//! ```
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::os::unix::fs::lchown;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::pipelines::{
+    PipelineRun, PipelineStep, RUN_ERROR, RUN_FAILED, RUN_SUCCESS, STEP_FAILED, STEP_SUCCESS,
+    STEP_TIMED_OUT,
+};
+use crate::domain::projects::{Project, ProjectConfig};
+use crate::domain::repos::Repository;
+use crate::domain::statuses::CommitStatus;
+use crate::domain::users::User;
+use crate::services::permissions::{self, Permission};
+use crate::utils::git::{self, RefUpdate};
+
+/// Файл описания пайплайна в репозитории
+const PIPELINE_FILE: &str = ".netbit/pipeline.toml";
+/// Контекст статуса коммита, который выставляет раннер
+const STATUS_CONTEXT: &str = "netbit/pipeline";
+const DEFAULT_STEP_TIMEOUT_SECS: u64 = 600;
+const MAX_STEP_TIMEOUT_SECS: u64 = 6 * 3600;
+const MAX_STEPS: usize = 50;
+/// Лог шага обрезается до этого размера
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Раннер создаётся при старте сервера, если пайплайны включены
+static RUNNER: OnceLock<Runner> = OnceLock::new();
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+struct Runner {
+    db: Database,
+    /// Ограничивает число одновременно выполняемых пайплайнов
+    semaphore: Arc<Semaphore>,
+    account: StepAccount,
+}
+
+/// Учётная запись, от имени которой выполняются шаги
+#[derive(Debug, Clone, Copy)]
+struct StepAccount {
+    uid: u32,
+    gid: u32,
+    /// Сервер работает от root и переключается на `uid` при запуске шага
+    switch: bool,
+}
+
+/// Содержимое `.netbit/pipeline.toml`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PipelineConfig {
+    /// Пустой список — пайплайн запускается для всех веток
+    #[serde(default)]
+    branches: Vec<String>,
+    #[serde(default)]
+    steps: Vec<StepConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StepConfig {
+    name: String,
+    /// Команда для `sh -c`
+    run: String,
+    /// Таймаут в секундах
+    timeout: Option<u64>,
+}
+
+/// Запуск пайплайна с шагами
+#[derive(Debug, Serialize)]
+pub struct PipelineRunDetails {
+    #[serde(flatten)]
+    pub run: PipelineRun,
+    pub steps: Vec<PipelineStep>,
+}
+
+/// Результат выполнения шага
+struct StepOutcome {
+    status: &'static str,
+    exit_code: Option<i32>,
+    log: String,
+}
+
+// ============================================================================
+// ЗАПУСК
+// ============================================================================
+
+/// Включает раннер; `concurrency = 0` отключает пайплайны
+///
+/// Шаги выполняются от имени `user`; если переключиться на него нельзя,
+/// пайплайны остаются выключенными. Запуски, не завершённые до перезапуска
+/// сервера, помечаются как прерванные.
+pub async fn init(db: Database, concurrency: usize, user: &str) {
+    if concurrency == 0 {
+        info!("Pipelines are disabled");
+        return;
+    }
+    let account = match resolve_account(user) {
+        Ok(account) => account,
+        Err(e) => {
+            error!("Pipelines are disabled: {}", e);
+            return;
+        }
+    };
+
+    match PipelineRun::abort_unfinished("Interrupted by server restart", db.get_pool()).await {
+        Ok(0) => {}
+        Ok(count) => warn!("Marked {} interrupted pipeline runs as failed", count),
+        Err(e) => error!("Failed to clean up interrupted pipeline runs: {}", e),
+    }
+
+    let _ = RUNNER.set(Runner {
+        db,
+        semaphore: Arc::new(Semaphore::new(concurrency)),
+        account,
+    });
+}
+
+/// Находит пользователя для шагов и проверяет, что сервер может им стать
+fn resolve_account(user: &str) -> Result<StepAccount, String> {
+    let id = |args: &[&str]| -> Result<u32, String> {
+        let output = std::process::Command::new("id")
+            .args(args)
+            .output()
+            .map_err(|e| format!("failed to run id: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("user {} not found", user));
+        }
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .map_err(|_| format!("unexpected id output for {}", user))
+    };
+
+    let uid = id(&["-u", "--", user])?;
+    let gid = id(&["-g", "--", user])?;
+    if uid == 0 {
+        return Err("PIPELINE_USER must not be root".to_string());
+    }
+
+    let current = id(&["-u"])?;
+    if current != 0 && current != uid {
+        return Err(format!(
+            "the server must run as root to run steps as {}",
+            user
+        ));
+    }
+
+    Ok(StepAccount {
+        uid,
+        gid,
+        switch: current == 0,
+    })
+}
+
+/// Ставит в очередь пайплайны для веток, обновлённых push'ем
+pub fn schedule_push_pipelines(
+    repo_name: String,
+    updates: Vec<RefUpdate>,
+    trigger_user_id: Option<i64>,
+) {
+    let Some(runner) = RUNNER.get() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let repo = match Repository::find_by_name(&repo_name, runner.db.get_pool()).await {
+            Ok(Some(repo)) => repo,
+            Ok(None) => return,
+            Err(e) => {
+                error!(
+                    "Failed to load repository {} for pipelines: {}",
+                    repo_name, e
+                );
+                return;
+            }
+        };
+        match pipelines_allowed(&repo, trigger_user_id, &runner.db).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                error!("Failed to check pipelines for {}: {:?}", repo_name, e);
+                return;
+            }
+        }
+
+        for update in updates {
+            if !update.ref_name.starts_with("refs/heads/") || update.new_sha == git::ZERO_SHA {
+                continue;
+            }
+            if let Err(e) = enqueue_run(runner, &repo, update, trigger_user_id).await {
+                error!("Failed to start pipeline for {}: {:?}", repo_name, e);
+            }
+        }
+    });
+}
+
+/// Пайплайны включены в проекте, и автор push может писать в репозиторий
+///
+/// Push без пользователя (ключом развёртывания) пайплайны не запускает.
+async fn pipelines_allowed(
+    repo: &Repository,
+    trigger_user_id: Option<i64>,
+    db: &Database,
+) -> Result<bool, AppError> {
+    let pool = db.get_pool();
+    let config = ProjectConfig::load(repo.project_id, pool)
+        .await
+        .map_err(database_error)?;
+    if !config.pipelines.enabled {
+        return Ok(false);
+    }
+
+    let Some(user_id) = trigger_user_id else {
+        return Ok(false);
+    };
+    let Some(user) = User::find_by_id(user_id, pool)
+        .await
+        .map_err(database_error)?
+    else {
+        return Ok(false);
+    };
+    let Some(project) = Project::find_by_id(repo.project_id, pool)
+        .await
+        .map_err(database_error)?
+    else {
+        return Ok(false);
+    };
+
+    let permission = permissions::repository_permission(&project, repo, Some(&user), db)
+        .await
+        .map_err(database_error)?;
+    Ok(permission >= Permission::Write)
+}
+
+/// Создаёт запуск для обновлённой ветки и передаёт его на выполнение
+async fn enqueue_run(
+    runner: &'static Runner,
+    repo: &Repository,
+    update: RefUpdate,
+    trigger_user_id: Option<i64>,
+) -> Result<(), AppError> {
+    let repository_id = repo
+        .id
+        .ok_or_else(|| internal_error("Repository must have id"))?;
+    let repo_path = git::repository_path(&repo.name);
+
+    // Push мог быть частично отклонён — запускаем только принятые обновления
+    let (current_sha, config) = {
+        let repo_path = repo_path.clone();
+        let update = update.clone();
+        tokio::task::spawn_blocking(move || {
+            let current_sha = git::resolve_revision(&repo_path, &update.ref_name);
+            let config = git::read_file(&repo_path, &update.new_sha, PIPELINE_FILE);
+            (current_sha, config)
+        })
+        .await
+        .map_err(|e| internal_error(&format!("Pipeline task failed: {}", e)))?
+    };
+    if current_sha.as_deref() != Some(update.new_sha.as_str()) {
+        return Ok(());
+    }
+    let Some(config) = config.map_err(|e| internal_error(&e))? else {
+        return Ok(());
+    };
+
+    let branch = update.ref_name.trim_start_matches("refs/heads/");
+    let db = &runner.db;
+    let config = match parse_config(&config) {
+        Ok(config) => config,
+        Err(message) => {
+            // Ошибку конфигурации показываем как неудачный запуск
+            let run = PipelineRun::create(
+                repository_id,
+                &update.new_sha,
+                &update.ref_name,
+                trigger_user_id,
+                db.get_pool(),
+            )
+            .await
+            .map_err(database_error)?;
+            PipelineRun::finish(run.id, RUN_ERROR, Some(&message), db.get_pool())
+                .await
+                .map_err(database_error)?;
+            set_commit_status(&run, "error", &message, db).await;
+            return Ok(());
+        }
+    };
+    if !config.branches.is_empty() && !config.branches.iter().any(|b| b == branch) {
+        return Ok(());
+    }
+
+    let run = PipelineRun::create(
+        repository_id,
+        &update.new_sha,
+        &update.ref_name,
+        trigger_user_id,
+        db.get_pool(),
+    )
+    .await
+    .map_err(database_error)?;
+    let steps: Vec<(String, String, i32)> = config
+        .steps
+        .into_iter()
+        .map(|step| {
+            let timeout = step.timeout.unwrap_or(DEFAULT_STEP_TIMEOUT_SECS);
+            (step.name, step.run, timeout as i32)
+        })
+        .collect();
+    let steps = PipelineStep::create_all(run.id, &steps, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    set_commit_status(&run, "pending", "Pipeline is queued", db).await;
+
+    info!(
+        "Pipeline #{} queued for {} at {}",
+        run.id, repo.name, update.new_sha
+    );
+
+    let repo_name = repo.name.clone();
+    tokio::spawn(async move {
+        let Ok(_permit) = runner.semaphore.acquire().await else {
+            return;
+        };
+        execute_run(&run, steps, &repo_name, repo_path, runner).await;
+    });
+
+    Ok(())
+}
+
+/// Разбирает и проверяет конфигурацию пайплайна
+fn parse_config(content: &[u8]) -> Result<PipelineConfig, String> {
+    let content = std::str::from_utf8(content)
+        .map_err(|_| format!("{} is not valid UTF-8", PIPELINE_FILE))?;
+    let config: PipelineConfig =
+        toml::from_str(content).map_err(|e| format!("Invalid {}: {}", PIPELINE_FILE, e))?;
+
+    if config.steps.is_empty() {
+        return Err(format!("{} declares no steps", PIPELINE_FILE));
+    }
+    if config.steps.len() > MAX_STEPS {
+        return Err(format!(
+            "Pipeline cannot have more than {} steps",
+            MAX_STEPS
+        ));
+    }
+    for step in &config.steps {
+        if step.name.trim().is_empty() || step.run.trim().is_empty() {
+            return Err("Every step must have a name and a run command".to_string());
+        }
+        if let Some(timeout) = step.timeout {
+            if !(1..=MAX_STEP_TIMEOUT_SECS).contains(&timeout) {
+                return Err(format!(
+                    "Step timeout must be between 1 and {} seconds",
+                    MAX_STEP_TIMEOUT_SECS
+                ));
+            }
+        }
+    }
+
+    Ok(config)
+}
+
+// ============================================================================
+// ВЫПОЛНЕНИЕ
+// ============================================================================
+
+/// Выполняет шаги запуска в рабочей копии коммита
+async fn execute_run(
+    run: &PipelineRun,
+    steps: Vec<PipelineStep>,
+    repo_name: &str,
+    repo_path: String,
+    runner: &Runner,
+) {
+    let db = &runner.db;
+    if let Err(e) = PipelineRun::mark_running(run.id, db.get_pool()).await {
+        error!("Failed to update pipeline #{}: {}", run.id, e);
+    }
+    set_commit_status(run, "pending", "Pipeline is running", db).await;
+
+    let (status, error) = match run_steps(run, &steps, repo_name, repo_path, runner).await {
+        Ok(true) => (RUN_SUCCESS, None),
+        Ok(false) => (RUN_FAILED, None),
+        Err(e) => {
+            error!("Pipeline #{} failed to run: {}", run.id, e);
+            (RUN_ERROR, Some(e))
+        }
+    };
+
+    if let Err(e) = PipelineStep::skip_pending(run.id, db.get_pool()).await {
+        error!("Failed to update pipeline #{} steps: {}", run.id, e);
+    }
+    if let Err(e) = PipelineRun::finish(run.id, status, error.as_deref(), db.get_pool()).await {
+        error!("Failed to update pipeline #{}: {}", run.id, e);
+    }
+
+    let (state, description) = match status {
+        RUN_SUCCESS => ("success", "Pipeline succeeded"),
+        RUN_FAILED => ("failure", "Pipeline failed"),
+        _ => ("error", "Pipeline could not be run"),
+    };
+    set_commit_status(run, state, description, db).await;
+    info!("Pipeline #{} finished: {}", run.id, status);
+}
+
+/// Создаёт рабочую копию и выполняет шаги до первой ошибки
+///
+/// Возвращает `true`, если все шаги завершились успешно.
+async fn run_steps(
+    run: &PipelineRun,
+    steps: &[PipelineStep],
+    repo_name: &str,
+    repo_path: String,
+    runner: &Runner,
+) -> Result<bool, String> {
+    let db = &runner.db;
+    let account = runner.account;
+    let workspace =
+        tempfile::tempdir().map_err(|e| format!("Failed to create workspace: {}", e))?;
+    let worktree = workspace.path().join("worktree");
+
+    {
+        let repo_path = repo_path.clone();
+        let worktree = worktree.clone();
+        let sha = run.commit_sha.clone();
+        let workspace = workspace.path().to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            git::add_worktree(&repo_path, &worktree, &sha)?;
+            // Рабочая копия и домашний каталог принадлежат пользователю шагов
+            if account.switch {
+                chown_tree(&workspace, account)
+                    .map_err(|e| format!("Failed to prepare workspace: {}", e))?;
+            }
+            Ok::<_, String>(())
+        })
+        .await
+        .map_err(|e| format!("Pipeline task failed: {}", e))??;
+    }
+
+    let env = [
+        ("CI", "true".to_string()),
+        ("NETBIT_COMMIT_SHA", run.commit_sha.clone()),
+        ("NETBIT_REF", run.ref_name.clone()),
+        ("NETBIT_REPOSITORY", repo_name.to_string()),
+    ];
+
+    let mut succeeded = true;
+    for step in steps {
+        if let Err(e) = PipelineStep::mark_running(step.id, db.get_pool()).await {
+            error!("Failed to update pipeline step {}: {}", step.id, e);
+        }
+
+        let log_path = workspace.path().join(format!("step-{}.log", step.position));
+        let outcome = run_step(step, &worktree, workspace.path(), &log_path, &env, account).await;
+
+        if let Err(e) = PipelineStep::finish(
+            step.id,
+            outcome.status,
+            outcome.exit_code,
+            &outcome.log,
+            db.get_pool(),
+        )
+        .await
+        {
+            error!("Failed to update pipeline step {}: {}", step.id, e);
+        }
+
+        if outcome.status != STEP_SUCCESS {
+            succeeded = false;
+            break;
+        }
+    }
+
+    let _ = tokio::task::spawn_blocking(move || git::remove_worktree(&repo_path, &worktree)).await;
+
+    Ok(succeeded)
+}
+
+/// Передаёт каталог со всем содержимым пользователю шагов
+fn chown_tree(path: &Path, account: StepAccount) -> std::io::Result<()> {
+    lchown(path, Some(account.uid), Some(account.gid))?;
+    if path.symlink_metadata()?.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            chown_tree(&entry?.path(), account)?;
+        }
+    }
+    Ok(())
+}
+
+/// Выполняет команду шага через `sh -c` с таймаутом
+///
+/// Окружение сервера не передаётся, чтобы шаги не видели его секретов, а сам
+/// процесс запускается от имени непривилегированного пользователя.
+async fn run_step(
+    step: &PipelineStep,
+    worktree: &Path,
+    home: &Path,
+    log_path: &Path,
+    env: &[(&str, String)],
+    account: StepAccount,
+) -> StepOutcome {
+    let failed = |log: String| StepOutcome {
+        status: STEP_FAILED,
+        exit_code: None,
+        log,
+    };
+
+    let (stdout, stderr) = match File::create(log_path).and_then(|f| Ok((f.try_clone()?, f))) {
+        Ok(files) => files,
+        Err(e) => return failed(format!("Failed to create log file: {}", e)),
+    };
+
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(&step.command)
+        .current_dir(worktree)
+        .env_clear()
+        .env(
+            "PATH",
+            std::env::var("PATH").unwrap_or_else(|_| "/usr/local/bin:/usr/bin:/bin".to_string()),
+        )
+        .env("HOME", home)
+        .env("LANG", "C.UTF-8")
+        .envs(env.iter().map(|(key, value)| (*key, value.as_str())))
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(stdout))
+        .stderr(Stdio::from(stderr))
+        // Отдельная группа процессов, чтобы по таймауту завершить и потомков
+        .process_group(0)
+        .kill_on_drop(true);
+    if account.switch {
+        // Дополнительные группы root сбрасываются при смене пользователя
+        command.uid(account.uid).gid(account.gid);
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => return failed(format!("Failed to start step: {}", e)),
+    };
+
+    let timeout = Duration::from_secs(step.timeout_secs.max(1) as u64);
+    let (status, exit_code) = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(exit)) if exit.success() => (STEP_SUCCESS, exit.code()),
+        Ok(Ok(exit)) => (STEP_FAILED, exit.code()),
+        Ok(Err(e)) => return failed(format!("Failed to wait for step: {}", e)),
+        Err(_) => {
+            if let Some(pid) = child.id() {
+                let _ = Command::new("kill")
+                    .args(["-KILL", "--", &format!("-{}", pid)])
+                    .status()
+                    .await;
+            }
+            let _ = child.wait().await;
+            (STEP_TIMED_OUT, None)
+        }
+    };
+
+    let mut log = read_log(log_path);
+    if status == STEP_TIMED_OUT {
+        log.push_str(&format!(
+            "\n[netbit] Step timed out after {} seconds\n",
+            timeout.as_secs()
+        ));
+    }
+
+    StepOutcome {
+        status,
+        exit_code,
+        log,
+    }
+}
+
+/// Читает лог шага, обрезая его до `MAX_LOG_BYTES`
+fn read_log(log_path: &Path) -> String {
+    let mut buffer = Vec::new();
+    let truncated = match File::open(log_path) {
+        Ok(file) => {
+            let _ = file.take(MAX_LOG_BYTES + 1).read_to_end(&mut buffer);
+            buffer.len() as u64 > MAX_LOG_BYTES
+        }
+        Err(_) => false,
+    };
+    buffer.truncate(MAX_LOG_BYTES as usize);
+
+    let mut log = String::from_utf8_lossy(&buffer).into_owned();
+    if truncated {
+        log.push_str("\n[netbit] Log truncated\n");
+    }
+    log
+}
+
+// ============================================================================
+// ПРОСМОТР
+// ============================================================================
+
+/// Запуски репозитория, новые первыми
+pub async fn list_runs(
+    repo: &Repository,
+    page: i64,
+    per_page: i64,
+    db: &Database,
+) -> Result<Vec<PipelineRun>, AppError> {
+    let repository_id = repo
+        .id
+        .ok_or_else(|| internal_error("Repository must have id"))?;
+
+    PipelineRun::find_by_repository(
+        repository_id,
+        per_page,
+        (page - 1) * per_page,
+        db.get_pool(),
+    )
+    .await
+    .map_err(database_error)
+}
+
+/// Запуск вместе с шагами и их логами
+pub async fn get_run(
+    repo: &Repository,
+    run_id: i64,
+    db: &Database,
+) -> Result<PipelineRunDetails, AppError> {
+    let repository_id = repo
+        .id
+        .ok_or_else(|| internal_error("Repository must have id"))?;
+
+    let run = PipelineRun::find_by_id(run_id, repository_id, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Pipeline run not found"))?;
+    let steps = PipelineStep::find_by_run(run.id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+
+    Ok(PipelineRunDetails { run, steps })
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Выставляет статус коммита от имени раннера
+async fn set_commit_status(run: &PipelineRun, state: &str, description: &str, db: &Database) {
+    let status = CommitStatus {
+        id: None,
+        repository_id: run.repository_id,
+        commit_sha: run.commit_sha.clone(),
+        state: state.to_string(),
+        context: STATUS_CONTEXT.to_string(),
+        description: Some(format!("#{}: {}", run.id, description)),
+        target_url: None,
+        creator_id: run.trigger_user_id,
+        created_at: None,
+        updated_at: None,
+    };
+
+    if let Err(e) = status.upsert(db.get_pool()).await {
+        error!("Failed to set status for pipeline #{}: {}", run.id, e);
+    }
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = parse_config(
+            br#"
+branches = ["main"]
+
+[[steps]]
+name = "build"
+run = "cargo build"
+
+[[steps]]
+name = "test"
+run = "cargo test"
+timeout = 900
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.branches, vec!["main"]);
+        assert_eq!(config.steps.len(), 2);
+        assert_eq!(config.steps[0].run, "cargo build");
+        assert_eq!(config.steps[0].timeout, None);
+        assert_eq!(config.steps[1].timeout, Some(900));
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        let invalid: [&[u8]; 7] = [
+            b"\xff\xfe",
+            b"steps = 1",
+            b"branches = []",
+            b"[[steps]]\nname = \"x\"\nrun = \"true\"\nimage = \"alpine\"",
+            b"[[steps]]\nname = \" \"\nrun = \"true\"",
+            b"[[steps]]\nname = \"x\"\nrun = \"true\"\ntimeout = 0",
+            b"[[steps]]\nname = \"x\"\nrun = \"true\"\ntimeout = 999999",
+        ];
+        for content in invalid {
+            assert!(
+                parse_config(content).is_err(),
+                "{}",
+                String::from_utf8_lossy(content)
+            );
+        }
+
+        let too_many = "[[steps]]\nname = \"x\"\nrun = \"true\"\n".repeat(MAX_STEPS + 1);
+        assert!(parse_config(too_many.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,448 @@
+//! Конфигурация проекта: схема, проверка, ревизии и откат
+//!
+//! Обновления принимаются как произвольный JSON и сначала проверяются по
+//! схеме, которую API отдаёт клиентам, затем — правилами, которые схемой не
+//! выразить. Каждое сохранение создаёт новую ревизию; откат тоже записывается
+//! новой ревизией со ссылкой на восстановленную, так что история не теряется.
+//!
+//! Здесь же push и коммиты через API файлов проверяются на соответствие
+//! правилам защиты веток.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::events::EVENT_CONFIG_CHANGED;
+use crate::domain::projects::{ConfigRevision, Project, ProjectConfig, PushAccess};
+use crate::domain::repos::Repository;
+use crate::domain::users::User;
+use crate::services::activity as activity_service;
+use crate::services::permissions::Permission;
+use crate::utils::git;
+use crate::utils::json::{self, JsonChange};
+
+/// Больше правил защиты веток в одном проекте не сохраняется
+const MAX_BRANCH_RULES: u64 = 50;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Сохранённая ревизия конфигурации
+#[derive(Debug, Serialize)]
+pub struct SavedConfig {
+    pub revision: i32,
+    pub config: ProjectConfig,
+}
+
+/// Ревизия с конфигурацией и изменениями относительно предыдущей
+#[derive(Debug, Serialize)]
+pub struct RevisionDetails {
+    #[serde(flatten)]
+    pub revision: ConfigRevision,
+    pub config: ProjectConfig,
+    pub changes: Vec<JsonChange>,
+}
+
+// ============================================================================
+// СХЕМА
+// ============================================================================
+
+/// JSON Schema конфигурации проекта
+pub fn schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "NetBit project configuration",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "metadata": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "tags": {
+                        "type": "array",
+                        "maxItems": 20,
+                        "items": {"type": "string", "minLength": 1, "maxLength": 50}
+                    }
+                }
+            },
+            "security": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "secret_scanning": {"enum": ["off", "notify", "reject"], "default": "notify"}
+                }
+            },
+            "default_branch": {
+                "type": "string",
+                "minLength": 1,
+                "maxLength": 100,
+                "pattern": "^[A-Za-z0-9_][A-Za-z0-9._/-]*$",
+                "default": "main"
+            },
+            "visibility": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "repositories": {
+                        "enum": ["inherit", "public", "private"],
+                        "default": "inherit"
+                    }
+                }
+            },
+            "branch_rules": {
+                "type": "array",
+                "maxItems": MAX_BRANCH_RULES,
+                "items": {
+                    "type": "object",
+                    "required": ["pattern"],
+                    "additionalProperties": false,
+                    "properties": {
+                        "pattern": {"type": "string", "minLength": 1, "maxLength": 100},
+                        "allow_force_push": {"type": "boolean", "default": false},
+                        "allow_deletion": {"type": "boolean", "default": false},
+                        "push_access": {"enum": ["write", "admin"], "default": "write"}
+                    }
+                }
+            },
+            "notifications": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "secret_findings": {"type": "boolean", "default": true},
+                    "recipients": {
+                        "type": "array",
+                        "maxItems": 20,
+                        "items": {"type": "string"}
+                    }
+                }
+            },
+            "spark": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "enabled": {"type": "boolean", "default": false},
+                    "package": {"type": ["string", "null"], "minLength": 1, "maxLength": 100},
+                    "tag_pattern": {"type": "string", "minLength": 1, "default": "v*"}
+                }
+            },
+            "pipelines": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "enabled": {"type": "boolean", "default": false}
+                }
+            }
+        }
+    })
+}
+
+// ============================================================================
+// ЧТЕНИЕ И ИЗМЕНЕНИЕ
+// ============================================================================
+
+/// Текущая конфигурация проекта
+pub async fn load(project: &Project, db: &Database) -> Result<ProjectConfig, AppError> {
+    ProjectConfig::load(project_id(project)?, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Проверяет конфигурацию и сохраняет её новой ревизией
+pub async fn update(
+    project: &Project,
+    author: &User,
+    raw: &Value,
+    db: &Database,
+) -> Result<SavedConfig, AppError> {
+    let errors = json::validate(&schema(), raw);
+    if !errors.is_empty() {
+        let details: Vec<String> = errors
+            .iter()
+            .map(|error| format!("{}: {}", display_path(&error.path), error.message))
+            .collect();
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            &format!("Invalid configuration: {}", details.join("; ")),
+        ));
+    }
+    let config: ProjectConfig = serde_json::from_value(raw.clone()).map_err(|e| {
+        AppError::new(
+            ErrorType::ValidationError,
+            &format!("Invalid configuration: {}", e),
+        )
+    })?;
+    check_config(&config, db).await?;
+
+    save(project, author, config, None, db).await
+}
+
+/// Записывает конфигурацию ревизии `revision` новой ревизией
+pub async fn rollback(
+    project: &Project,
+    author: &User,
+    revision: i32,
+    db: &Database,
+) -> Result<SavedConfig, AppError> {
+    let config = load_revision(project_id(project)?, revision, db).await?;
+    save(project, author, config, Some(revision), db).await
+}
+
+// ============================================================================
+// РЕВИЗИИ
+// ============================================================================
+
+/// История ревизий, новые первыми
+pub async fn revisions(project: &Project, db: &Database) -> Result<Vec<ConfigRevision>, AppError> {
+    ConfigRevision::find_by_project(project_id(project)?, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Ревизия с конфигурацией и изменениями относительно предыдущей
+pub async fn revision(
+    project: &Project,
+    revision: i32,
+    db: &Database,
+) -> Result<RevisionDetails, AppError> {
+    let id = project_id(project)?;
+    let info = ConfigRevision::find(id, revision, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(revision_not_found)?;
+    let config = load_revision(id, revision, db).await?;
+    let previous = if revision > 1 {
+        ProjectConfig::load_revision(id, revision - 1, db.get_pool())
+            .await
+            .map_err(database_error)?
+    } else {
+        None
+    };
+
+    let changes = json::diff(
+        &to_value(&previous.unwrap_or_default())?,
+        &to_value(&config)?,
+    );
+    Ok(RevisionDetails {
+        revision: info,
+        config,
+        changes,
+    })
+}
+
+/// Изменения между двумя ревизиями
+pub async fn diff(
+    project: &Project,
+    from: i32,
+    to: i32,
+    db: &Database,
+) -> Result<Vec<JsonChange>, AppError> {
+    let id = project_id(project)?;
+    let old = load_revision(id, from, db).await?;
+    let new = load_revision(id, to, db).await?;
+    Ok(json::diff(&to_value(&old)?, &to_value(&new)?))
+}
+
+// ============================================================================
+// ЗАЩИТА ВЕТОК
+// ============================================================================
+
+/// Проверяет push в репозиторий `repo_name` по правилам защиты веток
+///
+/// Возвращает строки объяснения для git клиента; пустой список означает, что
+/// push разрешён. `permission` — уровень доступа автора push.
+pub async fn check_push(
+    repo_name: &str,
+    body: &[u8],
+    permission: Permission,
+    db: &Database,
+) -> Result<Vec<String>, AppError> {
+    let pool = db.get_pool();
+    let Some(repository) = Repository::find_by_name(repo_name, pool)
+        .await
+        .map_err(database_error)?
+    else {
+        return Ok(Vec::new());
+    };
+    let config = ProjectConfig::load(repository.project_id, pool)
+        .await
+        .map_err(database_error)?;
+    if config.branch_rules.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut violations = Vec::new();
+    let mut no_force_push = Vec::new();
+    for update in git::parse_push_commands(body) {
+        let Some(branch) = update.ref_name.strip_prefix("refs/heads/") else {
+            continue;
+        };
+        let Some(rule) = config.branch_rule(branch) else {
+            continue;
+        };
+        if rule.push_access == PushAccess::Admin && permission < Permission::Admin {
+            violations.push(format!("  {}: admin access is required to push", branch));
+        } else if update.new_sha == git::ZERO_SHA {
+            if !rule.allow_deletion {
+                violations.push(format!("  {}: deleting the branch is not allowed", branch));
+            }
+        } else if !rule.allow_force_push {
+            no_force_push.push(update.ref_name.clone());
+        }
+    }
+
+    if !no_force_push.is_empty() {
+        let repo_path = git::repository_path(repo_name);
+        let body = body.to_vec();
+        let forced = tokio::task::spawn_blocking(move || git::forced_updates(&repo_path, &body))
+            .await
+            .map_err(|e| internal_error(&e.to_string()))?
+            .map_err(|e| internal_error(&e))?;
+        for ref_name in forced.iter().filter(|name| no_force_push.contains(name)) {
+            violations.push(format!(
+                "  {}: force push is not allowed",
+                ref_name.trim_start_matches("refs/heads/")
+            ));
+        }
+    }
+
+    if violations.is_empty() {
+        return Ok(violations);
+    }
+    let mut lines = vec!["Push rejected: protected branch rules were violated".to_string()];
+    lines.extend(violations);
+    Ok(lines)
+}
+
+/// Проверяет коммит через API файлов в ветку `branch` репозитория
+///
+/// Такой коммит всегда продолжает ветку и не удаляет её, поэтому из правил
+/// применяется только требование прав администратора.
+pub async fn check_commit(
+    repository: &Repository,
+    branch: &str,
+    permission: Permission,
+    db: &Database,
+) -> Result<(), AppError> {
+    let config = ProjectConfig::load(repository.project_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    match config.branch_rule(branch) {
+        Some(rule) if rule.push_access == PushAccess::Admin && permission < Permission::Admin => {
+            Err(AppError::new(
+                ErrorType::AuthorizationError,
+                &format!("Admin access is required to commit to {}", branch),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+async fn save(
+    project: &Project,
+    author: &User,
+    config: ProjectConfig,
+    restored_from: Option<i32>,
+    db: &Database,
+) -> Result<SavedConfig, AppError> {
+    let revision = config
+        .save_revision(
+            project_id(project)?,
+            author.id,
+            restored_from,
+            db.get_pool(),
+        )
+        .await
+        .map_err(database_error)?;
+    activity_service::record(
+        project_id(project)?,
+        None,
+        author.id,
+        EVENT_CONFIG_CHANGED,
+        json!({"revision": revision, "restored_from": restored_from}),
+        db,
+    )
+    .await;
+    Ok(SavedConfig { revision, config })
+}
+
+/// Проверки, которые не выразить схемой
+async fn check_config(config: &ProjectConfig, db: &Database) -> Result<(), AppError> {
+    let branch = &config.default_branch;
+    if branch.contains("..")
+        || branch.contains("//")
+        || branch.ends_with('/')
+        || branch.ends_with('.')
+        || branch.ends_with(".lock")
+    {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Invalid configuration: default_branch is not a valid branch name",
+        ));
+    }
+
+    for username in &config.notifications.recipients {
+        let exists = User::find_by_username(username, db.get_pool())
+            .await
+            .map_err(database_error)?
+            .is_some();
+        if !exists {
+            return Err(AppError::new(
+                ErrorType::ValidationError,
+                &format!(
+                    "Invalid configuration: notification recipient {} not found",
+                    username
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+async fn load_revision(
+    project_id: i64,
+    revision: i32,
+    db: &Database,
+) -> Result<ProjectConfig, AppError> {
+    ProjectConfig::load_revision(project_id, revision, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(revision_not_found)
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() {
+        "/"
+    } else {
+        path
+    }
+}
+
+fn to_value(config: &ProjectConfig) -> Result<Value, AppError> {
+    serde_json::to_value(config).map_err(|e| internal_error(&e.to_string()))
+}
+
+fn revision_not_found() -> AppError {
+    AppError::new(ErrorType::NotFoundError, "Configuration revision not found")
+}
+
+fn project_id(project: &Project) -> Result<i64, AppError> {
+    project
+        .id
+        .ok_or_else(|| internal_error("Project must have id"))
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
@@ -0,0 +1,587 @@
+//! Жизненный цикл проекта: архив, удаление с восстановлением и передача
+//!
+//! Архивный проект доступен только для чтения (см. `services::permissions`),
+//! push в его репозитории отклоняется. Удалённый проект скрывается сразу, а
+//! стирается вместе с репозиториями, вики и файлами релизов только после
+//! `RETENTION_DAYS`; до этого его можно восстановить. Окончательное удаление
+//! выполняет планировщик обслуживания.
+//!
+//! Удаляет и передаёт проект только владелец, а передача выполняется после
+//! того, как её примет новый владелец.
+
+use chrono::{DateTime, Duration, Utc};
+use log::{error, info};
+use serde::Serialize;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::notifications::Notification;
+use crate::domain::organizations::Organization;
+use crate::domain::projects::Project;
+use crate::domain::repos::Repository;
+use crate::domain::transfers::ProjectTransfer;
+use crate::domain::users::User;
+use crate::services::projects::{self as project_service, ProjectOwner};
+use crate::services::releases as release_service;
+use crate::services::wiki as wiki_service;
+use crate::utils::{git, helpers};
+
+/// Сколько дней удалённый проект можно восстановить
+pub const RETENTION_DAYS: i64 = 30;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Удалённый проект и срок, до которого его можно восстановить
+#[derive(Debug, Serialize)]
+pub struct DeletedProject {
+    #[serde(flatten)]
+    pub project: Project,
+    pub purge_at: Option<DateTime<Utc>>,
+}
+
+impl DeletedProject {
+    fn new(project: Project) -> Self {
+        let purge_at = project
+            .deleted_at
+            .map(|deleted_at| deleted_at + Duration::days(RETENTION_DAYS));
+        DeletedProject { project, purge_at }
+    }
+}
+
+// ============================================================================
+// АРХИВ
+// ============================================================================
+
+/// Переводит проект в архив
+pub async fn archive(project: &Project, db: &Database) -> Result<Project, AppError> {
+    if project.is_archived() {
+        return Err(AppError::new(
+            ErrorType::ConflictError,
+            "Project is already archived",
+        ));
+    }
+    let id = project_id(project)?;
+    Project::set_archived(id, true, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    reload(id, db).await
+}
+
+/// Возвращает проект из архива
+pub async fn unarchive(project: &Project, db: &Database) -> Result<Project, AppError> {
+    if !project.is_archived() {
+        return Err(AppError::new(
+            ErrorType::ConflictError,
+            "Project is not archived",
+        ));
+    }
+    let id = project_id(project)?;
+    Project::set_archived(id, false, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    reload(id, db).await
+}
+
+// ============================================================================
+// УДАЛЕНИЕ И ВОССТАНОВЛЕНИЕ
+// ============================================================================
+
+/// Помечает проект удалённым; данные сохраняются до конца срока хранения
+///
+/// Удалить проект может только его владелец.
+pub async fn delete(
+    project: &Project,
+    actor: &User,
+    db: &Database,
+) -> Result<DeletedProject, AppError> {
+    check_owner(project, actor, "delete", db).await?;
+    let id = project_id(project)?;
+    Project::set_deleted(id, true, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    Ok(DeletedProject::new(reload(id, db).await?))
+}
+
+/// Восстанавливает удалённый проект
+pub async fn restore(project: &Project, db: &Database) -> Result<Project, AppError> {
+    let id = project_id(project)?;
+    Project::set_deleted(id, false, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    reload(id, db).await
+}
+
+/// Удалённые проекты, которые пользователь может восстановить
+pub async fn list_deleted(user: &User, db: &Database) -> Result<Vec<DeletedProject>, AppError> {
+    let user_id = user.id.ok_or_else(|| internal_error("User must have id"))?;
+    let projects = Project::find_deleted_for_user(user_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    Ok(projects.into_iter().map(DeletedProject::new).collect())
+}
+
+/// Стирает проекты, срок хранения которых истёк
+pub async fn purge_expired(db: &Database) {
+    let before = Utc::now() - Duration::days(RETENTION_DAYS);
+    let projects = match Project::find_deleted_before(before, db.get_pool()).await {
+        Ok(projects) => projects,
+        Err(e) => {
+            error!("Failed to find expired projects: {}", e);
+            return;
+        }
+    };
+
+    for project in projects {
+        let Some(id) = project.id else {
+            continue;
+        };
+        if let Err(e) = purge(id, db).await {
+            error!("Failed to purge project {}: {:?}", id, e);
+            continue;
+        }
+        info!("Purged deleted project {} ({})", project.name, id);
+    }
+}
+
+/// Удаляет проект из базы, затем репозитории и файлы релизов с диска
+async fn purge(id: i64, db: &Database) -> Result<(), AppError> {
+    let pool = db.get_pool();
+    let repositories = Repository::find_by_project(id, pool)
+        .await
+        .map_err(database_error)?;
+    Project::delete(id, pool).await.map_err(database_error)?;
+
+    for repo in repositories {
+        let paths = [
+            git::repository_path(&repo.name),
+            git::repository_path(&wiki_service::wiki_repository_name(&repo.name)),
+        ];
+        for path in paths {
+            if let Err(e) = helpers::safe_remove_dir(&path) {
+                error!("Failed to remove repository {}: {}", path, e);
+            }
+        }
+        if let Some(repository_id) = repo.id {
+            release_service::delete_repository_files(repository_id).await;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// ПЕРЕДАЧА
+// ============================================================================
+
+/// Запрашивает передачу проекта пользователю или организации `target`
+///
+/// Передать проект может только его владелец. Проект переходит к новому
+/// владельцу, когда тот примет запрос (`accept_transfer`); новый запрос
+/// заменяет прежний.
+pub async fn request_transfer(
+    project: &Project,
+    actor: &User,
+    target: &str,
+    db: &Database,
+) -> Result<ProjectTransfer, AppError> {
+    check_owner(project, actor, "transfer", db).await?;
+    let id = project_id(project)?;
+    let actor_id = actor
+        .id
+        .ok_or_else(|| internal_error("User must have id"))?;
+    let owner = project_service::find_owner(target, db)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "User or organization not found"))?;
+    let (owner_id, organization_id) = new_owner(project, &owner)?;
+    check_transfer_target(project, &owner, owner_id, organization_id, db).await?;
+
+    let pool = db.get_pool();
+    let (target_user_id, target_organization_id) = match &owner {
+        ProjectOwner::User(_) => (Some(owner_id), None),
+        ProjectOwner::Organization(org) => (None, Some(org.id)),
+    };
+    ProjectTransfer::request(id, target_user_id, target_organization_id, actor_id, pool)
+        .await
+        .map_err(database_error)?;
+    let transfer = ProjectTransfer::find_by_project(id, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| internal_error("Transfer request was not saved"))?;
+
+    notify_transfer_target(&transfer, &owner, actor, db).await;
+    Ok(transfer)
+}
+
+/// Запросы на передачу, которые пользователь может принять
+pub async fn pending_transfers(
+    user: &User,
+    db: &Database,
+) -> Result<Vec<ProjectTransfer>, AppError> {
+    let user_id = user.id.ok_or_else(|| internal_error("User must have id"))?;
+    ProjectTransfer::find_for_user(user_id, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Принимает передачу проекта `project_id`
+///
+/// Репозитории, соавторы и доступ команд переходят вместе с проектом, а
+/// прежний владелец теряет роль owner и вместе с ней права на проект. Запрос
+/// отменяется, если его автор больше не владеет проектом.
+pub async fn accept_transfer(
+    project_id: i64,
+    user: &User,
+    db: &Database,
+) -> Result<Project, AppError> {
+    let pool = db.get_pool();
+    let transfer = find_acceptable_transfer(project_id, user, db).await?;
+    let project = Project::find_by_id(project_id, pool)
+        .await
+        .map_err(database_error)?
+        .filter(|project| !project.is_deleted())
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Project not found"))?;
+
+    let requester_owns = match transfer.requested_by {
+        Some(requester_id) => match User::find_by_id(requester_id, pool)
+            .await
+            .map_err(database_error)?
+        {
+            Some(requester) => is_owner(&project, &requester, db).await?,
+            None => false,
+        },
+        None => false,
+    };
+    if !requester_owns {
+        ProjectTransfer::delete(project_id, pool)
+            .await
+            .map_err(database_error)?;
+        return Err(AppError::new(
+            ErrorType::ConflictError,
+            "The transfer request is no longer valid",
+        ));
+    }
+
+    let owner = match (transfer.target_user_id, transfer.target_organization_id) {
+        (Some(user_id), _) => User::find_by_id(user_id, pool)
+            .await
+            .map_err(database_error)?
+            .map(ProjectOwner::User),
+        (None, Some(organization_id)) => Organization::find_by_id(organization_id, pool)
+            .await
+            .map_err(database_error)?
+            .map(ProjectOwner::Organization),
+        (None, None) => None,
+    }
+    .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "User or organization not found"))?;
+    let (owner_id, organization_id) = new_owner(&project, &owner)?;
+    check_transfer_target(&project, &owner, owner_id, organization_id, db).await?;
+
+    Project::transfer(project_id, owner_id, organization_id, pool)
+        .await
+        .map_err(database_error)?;
+    reload(project_id, db).await
+}
+
+/// Отклоняет передачу проекта; владелец проекта так же отменяет свой запрос
+pub async fn decline_transfer(project_id: i64, user: &User, db: &Database) -> Result<(), AppError> {
+    let pool = db.get_pool();
+    let cancelled_by_owner = match Project::find_by_id(project_id, pool)
+        .await
+        .map_err(database_error)?
+    {
+        Some(project) => is_owner(&project, user, db).await?,
+        None => false,
+    };
+    if !cancelled_by_owner {
+        find_acceptable_transfer(project_id, user, db).await?;
+    }
+    if !ProjectTransfer::delete(project_id, pool)
+        .await
+        .map_err(database_error)?
+    {
+        return Err(transfer_not_found());
+    }
+    Ok(())
+}
+
+/// Запрос на передачу проекта, если пользователь может его принять
+async fn find_acceptable_transfer(
+    project_id: i64,
+    user: &User,
+    db: &Database,
+) -> Result<ProjectTransfer, AppError> {
+    let user_id = user.id.ok_or_else(|| internal_error("User must have id"))?;
+    let transfer = ProjectTransfer::find_by_project(project_id, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(transfer_not_found)?;
+    let organization_role = match transfer.target_organization_id {
+        Some(organization_id) => Organization::member_role(organization_id, user_id, db.get_pool())
+            .await
+            .map_err(database_error)?,
+        None => None,
+    };
+    if !can_accept(&transfer, user_id, organization_role.as_deref()) {
+        // Чужие запросы не раскрываются
+        return Err(transfer_not_found());
+    }
+    Ok(transfer)
+}
+
+/// Владелец и организация проекта после передачи владельцу `owner`
+fn new_owner(project: &Project, owner: &ProjectOwner) -> Result<(i64, Option<i64>), AppError> {
+    match owner {
+        ProjectOwner::User(user) => {
+            let user_id = user.id.ok_or_else(|| internal_error("User must have id"))?;
+            Ok((user_id, None))
+        }
+        ProjectOwner::Organization(org) => Ok((project.owner_id, Some(org.id))),
+    }
+}
+
+/// Проект не принадлежит `owner` и в его пространстве нет проекта с тем же именем
+async fn check_transfer_target(
+    project: &Project,
+    owner: &ProjectOwner,
+    owner_id: i64,
+    organization_id: Option<i64>,
+    db: &Database,
+) -> Result<(), AppError> {
+    if owner_id == project.owner_id && organization_id == project.organization_id {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Project already belongs to this owner",
+        ));
+    }
+
+    if project_service::find_in_namespace(&project.name, owner, db)
+        .await
+        .map_err(database_error)?
+        .is_some()
+    {
+        return Err(AppError::new(
+            ErrorType::ConflictError,
+            &format!(
+                "{} already has a project named {}",
+                owner.name(),
+                project.name
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Сообщает новому владельцу (владельцам и администраторам организации) о запросе
+async fn notify_transfer_target(
+    transfer: &ProjectTransfer,
+    owner: &ProjectOwner,
+    actor: &User,
+    db: &Database,
+) {
+    let pool = db.get_pool();
+    let recipients = match owner {
+        ProjectOwner::User(user) => user.id.into_iter().collect(),
+        ProjectOwner::Organization(org) => match Organization::members(org.id, pool).await {
+            Ok(members) => members
+                .into_iter()
+                .filter(|member| member.role == "owner" || member.role == "admin")
+                .map(|member| member.user_id)
+                .collect(),
+            Err(e) => {
+                error!("Failed to load organization members for transfer: {}", e);
+                Vec::new()
+            }
+        },
+    };
+
+    let content = format!(
+        "{} wants to transfer the project {}/{} to {}. Accept or decline it in your pending transfers.",
+        actor.username,
+        transfer.namespace.as_deref().unwrap_or_default(),
+        transfer.project_name,
+        owner.name()
+    );
+    for user_id in recipients {
+        let notification = Notification {
+            id: None,
+            name: "Project transfer".to_string(),
+            content: content.clone(),
+            user_id: Some(user_id),
+            created_at: None,
+        };
+        if let Err(e) = notification.create(pool).await {
+            error!("Failed to notify about project transfer: {}", e);
+        }
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Владелец проекта: личный владелец или владелец организации проекта
+///
+/// Администраторам проекта и организации удаление и передача недоступны.
+async fn is_owner(project: &Project, user: &User, db: &Database) -> Result<bool, AppError> {
+    let Some(user_id) = user.id else {
+        return Ok(false);
+    };
+    let organization_role = match project.organization_id {
+        Some(organization_id) => Organization::member_role(organization_id, user_id, db.get_pool())
+            .await
+            .map_err(database_error)?,
+        None => None,
+    };
+    Ok(owns(project, user_id, organization_role.as_deref()))
+}
+
+fn owns(project: &Project, user_id: i64, organization_role: Option<&str>) -> bool {
+    match project.organization_id {
+        Some(_) => organization_role == Some("owner"),
+        None => project.owner_id == user_id,
+    }
+}
+
+/// Принять передачу может новый владелец или владелец либо администратор
+/// организации, которой передаётся проект
+fn can_accept(transfer: &ProjectTransfer, user_id: i64, organization_role: Option<&str>) -> bool {
+    match transfer.target_organization_id {
+        Some(_) => matches!(organization_role, Some("owner" | "admin")),
+        None => transfer.target_user_id == Some(user_id),
+    }
+}
+
+async fn check_owner(
+    project: &Project,
+    user: &User,
+    action: &str,
+    db: &Database,
+) -> Result<(), AppError> {
+    if is_owner(project, user, db).await? {
+        return Ok(());
+    }
+    Err(AppError::new(
+        ErrorType::AuthorizationError,
+        &format!("Only the project owner can {} the project", action),
+    ))
+}
+
+fn transfer_not_found() -> AppError {
+    AppError::new(ErrorType::NotFoundError, "Transfer request not found")
+}
+
+async fn reload(id: i64, db: &Database) -> Result<Project, AppError> {
+    Project::find_by_id(id, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Project not found"))
+}
+
+fn project_id(project: &Project) -> Result<i64, AppError> {
+    project
+        .id
+        .ok_or_else(|| internal_error("Project must have id"))
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::permissions::{self as permission_service, Permission};
+
+    fn project(owner_id: i64, organization_id: Option<i64>) -> Project {
+        Project {
+            id: Some(1),
+            name: "demo".to_string(),
+            owner_id,
+            organization_id,
+            description: None,
+            is_public: false,
+            created_at: None,
+            archived_at: None,
+            deleted_at: None,
+        }
+    }
+
+    fn transfer(
+        target_user_id: Option<i64>,
+        target_organization_id: Option<i64>,
+    ) -> ProjectTransfer {
+        ProjectTransfer {
+            project_id: 1,
+            project_name: "demo".to_string(),
+            namespace: Some("alice".to_string()),
+            target_user_id,
+            target_organization_id,
+            target: None,
+            requested_by: Some(1),
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_owns_personal_project() {
+        let personal = project(1, None);
+        assert!(owns(&personal, 1, None));
+        assert!(!owns(&personal, 2, None));
+        // Роль в чужой организации не даёт прав на личный проект
+        assert!(!owns(&personal, 2, Some("owner")));
+    }
+
+    #[test]
+    fn test_owns_organization_project() {
+        let shared = project(1, Some(7));
+        assert!(owns(&shared, 2, Some("owner")));
+        assert!(!owns(&shared, 2, Some("admin")));
+        assert!(!owns(&shared, 2, Some("member")));
+        // Создатель проекта, покинувший организацию, больше им не владеет
+        assert!(!owns(&shared, 1, None));
+    }
+
+    #[test]
+    fn test_previous_owner_after_transfer() {
+        // Проект пользователя 1 передан пользователю 2: роль owner прежнего
+        // владельца удалена вместе с его членством
+        let transferred = project(2, None);
+        assert!(!owns(&transferred, 1, None));
+        assert_eq!(
+            permission_service::granted_by_roles(&transferred, 1, None, &[], &[]),
+            Permission::None
+        );
+        assert_eq!(
+            permission_service::granted_by_roles(&transferred, 2, None, &[], &[]),
+            Permission::Admin
+        );
+
+        // Переданный организации проект прежний владелец только читает, если
+        // он рядовой участник организации
+        let transferred = project(1, Some(7));
+        assert_ne!(
+            permission_service::granted_by_roles(&transferred, 1, Some("member"), &[], &[]),
+            Permission::Admin
+        );
+    }
+
+    #[test]
+    fn test_can_accept() {
+        let to_user = transfer(Some(2), None);
+        assert!(can_accept(&to_user, 2, None));
+        assert!(!can_accept(&to_user, 3, None));
+        assert!(!can_accept(&to_user, 3, Some("owner")));
+
+        let to_organization = transfer(None, Some(7));
+        assert!(can_accept(&to_organization, 3, Some("owner")));
+        assert!(can_accept(&to_organization, 3, Some("admin")));
+        assert!(!can_accept(&to_organization, 3, Some("member")));
+        assert!(!can_accept(&to_organization, 3, None));
+    }
+}
@@ -1,12 +1,36 @@
 //! Сервисный слой для операций с проектами
 
+pub mod config;
+pub mod lifecycle;
+
+use serde::Serialize;
+
 use crate::core::database::Database;
 use crate::domain::{
-    projects::{Project, ProjectConfig},
+    organizations::Organization,
+    projects::Project,
     repos::Repository,
     users::User,
 };
 
+/// Владелец пространства имён `/{owner}/...`: пользователь или организация
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ProjectOwner {
+    User(User),
+    Organization(Organization),
+}
+
+impl ProjectOwner {
+    /// Имя пространства в URL
+    pub fn name(&self) -> &str {
+        match self {
+            ProjectOwner::User(user) => &user.username,
+            ProjectOwner::Organization(org) => &org.name,
+        }
+    }
+}
+
 /// Возвращает проекты по владельцу
 pub async fn list_by_owner(owner_id: i64, db: &Database) -> Result<Vec<Project>, sqlx::Error> {
     Project::find_by_owner(owner_id, db.get_pool()).await
@@ -17,40 +41,62 @@ pub async fn list_public(db: &Database) -> Result<Vec<Project>, sqlx::Error> {
     Project::find_public(db.get_pool()).await
 }
 
-/// Создаёт новый проект
-pub async fn create(project: Project, db: &Database) -> Result<(), sqlx::Error> {
-    project.create(db.get_pool()).await.map(|_| ())
+/// Находит пользователя или организацию по имени пространства
+///
+/// Имена пользователей и организаций не пересекаются, поэтому порядок
+/// поиска не важен.
+pub async fn find_owner(name: &str, db: &Database) -> Result<Option<ProjectOwner>, sqlx::Error> {
+    let pool = db.get_pool();
+    if let Some(user) = User::find_by_username(name, pool).await? {
+        return Ok(Some(ProjectOwner::User(user)));
+    }
+    Ok(Organization::find_by_name(name, pool)
+        .await?
+        .map(ProjectOwner::Organization))
 }
 
-/// Находит пользователя по имени
-pub async fn find_owner(username: &str, db: &Database) -> Result<Option<User>, sqlx::Error> {
-    User::find_by_username(username, db.get_pool()).await
-}
-
-/// Находит проект по имени и владельцу
+/// Находит проект по имени в пространстве владельца; удалённые не находятся
 pub async fn find_project(
     name: &str,
-    owner_id: i64,
+    owner: &ProjectOwner,
     db: &Database,
 ) -> Result<Option<Project>, sqlx::Error> {
-    Project::find_by_name_and_owner(name, owner_id, db.get_pool()).await
+    Ok(find_in_namespace(name, owner, db)
+        .await?
+        .filter(|project| !project.is_deleted()))
 }
 
-/// Возвращает репозитории проекта
-pub async fn repositories(project_id: i64, db: &Database) -> Result<Vec<Repository>, sqlx::Error> {
-    Repository::find_by_project(project_id, db.get_pool()).await
+/// Находит удалённый, но ещё не стёртый проект по имени
+pub async fn find_deleted_project(
+    name: &str,
+    owner: &ProjectOwner,
+    db: &Database,
+) -> Result<Option<Project>, sqlx::Error> {
+    Ok(find_in_namespace(name, owner, db)
+        .await?
+        .filter(Project::is_deleted))
 }
 
-/// Возвращает конфигурацию проекта
-pub async fn config(project: &Project, db: &Database) -> Result<ProjectConfig, sqlx::Error> {
-    project.get_config(db.get_pool()).await
+/// Проект с именем `name` в пространстве владельца, в том числе удалённый
+pub(crate) async fn find_in_namespace(
+    name: &str,
+    owner: &ProjectOwner,
+    db: &Database,
+) -> Result<Option<Project>, sqlx::Error> {
+    match owner {
+        ProjectOwner::User(user) => {
+            let Some(user_id) = user.id else {
+                return Ok(None);
+            };
+            Project::find_by_name_and_owner(name, user_id, db.get_pool()).await
+        }
+        ProjectOwner::Organization(org) => {
+            Project::find_by_name_and_organization(name, org.id, db.get_pool()).await
+        }
+    }
 }
 
-/// Обновляет конфигурацию проекта
-pub async fn update_config(
-    project: &Project,
-    config: &ProjectConfig,
-    db: &Database,
-) -> Result<(), sqlx::Error> {
-    project.update_config(config, db.get_pool()).await
+/// Возвращает репозитории проекта
+pub async fn repositories(project_id: i64, db: &Database) -> Result<Vec<Repository>, sqlx::Error> {
+    Repository::find_by_project(project_id, db.get_pool()).await
 }
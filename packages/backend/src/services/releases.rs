@@ -0,0 +1,331 @@
+//! Сервис релизов: привязка к тегам и хранение файлов на диске
+
+use log::{debug, error};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+use crate::core::config::load_config;
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::events::EVENT_RELEASE_PUBLISHED;
+use crate::domain::releases::{Release, ReleaseAsset};
+use crate::domain::repos::Repository;
+use crate::services::activity as activity_service;
+use crate::utils::git;
+
+/// Корневая директория файлов релизов (ASSETS_PATH)
+static ASSETS_PATH: LazyLock<String> = LazyLock::new(|| load_config().assets_path);
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Параметры создания релиза
+#[derive(Debug)]
+pub struct NewRelease {
+    pub tag_name: String,
+    /// Ветка или коммит для нового тега (по умолчанию — ветка по умолчанию)
+    pub target: Option<String>,
+    pub title: Option<String>,
+    pub notes: Option<String>,
+    pub is_draft: bool,
+    pub is_prerelease: bool,
+}
+
+/// Загружаемый файл релиза
+#[derive(Debug)]
+pub struct NewAsset {
+    pub file_name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+// ============================================================================
+// РЕЛИЗЫ
+// ============================================================================
+
+/// Создаёт релиз для тега
+///
+/// Если тега ещё нет, он создаётся на указанной ревизии.
+/// Существующий тег должен указывать на тот же коммит, что и `target`.
+pub async fn create_release(
+    repo: &Repository,
+    author_id: i64,
+    input: NewRelease,
+    db: &Database,
+) -> Result<Release, AppError> {
+    let repository_id = repo
+        .id
+        .ok_or_else(|| internal_error("Repository must have id"))?;
+    let pool = db.get_pool();
+
+    if Release::find_by_tag(repository_id, &input.tag_name, pool)
+        .await
+        .map_err(database_error)?
+        .is_some()
+    {
+        return Err(AppError::new(
+            ErrorType::ConflictError,
+            "Release for this tag already exists",
+        ));
+    }
+
+    let target_sha = {
+        let repo_path = git::repository_path(&repo.name);
+        let tag_name = input.tag_name.clone();
+        let target = input.target.clone();
+        tokio::task::spawn_blocking(move || ensure_tag(&repo_path, &tag_name, target.as_deref()))
+            .await
+            .map_err(|e| internal_error(&format!("Release task failed: {}", e)))??
+    };
+
+    let release = Release {
+        id: None,
+        repository_id,
+        title: input
+            .title
+            .filter(|title| !title.trim().is_empty())
+            .unwrap_or_else(|| input.tag_name.clone()),
+        tag_name: input.tag_name,
+        target_sha,
+        notes: input.notes,
+        is_draft: input.is_draft,
+        is_prerelease: input.is_prerelease,
+        author_id,
+        created_at: None,
+        published_at: None,
+    };
+    release.create(pool).await.map_err(database_error)?;
+
+    let created = Release::find_by_tag(repository_id, &release.tag_name, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| internal_error("Created release not found"))?;
+
+    // Черновик не публикуется, подписчики о нём не узнают
+    if !created.is_draft {
+        activity_service::record(
+            repo.project_id,
+            Some(repository_id),
+            Some(author_id),
+            EVENT_RELEASE_PUBLISHED,
+            json!({
+                "tag": created.tag_name,
+                "title": created.title,
+                "prerelease": created.is_prerelease
+            }),
+            db,
+        )
+        .await;
+    }
+    Ok(created)
+}
+
+/// Удаляет релиз и его файлы (тег в репозитории остаётся)
+pub async fn delete_release(release: &Release, db: &Database) -> Result<(), AppError> {
+    let release_id = release
+        .id
+        .ok_or_else(|| internal_error("Release must have id"))?;
+
+    Release::delete(release_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+
+    let release_dir = PathBuf::from(ASSETS_PATH.as_str())
+        .join(release.repository_id.to_string())
+        .join(release_id.to_string());
+    if let Err(e) = tokio::fs::remove_dir_all(&release_dir).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            error!("Failed to remove release files {:?}: {}", release_dir, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Удаляет файлы всех релизов репозитория; записи удаляются вместе с ним
+pub async fn delete_repository_files(repository_id: i64) {
+    let repository_dir = PathBuf::from(ASSETS_PATH.as_str()).join(repository_id.to_string());
+    if let Err(e) = tokio::fs::remove_dir_all(&repository_dir).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            error!("Failed to remove release files {:?}: {}", repository_dir, e);
+        }
+    }
+}
+
+/// Проверяет имя тега и возвращает SHA коммита, на который он указывает
+fn ensure_tag(repo_path: &str, tag_name: &str, target: Option<&str>) -> Result<String, AppError> {
+    if tag_name.starts_with('-') || !git::is_valid_ref_name("tags", tag_name) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Invalid tag name",
+        ));
+    }
+
+    let target_sha = match target {
+        Some(target) => Some(git::resolve_revision(repo_path, target).ok_or_else(|| {
+            AppError::new(ErrorType::ValidationError, "Target revision not found")
+        })?),
+        None => None,
+    };
+
+    if let Some(tag_sha) = git::resolve_revision(repo_path, &format!("refs/tags/{}", tag_name)) {
+        return match target_sha {
+            Some(target_sha) if target_sha != tag_sha => Err(AppError::new(
+                ErrorType::ConflictError,
+                "Tag already exists and points to another commit",
+            )),
+            _ => Ok(tag_sha),
+        };
+    }
+
+    let target_sha = match target_sha {
+        Some(sha) => sha,
+        None => git::resolve_default_head(repo_path)
+            .map(|(_, sha)| sha)
+            .ok_or_else(|| AppError::new(ErrorType::ValidationError, "Repository is empty"))?,
+    };
+
+    git::create_tag(repo_path, tag_name, &target_sha)
+        .map_err(|e| internal_error(&format!("Failed to create tag: {}", e)))?;
+    debug!("Created tag {} at {}", tag_name, target_sha);
+
+    Ok(target_sha)
+}
+
+// ============================================================================
+// ФАЙЛЫ РЕЛИЗОВ
+// ============================================================================
+
+/// Сохраняет файл релиза на диск и регистрирует его с контрольной суммой
+pub async fn upload_asset(
+    release: &Release,
+    uploader_id: i64,
+    asset: NewAsset,
+    db: &Database,
+) -> Result<ReleaseAsset, AppError> {
+    let release_id = release
+        .id
+        .ok_or_else(|| internal_error("Release must have id"))?;
+    let pool = db.get_pool();
+
+    if !is_valid_file_name(&asset.file_name) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Invalid file name",
+        ));
+    }
+    if asset.data.is_empty() {
+        return Err(AppError::new(ErrorType::ValidationError, "File is empty"));
+    }
+    if ReleaseAsset::find_by_name(release_id, &asset.file_name, pool)
+        .await
+        .map_err(database_error)?
+        .is_some()
+    {
+        return Err(AppError::new(
+            ErrorType::ConflictError,
+            "Asset with this name already exists",
+        ));
+    }
+
+    let storage_path = format!(
+        "{}/{}/{}",
+        release.repository_id,
+        release_id,
+        Uuid::new_v4()
+    );
+    let size = asset.data.len() as i64;
+
+    let sha256 = {
+        let path = asset_path(&storage_path);
+        let data = asset.data;
+        tokio::task::spawn_blocking(move || write_asset_file(&path, &data))
+            .await
+            .map_err(|e| internal_error(&format!("Upload task failed: {}", e)))?
+            .map_err(|e| internal_error(&format!("Failed to store asset: {}", e)))?
+    };
+
+    let record = ReleaseAsset {
+        id: None,
+        release_id,
+        file_name: asset.file_name,
+        content_type: asset.content_type,
+        size,
+        sha256,
+        storage_path,
+        download_count: 0,
+        uploader_id,
+        created_at: None,
+    };
+
+    if let Err(e) = record.create(pool).await {
+        // Запись не создана — файл на диске больше не нужен
+        let _ = tokio::fs::remove_file(asset_path(&record.storage_path)).await;
+        return Err(database_error(e));
+    }
+
+    ReleaseAsset::find_by_name(release_id, &record.file_name, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| internal_error("Uploaded asset not found"))
+}
+
+/// Удаляет файл релиза с диска и из базы данных
+pub async fn delete_asset(asset: &ReleaseAsset, db: &Database) -> Result<(), AppError> {
+    let asset_id = asset
+        .id
+        .ok_or_else(|| internal_error("Asset must have id"))?;
+
+    ReleaseAsset::delete(asset_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+
+    if let Err(e) = tokio::fs::remove_file(asset_path(&asset.storage_path)).await {
+        error!("Failed to remove asset file {}: {}", asset.storage_path, e);
+    }
+
+    Ok(())
+}
+
+/// Абсолютный путь к файлу релиза на диске
+pub fn asset_path(storage_path: &str) -> PathBuf {
+    PathBuf::from(ASSETS_PATH.as_str()).join(storage_path)
+}
+
+/// Записывает файл и возвращает его SHA-256
+fn write_asset_file(path: &PathBuf, data: &[u8]) -> std::io::Result<String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, data)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Имя файла не должно содержать путей и управляющих символов
+fn is_valid_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 255
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && !name.chars().any(char::is_control)
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
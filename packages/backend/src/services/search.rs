@@ -0,0 +1,223 @@
+//! Сервис индексации и поиска по коду репозиториев
+
+use log::{debug, error, info};
+use serde::Serialize;
+
+use crate::core::database::Database;
+use crate::domain::projects::Project;
+use crate::domain::repos::Repository;
+use crate::domain::search::{CodeDocument, CodeSearchFilters, CodeSearchRow};
+use crate::domain::users::User;
+use crate::services::permissions as permission_service;
+use crate::utils::{git, languages};
+
+/// Файлы крупнее этого размера не индексируются
+const MAX_INDEXED_FILE_SIZE: u64 = 512 * 1024;
+/// Максимум совпадающих строк, возвращаемых для одного файла
+const MAX_LINE_MATCHES: usize = 20;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Совпадение в конкретной строке файла
+#[derive(Debug, Serialize)]
+pub struct LineMatch {
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// Результат поиска по одному файлу
+#[derive(Debug, Serialize)]
+pub struct CodeSearchResult {
+    pub owner: String,
+    pub project: String,
+    pub repository: String,
+    pub path: String,
+    pub language: Option<String>,
+    pub commit_sha: String,
+    pub matches: Vec<LineMatch>,
+}
+
+// ============================================================================
+// ИНДЕКСАЦИЯ
+// ============================================================================
+
+/// Переиндексирует ветку по умолчанию репозитория
+///
+/// Возвращает количество проиндексированных файлов.
+pub async fn index_repository(repo: &Repository, db: &Database) -> Result<usize, String> {
+    let repository_id = repo.id.ok_or("Repository must have id")?;
+    let repo_path = git::repository_path(&repo.name);
+
+    let head = {
+        let repo_path = repo_path.clone();
+        tokio::task::spawn_blocking(move || git::resolve_default_head(&repo_path))
+            .await
+            .map_err(|e| format!("Indexing task failed: {}", e))?
+    };
+
+    // Пустой репозиторий — индексировать нечего
+    let Some((_, head)) = head else {
+        return Ok(0);
+    };
+
+    let indexed = CodeDocument::indexed_commit(repository_id, db.get_pool())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    if indexed.as_deref() == Some(head.as_str()) {
+        debug!("Repository {} already indexed at {}", repo.name, head);
+        return Ok(0);
+    }
+
+    let documents = {
+        let repo_path = repo_path.clone();
+        let head = head.clone();
+        tokio::task::spawn_blocking(move || collect_documents(&repo_path, &head))
+            .await
+            .map_err(|e| format!("Indexing task failed: {}", e))??
+    };
+
+    CodeDocument::replace_for_repository(repository_id, &head, &documents, db.get_pool())
+        .await
+        .map_err(|e| format!("Failed to store search index: {}", e))?;
+
+    info!(
+        "Indexed {} files of repository {} at {}",
+        documents.len(),
+        repo.name,
+        head
+    );
+    Ok(documents.len())
+}
+
+/// Запускает фоновую переиндексацию репозитория (после push)
+pub fn schedule_repository_index(repo_name: String, db: Database) {
+    tokio::spawn(async move {
+        let repo = match Repository::find_by_name(&repo_name, db.get_pool()).await {
+            Ok(Some(repo)) => repo,
+            Ok(None) => return,
+            Err(e) => {
+                error!("Failed to load repository {} for indexing: {}", repo_name, e);
+                return;
+            }
+        };
+
+        if let Err(e) = index_repository(&repo, &db).await {
+            error!("Failed to index repository {}: {}", repo_name, e);
+        }
+    });
+}
+
+/// Извлекает текстовые файлы ревизии для индекса
+fn collect_documents(repo_path: &str, revision: &str) -> Result<Vec<CodeDocument>, String> {
+    let reader = git::open_reader(repo_path);
+    let files: Vec<git::GitFile> = reader
+        .list_tree(revision, true)?
+        .into_iter()
+        .filter(|file| file.file_type == "blob")
+        .filter(|file| file.size.unwrap_or(0) <= MAX_INDEXED_FILE_SIZE)
+        .collect();
+
+    let hashes: Vec<String> = files.iter().map(|file| file.hash.clone()).collect();
+    let blobs = reader.read_objects(&hashes, "blob")?;
+
+    let documents = files
+        .into_iter()
+        .zip(blobs)
+        .filter_map(|(file, blob)| {
+            let blob = blob?;
+            if languages::is_binary(&blob) {
+                return None;
+            }
+            let content = String::from_utf8(blob).ok()?;
+
+            Some(CodeDocument {
+                language: languages::detect_language(&file.name).map(str::to_string),
+                path: file.name,
+                content,
+            })
+        })
+        .collect();
+
+    Ok(documents)
+}
+
+// ============================================================================
+// ПОИСК
+// ============================================================================
+
+/// Выполняет поиск и выделяет совпадающие строки
+///
+/// Кроме публичных репозиториев публичных проектов зритель находит код
+/// репозиториев, которые может читать по `services::permissions`.
+pub async fn search_code(
+    query: &str,
+    filters: &CodeSearchFilters,
+    viewer: Option<&User>,
+    limit: i64,
+    offset: i64,
+    db: &Database,
+) -> Result<Vec<CodeSearchResult>, sqlx::Error> {
+    let readable_ids = match viewer {
+        Some(viewer) => readable_repository_ids(viewer, db).await?,
+        None => Vec::new(),
+    };
+    let rows =
+        CodeDocument::search(query, filters, &readable_ids, limit, offset, db.get_pool()).await?;
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| term.to_lowercase())
+        .collect();
+
+    Ok(rows
+        .into_iter()
+        .map(|row: CodeSearchRow| CodeSearchResult {
+            matches: find_line_matches(&row.content, &terms),
+            owner: row.owner_username,
+            project: row.project_name,
+            repository: row.repository_name,
+            path: row.path,
+            language: row.language,
+            commit_sha: row.commit_sha,
+        })
+        .collect())
+}
+
+/// Репозитории проектов, к которым у пользователя есть доступ, доступные ему
+/// на чтение
+async fn readable_repository_ids(viewer: &User, db: &Database) -> Result<Vec<i64>, sqlx::Error> {
+    let Some(user_id) = viewer.id else {
+        return Ok(Vec::new());
+    };
+
+    let mut ids = Vec::new();
+    for project in Project::find_accessible(user_id, db.get_pool()).await? {
+        let Some(project_id) = project.id else {
+            continue;
+        };
+        let repositories = Repository::find_by_project(project_id, db.get_pool()).await?;
+        let readable =
+            permission_service::readable_repositories(&project, repositories, Some(viewer), db)
+                .await?;
+        ids.extend(readable.into_iter().filter_map(|repo| repo.id));
+    }
+    Ok(ids)
+}
+
+/// Находит строки, содержащие любой из терминов запроса (без учёта регистра)
+fn find_line_matches(content: &str, terms: &[String]) -> Vec<LineMatch> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.to_lowercase();
+            terms.iter().any(|term| line.contains(term.as_str()))
+        })
+        .take(MAX_LINE_MATCHES)
+        .map(|(index, line)| LineMatch {
+            line_number: index + 1,
+            line: line.to_string(),
+        })
+        .collect()
+}
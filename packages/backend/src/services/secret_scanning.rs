@@ -0,0 +1,373 @@
+//! Проверка push на секреты
+//!
+//! Перед `git receive-pack` новые блобы push проверяются правилами из
+//! `utils::secrets`. Режим задаётся в настройках проекта: `reject` отклоняет
+//! push, `notify` принимает его, сохраняет находки и уведомляет владельца
+//! проекта. Вики не проверяется.
+
+use log::error;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::notifications::Notification;
+use crate::domain::projects::{NotificationSettings, Project, SecretScanningMode};
+use crate::domain::repos::Repository;
+use crate::domain::secret_findings::{
+    NewSecretFinding, SecretFinding, STATE_IGNORED, STATE_OPEN, STATE_RESOLVED,
+};
+use crate::domain::users::User;
+use crate::utils::git;
+use crate::utils::secrets::{self, SecretMatch};
+
+/// Сколько находок перечисляется в отказе и уведомлении
+const MAX_LISTED_FINDINGS: usize = 10;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Секрет в одном из блобов push
+#[derive(Debug, Clone)]
+pub struct DetectedSecret {
+    pub blob_sha: String,
+    pub path: String,
+    pub ref_name: String,
+    pub commit_sha: String,
+    pub secret: SecretMatch,
+}
+
+/// Результат проверки push, в котором найдены секреты
+#[derive(Debug)]
+pub struct PushScan {
+    pub repository: Repository,
+    pub project: Project,
+    pub mode: SecretScanningMode,
+    /// Кого уведомлять о находках
+    pub notifications: NotificationSettings,
+    pub secrets: Vec<DetectedSecret>,
+}
+
+impl PushScan {
+    /// Push нужно отклонить
+    pub fn rejects(&self) -> bool {
+        self.mode == SecretScanningMode::Reject
+    }
+
+    /// Объяснение для git клиента, по строке на находку
+    pub fn rejection_message(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Push rejected: {} possible secret(s) found in pushed files",
+            self.secrets.len()
+        )];
+        lines.extend(self.listed_findings());
+        lines.push("Remove the secrets from history, revoke them and push again.".to_string());
+        lines
+    }
+
+    /// Объяснение отказа в изменении файла через API
+    pub fn file_rejection_message(&self) -> String {
+        let mut lines = vec![format!(
+            "Commit rejected: {} possible secret(s) found in file content",
+            self.secrets.len()
+        )];
+        lines.extend(self.listed_findings());
+        lines.push("Remove the secrets and commit again.".to_string());
+        lines.join("\n")
+    }
+
+    fn listed_findings(&self) -> Vec<String> {
+        let mut lines: Vec<_> = self
+            .secrets
+            .iter()
+            .take(MAX_LISTED_FINDINGS)
+            .map(describe)
+            .collect();
+        if self.secrets.len() > MAX_LISTED_FINDINGS {
+            lines.push(format!(
+                "  ...and {} more",
+                self.secrets.len() - MAX_LISTED_FINDINGS
+            ));
+        }
+        lines
+    }
+
+    /// Привязывает находки проверки файла к созданному коммиту
+    pub fn assign_commit(&mut self, commit_sha: &str, blob_sha: &str, ref_name: &str) {
+        for detected in &mut self.secrets {
+            detected.commit_sha = commit_sha.to_string();
+            detected.blob_sha = blob_sha.to_string();
+            detected.ref_name = ref_name.to_string();
+        }
+    }
+}
+
+// ============================================================================
+// ПРОВЕРКА PUSH
+// ============================================================================
+
+/// Проверяет блобы push в репозиторий `repo_name`
+///
+/// Возвращает `None`, если проверка в проекте выключена или секретов нет.
+/// Сбой самой проверки в режиме `reject` возвращается ошибкой, чтобы push не
+/// прошёл непроверенным; в режиме `notify` он только записывается в лог.
+pub async fn scan_push(
+    repo_name: &str,
+    body: &[u8],
+    db: &Database,
+) -> Result<Option<PushScan>, AppError> {
+    let Some(repository) = Repository::find_by_name(repo_name, db.get_pool())
+        .await
+        .map_err(database_error)?
+    else {
+        return Ok(None);
+    };
+    let Some((project, mode, notifications)) = scanning_settings(&repository, db).await? else {
+        return Ok(None);
+    };
+
+    let repo_path = git::repository_path(repo_name);
+    let body = body.to_vec();
+    let secrets = tokio::task::spawn_blocking(move || {
+        let updates = git::parse_push_commands(&body);
+        let blobs = git::pushed_blobs(&repo_path, &body, &updates, secrets::MAX_SCAN_SIZE)?;
+        Ok::<_, String>(
+            blobs
+                .into_iter()
+                .flat_map(|blob| {
+                    secrets::scan(&blob.content)
+                        .into_iter()
+                        .map(move |secret| DetectedSecret {
+                            blob_sha: blob.sha.clone(),
+                            path: blob.path.clone(),
+                            ref_name: blob.ref_name.clone(),
+                            commit_sha: blob.commit_sha.clone(),
+                            secret,
+                        })
+                })
+                .collect::<Vec<_>>(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+
+    let secrets = match secrets {
+        Ok(secrets) => secrets,
+        Err(e) if mode == SecretScanningMode::Reject => return Err(internal_error(&e)),
+        Err(e) => {
+            error!("Secret scanning failed for {}: {}", repo_name, e);
+            return Ok(None);
+        }
+    };
+    if secrets.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(PushScan {
+        repository,
+        project,
+        mode,
+        notifications,
+        secrets,
+    }))
+}
+
+/// Проверяет содержимое файла, записываемого через API файлов
+///
+/// Коммит ещё не создан, поэтому SHA коммита и блоба у находок пустые;
+/// после записи их заполняет `PushScan::assign_commit`.
+pub async fn scan_file(
+    repository: &Repository,
+    path: &str,
+    content: &[u8],
+    db: &Database,
+) -> Result<Option<PushScan>, AppError> {
+    let Some((project, mode, notifications)) = scanning_settings(repository, db).await? else {
+        return Ok(None);
+    };
+
+    let secrets: Vec<_> = secrets::scan(content)
+        .into_iter()
+        .map(|secret| DetectedSecret {
+            blob_sha: String::new(),
+            path: path.to_string(),
+            ref_name: String::new(),
+            commit_sha: String::new(),
+            secret,
+        })
+        .collect();
+    if secrets.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(PushScan {
+        repository: repository.clone(),
+        project,
+        mode,
+        notifications,
+        secrets,
+    }))
+}
+
+/// Сохраняет находки принятого push и сообщает о новых владельцу проекта и
+/// получателям из настроек уведомлений
+pub async fn record_findings(
+    scan: &PushScan,
+    pusher_id: Option<i64>,
+    db: &Database,
+) -> Result<(), AppError> {
+    let pool = db.get_pool();
+    let repository_id = repository_id(&scan.repository)?;
+
+    let mut recorded = Vec::new();
+    for detected in &scan.secrets {
+        let finding = NewSecretFinding {
+            repository_id,
+            rule: detected.secret.rule.to_string(),
+            blob_sha: detected.blob_sha.clone(),
+            path: detected.path.clone(),
+            line: detected.secret.line as i32,
+            redacted: detected.secret.redacted.clone(),
+            commit_sha: detected.commit_sha.clone(),
+            ref_name: detected.ref_name.clone(),
+            pusher_id,
+        };
+        if SecretFinding::create(&finding, pool)
+            .await
+            .map_err(database_error)?
+            .is_some()
+        {
+            recorded.push(detected);
+        }
+    }
+
+    if recorded.is_empty() || !scan.notifications.secret_findings {
+        return Ok(());
+    }
+
+    let mut content = format!(
+        "{} possible secret(s) were pushed to {}:\n",
+        recorded.len(),
+        scan.repository.name
+    );
+    for detected in recorded.iter().take(MAX_LISTED_FINDINGS) {
+        content.push_str(&describe(detected));
+        content.push('\n');
+    }
+    if recorded.len() > MAX_LISTED_FINDINGS {
+        content.push_str(&format!(
+            "  ...and {} more\n",
+            recorded.len() - MAX_LISTED_FINDINGS
+        ));
+    }
+    content.push_str("Revoke the exposed credentials and resolve the findings.");
+
+    let mut recipients = vec![scan.project.owner_id];
+    for username in &scan.notifications.recipients {
+        match User::find_by_username(username, pool).await {
+            Ok(Some(User { id: Some(id), .. })) if !recipients.contains(&id) => recipients.push(id),
+            Ok(_) => {}
+            Err(e) => error!("Failed to find notification recipient {}: {}", username, e),
+        }
+    }
+    for user_id in recipients {
+        let notification = Notification {
+            user_id: Some(user_id),
+            ..Notification::new("Secrets detected".to_string(), content.clone())
+                .map_err(|e| internal_error(&e))?
+        };
+        if let Err(e) = notification.create(pool).await {
+            error!("Failed to notify about secret findings: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// УПРАВЛЕНИЕ НАХОДКАМИ
+// ============================================================================
+
+/// Находки репозитория, при необходимости только в одном состоянии
+pub async fn list_findings(
+    repo: &Repository,
+    state: Option<&str>,
+    db: &Database,
+) -> Result<Vec<SecretFinding>, AppError> {
+    if let Some(state) = state {
+        validate_state(state)?;
+    }
+    SecretFinding::find_by_repository(repository_id(repo)?, state, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Отмечает находку решённой, проигнорированной или снова открытой
+pub async fn update_finding_state(
+    repo: &Repository,
+    id: i64,
+    state: &str,
+    user: &User,
+    db: &Database,
+) -> Result<SecretFinding, AppError> {
+    validate_state(state)?;
+    SecretFinding::update_state(id, repository_id(repo)?, state, user.id, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Secret finding not found"))
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Проект репозитория и его настройки проверки; `None`, если проверка выключена
+async fn scanning_settings(
+    repository: &Repository,
+    db: &Database,
+) -> Result<Option<(Project, SecretScanningMode, NotificationSettings)>, AppError> {
+    let pool = db.get_pool();
+    let project = Project::find_by_id(repository.project_id, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| internal_error("Repository project not found"))?;
+    let config = project.get_config(pool).await.map_err(database_error)?;
+    let mode = config.security.secret_scanning;
+    if mode == SecretScanningMode::Off {
+        return Ok(None);
+    }
+    Ok(Some((project, mode, config.notifications)))
+}
+
+fn describe(detected: &DetectedSecret) -> String {
+    let mut line = format!(
+        "  {}:{} {} ({})",
+        detected.path, detected.secret.line, detected.secret.description, detected.secret.redacted
+    );
+    if !detected.ref_name.is_empty() {
+        line.push_str(&format!(" on {}", detected.ref_name));
+    }
+    line
+}
+
+fn validate_state(state: &str) -> Result<(), AppError> {
+    if [STATE_OPEN, STATE_RESOLVED, STATE_IGNORED].contains(&state) {
+        Ok(())
+    } else {
+        Err(AppError::new(
+            ErrorType::ValidationError,
+            "State must be one of: open, resolved, ignored",
+        ))
+    }
+}
+
+fn repository_id(repo: &Repository) -> Result<i64, AppError> {
+    repo.id
+        .ok_or_else(|| internal_error("Repository must have id"))
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
@@ -0,0 +1,231 @@
+//! Сервис ключей подписи и проверки подписей коммитов
+//!
+//! Подписавший определяется по зарегистрированному ключу, которым сделана
+//! подпись; ключ принадлежит не более чем одному пользователю.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::signing_keys::{SignerKey, SigningKey};
+use crate::utils::git::{self, GitCommit};
+use crate::utils::signing::{self, SignatureCheck, SignatureStatus};
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Результат проверки подписи коммита для API
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitVerification {
+    #[serde(flatten)]
+    pub check: SignatureCheck,
+    /// Тип подписи: gpg, ssh (None для неподписанных коммитов)
+    pub signature_type: Option<&'static str>,
+    /// Пользователь, которому принадлежит ключ подписи
+    pub signer: Option<String>,
+}
+
+// ============================================================================
+// КЛЮЧИ ПОЛЬЗОВАТЕЛЯ
+// ============================================================================
+
+/// Проверяет и сохраняет публичный ключ пользователя
+pub async fn add_key(
+    user_id: i64,
+    title: &str,
+    public_key: &str,
+    db: &Database,
+) -> Result<SigningKey, AppError> {
+    let key_type = signing::detect_key_type(public_key).ok_or_else(|| {
+        AppError::new(
+            ErrorType::ValidationError,
+            "Unsupported key format: expected an armored GPG public key or an SSH public key",
+        )
+    })?;
+
+    let (public_key, fingerprint, subkey_fingerprints) = {
+        let public_key = public_key.trim().to_string();
+        tokio::task::spawn_blocking(move || match key_type {
+            signing::KEY_TYPE_GPG => signing::gpg_key_fingerprints(&public_key)
+                .map(|(fpr, subkeys)| (public_key, fpr, subkeys)),
+            _ => signing::ssh_key_fingerprint(&public_key).map(|(key, fpr)| (key, fpr, Vec::new())),
+        })
+        .await
+        .map_err(|e| {
+            AppError::with_details(
+                ErrorType::InternalError,
+                "Internal server error",
+                &e.to_string(),
+            )
+        })?
+        .map_err(|e| AppError::new(ErrorType::ValidationError, &e))?
+    };
+
+    // Ключ определяет подписавшего, поэтому один ключ нельзя привязать к двум аккаунтам
+    let pool = db.get_pool();
+    if SigningKey::find_by_fingerprint(&fingerprint, pool)
+        .await
+        .map_err(database_error)?
+        .is_some()
+    {
+        return Err(AppError::new(
+            ErrorType::ConflictError,
+            "This key is already registered",
+        ));
+    }
+
+    let key = SigningKey {
+        id: None,
+        user_id,
+        key_type: key_type.to_string(),
+        title: if title.trim().is_empty() {
+            fingerprint.clone()
+        } else {
+            title.trim().to_string()
+        },
+        public_key,
+        fingerprint,
+        subkey_fingerprints,
+        created_at: None,
+    };
+    key.create(pool).await.map_err(database_error)?;
+
+    SigningKey::find_by_fingerprint(&key.fingerprint, pool)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| {
+            AppError::with_details(
+                ErrorType::InternalError,
+                "Internal server error",
+                "Created key not found",
+            )
+        })
+}
+
+// ============================================================================
+// ПРОВЕРКА ПОДПИСЕЙ
+// ============================================================================
+
+/// Проверяет подписи коммитов зарегистрированными ключами
+///
+/// Результат возвращается в том же порядке, что и `commits`.
+pub async fn verify_commits(
+    repo_path: &str,
+    commits: &[GitCommit],
+    db: &Database,
+) -> Result<Vec<CommitVerification>, String> {
+    // Тип подписи и ID ключа каждого коммита (None для неподписанных)
+    let signatures: Vec<Option<(&'static str, Option<String>)>> = {
+        let repo_path = repo_path.to_string();
+        let shas: Vec<String> = commits.iter().map(|c| c.sha.clone()).collect();
+        tokio::task::spawn_blocking(move || -> Result<_, String> {
+            let raw_commits = git::read_objects(&repo_path, &shas, "commit")?;
+            shas.iter()
+                .zip(raw_commits)
+                .map(|(sha, raw)| {
+                    let Some(signature_type) =
+                        raw.as_deref().and_then(signing::commit_signature_type)
+                    else {
+                        return Ok(None);
+                    };
+                    let key_id = signing::signature_key_id(&repo_path, sha, signature_type)?;
+                    Ok(Some((signature_type, key_id)))
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| format!("Verification task failed: {}", e))??
+    };
+
+    // Загружаем только ключи, которыми подписаны коммиты
+    let mut signers: HashMap<(&'static str, String), Vec<SignerKey>> = HashMap::new();
+    for (signature_type, key_id) in signatures.iter().flatten() {
+        let Some(key_id) = key_id else { continue };
+        let lookup = (*signature_type, key_id.clone());
+        if signers.contains_key(&lookup) {
+            continue;
+        }
+        let keys = SigningKey::find_signers(signature_type, key_id, db.get_pool())
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        signers.insert(lookup, keys);
+    }
+
+    let repo_path = repo_path.to_string();
+    let shas: Vec<String> = commits.iter().map(|c| c.sha.clone()).collect();
+    tokio::task::spawn_blocking(move || {
+        shas.iter()
+            .zip(signatures)
+            .map(|(sha, signature)| verify_commit(&repo_path, sha, signature, &signers))
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Verification task failed: {}", e))
+}
+
+fn verify_commit(
+    repo_path: &str,
+    sha: &str,
+    signature: Option<(&'static str, Option<String>)>,
+    signers: &HashMap<(&'static str, String), Vec<SignerKey>>,
+) -> CommitVerification {
+    let Some((signature_type, key_id)) = signature else {
+        return CommitVerification {
+            check: SignatureCheck::unverified("unsigned"),
+            signature_type: None,
+            signer: None,
+        };
+    };
+    let unverified = |check| CommitVerification {
+        check,
+        signature_type: Some(signature_type),
+        signer: None,
+    };
+
+    let Some(key_id) = key_id else {
+        let reason = match signature_type {
+            signing::KEY_TYPE_GPG | signing::KEY_TYPE_SSH => "invalid",
+            _ => "unsupported_signature",
+        };
+        return unverified(SignatureCheck::unverified(reason));
+    };
+    let candidates = signers
+        .get(&(signature_type, key_id.clone()))
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if candidates.is_empty() {
+        return unverified(SignatureCheck::unknown_key(Some(key_id)));
+    }
+
+    let keys: Vec<String> = candidates
+        .iter()
+        .map(|key| key.public_key.clone())
+        .collect();
+    let check = signing::verify_commit(repo_path, sha, signature_type, &keys);
+    if check.status != SignatureStatus::Verified {
+        return unverified(check);
+    }
+
+    // Подписавший — владелец ключа, отпечаток которого сообщила проверка
+    let signer = candidates
+        .iter()
+        .find(|key| check.fingerprint.as_deref() == Some(key.fingerprint.as_str()));
+    match signer {
+        Some(signer) => CommitVerification {
+            signer: Some(signer.username.clone()),
+            check,
+            signature_type: Some(signature_type),
+        },
+        None => unverified(SignatureCheck::unknown_key(check.fingerprint)),
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
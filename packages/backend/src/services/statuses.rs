@@ -0,0 +1,181 @@
+//! Сервис статусов коммитов, которые присылают внешние CI системы
+
+use serde::Serialize;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::repos::Repository;
+use crate::domain::statuses::{CommitStatus, STATES};
+use crate::utils::git;
+
+/// Контекст статуса, если CI его не указала
+const DEFAULT_CONTEXT: &str = "default";
+const MAX_CONTEXT_LENGTH: usize = 255;
+const MAX_DESCRIPTION_LENGTH: usize = 1024;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Параметры нового статуса
+#[derive(Debug)]
+pub struct NewStatus {
+    pub state: String,
+    pub context: Option<String>,
+    pub description: Option<String>,
+    pub target_url: Option<String>,
+}
+
+/// Сводный статус ревизии по всем контекстам
+#[derive(Debug, Serialize)]
+pub struct CombinedStatus {
+    /// failure, если хоть один статус failure или error; pending, если есть
+    /// незавершённые проверки или статусов нет; иначе success
+    pub state: &'static str,
+    pub sha: String,
+    pub total_count: usize,
+    pub statuses: Vec<CommitStatus>,
+}
+
+// ============================================================================
+// СТАТУСЫ
+// ============================================================================
+
+/// Сохраняет статус коммита
+///
+/// `sha` может быть сокращённым, статус сохраняется для полного SHA коммита.
+pub async fn create_status(
+    repo: &Repository,
+    sha: &str,
+    creator_id: i64,
+    input: NewStatus,
+    db: &Database,
+) -> Result<CommitStatus, AppError> {
+    let repository_id = repo
+        .id
+        .ok_or_else(|| internal_error("Repository must have id"))?;
+
+    if !STATES.contains(&input.state.as_str()) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "State must be one of: pending, success, failure, error",
+        ));
+    }
+
+    let context = input
+        .context
+        .as_deref()
+        .map(str::trim)
+        .filter(|context| !context.is_empty())
+        .unwrap_or(DEFAULT_CONTEXT);
+    if context.len() > MAX_CONTEXT_LENGTH {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Context is too long",
+        ));
+    }
+    if input
+        .description
+        .as_ref()
+        .is_some_and(|d| d.len() > MAX_DESCRIPTION_LENGTH)
+    {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Description is too long",
+        ));
+    }
+    if let Some(url) = &input.target_url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(AppError::new(
+                ErrorType::ValidationError,
+                "Target URL must be an http(s) URL",
+            ));
+        }
+    }
+
+    // Статус привязывается только к коммиту, а не к ветке или тегу
+    if sha.len() < 4 || sha.len() > 40 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "A commit SHA is required",
+        ));
+    }
+    let commit_sha = resolve_commit(repo, sha)
+        .await?
+        .ok_or_else(|| AppError::new(ErrorType::NotFoundError, "Commit not found"))?;
+
+    let status = CommitStatus {
+        id: None,
+        repository_id,
+        commit_sha,
+        state: input.state,
+        context: context.to_string(),
+        description: input.description,
+        target_url: input.target_url,
+        creator_id: Some(creator_id),
+        created_at: None,
+        updated_at: None,
+    };
+
+    status.upsert(db.get_pool()).await.map_err(database_error)
+}
+
+/// Возвращает статусы ревизии (ветки, тега или SHA) и их сводное состояние
+///
+/// `None`, если ревизия не найдена.
+pub async fn combined_status(
+    repo: &Repository,
+    revision: &str,
+    db: &Database,
+) -> Result<Option<CombinedStatus>, AppError> {
+    let repository_id = repo
+        .id
+        .ok_or_else(|| internal_error("Repository must have id"))?;
+
+    let Some(sha) = resolve_commit(repo, revision).await? else {
+        return Ok(None);
+    };
+    let statuses = CommitStatus::find_by_commit(repository_id, &sha, db.get_pool())
+        .await
+        .map_err(database_error)?;
+
+    Ok(Some(CombinedStatus {
+        state: combine_states(&statuses),
+        sha,
+        total_count: statuses.len(),
+        statuses,
+    }))
+}
+
+fn combine_states(statuses: &[CommitStatus]) -> &'static str {
+    if statuses
+        .iter()
+        .any(|s| s.state == "failure" || s.state == "error")
+    {
+        "failure"
+    } else if statuses.is_empty() || statuses.iter().any(|s| s.state == "pending") {
+        "pending"
+    } else {
+        "success"
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+async fn resolve_commit(repo: &Repository, revision: &str) -> Result<Option<String>, AppError> {
+    let repo_path = git::repository_path(&repo.name);
+    let revision = revision.to_string();
+    tokio::task::spawn_blocking(move || git::resolve_revision(&repo_path, &revision))
+        .await
+        .map_err(|e| internal_error(&format!("Status task failed: {}", e)))
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
@@ -0,0 +1,225 @@
+//! Создание репозиториев из шаблонов
+//!
+//! Дерево ветки по умолчанию шаблона копируется в новый репозиторий одним
+//! коммитом без истории шаблона. Плейсхолдеры вида `{{project_name}}`
+//! подставляются в содержимое текстовых файлов и в пути.
+
+use chrono::Datelike;
+
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::repos::Repository;
+use crate::domain::users::User;
+use crate::utils::git::{self, TreeEntry};
+use crate::utils::languages;
+
+/// Режим git для символических ссылок: их содержимое — путь, а не текст
+const SYMLINK_MODE: &str = "120000";
+
+/// Запись дерева шаблона и содержимое блоба (`None` у подмодулей)
+type TemplateFile = (TreeEntry, Option<Vec<u8>>);
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Значения плейсхолдеров для нового репозитория
+#[derive(Debug, Clone)]
+pub struct TemplateVariables {
+    /// Владелец проекта
+    pub owner: String,
+    pub project_name: String,
+    pub repo_name: String,
+    pub description: String,
+}
+
+impl TemplateVariables {
+    fn pairs(&self) -> [(&'static str, String); 5] {
+        [
+            ("{{owner}}", self.owner.clone()),
+            ("{{project_name}}", self.project_name.clone()),
+            ("{{repo_name}}", self.repo_name.clone()),
+            ("{{description}}", self.description.clone()),
+            ("{{year}}", chrono::Utc::now().year().to_string()),
+        ]
+    }
+}
+
+// ============================================================================
+// ГЕНЕРАЦИЯ
+// ============================================================================
+
+/// Заполняет пустой репозиторий `target` содержимым шаблона
+pub async fn generate_from_template(
+    template: &Repository,
+    target: &Repository,
+    variables: TemplateVariables,
+    author: &User,
+) -> Result<(), AppError> {
+    if !template.is_template {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Repository is not a template",
+        ));
+    }
+
+    let template_path = git::repository_path(&template.name);
+    let target_path = git::repository_path(&target.name);
+    let template_name = template.name.clone();
+    let author_name = author.username.clone();
+    let author_email = author
+        .email
+        .clone()
+        .unwrap_or_else(|| format!("{}@users.noreply.netbit", author.username));
+
+    tokio::task::spawn_blocking(move || {
+        let Some((branch, sha)) = git::open_reader(&template_path).default_head() else {
+            return Err(AppError::new(
+                ErrorType::ValidationError,
+                "Template repository is empty",
+            ));
+        };
+
+        let files =
+            render_tree(&template_path, &sha, &variables).map_err(|e| internal_error(&e))?;
+        let message = format!("Initial commit from template {}", template_name);
+        git::write_initial_commit(
+            &target_path,
+            &branch,
+            &files,
+            &author_name,
+            &author_email,
+            &message,
+        )
+        .map_err(|e| internal_error(&e))
+    })
+    .await
+    .map_err(|e| internal_error(&format!("Template task failed: {}", e)))?
+}
+
+/// Читает файлы коммита шаблона и подставляет значения плейсхолдеров
+fn render_tree(
+    repo_path: &str,
+    sha: &str,
+    variables: &TemplateVariables,
+) -> Result<Vec<TemplateFile>, String> {
+    let pairs = variables.pairs();
+    let entries = git::list_tree_entries(repo_path, sha)?;
+
+    let hashes: Vec<String> = entries
+        .iter()
+        .filter(|entry| entry.object_type == "blob")
+        .map(|entry| entry.hash.clone())
+        .collect();
+    let mut blobs = git::read_objects(repo_path, &hashes, "blob")?.into_iter();
+
+    let mut files = Vec::with_capacity(entries.len());
+    for mut entry in entries {
+        let path = render(&entry.path, &pairs);
+        if is_safe_path(&path) {
+            entry.path = path;
+        }
+
+        // Подмодуль переносится ссылкой на коммит
+        if entry.object_type != "blob" {
+            files.push((entry, None));
+            continue;
+        }
+
+        let content = blobs
+            .next()
+            .flatten()
+            .ok_or_else(|| format!("Blob {} is missing", entry.hash))?;
+        let is_text = entry.mode != SYMLINK_MODE && !languages::is_binary(&content);
+        let content = match String::from_utf8(content) {
+            Ok(text) if is_text => render(&text, &pairs).into_bytes(),
+            Ok(text) => text.into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+        files.push((entry, Some(content)));
+    }
+
+    Ok(files)
+}
+
+/// Подставляет значения плейсхолдеров в текст
+fn render(text: &str, pairs: &[(&'static str, String)]) -> String {
+    if !text.contains("{{") {
+        return text.to_string();
+    }
+
+    pairs
+        .iter()
+        .fold(text.to_string(), |text, (placeholder, value)| {
+            text.replace(placeholder, value)
+        })
+}
+
+/// Путь после подстановки не должен выходить за пределы дерева
+fn is_safe_path(path: &str) -> bool {
+    !path.starts_with('/')
+        && path
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != ".." && part != ".git")
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> TemplateVariables {
+        TemplateVariables {
+            owner: "alice".to_string(),
+            project_name: "demo".to_string(),
+            repo_name: "api".to_string(),
+            description: "Demo API".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let pairs = variables().pairs();
+
+        assert_eq!(
+            render("# {{repo_name}} by {{owner}}/{{project_name}}", &pairs),
+            "# api by alice/demo"
+        );
+        assert_eq!(
+            render("{{description}}{{description}}", &pairs),
+            "Demo APIDemo API"
+        );
+        assert_eq!(
+            render("(c) {{year}}", &pairs),
+            format!("(c) {}", chrono::Utc::now().year())
+        );
+        assert_eq!(
+            render("{{unknown}} {{ owner }}", &pairs),
+            "{{unknown}} {{ owner }}"
+        );
+        assert_eq!(render("no placeholders", &pairs), "no placeholders");
+    }
+
+    #[test]
+    fn test_is_safe_path() {
+        assert!(is_safe_path("README.md"));
+        assert!(is_safe_path("src/api/main.rs"));
+        assert!(is_safe_path(".github/workflows/ci.yml"));
+
+        assert!(!is_safe_path(""));
+        assert!(!is_safe_path("/etc/passwd"));
+        assert!(!is_safe_path("../outside"));
+        assert!(!is_safe_path("src/../../outside"));
+        assert!(!is_safe_path("./README.md"));
+        assert!(!is_safe_path("src//main.rs"));
+        assert!(!is_safe_path("src/"));
+        assert!(!is_safe_path(".git/config"));
+        assert!(!is_safe_path("sub/.git/hooks/pre-commit"));
+    }
+}
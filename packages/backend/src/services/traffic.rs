@@ -0,0 +1,340 @@
+//! Статистика посещений репозиториев
+//!
+//! Просмотры считаются по запросу страницы репозитория, клоны и fetch — по
+//! запросам `git upload-pack`, отдавшим packfile. Уникальные посетители
+//! считаются за день: пользователь по id, аноним по хэшу адреса и
+//! User-Agent с солью из секрета сервера и даты, так что сами адреса не
+//! хранятся, а хэши разных дней не сопоставить. Запись идёт в фоне и не
+//! задерживает ответ.
+
+use actix_web::HttpRequest;
+use chrono::{Duration, NaiveDate, Utc};
+use log::{error, info};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::core::config::load_config;
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::repos::Repository;
+use crate::domain::traffic::{RepositoryTraffic, TrafficKind, TrafficReferrer};
+use crate::domain::users::User;
+use crate::services::wiki::WIKI_SUFFIX;
+use crate::utils::git::UploadPackTransfer;
+
+/// Секрет сервера, из которого выводится дневная соль хэша посетителя
+static VISITOR_SECRET: LazyLock<String> = LazyLock::new(|| load_config().jwt_secret);
+
+/// Периоды графиков, доступные владельцу
+pub const ALLOWED_PERIODS: [i64; 2] = [14, 30];
+
+/// Сколько дней хранится статистика
+const RETENTION_DAYS: i64 = 90;
+
+/// Сколько путей переходов показывается
+const TOP_REFERRERS: i64 = 10;
+
+/// Максимальная длина сохраняемого пути перехода
+const MAX_REFERRER_LENGTH: usize = 255;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Сумма за период
+#[derive(Debug, Default, Serialize)]
+pub struct TrafficTotals {
+    pub count: i64,
+    /// Различные посетители за весь период
+    pub uniques: i64,
+}
+
+/// Обращения к репозиторию за один день
+#[derive(Debug, Serialize)]
+pub struct TrafficDay {
+    pub date: NaiveDate,
+    pub views: i64,
+    pub unique_visitors: i64,
+    pub clones: i64,
+    pub unique_cloners: i64,
+    pub fetches: i64,
+    pub unique_fetchers: i64,
+}
+
+/// Статистика репозитория за период
+#[derive(Debug, Serialize)]
+pub struct TrafficSummary {
+    pub days: i64,
+    pub views: TrafficTotals,
+    pub clones: TrafficTotals,
+    pub fetches: TrafficTotals,
+    /// По дню на каждую дату периода, включая дни без обращений
+    pub daily: Vec<TrafficDay>,
+    pub referrers: Vec<TrafficReferrer>,
+}
+
+// ============================================================================
+// ЗАПИСЬ
+// ============================================================================
+
+/// Идентификатор посетителя для подсчёта уникальных обращений
+pub fn visitor(req: &HttpRequest, viewer: Option<&User>) -> String {
+    if let Some(id) = viewer.and_then(|user| user.id) {
+        return format!("u:{}", id);
+    }
+
+    let address = req
+        .connection_info()
+        .realip_remote_addr()
+        .unwrap_or_default()
+        .to_string();
+    let agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    anonymous_visitor(&VISITOR_SECRET, Utc::now().date_naive(), &address, agent)
+}
+
+/// Хэш анонимного посетителя; соль меняется каждый день
+fn anonymous_visitor(secret: &str, day: NaiveDate, address: &str, agent: &str) -> String {
+    let salt = Sha256::new()
+        .chain_update(b"traffic-visitor\n")
+        .chain_update(secret.as_bytes())
+        .chain_update(b"\n")
+        .chain_update(day.to_string().as_bytes())
+        .finalize();
+
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(address.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(agent.as_bytes());
+    format!("a:{:x}", hasher.finalize())
+}
+
+/// Путь из заголовка Referer без схемы, хоста и параметров
+pub fn referrer_path(req: &HttpRequest) -> Option<String> {
+    let referer = req.headers().get("Referer")?.to_str().ok()?;
+    let path = match referer.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |start| &rest[start..]),
+        None => referer,
+    };
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    let mut end = path.len().min(MAX_REFERRER_LENGTH);
+    while !path.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some(path[..end].to_string())
+}
+
+/// Учитывает просмотр репозитория в фоне
+pub fn schedule_view(repository_id: i64, visitor: String, referrer: Option<String>, db: Database) {
+    tokio::spawn(async move {
+        let pool = db.get_pool();
+        if let Err(e) =
+            RepositoryTraffic::record(repository_id, TrafficKind::View, &visitor, pool).await
+        {
+            error!("Failed to record repository view: {}", e);
+        }
+        if let Some(path) = referrer {
+            if let Err(e) = RepositoryTraffic::record_referrer(repository_id, &path, pool).await {
+                error!("Failed to record repository referrer: {}", e);
+            }
+        }
+    });
+}
+
+/// Учитывает клон или fetch репозитория в фоне; обращения к вики не считаются
+pub fn schedule_transfer(
+    repo_name: String,
+    transfer: UploadPackTransfer,
+    visitor: String,
+    db: Database,
+) {
+    if repo_name.ends_with(WIKI_SUFFIX) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let pool = db.get_pool();
+        let repo = match Repository::find_by_name(&repo_name, pool).await {
+            Ok(Some(repo)) => repo,
+            Ok(None) => return,
+            Err(e) => {
+                error!("Failed to load repository {} for traffic: {}", repo_name, e);
+                return;
+            }
+        };
+
+        let kind = match transfer {
+            UploadPackTransfer::Clone => TrafficKind::Clone,
+            UploadPackTransfer::Fetch => TrafficKind::Fetch,
+        };
+        if let Err(e) = RepositoryTraffic::record(repo.id.unwrap(), kind, &visitor, pool).await {
+            error!("Failed to record repository {}: {}", kind.as_str(), e);
+        }
+    });
+}
+
+/// Удаляет статистику старше срока хранения
+pub async fn prune(db: &Database) {
+    let before = Utc::now().date_naive() - Duration::days(RETENTION_DAYS);
+    match RepositoryTraffic::prune(before, db.get_pool()).await {
+        Ok(0) => {}
+        Ok(removed) => info!("Pruned {} repository traffic rows", removed),
+        Err(e) => error!("Failed to prune repository traffic: {}", e),
+    }
+}
+
+// ============================================================================
+// ОТЧЁТ
+// ============================================================================
+
+/// Статистика репозитория за последние `days` дней, включая сегодняшний
+pub async fn summary(
+    repo: &Repository,
+    days: i64,
+    db: &Database,
+) -> Result<TrafficSummary, AppError> {
+    if !ALLOWED_PERIODS.contains(&days) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "days must be 14 or 30",
+        ));
+    }
+
+    let pool = db.get_pool();
+    let repository_id = repo.id.unwrap();
+    let today = Utc::now().date_naive();
+    let since = today - Duration::days(days - 1);
+
+    let counts = RepositoryTraffic::daily(repository_id, since, pool)
+        .await
+        .map_err(database_error)?;
+    let uniques: HashMap<String, i64> = RepositoryTraffic::uniques(repository_id, since, pool)
+        .await
+        .map_err(database_error)?
+        .into_iter()
+        .collect();
+    let referrers = RepositoryTraffic::top_referrers(repository_id, since, TOP_REFERRERS, pool)
+        .await
+        .map_err(database_error)?;
+
+    let mut daily: Vec<TrafficDay> = since
+        .iter_days()
+        .take_while(|date| *date <= today)
+        .map(|date| TrafficDay {
+            date,
+            views: 0,
+            unique_visitors: 0,
+            clones: 0,
+            unique_cloners: 0,
+            fetches: 0,
+            unique_fetchers: 0,
+        })
+        .collect();
+    let totals = |kind: TrafficKind| TrafficTotals {
+        count: counts
+            .iter()
+            .filter(|count| count.kind == kind.as_str())
+            .map(|count| count.count)
+            .sum(),
+        uniques: uniques.get(kind.as_str()).copied().unwrap_or(0),
+    };
+    let views = totals(TrafficKind::View);
+    let clones = totals(TrafficKind::Clone);
+    let fetches = totals(TrafficKind::Fetch);
+
+    for count in &counts {
+        let Some(day) = daily.iter_mut().find(|day| day.date == count.day) else {
+            continue;
+        };
+        let (total, unique) = match count.kind.as_str() {
+            "view" => (&mut day.views, &mut day.unique_visitors),
+            "clone" => (&mut day.clones, &mut day.unique_cloners),
+            "fetch" => (&mut day.fetches, &mut day.unique_fetchers),
+            _ => continue,
+        };
+        *total = count.count;
+        *unique = count.uniques;
+    }
+
+    Ok(TrafficSummary {
+        days,
+        views,
+        clones,
+        fetches,
+        daily,
+        referrers,
+    })
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn referrer(value: &str) -> Option<String> {
+        referrer_path(
+            &TestRequest::default()
+                .insert_header(("Referer", value))
+                .to_http_request(),
+        )
+    }
+
+    #[test]
+    fn test_referrer_path() {
+        assert_eq!(
+            referrer("https://netbit.dev/alice/demo/app?tab=code#readme").as_deref(),
+            Some("/alice/demo/app")
+        );
+        assert_eq!(referrer("http://localhost:3000").as_deref(), Some("/"));
+        assert_eq!(referrer("/explore?q=rust").as_deref(), Some("/explore"));
+        assert_eq!(referrer("android-app://com.example"), Some("/".to_string()));
+        assert_eq!(referrer("not a url"), None);
+        assert_eq!(
+            referrer_path(&TestRequest::default().to_http_request()),
+            None
+        );
+
+        let long = referrer(&format!("https://netbit.dev/{}", "a".repeat(400))).unwrap();
+        assert_eq!(long.len(), MAX_REFERRER_LENGTH);
+    }
+
+    #[test]
+    fn test_anonymous_visitor() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let next_day = day.succ_opt().unwrap();
+        let visitor = anonymous_visitor("secret", day, "10.0.0.1", "curl/8.0");
+
+        assert!(visitor.starts_with("a:"));
+        assert_eq!(
+            visitor,
+            anonymous_visitor("secret", day, "10.0.0.1", "curl/8.0")
+        );
+        assert_ne!(
+            visitor,
+            anonymous_visitor("secret", next_day, "10.0.0.1", "curl/8.0")
+        );
+        assert_ne!(
+            visitor,
+            anonymous_visitor("other", day, "10.0.0.1", "curl/8.0")
+        );
+        assert_ne!(
+            visitor,
+            anonymous_visitor("secret", day, "10.0.0.2", "curl/8.0")
+        );
+    }
+}
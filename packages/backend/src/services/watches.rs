@@ -0,0 +1,446 @@
+//! Звёзды и подписки на проекты
+//!
+//! Звёзды — публичный сигнал популярности: их число показывается в каталоге
+//! публичных проектов. Подписка определяет, какие события проекта попадают в
+//! ленту пользователя и в его уведомления: `all` — все события, `releases` —
+//! только релизы, `ignore` — ничего. Участники проекта (все, у кого есть
+//! доступ ко всему проекту, в том числе через команду или организацию)
+//! подписаны на `all`, пока не выберут другой уровень; остальным нужно
+//! подписаться явно.
+//! Уведомления о найденных секретах настраиваются в конфигурации проекта и от
+//! подписок не зависят.
+
+use log::error;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::events::{
+    ProjectEvent, EVENT_CONFIG_CHANGED, EVENT_MEMBER_ADDED, EVENT_PACKAGE_PUBLISHED, EVENT_PUSH,
+    EVENT_RELEASE_PUBLISHED, EVENT_REPOSITORY_CREATED,
+};
+use crate::domain::notifications::Notification;
+use crate::domain::projects::Project;
+use crate::domain::repos::Repository;
+use crate::domain::users::User;
+use crate::domain::watches::{ProjectStar, ProjectWatch, WATCH_ALL, WATCH_LEVELS, WATCH_RELEASES};
+use crate::services::permissions::{self as permission_service, Permission};
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Звезда текущего пользователя и общее число звёзд проекта
+#[derive(Debug, Serialize)]
+pub struct StarState {
+    pub starred: bool,
+    pub stars: i64,
+}
+
+/// Подписка текущего пользователя на проект
+#[derive(Debug, Serialize)]
+pub struct WatchState {
+    /// all, releases или ignore; `None`, если пользователь не подписан
+    pub level: Option<String>,
+    /// `false`, если уровень достался участнику проекта по умолчанию
+    pub explicit: bool,
+}
+
+impl From<Option<ProjectWatch>> for WatchState {
+    fn from(watch: Option<ProjectWatch>) -> Self {
+        match watch {
+            Some(watch) => WatchState {
+                level: Some(watch.level),
+                explicit: watch.explicit,
+            },
+            None => WatchState {
+                level: None,
+                explicit: false,
+            },
+        }
+    }
+}
+
+/// Проект, за которым следит пользователь, и уровень подписки
+#[derive(Debug)]
+pub struct WatchedProject {
+    pub project: Project,
+    pub level: String,
+}
+
+// ============================================================================
+// ЗВЁЗДЫ
+// ============================================================================
+
+/// Отмечает проект звездой
+pub async fn star(project: &Project, user: &User, db: &Database) -> Result<StarState, AppError> {
+    let (project_id, user_id) = (project_id(project)?, user_id(user)?);
+    ProjectStar::add(project_id, user_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    star_state(project, user, db).await
+}
+
+/// Снимает звезду с проекта
+pub async fn unstar(project: &Project, user: &User, db: &Database) -> Result<StarState, AppError> {
+    let (project_id, user_id) = (project_id(project)?, user_id(user)?);
+    ProjectStar::remove(project_id, user_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    star_state(project, user, db).await
+}
+
+/// Звезда пользователя и число звёзд проекта
+pub async fn star_state(
+    project: &Project,
+    user: &User,
+    db: &Database,
+) -> Result<StarState, AppError> {
+    let (project_id, user_id) = (project_id(project)?, user_id(user)?);
+    let starred = ProjectStar::exists(project_id, user_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    Ok(StarState {
+        starred,
+        stars: star_count(project, db).await?,
+    })
+}
+
+/// Пользователи, отметившие проект
+pub async fn stargazers(project: &Project, db: &Database) -> Result<Vec<ProjectStar>, AppError> {
+    ProjectStar::find_by_project(project_id(project)?, db.get_pool())
+        .await
+        .map_err(database_error)
+}
+
+/// Число звёзд проекта
+pub async fn star_count(project: &Project, db: &Database) -> Result<i64, AppError> {
+    let counts = star_counts(&[project_id(project)?], db).await?;
+    Ok(counts.values().sum())
+}
+
+/// Число звёзд каждого из проектов; у проектов без звёзд записи нет
+pub async fn star_counts(
+    project_ids: &[i64],
+    db: &Database,
+) -> Result<HashMap<i64, i64>, AppError> {
+    Ok(ProjectStar::counts(project_ids, db.get_pool())
+        .await
+        .map_err(database_error)?
+        .into_iter()
+        .collect())
+}
+
+// ============================================================================
+// ПОДПИСКИ
+// ============================================================================
+
+/// Подписка пользователя на проект
+pub async fn watch_state(
+    project: &Project,
+    user: &User,
+    db: &Database,
+) -> Result<WatchState, AppError> {
+    let (project_id, user_id) = (project_id(project)?, user_id(user)?);
+    let watch = ProjectWatch::find(project_id, user_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    Ok(watch.into())
+}
+
+/// Задаёт уровень подписки
+pub async fn set_watch(
+    project: &Project,
+    user: &User,
+    level: &str,
+    db: &Database,
+) -> Result<WatchState, AppError> {
+    if !WATCH_LEVELS.contains(&level) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Watch level must be one of: all, releases, ignore",
+        ));
+    }
+    let (project_id, user_id) = (project_id(project)?, user_id(user)?);
+    ProjectWatch::upsert(project_id, user_id, level, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    watch_state(project, user, db).await
+}
+
+/// Удаляет явную подписку: участник проекта возвращается к уровню по умолчанию
+pub async fn reset_watch(
+    project: &Project,
+    user: &User,
+    db: &Database,
+) -> Result<WatchState, AppError> {
+    let (project_id, user_id) = (project_id(project)?, user_id(user)?);
+    ProjectWatch::delete(project_id, user_id, db.get_pool())
+        .await
+        .map_err(database_error)?;
+    watch_state(project, user, db).await
+}
+
+/// Проекты, за которыми следит пользователь; удалённые пропускаются
+pub async fn watched_projects(user: &User, db: &Database) -> Result<Vec<WatchedProject>, AppError> {
+    let pool = db.get_pool();
+    let watches = ProjectWatch::find_by_user(user_id(user)?, pool)
+        .await
+        .map_err(database_error)?;
+
+    let mut watched = Vec::with_capacity(watches.len());
+    for watch in watches {
+        let project = Project::find_by_id(watch.project_id, pool)
+            .await
+            .map_err(database_error)?;
+        if let Some(project) = project.filter(|project| !project.is_deleted()) {
+            watched.push(WatchedProject {
+                project,
+                level: watch.level,
+            });
+        }
+    }
+    Ok(watched)
+}
+
+// ============================================================================
+// УВЕДОМЛЕНИЯ
+// ============================================================================
+
+/// Уведомляет подписчиков проекта о событии `event_id`
+///
+/// Подписчики с уровнем `releases` получают только релизы. Автор события и
+/// те, кому проект или репозиторий события недоступен, уведомления не получают.
+pub async fn notify_watchers(event_id: i64, db: &Database) {
+    if let Err(e) = try_notify_watchers(event_id, db).await {
+        error!(
+            "Failed to notify watchers about event {}: {:?}",
+            event_id, e
+        );
+    }
+}
+
+async fn try_notify_watchers(event_id: i64, db: &Database) -> Result<(), AppError> {
+    let pool = db.get_pool();
+    let Some(event) = ProjectEvent::find_by_id(event_id, pool)
+        .await
+        .map_err(database_error)?
+    else {
+        return Ok(());
+    };
+    let Some(project) = Project::find_by_id(event.project_id, pool)
+        .await
+        .map_err(database_error)?
+        .filter(|project| !project.is_deleted())
+    else {
+        return Ok(());
+    };
+    let repo = match event.repository_id {
+        Some(id) => Repository::find_by_id(id, pool)
+            .await
+            .map_err(database_error)?,
+        None => None,
+    };
+    let watchers = ProjectWatch::find_by_project(event.project_id, pool)
+        .await
+        .map_err(database_error)?;
+
+    let (title, content) = describe(&event);
+    let name = format!("{} in {}", title, event.project);
+    let name = if name.len() <= 100 {
+        name
+    } else {
+        title.to_string()
+    };
+
+    for watch in watchers {
+        if !wants(&watch, &event) {
+            continue;
+        }
+        let Some(user) = User::find_by_id(watch.user_id, pool)
+            .await
+            .map_err(database_error)?
+        else {
+            continue;
+        };
+
+        let permission = match &repo {
+            Some(repo) => {
+                permission_service::repository_permission(&project, repo, Some(&user), db).await
+            }
+            None => permission_service::project_permission(&project, Some(&user), db).await,
+        }
+        .map_err(database_error)?;
+        if permission < Permission::Read {
+            continue;
+        }
+
+        let notification = Notification {
+            user_id: Some(watch.user_id),
+            ..Notification::new(name.clone(), content.clone()).map_err(|e| internal_error(&e))?
+        };
+        if let Err(e) = notification.create(pool).await {
+            error!("Failed to notify watcher {}: {}", watch.user_id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Нужно ли подписчику уведомление о событии: `releases` получает только
+/// релизы, `ignore` — ничего, а автор события — никогда
+fn wants(watch: &ProjectWatch, event: &ProjectEvent) -> bool {
+    let wanted = match watch.level.as_str() {
+        WATCH_ALL => true,
+        WATCH_RELEASES => event.kind == EVENT_RELEASE_PUBLISHED,
+        _ => false,
+    };
+    wanted && event.actor_id != Some(watch.user_id)
+}
+
+/// Заголовок и текст уведомления о событии
+fn describe(event: &ProjectEvent) -> (&'static str, String) {
+    let actor = event.actor.as_deref().unwrap_or("Someone");
+    let repository = event.repository.as_deref().unwrap_or("a repository");
+    let field = |name: &str| {
+        event
+            .payload
+            .get(name)
+            .map(|value| match value.as_str() {
+                Some(text) => text.to_string(),
+                None => value.to_string(),
+            })
+            .unwrap_or_default()
+    };
+
+    match event.kind.as_str() {
+        EVENT_REPOSITORY_CREATED => (
+            "New repository",
+            format!("{} created repository {}", actor, repository),
+        ),
+        EVENT_CONFIG_CHANGED => (
+            "Configuration changed",
+            format!(
+                "{} saved configuration revision {}",
+                actor,
+                field("revision")
+            ),
+        ),
+        EVENT_PUSH => {
+            let refs: Vec<String> = event
+                .payload
+                .get("refs")
+                .and_then(|refs| refs.as_array())
+                .map(|refs| {
+                    refs.iter()
+                        .filter_map(|update| update.get("ref").and_then(|name| name.as_str()))
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            (
+                "Push",
+                format!("{} pushed to {}: {}", actor, repository, refs.join(", ")),
+            )
+        }
+        EVENT_PACKAGE_PUBLISHED => (
+            "Package published",
+            format!(
+                "{} published {} {}",
+                actor,
+                field("package"),
+                field("version")
+            ),
+        ),
+        EVENT_MEMBER_ADDED => (
+            "New member",
+            format!("{} added {} as {}", actor, field("username"), field("role")),
+        ),
+        EVENT_RELEASE_PUBLISHED => (
+            "New release",
+            format!(
+                "{} published release {} ({}) in {}",
+                actor,
+                field("title"),
+                field("tag"),
+                repository
+            ),
+        ),
+        _ => ("Project activity", format!("{}: {}", actor, event.kind)),
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn project_id(project: &Project) -> Result<i64, AppError> {
+    project
+        .id
+        .ok_or_else(|| internal_error("Project must have id"))
+}
+
+fn user_id(user: &User) -> Result<i64, AppError> {
+    user.id.ok_or_else(|| internal_error("User must have id"))
+}
+
+fn database_error(e: sqlx::Error) -> AppError {
+    AppError::with_details(ErrorType::DatabaseError, "Database error", &e.to_string())
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::watches::WATCH_IGNORE;
+    use serde_json::json;
+
+    fn watch(level: &str) -> ProjectWatch {
+        ProjectWatch {
+            project_id: 1,
+            user_id: 2,
+            level: level.to_string(),
+            explicit: true,
+        }
+    }
+
+    fn event(kind: &str, actor_id: i64) -> ProjectEvent {
+        ProjectEvent {
+            id: 1,
+            project_id: 1,
+            repository_id: None,
+            project: "alice/demo".to_string(),
+            repository: None,
+            actor_id: Some(actor_id),
+            actor: None,
+            kind: kind.to_string(),
+            payload: json!({}),
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_wants_by_level() {
+        let push = event(EVENT_PUSH, 1);
+        let release = event(EVENT_RELEASE_PUBLISHED, 1);
+
+        assert!(wants(&watch(WATCH_ALL), &push));
+        assert!(wants(&watch(WATCH_ALL), &release));
+        assert!(!wants(&watch(WATCH_RELEASES), &push));
+        assert!(wants(&watch(WATCH_RELEASES), &release));
+        assert!(!wants(&watch(WATCH_IGNORE), &push));
+        assert!(!wants(&watch(WATCH_IGNORE), &release));
+    }
+
+    #[test]
+    fn test_wants_skips_actor() {
+        assert!(!wants(&watch(WATCH_ALL), &event(EVENT_PUSH, 2)));
+        assert!(!wants(
+            &watch(WATCH_RELEASES),
+            &event(EVENT_RELEASE_PUBLISHED, 2)
+        ));
+    }
+}
@@ -0,0 +1,387 @@
+//! Вики репозитория: страницы Markdown в отдельном bare-репозитории
+//!
+//! Вики репозитория `name` хранится рядом с ним как `name.wiki.git` и создаётся
+//! при первой записи. Страница `page` — файл `page.md` в корне ветки по
+//! умолчанию, поэтому вики можно клонировать и править обычным git.
+
+use serde::Serialize;
+
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::repos::Repository;
+use crate::domain::users::User;
+use crate::services::files::{self as file_service, CommitOptions, FileCommit, FileOperation};
+use crate::utils::git::{self, GitCommit};
+
+/// Суффикс имени репозитория вики
+pub const WIKI_SUFFIX: &str = ".wiki";
+/// Расширение файлов страниц
+const PAGE_EXTENSION: &str = ".md";
+const MAX_PAGE_NAME_LENGTH: usize = 100;
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Страница в списке
+#[derive(Debug, Serialize)]
+pub struct WikiPage {
+    pub name: String,
+    /// Первый заголовок `# ...` страницы или её имя
+    pub title: String,
+    /// SHA блоба страницы; нужен для изменения и удаления
+    pub sha: String,
+}
+
+/// Страница с содержимым
+#[derive(Debug, Serialize)]
+pub struct WikiPageContent {
+    pub name: String,
+    pub title: String,
+    pub sha: String,
+    pub content: String,
+    /// Ревизия, из которой прочитана страница
+    pub revision: String,
+}
+
+// ============================================================================
+// РЕПОЗИТОРИЙ ВИКИ
+// ============================================================================
+
+/// Имя репозитория вики для репозитория `repo_name`
+pub fn wiki_repository_name(repo_name: &str) -> String {
+    format!("{}{}", repo_name, WIKI_SUFFIX)
+}
+
+/// Создаёт репозиторий вики, если его ещё нет, и возвращает путь к нему
+pub fn ensure_wiki(repo_name: &str) -> Result<String, AppError> {
+    let wiki_path = git::repository_path(&wiki_repository_name(repo_name));
+    if !git::repository_exists(&wiki_path) {
+        git::create_bare_repository(&wiki_path).map_err(|e| internal_error(&e))?;
+    }
+    Ok(wiki_path)
+}
+
+// ============================================================================
+// СТРАНИЦЫ
+// ============================================================================
+
+/// Страницы вики по алфавиту (пустой список, если вики ещё не создана)
+pub async fn list_pages(repo: &Repository) -> Result<Vec<WikiPage>, AppError> {
+    let wiki_path = git::repository_path(&wiki_repository_name(&repo.name));
+
+    run_blocking(move || {
+        if !git::repository_exists(&wiki_path) {
+            return Ok(Vec::new());
+        }
+        let Some((_, head)) = git::resolve_default_head(&wiki_path) else {
+            return Ok(Vec::new());
+        };
+
+        let entries: Vec<_> = git::list_tree_entries(&wiki_path, &head)
+            .map_err(|e| internal_error(&e))?
+            .into_iter()
+            .filter(|entry| entry.object_type == "blob")
+            .filter_map(|entry| {
+                let name = entry.path.strip_suffix(PAGE_EXTENSION)?.to_string();
+                is_valid_page_name(&name).then_some((name, entry.hash))
+            })
+            .collect();
+
+        let hashes: Vec<String> = entries.iter().map(|(_, hash)| hash.clone()).collect();
+        let blobs =
+            git::read_objects(&wiki_path, &hashes, "blob").map_err(|e| internal_error(&e))?;
+
+        let mut pages: Vec<WikiPage> = entries
+            .into_iter()
+            .zip(blobs)
+            .map(|((name, sha), content)| {
+                let content = String::from_utf8_lossy(content.as_deref().unwrap_or_default());
+                WikiPage {
+                    title: page_title(&name, &content),
+                    name,
+                    sha,
+                }
+            })
+            .collect();
+        pages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(pages)
+    })
+    .await
+}
+
+/// Страница на ветке по умолчанию или в указанной ревизии
+pub async fn get_page(
+    repo: &Repository,
+    name: &str,
+    revision: Option<String>,
+) -> Result<WikiPageContent, AppError> {
+    let name = validate_page_name(name)?;
+    let wiki_path = git::repository_path(&wiki_repository_name(&repo.name));
+
+    run_blocking(move || {
+        if !git::repository_exists(&wiki_path) {
+            return Err(not_found("Page not found"));
+        }
+        let revision = match revision {
+            Some(revision) => git::resolve_revision(&wiki_path, &revision)
+                .ok_or_else(|| not_found("Revision not found"))?,
+            None => git::resolve_default_head(&wiki_path)
+                .map(|(_, sha)| sha)
+                .ok_or_else(|| not_found("Page not found"))?,
+        };
+
+        let path = page_path(&name);
+        let entry = git::tree_entry(&wiki_path, &revision, &path)
+            .map_err(|e| internal_error(&e))?
+            .filter(|entry| entry.object_type == "blob")
+            .ok_or_else(|| not_found("Page not found"))?;
+        let content = git::read_file(&wiki_path, &revision, &path)
+            .map_err(|e| internal_error(&e))?
+            .ok_or_else(|| not_found("Page not found"))?;
+        let content = String::from_utf8_lossy(&content).into_owned();
+
+        Ok(WikiPageContent {
+            title: page_title(&name, &content),
+            name,
+            sha: entry.hash,
+            content,
+            revision,
+        })
+    })
+    .await
+}
+
+/// Создаёт (`expected_sha = None`) или изменяет страницу
+pub async fn save_page(
+    repo: &Repository,
+    name: &str,
+    content: String,
+    expected_sha: Option<String>,
+    message: Option<String>,
+    author: &User,
+) -> Result<FileCommit, AppError> {
+    let name = validate_page_name(name)?;
+    let wiki_path = ensure_wiki(&repo.name)?;
+
+    let operation = FileOperation::Write {
+        path: page_path(&name),
+        content: Some(content.into_bytes()),
+        expected_sha,
+        from_path: None,
+    };
+    file_service::commit_file_at(wiki_path, operation, page_options(message), author).await
+}
+
+/// Удаляет страницу
+pub async fn delete_page(
+    repo: &Repository,
+    name: &str,
+    expected_sha: String,
+    message: Option<String>,
+    author: &User,
+) -> Result<FileCommit, AppError> {
+    let name = validate_page_name(name)?;
+    let wiki_path = ensure_wiki(&repo.name)?;
+
+    let operation = FileOperation::Delete {
+        path: page_path(&name),
+        expected_sha,
+    };
+    file_service::commit_file_at(wiki_path, operation, page_options(message), author).await
+}
+
+/// Коммиты, изменявшие страницу, новые первыми
+pub async fn page_history(
+    repo: &Repository,
+    name: &str,
+    limit: usize,
+    skip: usize,
+) -> Result<Vec<GitCommit>, AppError> {
+    let name = validate_page_name(name)?;
+    let wiki_path = git::repository_path(&wiki_repository_name(&repo.name));
+
+    run_blocking(move || {
+        if !git::repository_exists(&wiki_path) {
+            return Ok(Vec::new());
+        }
+        let Some((_, head)) = git::resolve_default_head(&wiki_path) else {
+            return Ok(Vec::new());
+        };
+        git::get_path_commits(&wiki_path, &head, &page_path(&name), limit, skip)
+            .map_err(|e| internal_error(&e))
+    })
+    .await
+}
+
+/// Возвращает страницу к состоянию в коммите `revision` новым коммитом
+///
+/// Если в той ревизии страницы не было, страница удаляется.
+pub async fn revert_page(
+    repo: &Repository,
+    name: &str,
+    revision: &str,
+    author: &User,
+) -> Result<FileCommit, AppError> {
+    let name = validate_page_name(name)?;
+    let wiki_path = ensure_wiki(&repo.name)?;
+
+    let (target, current) = {
+        let wiki_path = wiki_path.clone();
+        let revision = revision.to_string();
+        let path = page_path(&name);
+        run_blocking(move || {
+            let target_sha = git::resolve_revision(&wiki_path, &revision)
+                .ok_or_else(|| not_found("Revision not found"))?;
+            let target =
+                git::read_file(&wiki_path, &target_sha, &path).map_err(|e| internal_error(&e))?;
+            let current = match git::resolve_default_head(&wiki_path) {
+                Some((_, head)) => git::tree_entry(&wiki_path, &head, &path)
+                    .map_err(|e| internal_error(&e))?
+                    .map(|entry| entry.hash),
+                None => None,
+            };
+            Ok(((target_sha, target), current))
+        })
+        .await?
+    };
+
+    let (target_sha, target_content) = target;
+    let short_sha = &target_sha[..target_sha.len().min(7)];
+    let message = Some(format!("Revert {} to {}", name, short_sha));
+
+    let operation = match (target_content, current) {
+        (Some(content), expected_sha) => FileOperation::Write {
+            path: page_path(&name),
+            content: Some(content),
+            expected_sha,
+            from_path: None,
+        },
+        (None, Some(expected_sha)) => FileOperation::Delete {
+            path: page_path(&name),
+            expected_sha,
+        },
+        (None, None) => return Err(not_found("Page not found")),
+    };
+    file_service::commit_file_at(wiki_path, operation, page_options(message), author).await
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Имя страницы: латиница, цифры, `-` и `_`; без вложенных директорий
+fn is_valid_page_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PAGE_NAME_LENGTH
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn validate_page_name(name: &str) -> Result<String, AppError> {
+    let name = name.strip_suffix(PAGE_EXTENSION).unwrap_or(name);
+    if !is_valid_page_name(name) {
+        return Err(AppError::new(
+            ErrorType::ValidationError,
+            "Page name may only contain letters, digits, '-' and '_'",
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn page_path(name: &str) -> String {
+    format!("{}{}", name, PAGE_EXTENSION)
+}
+
+fn page_title(name: &str, content: &str) -> String {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| name.replace(['-', '_'], " "))
+}
+
+/// Вики пишется только в ветку по умолчанию
+fn page_options(message: Option<String>) -> CommitOptions {
+    CommitOptions {
+        branch: None,
+        new_branch: None,
+        message,
+    }
+}
+
+async fn run_blocking<T, F>(task: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| internal_error(&format!("Wiki task failed: {}", e)))?
+}
+
+fn not_found(message: &str) -> AppError {
+    AppError::new(ErrorType::NotFoundError, message)
+}
+
+fn internal_error(details: &str) -> AppError {
+    AppError::with_details(ErrorType::InternalError, "Internal server error", details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_page_name() {
+        assert!(is_valid_page_name("Home"));
+        assert!(is_valid_page_name("Getting-Started_2"));
+        assert!(is_valid_page_name(&"a".repeat(MAX_PAGE_NAME_LENGTH)));
+
+        for name in [
+            "",
+            "-flag",
+            "../secret",
+            "..",
+            "dir/page",
+            "/etc/passwd",
+            "dir\\page",
+            ".hidden",
+            "with space",
+            "page.md",
+            "nul\0",
+            "Страница",
+        ] {
+            assert!(!is_valid_page_name(name), "{:?}", name);
+        }
+        assert!(!is_valid_page_name(&"a".repeat(MAX_PAGE_NAME_LENGTH + 1)));
+    }
+
+    #[test]
+    fn test_validate_page_name() {
+        assert_eq!(validate_page_name("Home").unwrap(), "Home");
+        assert_eq!(validate_page_name("Home.md").unwrap(), "Home");
+        assert!(validate_page_name(".md").is_err());
+        assert!(validate_page_name("../Home.md").is_err());
+        assert!(validate_page_name("Home.md.md").is_err());
+    }
+
+    #[test]
+    fn test_page_title() {
+        assert_eq!(page_title("Home", "# Welcome\n\nText"), "Welcome");
+        assert_eq!(
+            page_title("Home", "Intro\n#  Spaced title  \n"),
+            "Spaced title"
+        );
+        assert_eq!(page_title("Home", "## Subtitle\n#NoSpace"), "Home");
+        assert_eq!(
+            page_title("Getting-Started_guide", "no heading"),
+            "Getting Started guide"
+        );
+        assert_eq!(page_title("Empty-heading", "# \n"), "Empty heading");
+        assert_eq!(page_title("Blank", ""), "Blank");
+    }
+}
@@ -0,0 +1,89 @@
+//! API обработчики ленты активности проектов
+
+use crate::core::database::Database;
+use crate::services::activity as activity_service;
+use crate::transports::http::projects::resolve_project;
+use crate::transports::http::users::{self as user, app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl ActivityQuery {
+    fn page(&self) -> (i64, i64) {
+        (
+            self.page.unwrap_or(1).max(1),
+            self.per_page.unwrap_or(30).clamp(1, 100),
+        )
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// События проекта, новые первыми
+pub async fn project_activity(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<ActivityQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let (page, per_page) = query.page();
+    match activity_service::project_activity(&ctx.project, ctx.viewer.as_ref(), page, per_page, &db)
+        .await
+    {
+        Ok(events) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(events),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Лента текущего пользователя по проектам, за которыми он следит
+pub async fn dashboard(
+    req: HttpRequest,
+    query: web::Query<ActivityQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    let (page, per_page) = query.page();
+    match activity_service::dashboard(&viewer, page, per_page, &db).await {
+        Ok(events) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(events),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
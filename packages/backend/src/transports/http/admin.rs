@@ -0,0 +1,76 @@
+//! API обработчики администрирования сервера
+
+use crate::core::database::Database;
+use crate::domain::maintenance::RepositoryMaintenance;
+use crate::services::maintenance::{self as maintenance_service, SchedulerStatus};
+use crate::transports::http::users::{self as user, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use log::error;
+use serde::Serialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct MaintenanceOverview {
+    pub scheduler: SchedulerStatus,
+    pub repositories: Vec<RepositoryMaintenance>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response() -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Administrator access required".to_string()),
+        data: None,
+    })
+}
+
+fn create_error_response(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Состояние планировщика и обслуживания репозиториев
+pub async fn get_maintenance(req: HttpRequest, db: web::Data<Database>) -> Result<HttpResponse> {
+    let Some(current_user) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+    if !current_user.is_admin {
+        return Ok(create_forbidden_response());
+    }
+
+    match RepositoryMaintenance::find_all(db.get_pool()).await {
+        Ok(repositories) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(MaintenanceOverview {
+                scheduler: maintenance_service::scheduler_status(),
+                repositories,
+            }),
+        })),
+        Err(e) => {
+            error!("Database error: {}", e);
+            Ok(create_error_response("Database error"))
+        }
+    }
+}
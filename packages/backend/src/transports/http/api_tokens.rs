@@ -0,0 +1,159 @@
+//! API обработчики персональных API токенов
+
+use crate::core::database::Database;
+use crate::domain::auth::ApiToken;
+use crate::domain::users::User;
+use crate::services::auth as auth_service;
+use crate::transports::http::users::{self as user, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use log::error;
+use serde::Deserialize;
+
+/// Максимальный срок действия токена
+const MAX_TOKEN_LIFETIME_DAYS: i64 = 365;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    /// Срок действия в днях (без срока, если не указан)
+    pub expires_in_days: Option<i64>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Пользователь сессии входа (JWT)
+///
+/// Создавать и отзывать токены можно только из сессии: запрос с API токеном или
+/// паролем в Basic авторизации не должен выпускать новые токены.
+async fn check_session(req: &HttpRequest, db: &Database) -> Option<User> {
+    auth_service::verify_jwt_token(req, db).await
+}
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_bad_request_response(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_error_response(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_not_found_response(message: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Список API токенов текущего пользователя (без значений токенов)
+pub async fn list_tokens(req: HttpRequest, db: web::Data<Database>) -> Result<HttpResponse> {
+    let Some(current_user) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match ApiToken::find_by_user(current_user.id.unwrap(), db.get_pool()).await {
+        Ok(tokens) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(tokens),
+        })),
+        Err(e) => {
+            error!("Database error: {}", e);
+            Ok(create_error_response("Database error"))
+        }
+    }
+}
+
+/// Создание API токена; значение возвращается только в этом ответе
+///
+/// Требует сессии входа, см. [`check_session`].
+pub async fn create_token(
+    req: HttpRequest,
+    token_req: web::Json<CreateApiTokenRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(current_user) = check_session(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    let name = token_req.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Ok(create_bad_request_response(
+            "Token name must be between 1 and 100 characters",
+        ));
+    }
+    if let Some(days) = token_req.expires_in_days {
+        if !(1..=MAX_TOKEN_LIFETIME_DAYS).contains(&days) {
+            return Ok(create_bad_request_response(&format!(
+                "expires_in_days must be between 1 and {}",
+                MAX_TOKEN_LIFETIME_DAYS
+            )));
+        }
+    }
+
+    match auth_service::create_api_token(
+        current_user.id.unwrap(),
+        name,
+        token_req.expires_in_days,
+        &db,
+    )
+    .await
+    {
+        Ok(token) => Ok(HttpResponse::Created().json(ApiResponse {
+            success: true,
+            message: Some("Token created. Copy it now, it will not be shown again".to_string()),
+            data: Some(token),
+        })),
+        Err(e) => Ok(create_error_response(&e)),
+    }
+}
+
+/// Отзыв API токена (только из сессии входа)
+pub async fn delete_token(
+    req: HttpRequest,
+    path: web::Path<i64>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(current_user) = check_session(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match ApiToken::delete(path.into_inner(), current_user.id.unwrap(), db.get_pool()).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Token revoked successfully".to_string()),
+            data: None,
+        })),
+        Ok(false) => Ok(create_not_found_response("Token not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            Ok(create_error_response("Database error"))
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! API обработчики соавторов проектов и репозиториев
+
+use crate::core::database::Database;
+use crate::services::permissions::{self as permission_service, CollaboratorScope, Permission};
+use crate::transports::http::projects::resolve_project;
+use crate::transports::http::repositories::resolve_repository;
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use log::error;
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct GrantRoleRequest {
+    /// read, write или admin
+    pub role: String,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+/// Просмотр соавторов доступен с правом записи, управление — администраторам
+fn check_permission(
+    authenticated: bool,
+    permission: Permission,
+    required: Permission,
+) -> Option<HttpResponse> {
+    if !authenticated {
+        return Some(create_unauthorized_response());
+    }
+    if permission >= required {
+        return None;
+    }
+    let message = match required {
+        Permission::Admin => "Admin access is required to manage collaborators",
+        _ => "Write access is required to view collaborators",
+    };
+    Some(create_forbidden_response(message))
+}
+
+async fn list_response(scope: CollaboratorScope, db: &Database) -> HttpResponse {
+    match permission_service::list_collaborators(scope, db).await {
+        Ok(collaborators) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(collaborators),
+        }),
+        Err(e) => {
+            error!("Failed to list collaborators: {:?}", e);
+            app_error_response(e)
+        }
+    }
+}
+
+// ============================================================================
+// API HANDLERS: ПРОЕКТ
+// ============================================================================
+
+/// Соавторы проекта
+pub async fn list_project_collaborators(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) =
+        check_permission(ctx.viewer.is_some(), ctx.permission, Permission::Write)
+    {
+        return Ok(response);
+    }
+
+    let scope = CollaboratorScope::Project(ctx.project.id.unwrap());
+    Ok(list_response(scope, &db).await)
+}
+
+/// Выдача или смена роли пользователя на весь проект
+pub async fn grant_project_collaborator(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    role_req: web::Json<GrantRoleRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, collaborator) = path.into_inner();
+    let ctx = match resolve_project(&req, &db, (username, project_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) =
+        check_permission(ctx.viewer.is_some(), ctx.permission, Permission::Admin)
+    {
+        return Ok(response);
+    }
+
+    let scope = CollaboratorScope::Project(ctx.project.id.unwrap());
+    match permission_service::grant(scope, &ctx.project, &collaborator, &role_req.role, &db).await {
+        Ok(collaborator) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Collaborator saved".to_string()),
+            data: Some(collaborator),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Отзыв роли пользователя на проект
+pub async fn revoke_project_collaborator(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, collaborator) = path.into_inner();
+    let ctx = match resolve_project(&req, &db, (username, project_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) =
+        check_permission(ctx.viewer.is_some(), ctx.permission, Permission::Admin)
+    {
+        return Ok(response);
+    }
+
+    let scope = CollaboratorScope::Project(ctx.project.id.unwrap());
+    match permission_service::revoke(scope, &collaborator, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Collaborator removed".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+// ============================================================================
+// API HANDLERS: РЕПОЗИТОРИЙ
+// ============================================================================
+
+/// Соавторы репозитория (без соавторов всего проекта)
+pub async fn list_repo_collaborators(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) =
+        check_permission(ctx.viewer.is_some(), ctx.permission, Permission::Write)
+    {
+        return Ok(response);
+    }
+
+    let scope = CollaboratorScope::Repository(ctx.repo.id.unwrap());
+    Ok(list_response(scope, &db).await)
+}
+
+/// Выдача или смена роли пользователя на репозиторий
+pub async fn grant_repo_collaborator(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    role_req: web::Json<GrantRoleRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, collaborator) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) =
+        check_permission(ctx.viewer.is_some(), ctx.permission, Permission::Admin)
+    {
+        return Ok(response);
+    }
+
+    let scope = CollaboratorScope::Repository(ctx.repo.id.unwrap());
+    match permission_service::grant(scope, &ctx.project, &collaborator, &role_req.role, &db).await {
+        Ok(collaborator) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Collaborator saved".to_string()),
+            data: Some(collaborator),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Отзыв роли пользователя на репозиторий
+pub async fn revoke_repo_collaborator(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, collaborator) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) =
+        check_permission(ctx.viewer.is_some(), ctx.permission, Permission::Admin)
+    {
+        return Ok(response);
+    }
+
+    let scope = CollaboratorScope::Repository(ctx.repo.id.unwrap());
+    match permission_service::revoke(scope, &collaborator, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Collaborator removed".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
@@ -0,0 +1,140 @@
+//! API обработчики ключей развёртывания репозитория
+
+use crate::core::database::Database;
+use crate::services::deploy_keys::{self as deploy_key_service, NewKeyInput};
+use crate::transports::http::repositories::{resolve_repository, RepoContext};
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct AddDeployKeyRequest {
+    pub title: String,
+    /// SSH ключи не поддерживаются; поле принимается, чтобы отклонить запрос
+    pub public_key: Option<String>,
+    /// По умолчанию ключ даёт только чтение
+    #[serde(default = "default_read_only")]
+    pub read_only: bool,
+}
+
+fn default_read_only() -> bool {
+    true
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+/// Находит репозиторий и проверяет права администратора
+async fn resolve_admin(
+    req: &HttpRequest,
+    db: &Database,
+    path: (String, String, String),
+) -> std::result::Result<RepoContext, HttpResponse> {
+    let ctx = resolve_repository(req, db, path).await?;
+    if ctx.viewer.is_none() {
+        return Err(create_unauthorized_response());
+    }
+    if !ctx.can_admin() {
+        return Err(create_forbidden_response(
+            "Admin access is required to manage deploy keys",
+        ));
+    }
+    Ok(ctx)
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Ключи развёртывания репозитория
+pub async fn list_deploy_keys(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_admin(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match deploy_key_service::list_keys(&ctx.repo, &db).await {
+        Ok(keys) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(keys),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Добавление SSH ключа или выпуск HTTP токена
+pub async fn add_deploy_key(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    key_req: web::Json<AddDeployKeyRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_admin(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let key_req = key_req.into_inner();
+    let input = NewKeyInput {
+        title: key_req.title,
+        public_key: key_req.public_key,
+        read_only: key_req.read_only,
+    };
+    let creator_id = ctx.viewer.as_ref().and_then(|u| u.id);
+    match deploy_key_service::add_key(&ctx.repo, input, creator_id, &db).await {
+        Ok(key) => Ok(HttpResponse::Created().json(ApiResponse {
+            success: true,
+            message: Some("Deploy key added successfully".to_string()),
+            data: Some(key),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Удаление ключа развёртывания
+pub async fn delete_deploy_key(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, i64)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, id) = path.into_inner();
+    let ctx = match resolve_admin(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match deploy_key_service::delete_key(&ctx.repo, id, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Deploy key deleted successfully".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
@@ -0,0 +1,171 @@
+//! API обработчики лент Atom/RSS
+
+use crate::core::config::load_config;
+use crate::core::database::Database;
+use crate::domain::users::User;
+use crate::services::feeds::{self as feed_service, FeedLinks};
+use crate::transports::http::repositories::resolve_repository;
+use crate::transports::http::users::{self as user, app_error_response, ApiResponse};
+use crate::utils::feed::{self, Feed, FeedFormat};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use log::error;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    /// `atom` (по умолчанию) или `rss`
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitsFeedQuery {
+    /// Ветка (по умолчанию — ветка по умолчанию)
+    pub branch: Option<String>,
+    pub format: Option<String>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_error_response(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_bad_request_response(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_not_found_response(message: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn parse_format(format: Option<&str>) -> Option<FeedFormat> {
+    FeedFormat::parse(format.unwrap_or("atom"))
+}
+
+/// Внешний адрес сервера из конфигурации; заголовку Host клиента не доверяем
+static BASE_URL: LazyLock<String> = LazyLock::new(|| load_config().public_url);
+
+fn create_feed_response(format: FeedFormat, feed: &Feed) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .body(feed::render(format, feed))
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Лента коммитов ветки
+pub async fn commits_feed(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<CommitsFeedQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(format) = parse_format(query.format.as_deref()) else {
+        return Ok(create_bad_request_response("format must be atom or rss"));
+    };
+    let (username, project_name, repo_name) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username.clone(), project_name, repo_name)).await
+    {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let url = format!("{}{}", *BASE_URL, req.uri());
+    let links = FeedLinks {
+        url: &url,
+        base_url: &BASE_URL,
+    };
+    match feed_service::commits_feed(
+        &username,
+        &ctx.project,
+        &ctx.repo,
+        query.into_inner().branch,
+        &links,
+    )
+    .await
+    {
+        Ok(feed) => Ok(create_feed_response(format, &feed)),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Лента релизов и тегов репозитория
+pub async fn releases_feed(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<FeedQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(format) = parse_format(query.format.as_deref()) else {
+        return Ok(create_bad_request_response("format must be atom or rss"));
+    };
+    let (username, project_name, repo_name) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username.clone(), project_name, repo_name)).await
+    {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let url = format!("{}{}", *BASE_URL, req.uri());
+    let links = FeedLinks {
+        url: &url,
+        base_url: &BASE_URL,
+    };
+    match feed_service::releases_feed(&username, &ctx.project, &ctx.repo, &links, &db).await {
+        Ok(feed) => Ok(create_feed_response(format, &feed)),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Лента активности пользователя, видимой читателю
+pub async fn user_feed(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<FeedQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(format) = parse_format(query.format.as_deref()) else {
+        return Ok(create_bad_request_response("format must be atom or rss"));
+    };
+    let username = path.into_inner();
+    let target = match User::find_by_username(&username, db.get_pool()).await {
+        Ok(Some(target)) => target,
+        Ok(None) => return Ok(create_not_found_response("User not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Ok(create_error_response("Database error"));
+        }
+    };
+    let viewer = user::check_auth(&req, &db).await;
+
+    let url = format!("{}{}", *BASE_URL, req.uri());
+    let links = FeedLinks {
+        url: &url,
+        base_url: &BASE_URL,
+    };
+    match feed_service::user_feed(&target, viewer.as_ref(), &links, &db).await {
+        Ok(feed) => Ok(create_feed_response(format, &feed)),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
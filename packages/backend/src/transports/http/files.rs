@@ -0,0 +1,295 @@
+//! API обработчики редактирования файлов в ветках (коммит на стороне сервера)
+
+use crate::core::database::Database;
+use crate::services::activity as activity_service;
+use crate::services::files::{self as file_service, CommitOptions, FileCommit, FileOperation};
+use crate::services::pipelines as pipeline_service;
+use crate::services::projects::config as config_service;
+use crate::services::search as search_service;
+use crate::services::secret_scanning as secret_scanning_service;
+use crate::transports::http::repositories::{resolve_repository, RepoContext};
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use crate::utils::git::RefUpdate;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use base64::{engine::general_purpose, Engine as _};
+use log::error;
+use serde::Deserialize;
+
+/// Максимальный размер JSON запроса с содержимым файла
+pub const MAX_REQUEST_SIZE: usize = 16 * 1024 * 1024;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct FileQuery {
+    /// Ветка, тег или SHA (по умолчанию — ветка по умолчанию)
+    pub branch: Option<String>,
+    /// `raw` (по умолчанию) — содержимое как есть, `rendered` — HTML для показа
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WriteFileRequest {
+    /// Новое содержимое; при переносе без него сохраняется прежнее
+    pub content: Option<String>,
+    /// `utf-8` (по умолчанию) или `base64`
+    pub encoding: Option<String>,
+    /// SHA блоба, который видел клиент (обязателен при изменении и переносе)
+    pub sha: Option<String>,
+    /// Прежний путь файла при переносе
+    pub from_path: Option<String>,
+    pub branch: Option<String>,
+    /// Записать изменение в новую ветку
+    pub new_branch: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteFileRequest {
+    pub sha: String,
+    pub branch: Option<String>,
+    pub new_branch: Option<String>,
+    pub message: Option<String>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_bad_request_response(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+/// Находит репозиторий и проверяет право записи
+async fn resolve_writable(
+    req: &HttpRequest,
+    db: &Database,
+    path: (String, String, String),
+) -> std::result::Result<RepoContext, HttpResponse> {
+    let ctx = resolve_repository(req, db, path).await?;
+    if ctx.viewer.is_none() {
+        return Err(create_unauthorized_response());
+    }
+    if !ctx.can_write() {
+        return Err(create_forbidden_response(
+            "Write access is required to edit files",
+        ));
+    }
+    Ok(ctx)
+}
+
+fn decode_content(content: String, encoding: Option<&str>) -> Option<Vec<u8>> {
+    match encoding.unwrap_or("utf-8") {
+        "utf-8" => Some(content.into_bytes()),
+        "base64" => general_purpose::STANDARD.decode(content.trim()).ok(),
+        _ => None,
+    }
+}
+
+/// Выполняет изменение и запускает то же, что и push: индексацию и пайплайны
+async fn commit_and_respond(
+    ctx: RepoContext,
+    operation: FileOperation,
+    options: CommitOptions,
+    db: &Database,
+) -> HttpResponse {
+    let author = ctx.viewer.as_ref().unwrap();
+
+    // Правила защиты веток действуют так же, как при push
+    let branch = match file_service::target_branch(&ctx.repo, &options).await {
+        Ok(branch) => branch,
+        Err(e) => return app_error_response(e),
+    };
+    if let Err(e) = config_service::check_commit(&ctx.repo, &branch, ctx.permission, db).await {
+        return app_error_response(e);
+    }
+
+    // Новое содержимое проверяется на секреты так же, как блобы push
+    let mut secret_scan = match &operation {
+        FileOperation::Write {
+            path,
+            content: Some(content),
+            ..
+        } => match secret_scanning_service::scan_file(&ctx.repo, path, content, db).await {
+            Ok(scan) => scan,
+            Err(e) => return app_error_response(e),
+        },
+        _ => None,
+    };
+    if let Some(scan) = secret_scan.as_ref().filter(|scan| scan.rejects()) {
+        return create_bad_request_response(&scan.file_rejection_message());
+    }
+
+    match file_service::commit_file(&ctx.repo, operation, options, author).await {
+        Ok(commit) => {
+            if let Some(scan) = secret_scan.as_mut() {
+                scan.assign_commit(
+                    &commit.commit_sha,
+                    commit.content_sha.as_deref().unwrap_or_default(),
+                    &format!("refs/heads/{}", commit.branch),
+                );
+                if let Err(e) = secret_scanning_service::record_findings(scan, author.id, db).await
+                {
+                    error!("Failed to record secret findings: {:?}", e);
+                }
+            }
+            schedule_push_hooks(&ctx, &commit, db).await;
+            HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                message: Some("Changes committed successfully".to_string()),
+                data: Some(commit),
+            })
+        }
+        Err(e) => app_error_response(e),
+    }
+}
+
+/// Обрабатывает коммит через API как push: журнал проекта, поисковый индекс
+/// и пайплайны
+async fn schedule_push_hooks(ctx: &RepoContext, commit: &FileCommit, db: &Database) {
+    let pusher_id = ctx.viewer.as_ref().and_then(|u| u.id);
+    let updates = vec![RefUpdate {
+        new_sha: commit.commit_sha.clone(),
+        ref_name: format!("refs/heads/{}", commit.branch),
+    }];
+    activity_service::record_push(&ctx.repo.name, &updates, pusher_id, db).await;
+
+    search_service::schedule_repository_index(ctx.repo.name.clone(), db.clone());
+    pipeline_service::schedule_push_pipelines(ctx.repo.name.clone(), updates, pusher_id);
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Содержимое файла ревизии или его подсвеченная разметка
+pub async fn get_file(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    query: web::Query<FileQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, file_path) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let query = query.into_inner();
+    let rendered = match query.mode.as_deref().unwrap_or("raw") {
+        "raw" => false,
+        "rendered" => true,
+        _ => return Ok(create_bad_request_response("mode must be raw or rendered")),
+    };
+
+    let blob = match file_service::read_file(&ctx.repo, &file_path, query.branch).await {
+        Ok(blob) => blob,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+    if !rendered {
+        return Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(blob.into_content()),
+        }));
+    }
+
+    match file_service::render_file(blob).await {
+        Ok(file) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(file),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Создание, изменение или перенос файла
+pub async fn write_file(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    file_req: web::Json<WriteFileRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, file_path) = path.into_inner();
+    let ctx = match resolve_writable(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let file_req = file_req.into_inner();
+    let content = match file_req.content {
+        Some(content) => match decode_content(content, file_req.encoding.as_deref()) {
+            Some(content) => Some(content),
+            None => {
+                return Ok(create_bad_request_response(
+                    "Content must be utf-8 text or valid base64",
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let operation = FileOperation::Write {
+        path: file_path,
+        content,
+        expected_sha: file_req.sha,
+        from_path: file_req.from_path,
+    };
+    let options = CommitOptions {
+        branch: file_req.branch,
+        new_branch: file_req.new_branch,
+        message: file_req.message,
+    };
+
+    Ok(commit_and_respond(ctx, operation, options, &db).await)
+}
+
+/// Удаление файла
+pub async fn delete_file(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    file_req: web::Json<DeleteFileRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, file_path) = path.into_inner();
+    let ctx = match resolve_writable(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let file_req = file_req.into_inner();
+    let operation = FileOperation::Delete {
+        path: file_path,
+        expected_sha: file_req.sha,
+    };
+    let options = CommitOptions {
+        branch: file_req.branch,
+        new_branch: file_req.new_branch,
+        message: file_req.message,
+    };
+
+    Ok(commit_and_respond(ctx, operation, options, &db).await)
+}
@@ -1,5 +1,19 @@
 use crate::core::database::Database;
+use crate::domain::projects::Project;
+use crate::domain::repos::Repository;
+use crate::domain::users::User;
+use crate::services::activity as activity_service;
+use crate::services::auth as auth_service;
+use crate::services::deploy_keys as deploy_key_service;
+use crate::services::permissions::{self as permission_service, Permission};
+use crate::services::pipelines as pipeline_service;
+use crate::services::projects::config as config_service;
+use crate::services::search as search_service;
+use crate::services::secret_scanning as secret_scanning_service;
+use crate::services::traffic as traffic_service;
+use crate::services::wiki::{self as wiki_service, WIKI_SUFFIX};
 use crate::transports::http::users as user;
+use crate::utils::git;
 use actix_web::{web, HttpRequest, HttpResponse};
 use log::{debug, error};
 use std::fs;
@@ -23,7 +37,8 @@ use std::process::{Command, Stdio};
 /// - Затем список ссылок в PKT-LINE формате
 ///
 /// # Ошибки
-/// - 401 Unauthorized если пользователь не аутентифицирован
+/// - 401 Unauthorized если для операции нужна аутентификация
+/// - 403 Forbidden если прав пользователя недостаточно
 /// - 400 Bad Request если неверный запрос
 /// - 500 Internal Server Error если команда git завершилась с ошибкой
 ///
@@ -34,44 +49,34 @@ use std::process::{Command, Stdio};
 /// 004895dcfa3633004da0049d3d0fa03f80589cbcaf31 refs/heads/main\0multi_ack\n
 /// ```
 pub async fn handle_info_refs(req: HttpRequest) -> HttpResponse {
-    // Проверяем авторизацию пользователя
-    if user::check_auth(&req, &req.app_data::<web::Data<Database>>().unwrap())
-        .await
-        .is_none()
-    {
-        return HttpResponse::Unauthorized()
-            .append_header(("WWW-Authenticate", "Basic realm=\"Git\""))
-            .finish();
+    // Извлекаем имя сервиса из query string; для push нужны права записи,
+    // для clone/fetch — чтения
+    let service = req
+        .query_string()
+        .strip_prefix("service=")
+        .unwrap_or_default();
+    let (git_command, required) = match service {
+        "git-upload-pack" => ("upload-pack", Permission::Read),
+        "git-receive-pack" => ("receive-pack", Permission::Write),
+        _ => return HttpResponse::BadRequest().finish(),
+    };
+    if let Err(response) = authorize(&req, required).await {
+        return response;
     }
 
-    let user_name = req.match_info().get("user_name").unwrap();
-    let repo_name = req.match_info().get("repo_name").unwrap();
-    let service = req.query_string();
+    let repo_name = repo_name_from_path(&req);
+    let repo_path = git::repository_path(&repo_name);
 
     debug!(
         "Handling info/refs for repo: {}, service: {}",
         repo_name, service
     );
 
-    // Извлекаем имя сервиса из query string
-    let service = match service.strip_prefix("service=") {
-        Some(s) => s,
-        None => return HttpResponse::BadRequest().finish(),
-    };
-
-    // Выбираем соответствующую Git команду
-    let git_command = if service == "git-upload-pack" {
-        "upload-pack"
-    } else {
-        "receive-pack"
-    };
-
     // Выполняем Git команду для получения списка ссылок
     let output = Command::new("git")
         .arg(git_command)
         .arg("--advertise-refs")
-        .arg(&user_name)
-        .arg(&repo_name)
+        .arg(&repo_path)
         .output()
         .expect("Failed to execute git command");
 
@@ -123,7 +128,8 @@ pub async fn handle_info_refs(req: HttpRequest) -> HttpResponse {
 /// Возвращает `HttpResponse` с данными в формате packfile
 ///
 /// # Ошибки
-/// - 401 Unauthorized если пользователь не аутентифицирован
+/// - 401 Unauthorized если для операции нужна аутентификация
+/// - 403 Forbidden если прав пользователя недостаточно
 /// - 500 Internal Server Error если команда git завершилась с ошибкой
 ///
 /// # Протокол
@@ -131,26 +137,20 @@ pub async fn handle_info_refs(req: HttpRequest) -> HttpResponse {
 /// 2. Сервер запускает `git-upload-pack --stateless-rpc`
 /// 3. Сервер возвращает упакованные объекты
 pub async fn handle_upload_pack(req: HttpRequest, body: web::Bytes) -> HttpResponse {
-    // Проверяем авторизацию пользователя
-    if user::check_auth(&req, &req.app_data::<web::Data<Database>>().unwrap())
-        .await
-        .is_none()
-    {
-        return HttpResponse::Unauthorized()
-            .append_header(("WWW-Authenticate", "Basic realm=\"Git\""))
-            .finish();
-    }
+    // Проверяем право чтения
+    let viewer = match authorize(&req, Permission::Read).await {
+        Ok((viewer, _)) => viewer,
+        Err(response) => return response,
+    };
 
-    let user_name = req.match_info().get("user_name").unwrap();
-    let repo_name = req.match_info().get("repo_name").unwrap();
-    let _repo_path = PathBuf::from("repositories").join(format!("{}.git", repo_name));
+    let repo_name = repo_name_from_path(&req);
+    let repo_path = git::repository_path(&repo_name);
 
     // Запускаем git-upload-pack в режиме stateless-rpc (для HTTP протокола)
     let mut child = Command::new("git")
         .arg("upload-pack")
         .arg("--stateless-rpc") // Важно для HTTP протокола
-        .arg(&user_name)
-        .arg(&repo_name)
+        .arg(&repo_path)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -178,6 +178,21 @@ pub async fn handle_upload_pack(req: HttpRequest, body: web::Bytes) -> HttpRespo
         return HttpResponse::InternalServerError().finish();
     }
 
+    // Учитываем клон или fetch, если клиент получил packfile
+    if let Some(transfer) = git::upload_pack_transfer(&body, &output.stdout) {
+        let db = req
+            .app_data::<web::Data<Database>>()
+            .unwrap()
+            .get_ref()
+            .clone();
+        traffic_service::schedule_transfer(
+            repo_name,
+            transfer,
+            traffic_service::visitor(&req, viewer.as_ref()),
+            db,
+        );
+    }
+
     // Возвращаем результат в формате packfile
     HttpResponse::Ok()
         .content_type("application/x-git-upload-pack-result")
@@ -197,7 +212,8 @@ pub async fn handle_upload_pack(req: HttpRequest, body: web::Bytes) -> HttpRespo
 /// Возвращает `HttpResponse` с результатом операции
 ///
 /// # Ошибки
-/// - 401 Unauthorized если пользователь не аутентифицирован
+/// - 401 Unauthorized если для операции нужна аутентификация
+/// - 403 Forbidden если прав пользователя недостаточно
 /// - 500 Internal Server Error если команда git завершилась с ошибкой
 ///
 /// # Протокол
@@ -205,27 +221,55 @@ pub async fn handle_upload_pack(req: HttpRequest, body: web::Bytes) -> HttpRespo
 /// 2. Сервер запускает `git-receive-pack --stateless-rpc`
 /// 3. Сервер обновляет ссылки и возвращает результат
 pub async fn handle_receive_pack(req: HttpRequest, body: web::Bytes) -> HttpResponse {
-    // Проверяем авторизацию и получаем имя пользователя
-    let _username =
-        match user::check_auth(&req, &req.app_data::<web::Data<Database>>().unwrap()).await {
-            Some(user) => user.username,
-            None => {
-                return HttpResponse::Unauthorized()
-                    .append_header(("WWW-Authenticate", "Basic realm=\"Git\""))
-                    .finish()
-            }
-        };
-    let user_name = req.match_info().get("user_name").unwrap();
-    let repo_name = req.match_info().get("repo_name").unwrap();
+    // Проверяем право записи и запоминаем автора push
+    let (pusher_id, permission) = match authorize(&req, Permission::Write).await {
+        Ok((pusher, permission)) => (pusher.and_then(|user| user.id), permission),
+        Err(response) => return response,
+    };
+    let repo_name = repo_name_from_path(&req);
+    let repo_path = git::repository_path(&repo_name);
 
     debug!("Handling receive-pack for repo: {}", repo_name);
 
+    let db = req.app_data::<web::Data<Database>>().unwrap().get_ref().clone();
+    let is_wiki = repo_name.ends_with(WIKI_SUFFIX);
+
+    // Правила защиты веток проекта; вики ими не защищается
+    if !is_wiki {
+        match config_service::check_push(&repo_name, &body, permission, &db).await {
+            Ok(violations) if !violations.is_empty() => {
+                return rejected_push_response(&body, "protected branch", &violations);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!("Branch protection check failed for {}: {:?}", repo_name, e);
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+    }
+
+    // Проверяем новые блобы на секреты до записи в репозиторий; если проверить
+    // не удалось, push не принимается
+    let secret_scan = if is_wiki {
+        None
+    } else {
+        match secret_scanning_service::scan_push(&repo_name, &body, &db).await {
+            Ok(scan) => scan,
+            Err(e) => {
+                error!("Secret scanning failed for {}: {:?}", repo_name, e);
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+    };
+    if let Some(scan) = secret_scan.as_ref().filter(|scan| scan.rejects()) {
+        return rejected_push_response(&body, "secret detected", &scan.rejection_message());
+    }
+
     // Запускаем git-receive-pack в режиме stateless-rpc
     let mut child = Command::new("git")
         .arg("receive-pack")
         .arg("--stateless-rpc")
-        .arg(&user_name)
-        .arg(&repo_name)
+        .arg(&repo_path)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -253,12 +297,176 @@ pub async fn handle_receive_pack(req: HttpRequest, body: web::Bytes) -> HttpResp
         return HttpResponse::InternalServerError().finish();
     }
 
+    // Вики не индексируется и не запускает пайплайны
+    if is_wiki {
+        return HttpResponse::Ok()
+            .content_type("application/x-git-receive-pack-result")
+            .body(output.stdout);
+    }
+
+    // Сохраняем найденные секреты и уведомляем владельца проекта
+    if let Some(scan) = &secret_scan {
+        if let Err(e) = secret_scanning_service::record_findings(scan, pusher_id, &db).await {
+            error!("Failed to record secret findings: {:?}", e);
+        }
+    }
+
+    // Записываем push в журнал проекта; отклонённые ссылки не учитываются
+    let updates = git::parse_push_commands(&body);
+    let updates = git::applied_updates(&repo_path, updates, &output.stdout);
+    activity_service::record_push(&repo_name, &updates, pusher_id, &db).await;
+
+    // Обновляем поисковый индекс ветки по умолчанию в фоне
+    search_service::schedule_repository_index(repo_name.clone(), db);
+
+    // Запускаем пайплайны для обновлённых веток
+    pipeline_service::schedule_push_pipelines(repo_name, updates, pusher_id);
+
     // Возвращаем результат операции
     HttpResponse::Ok()
         .content_type("application/x-git-receive-pack-result")
         .body(output.stdout)
 }
 
+/// Ответ git клиенту об отклонённом push без записи в репозиторий
+///
+/// Каждая ссылка получает `ng` в report-status, а объяснение передаётся в
+/// канал 2 side-band, который git выводит строками `remote:`.
+fn rejected_push_response(body: &[u8], reason: &str, message: &[String]) -> HttpResponse {
+    let mut report = git::pkt_line(b"unpack ok\n");
+    for update in git::parse_push_commands(body) {
+        report.extend(git::pkt_line(
+            format!("ng {} {}\n", update.ref_name, reason).as_bytes(),
+        ));
+    }
+    report.extend_from_slice(b"0000");
+
+    let response = if git::push_has_capability(body, "side-band-64k") {
+        let mut response = Vec::new();
+        for line in message {
+            response.extend(git::pkt_line(format!("\x02{}\n", line).as_bytes()));
+        }
+        // Данные side-band передаются пакетами не длиннее 65520 байт
+        for chunk in report.chunks(65515) {
+            let mut packet = vec![1u8];
+            packet.extend_from_slice(chunk);
+            response.extend(git::pkt_line(&packet));
+        }
+        response.extend_from_slice(b"0000");
+        response
+    } else {
+        report
+    };
+
+    HttpResponse::Ok()
+        .content_type("application/x-git-receive-pack-result")
+        .body(response)
+}
+
+/// Проверяет права пользователя на репозиторий из пути запроса
+///
+/// Возвращает аутентифицированного пользователя (аноним допустим для чтения
+/// публичных репозиториев) с его уровнем доступа либо готовый ответ: 401 с `WWW-Authenticate`, чтобы
+/// git запросил учётные данные, или 403, если их недостаточно.
+///
+/// Права на вики (`name.wiki`) совпадают с правами на репозиторий `name`;
+/// вики создаётся при первом push. Ключ развёртывания (токен `nbd_...`)
+/// заменяет пользователя: push с ним проходит без автора.
+async fn authorize(
+    req: &HttpRequest,
+    required: Permission,
+) -> Result<(Option<User>, Permission), HttpResponse> {
+    let db = req.app_data::<web::Data<Database>>().unwrap();
+    let pool = db.get_pool();
+    let repo_name = repo_name_from_path(req);
+    let wiki_of = repo_name.strip_suffix(WIKI_SUFFIX);
+
+    let repo = match Repository::find_by_name(wiki_of.unwrap_or(&repo_name), pool).await {
+        Ok(Some(repo)) => repo,
+        Ok(None) => return Err(HttpResponse::NotFound().finish()),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+    let project = match Project::find_by_id(repo.project_id, pool).await {
+        Ok(Some(project)) if !project.is_deleted() => project,
+        Ok(_) => return Err(HttpResponse::NotFound().finish()),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+
+    // Ключ развёртывания действует только на свой репозиторий и не даёт пользователя
+    let key_permission = match auth_service::extract_deploy_token(req) {
+        Some(token) => match deploy_key_service::authenticate(&repo, &token, db).await {
+            Ok(key) => key.map(|key| permission_service::deploy_key_permission(&key)),
+            Err(e) => {
+                error!("Database error: {}", e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        },
+        None => None,
+    };
+    let viewer = match key_permission {
+        Some(_) => None,
+        None => user::check_auth(req, db).await,
+    };
+    let permission = match key_permission {
+        // В вики ключ развёртывания только читает
+        Some(permission) if wiki_of.is_some() => permission.min(Permission::Read),
+        Some(permission) => permission,
+        None => {
+            match permission_service::repository_permission(&project, &repo, viewer.as_ref(), db)
+                .await
+            {
+                Ok(permission) => permission,
+                Err(e) => {
+                    error!("Database error: {}", e);
+                    return Err(HttpResponse::InternalServerError().finish());
+                }
+            }
+        }
+    };
+
+    // Архивный проект только для чтения, в том числе для ключей развёртывания
+    if project.is_archived() && required >= Permission::Write && permission >= Permission::Read {
+        return Err(HttpResponse::Forbidden()
+            .content_type("text/plain")
+            .body("Project is archived and read-only\n"));
+    }
+
+    if permission < required {
+        return Err(if viewer.is_none() && key_permission.is_none() {
+            HttpResponse::Unauthorized()
+                .append_header(("WWW-Authenticate", "Basic realm=\"Git\""))
+                .finish()
+        } else {
+            HttpResponse::Forbidden().finish()
+        });
+    }
+
+    if let Some(repo_name) = wiki_of {
+        if required >= Permission::Write {
+            if let Err(e) = wiki_service::ensure_wiki(repo_name) {
+                error!("Failed to create wiki repository: {:?}", e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        } else if !git::repository_exists(&git::repository_path(&repo_name_from_path(req))) {
+            return Err(HttpResponse::NotFound().finish());
+        }
+    }
+
+    Ok((viewer, permission))
+}
+
+/// Извлекает имя репозитория из пути запроса (суффикс `.git` необязателен)
+fn repo_name_from_path(req: &HttpRequest) -> String {
+    let repo_name = req.match_info().get("repo_name").unwrap_or_default();
+    repo_name.trim_end_matches(".git").to_string()
+}
+
 /// Обработчик для objects/info/packs - возвращает список pack-файлов
 ///
 /// Pack-файлы содержат сжатые Git объекты для эффективной передачи.
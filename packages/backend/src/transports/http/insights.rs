@@ -0,0 +1,206 @@
+//! API обработчики статистики репозиториев
+
+use crate::core::database::Database;
+use crate::services::insights as insights_service;
+use crate::transports::http::repositories::resolve_repository;
+use crate::transports::http::users::ApiResponse;
+use crate::utils::sbom::SbomFormat;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use log::error;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    /// Количество недель (по умолчанию 52)
+    pub weeks: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SbomQuery {
+    /// cyclonedx (по умолчанию) или spdx
+    pub format: Option<String>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_error_response(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_bad_request_response(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_not_found_response(message: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_success_response<T: Serialize>(data: T) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        message: None,
+        data: Some(data),
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Распределение кода по языкам
+pub async fn get_languages(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match insights_service::languages(&ctx.repo, &db).await {
+        Ok(stats) => Ok(create_success_response(stats)),
+        Err(e) => {
+            error!(
+                "Failed to compute languages for {}/{}: {}",
+                ctx.project.name, ctx.repo.name, e
+            );
+            Ok(create_error_response("Failed to compute languages"))
+        }
+    }
+}
+
+/// Рейтинг участников репозитория
+pub async fn get_contributors(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match insights_service::contributors(&ctx.repo, &db).await {
+        Ok(stats) => Ok(create_success_response(stats)),
+        Err(e) => {
+            error!(
+                "Failed to compute contributors for {}/{}: {}",
+                ctx.project.name, ctx.repo.name, e
+            );
+            Ok(create_error_response("Failed to compute contributors"))
+        }
+    }
+}
+
+/// Понедельная активность коммитов
+pub async fn get_activity(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<ActivityQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let weeks = query.weeks.unwrap_or(52).clamp(1, 520);
+    match insights_service::activity(&ctx.repo, weeks, &db).await {
+        Ok(stats) => Ok(create_success_response(stats)),
+        Err(e) => {
+            error!(
+                "Failed to compute activity for {}/{}: {}",
+                ctx.project.name, ctx.repo.name, e
+            );
+            Ok(create_error_response("Failed to compute activity"))
+        }
+    }
+}
+
+/// Граф зависимостей из манифестов ветки по умолчанию
+pub async fn get_dependencies(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match insights_service::dependencies(&ctx.repo, &db).await {
+        Ok(graph) => Ok(create_success_response(graph)),
+        Err(e) => {
+            error!(
+                "Failed to compute dependencies for {}/{}: {}",
+                ctx.project.name, ctx.repo.name, e
+            );
+            Ok(create_error_response("Failed to compute dependencies"))
+        }
+    }
+}
+
+/// Выгрузка SBOM в формате CycloneDX или SPDX
+pub async fn export_sbom(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<SbomQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name) = path.into_inner();
+    let format = match query.format.as_deref().map(SbomFormat::parse) {
+        None => SbomFormat::CycloneDx,
+        Some(Some(format)) => format,
+        Some(None) => {
+            return Ok(create_bad_request_response(
+                "Format must be one of: cyclonedx, spdx",
+            ))
+        }
+    };
+
+    let full_name = format!("{}/{}/{}", username, project_name, repo_name);
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match insights_service::sbom(&ctx.repo, &full_name, format, &db).await {
+        Ok(Some(document)) => Ok(HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                format!(
+                    "attachment; filename=\"{}.{}\"",
+                    ctx.repo.name,
+                    format.file_suffix()
+                ),
+            ))
+            .json(document)),
+        Ok(None) => Ok(create_not_found_response("Repository is empty")),
+        Err(e) => {
+            error!(
+                "Failed to build SBOM for {}/{}: {}",
+                ctx.project.name, ctx.repo.name, e
+            );
+            Ok(create_error_response("Failed to build SBOM"))
+        }
+    }
+}
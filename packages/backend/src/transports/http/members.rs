@@ -0,0 +1,206 @@
+//! API обработчики участников проекта и доступа команд к проекту
+
+use crate::core::database::Database;
+use crate::domain::users::User;
+use crate::services::members as member_service;
+use crate::services::permissions::Permission;
+use crate::transports::http::projects::{resolve_project, ProjectContext};
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct MemberRoleRequest {
+    /// owner, maintainer, developer, reporter или guest
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TeamRoleRequest {
+    /// maintainer, developer, reporter или guest
+    pub role: String,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+/// Находит проект и проверяет права администратора; возвращает и автора запроса
+async fn resolve_admin(
+    req: &HttpRequest,
+    db: &Database,
+    path: (String, String),
+) -> std::result::Result<(ProjectContext, User), HttpResponse> {
+    let mut ctx = resolve_project(req, db, path).await?;
+    let Some(viewer) = ctx.viewer.take() else {
+        return Err(create_unauthorized_response());
+    };
+    if ctx.permission < Permission::Admin {
+        return Err(create_forbidden_response(
+            "Admin access is required to manage project members",
+        ));
+    }
+    Ok((ctx, viewer))
+}
+
+// ============================================================================
+// API HANDLERS: УЧАСТНИКИ
+// ============================================================================
+
+/// Участники проекта
+pub async fn list_members(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match member_service::list_members(&ctx.project, &db).await {
+        Ok(members) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(members),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Добавление участника или смена его роли
+pub async fn set_member(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    role_req: web::Json<MemberRoleRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, member) = path.into_inner();
+    let (ctx, viewer) = match resolve_admin(&req, &db, (username, project_name)).await {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
+
+    match member_service::set_member(&ctx.project, &viewer, &member, &role_req.role, &db).await {
+        Ok(member) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Member saved".to_string()),
+            data: Some(member),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Исключение участника из проекта
+pub async fn remove_member(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, member) = path.into_inner();
+    let (ctx, viewer) = match resolve_admin(&req, &db, (username, project_name)).await {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
+
+    match member_service::remove_member(&ctx.project, &viewer, &member, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Member removed".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+// ============================================================================
+// API HANDLERS: КОМАНДЫ ПРОЕКТА
+// ============================================================================
+
+/// Команды с доступом к проекту
+pub async fn list_project_teams(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match member_service::list_project_teams(&ctx.project, &db).await {
+        Ok(teams) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(teams),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Выдача команде роли в проекте
+pub async fn grant_team(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    role_req: web::Json<TeamRoleRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, team_owner, team_name) = path.into_inner();
+    let (ctx, _) = match resolve_admin(&req, &db, (username, project_name)).await {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
+
+    match member_service::grant_team(&ctx.project, &team_owner, &team_name, &role_req.role, &db)
+        .await
+    {
+        Ok(grant) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Team access saved".to_string()),
+            data: Some(grant),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Отзыв доступа команды к проекту
+pub async fn revoke_team(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, team_owner, team_name) = path.into_inner();
+    let (ctx, _) = match resolve_admin(&req, &db, (username, project_name)).await {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
+
+    match member_service::revoke_team(&ctx.project, &team_owner, &team_name, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Team access removed".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
@@ -1,8 +1,28 @@
 //! HTTP транспорт — маршрутизация и контроллеры HTTP API
 
+pub mod activity;
+pub mod admin;
+pub mod api_tokens;
 pub mod chats;
+pub mod collaborators;
+pub mod deploy_keys;
+pub mod feeds;
+pub mod files;
 pub mod git;
+pub mod insights;
+pub mod members;
 pub mod notifications;
+pub mod organizations;
+pub mod pipelines;
 pub mod projects;
+pub mod releases;
 pub mod repositories;
+pub mod search;
+pub mod secret_findings;
+pub mod signing_keys;
+pub mod statuses;
+pub mod teams;
+pub mod traffic;
 pub mod users;
+pub mod watches;
+pub mod wiki;
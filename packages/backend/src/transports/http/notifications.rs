@@ -34,6 +34,32 @@ fn create_unauthorized_response() -> HttpResponse {
     })
 }
 
+fn create_not_found_response() -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Notification not found".to_string()),
+        data: None,
+    })
+}
+
+/// Уведомление, доступное пользователю; чужие уведомления не раскрываются
+async fn find_visible(
+    id: i64,
+    user: &crate::domain::users::User,
+    db: &Database,
+) -> std::result::Result<Notification, HttpResponse> {
+    match Notification::find_by_id(id, db.get_pool()).await {
+        Ok(Some(notification)) if notification.is_visible_to(user.id.unwrap_or_default()) => {
+            Ok(notification)
+        }
+        Ok(_) => Err(create_not_found_response()),
+        Err(e) => {
+            error!("Failed to fetch notification: {}", e);
+            Err(create_error_response("Failed to fetch notification"))
+        }
+    }
+}
+
 fn create_error_response(message: &str) -> HttpResponse {
     HttpResponse::InternalServerError().json(ApiResponse::<()> {
         success: false,
@@ -69,6 +95,7 @@ pub async fn create_notification(
         id: None,
         name: notification_req.name.clone(),
         content: notification_req.content.clone(),
+        user_id: None,
         created_at: None,
     };
 
@@ -87,15 +114,15 @@ pub async fn create_notification(
     }
 }
 
-/// Получение всех уведомлений
+/// Получение уведомлений текущего пользователя
 pub async fn get_notifications(req: HttpRequest, db: web::Data<Database>) -> Result<HttpResponse> {
-    let user = check_auth_or_unauthorized(&req, &db).await;
-    if user.is_none() {
-        return Ok(create_unauthorized_response());
-    }
+    let user = match check_auth_or_unauthorized(&req, &db).await {
+        Some(user) => user,
+        None => return Ok(create_unauthorized_response()),
+    };
 
     let pool = db.get_pool();
-    let notifications_result = Notification::find_all(pool).await;
+    let notifications_result = Notification::find_for_user(user.id.unwrap_or_default(), pool).await;
 
     match notifications_result {
         Ok(notifications) => Ok(HttpResponse::Ok().json(ApiResponse {
@@ -110,6 +137,27 @@ pub async fn get_notifications(req: HttpRequest, db: web::Data<Database>) -> Res
     }
 }
 
+/// Получение уведомления по ID
+pub async fn get_notification(
+    req: HttpRequest,
+    path: web::Path<i64>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let user = match check_auth_or_unauthorized(&req, &db).await {
+        Some(user) => user,
+        None => return Ok(create_unauthorized_response()),
+    };
+
+    match find_visible(path.into_inner(), &user, &db).await {
+        Ok(notification) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(notification),
+        })),
+        Err(response) => Ok(response),
+    }
+}
+
 /// Обновление уведомления
 pub async fn update_notification(
     req: HttpRequest,
@@ -119,16 +167,19 @@ pub async fn update_notification(
 ) -> Result<HttpResponse> {
     let notification_id = path.into_inner();
 
-    let user = check_auth_or_unauthorized(&req, &db).await;
-    if user.is_none() {
-        return Ok(create_unauthorized_response());
-    }
+    let user = match check_auth_or_unauthorized(&req, &db).await {
+        Some(user) => user,
+        None => return Ok(create_unauthorized_response()),
+    };
+    let existing = match find_visible(notification_id, &user, &db).await {
+        Ok(notification) => notification,
+        Err(response) => return Ok(response),
+    };
 
     let notification = Notification {
-        id: Some(notification_id),
         name: notification_req.name.clone(),
         content: notification_req.content.clone(),
-        created_at: None,
+        ..existing
     };
 
     let pool = db.get_pool();
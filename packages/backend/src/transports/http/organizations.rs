@@ -0,0 +1,204 @@
+//! API обработчики организаций
+
+use crate::core::database::Database;
+use crate::domain::organizations::Organization;
+use crate::domain::projects::Project;
+use crate::services::organizations as organization_service;
+use crate::transports::http::users::{self as user, app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateOrganizationRequest {
+    /// Имя в URL, общее пространство с именами пользователей
+    pub name: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrganizationRoleRequest {
+    /// owner, admin или member
+    pub role: String,
+}
+
+#[derive(Serialize)]
+pub struct OrganizationDetails {
+    pub organization: Organization,
+    /// Проекты, видимые текущему пользователю
+    pub projects: Vec<Project>,
+    /// Роль текущего пользователя, если он участник
+    pub role: Option<String>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Организации текущего пользователя
+pub async fn list_organizations(req: HttpRequest, db: web::Data<Database>) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match organization_service::list_for_user(&viewer, &db).await {
+        Ok(organizations) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(organizations),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Создание организации
+pub async fn create_organization(
+    req: HttpRequest,
+    org_req: web::Json<CreateOrganizationRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match organization_service::create(
+        &viewer,
+        &org_req.name,
+        org_req.display_name.as_deref(),
+        org_req.description.as_deref(),
+        &db,
+    )
+    .await
+    {
+        Ok(organization) => Ok(HttpResponse::Created().json(ApiResponse {
+            success: true,
+            message: Some("Organization created".to_string()),
+            data: Some(organization),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Организация и её проекты, видимые текущему пользователю
+pub async fn get_organization(
+    req: HttpRequest,
+    path: web::Path<String>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let organization = match organization_service::find(&path.into_inner(), &db).await {
+        Ok(organization) => organization,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+    let viewer = user::check_auth(&req, &db).await;
+
+    let projects = match organization_service::projects(&organization, viewer.as_ref(), &db).await {
+        Ok(projects) => projects,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+    let role = match &viewer {
+        Some(viewer) => match organization_service::member_role(&organization, viewer, &db).await {
+            Ok(role) => role,
+            Err(e) => return Ok(app_error_response(e)),
+        },
+        None => None,
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        message: None,
+        data: Some(OrganizationDetails {
+            organization,
+            projects,
+            role,
+        }),
+    }))
+}
+
+/// Участники организации
+pub async fn list_members(
+    path: web::Path<String>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let organization = match organization_service::find(&path.into_inner(), &db).await {
+        Ok(organization) => organization,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+
+    match organization_service::members(&organization, &db).await {
+        Ok(members) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(members),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Добавление участника организации или смена его роли
+pub async fn set_member(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    role_req: web::Json<OrganizationRoleRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+    let (name, member) = path.into_inner();
+    let organization = match organization_service::find(&name, &db).await {
+        Ok(organization) => organization,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+
+    match organization_service::set_member(&organization, &viewer, &member, &role_req.role, &db)
+        .await
+    {
+        Ok(member) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Member saved".to_string()),
+            data: Some(member),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Исключение участника из организации (или выход из неё)
+pub async fn remove_member(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+    let (name, member) = path.into_inner();
+    let organization = match organization_service::find(&name, &db).await {
+        Ok(organization) => organization,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+
+    match organization_service::remove_member(&organization, &viewer, &member, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Member removed".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
@@ -0,0 +1,69 @@
+//! API обработчики встроенных пайплайнов
+
+use crate::core::database::Database;
+use crate::services::pipelines as pipeline_service;
+use crate::transports::http::repositories::resolve_repository;
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct PipelinesQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Список запусков пайплайна репозитория
+pub async fn list_pipelines(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<PipelinesQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(30).clamp(1, 100);
+
+    match pipeline_service::list_runs(&ctx.repo, page, per_page, &db).await {
+        Ok(runs) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(runs),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Запуск пайплайна с шагами, кодами возврата и логами
+pub async fn get_pipeline(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, i64)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, run_id) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match pipeline_service::get_run(&ctx.repo, run_id, &db).await {
+        Ok(run) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(run),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
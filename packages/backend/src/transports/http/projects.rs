@@ -1,11 +1,44 @@
 use crate::core::database::Database;
+use crate::domain::events::EVENT_REPOSITORY_CREATED;
 use crate::domain::projects::{Project, ProjectConfig};
 use crate::domain::repos::Repository;
 use crate::domain::users::User;
-use crate::transports::http::users::{self as user, ApiResponse};
+use crate::services::activity as activity_service;
+use crate::services::members as member_service;
+use crate::services::organizations as organization_service;
+use crate::services::permissions::{self as permission_service, Permission};
+use crate::services::projects::{self as project_service, lifecycle, ProjectOwner};
+use crate::services::templates::{self as template_service, TemplateVariables};
+use crate::services::watches as watch_service;
+use crate::services::wiki as wiki_service;
+use crate::transports::http::repositories::resolve_repository;
+use crate::transports::http::users::{self as user, app_error_response, ApiResponse};
+use crate::utils::{git, helpers};
 use actix_web::{web, HttpRequest, HttpResponse, Result};
 use log::error;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Ответ на попытку изменить архивный проект
+const ARCHIVED_MESSAGE: &str = "Project is archived and read-only";
+
+/// Имена маршрутов проекта `/{user}/{project}/{name}`; репозиторий с таким
+/// именем был бы недоступен по своему адресу
+const RESERVED_REPOSITORY_NAMES: [&str; 13] = [
+    "activity",
+    "archive",
+    "collaborators",
+    "config",
+    "members",
+    "repos",
+    "restore",
+    "star",
+    "stargazers",
+    "teams",
+    "transfer",
+    "unarchive",
+    "watch",
+];
 
 // ============================================================================
 // СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
@@ -16,18 +49,49 @@ pub struct CreateProjectRequest {
     pub name: String,
     pub description: Option<String>,
     pub is_public: bool,
+    /// Организация-владелец; без неё проект создаётся в пространстве пользователя
+    #[serde(default)]
+    pub organization: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct CreateRepoInProjectRequest {
     pub name: String,
     pub description: Option<String>,
-    pub is_public: bool,
+    /// Без значения видимость берётся из конфигурации проекта
+    #[serde(default)]
+    pub is_public: Option<bool>,
+    /// Пометить новый репозиторий как шаблон
+    #[serde(default)]
+    pub is_template: bool,
+    /// Шаблон, из которого генерируется содержимое репозитория
+    pub template: Option<TemplateSource>,
+}
+
+/// Репозиторий-шаблон по пути `/{owner}/{project}/{repo}`
+#[derive(Serialize, Deserialize)]
+pub struct TemplateSource {
+    pub owner: String,
+    pub project: String,
+    pub repo: String,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct UpdateProjectConfigRequest {
-    pub config: ProjectConfig,
+    /// Проверяется по схеме из `GET /api/projects/config-schema`
+    pub config: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+pub struct ConfigDiffQuery {
+    pub from: i32,
+    pub to: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TransferProjectRequest {
+    /// Пользователь или организация, которым передаётся проект
+    pub new_owner: String,
 }
 
 #[derive(Serialize)]
@@ -42,7 +106,24 @@ pub struct ProjectDetails {
     pub project: Project,
     pub repositories: Vec<Repository>,
     pub config: ProjectConfig,
-    pub owner: User,
+    pub owner: ProjectOwner,
+    pub stars: i64,
+}
+
+/// Публичный проект в каталоге вместе с числом звёзд
+#[derive(Serialize)]
+pub struct PublicProject {
+    #[serde(flatten)]
+    pub project: Project,
+    pub stars: i64,
+}
+
+/// Проект из пути запроса вместе с правами текущего пользователя
+pub struct ProjectContext {
+    pub project: Project,
+    pub owner: ProjectOwner,
+    pub viewer: Option<User>,
+    pub permission: Permission,
 }
 
 // ============================================================================
@@ -93,11 +174,146 @@ fn create_forbidden_response(message: &str) -> HttpResponse {
     })
 }
 
+/// Находит проект по пути `/{user}/{project}` и проверяет право чтения
+///
+/// Права определяет `services::permissions`. При ошибке возвращает готовый HTTP ответ.
+pub(crate) async fn resolve_project(
+    req: &HttpRequest,
+    db: &Database,
+    (username, project_name): (String, String),
+) -> std::result::Result<ProjectContext, HttpResponse> {
+    let owner = match project_service::find_owner(&username, db).await {
+        Ok(Some(owner)) => owner,
+        Ok(None) => return Err(create_not_found_response("User or organization not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err(create_error_response("Database error"));
+        }
+    };
+
+    let project = match project_service::find_project(&project_name, &owner, db).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return Err(create_not_found_response("Project not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err(create_error_response("Database error"));
+        }
+    };
+
+    let viewer = user::check_auth(req, db).await;
+    let permission =
+        match permission_service::project_permission(&project, viewer.as_ref(), db).await {
+            Ok(permission) => permission,
+            Err(e) => {
+                error!("Database error: {}", e);
+                return Err(create_error_response("Database error"));
+            }
+        };
+    if permission < Permission::Read {
+        return Err(create_forbidden_response("Access denied"));
+    }
+
+    Ok(ProjectContext {
+        project,
+        owner,
+        viewer,
+        permission,
+    })
+}
+
+/// Находит проект для изменения конфигурации; возвращает и автора изменения
+async fn resolve_config_admin(
+    req: &HttpRequest,
+    db: &Database,
+    path: (String, String),
+) -> std::result::Result<(ProjectContext, User), HttpResponse> {
+    let mut ctx = resolve_project(req, db, path).await?;
+    let Some(viewer) = ctx.viewer.take() else {
+        return Err(create_unauthorized_response());
+    };
+    if ctx.project.is_archived() {
+        return Err(create_forbidden_response(ARCHIVED_MESSAGE));
+    }
+    if ctx.permission < Permission::Admin {
+        return Err(create_forbidden_response(
+            "Admin access is required to update configuration",
+        ));
+    }
+    Ok((ctx, viewer))
+}
+
+/// Находит проект для архивации, удаления, восстановления или передачи
+///
+/// Нужны права администратора без ограничения архива. `deleted` выбирает,
+/// ищется действующий проект или удалённый.
+async fn resolve_lifecycle_admin(
+    req: &HttpRequest,
+    db: &Database,
+    (username, project_name): (String, String),
+    deleted: bool,
+) -> std::result::Result<(Project, User), HttpResponse> {
+    let Some(viewer) = check_auth_or_unauthorized(req, db).await else {
+        return Err(create_unauthorized_response());
+    };
+    let owner = match project_service::find_owner(&username, db).await {
+        Ok(Some(owner)) => owner,
+        Ok(None) => return Err(create_not_found_response("User or organization not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err(create_error_response("Database error"));
+        }
+    };
+
+    let project = if deleted {
+        project_service::find_deleted_project(&project_name, &owner, db).await
+    } else {
+        project_service::find_project(&project_name, &owner, db).await
+    };
+    let project = match project {
+        Ok(Some(project)) => project,
+        Ok(None) => return Err(create_not_found_response("Project not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err(create_error_response("Database error"));
+        }
+    };
+
+    let permission =
+        match permission_service::management_permission(&project, Some(&viewer), db).await {
+            Ok(permission) => permission,
+            Err(e) => {
+                error!("Database error: {}", e);
+                return Err(create_error_response("Database error"));
+            }
+        };
+    if permission < Permission::Read {
+        return Err(create_not_found_response("Project not found"));
+    }
+    if permission < Permission::Admin {
+        return Err(create_forbidden_response(
+            "Admin access is required to manage the project",
+        ));
+    }
+    Ok((project, viewer))
+}
+
+/// Удаляет репозиторий, созданный запросом, который затем не удался
+async fn remove_created_repository(repo: &Repository, db: &Database) {
+    if let Some(id) = repo.id {
+        if let Err(e) = Repository::delete(id, db.get_pool()).await {
+            error!("Failed to remove repository {}: {}", repo.name, e);
+        }
+    }
+    if let Err(e) = helpers::safe_remove_dir(&git::repository_path(&repo.name)) {
+        error!("Failed to remove repository {} from disk: {}", repo.name, e);
+    }
+}
+
 // ============================================================================
 // API HANDLERS
 // ============================================================================
 
-/// Получение списка проектов пользователя
+/// Получение списка проектов пользователя: своих и тех, где он участник
 pub async fn list_projects(req: HttpRequest, db: web::Data<Database>) -> Result<HttpResponse> {
     let user = check_auth_or_unauthorized(&req, &db).await;
     if user.is_none() {
@@ -106,7 +322,7 @@ pub async fn list_projects(req: HttpRequest, db: web::Data<Database>) -> Result<
     let user = user.unwrap();
 
     let pool = db.get_pool();
-    let projects_result = Project::find_by_owner(user.id.unwrap(), pool).await;
+    let projects_result = Project::find_for_member(user.id.unwrap(), pool).await;
 
     match projects_result {
         Ok(projects) => Ok(HttpResponse::Ok().json(ApiResponse {
@@ -127,19 +343,35 @@ pub async fn list_public_projects(
     db: web::Data<Database>,
 ) -> Result<HttpResponse> {
     let pool = db.get_pool();
-    let projects_result = Project::find_public(pool).await;
-
-    match projects_result {
-        Ok(projects) => Ok(HttpResponse::Ok().json(ApiResponse {
-            success: true,
-            message: None,
-            data: Some(projects),
-        })),
+    let projects = match Project::find_public(pool).await {
+        Ok(projects) => projects,
         Err(e) => {
             error!("Failed to fetch public projects: {}", e);
-            Ok(create_error_response("Failed to fetch public projects"))
+            return Ok(create_error_response("Failed to fetch public projects"));
         }
-    }
+    };
+
+    let project_ids: Vec<i64> = projects.iter().filter_map(|project| project.id).collect();
+    let stars = match watch_service::star_counts(&project_ids, &db).await {
+        Ok(stars) => stars,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+    let projects: Vec<PublicProject> = projects
+        .into_iter()
+        .map(|project| PublicProject {
+            stars: project
+                .id
+                .and_then(|id| stars.get(&id).copied())
+                .unwrap_or(0),
+            project,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        message: None,
+        data: Some(projects),
+    }))
 }
 
 /// Создание нового проекта
@@ -156,9 +388,32 @@ pub async fn create_project(
 
     let pool = db.get_pool();
 
+    // Проект организации создают её владельцы и администраторы
+    let organization = match &project_req.organization {
+        Some(name) => {
+            let org = match organization_service::find(name, &db).await {
+                Ok(org) => org,
+                Err(e) => return Ok(app_error_response(e)),
+            };
+            match organization_service::can_manage(&org, &user, &db).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    return Ok(create_forbidden_response(
+                        "Organization admin access is required to create projects",
+                    ))
+                }
+                Err(e) => return Ok(app_error_response(e)),
+            }
+            Some(org)
+        }
+        None => None,
+    };
+
     // Проверяем существование проекта
-    let existing_project =
-        Project::find_by_name_and_owner(&project_req.name, user.id.unwrap(), pool).await;
+    let existing_project = match &organization {
+        Some(org) => Project::find_by_name_and_organization(&project_req.name, org.id, pool).await,
+        None => Project::find_by_name_and_owner(&project_req.name, user.id.unwrap(), pool).await,
+    };
     match existing_project {
         Ok(Some(_)) => {
             return Ok(create_bad_request_response(
@@ -173,22 +428,32 @@ pub async fn create_project(
     }
 
     // Создаем проект
-    let project = Project {
+    let mut project = Project {
         id: None,
         name: project_req.name.clone(),
         owner_id: user.id.unwrap(),
+        organization_id: organization.map(|org| org.id),
         description: project_req.description.clone(),
         is_public: project_req.is_public,
         created_at: None,
+        archived_at: None,
+        deleted_at: None,
     };
 
     let create_result = project.create(pool).await;
     match create_result {
-        Ok(_) => Ok(HttpResponse::Ok().json(ApiResponse {
-            success: true,
-            message: Some("Project created successfully".to_string()),
-            data: Some(project),
-        })),
+        Ok(project_id) => {
+            project.id = Some(project_id);
+            // Создатель становится участником проекта с ролью owner
+            if let Err(e) = member_service::add_owner(project_id, project.owner_id, &db).await {
+                return Ok(app_error_response(e));
+            }
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                message: Some("Project created successfully".to_string()),
+                data: Some(project),
+            }))
+        }
         Err(e) => {
             error!("Failed to create project: {}", e);
             Ok(create_error_response("Failed to create project"))
@@ -203,50 +468,42 @@ pub async fn get_project(
     db: web::Data<Database>,
 ) -> Result<HttpResponse> {
     let (username, project_name) = path.into_inner();
+    let ctx = match resolve_project(&req, &db, (username, project_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
     let pool = db.get_pool();
 
-    // Находим владельца
-    let owner = match User::find_by_username(&username, pool).await {
-        Ok(Some(user)) => user,
-        Ok(None) => return Ok(create_not_found_response("User not found")),
+    // Получаем репозитории, видимые пользователю, и конфигурацию
+    let all_repositories = Repository::find_by_project(ctx.project.id.unwrap(), pool)
+        .await
+        .unwrap_or_else(|_| Vec::new());
+    let repositories = match permission_service::readable_repositories(
+        &ctx.project,
+        all_repositories,
+        ctx.viewer.as_ref(),
+        &db,
+    )
+    .await
+    {
+        Ok(repositories) => repositories,
         Err(e) => {
             error!("Database error: {}", e);
             return Ok(create_error_response("Database error"));
         }
     };
-
-    // Находим проект
-    let project_result =
-        Project::find_by_name_and_owner(&project_name, owner.id.unwrap(), pool).await;
-    let project = match project_result {
-        Ok(Some(project)) => project,
-        Ok(None) => return Ok(create_not_found_response("Project not found")),
-        Err(e) => {
-            error!("Database error: {}", e);
-            return Ok(create_error_response("Database error"));
-        }
+    let config = ctx.project.get_config(pool).await.unwrap_or_default();
+    let stars = match watch_service::star_count(&ctx.project, &db).await {
+        Ok(stars) => stars,
+        Err(e) => return Ok(app_error_response(e)),
     };
 
-    // Проверяем права доступа
-    let current_user = user::check_auth(&req, &db).await;
-    let can_access =
-        project.is_public || current_user.as_ref().map(|u| u.id.unwrap()) == Some(project.owner_id);
-
-    if !can_access {
-        return Ok(create_forbidden_response("Access denied"));
-    }
-
-    // Получаем репозитории и конфигурацию
-    let repositories = Repository::find_by_project(project.id.unwrap(), pool)
-        .await
-        .unwrap_or_else(|_| Vec::new());
-    let config = project.get_config(pool).await.unwrap_or_default();
-
     let project_details = ProjectDetails {
-        project,
+        project: ctx.project,
         repositories,
         config,
-        owner,
+        owner: ctx.owner,
+        stars,
     };
 
     Ok(HttpResponse::Ok().json(ApiResponse {
@@ -271,36 +528,34 @@ pub async fn create_repo_in_project(
     }
     let user = user.unwrap();
 
-    let pool = db.get_pool();
-
-    // Находим владельца проекта
-    let owner = match User::find_by_username(&username, pool).await {
-        Ok(Some(user)) => user,
-        Ok(None) => return Ok(create_not_found_response("User not found")),
-        Err(e) => {
-            error!("Database error: {}", e);
-            return Ok(create_error_response("Database error"));
-        }
+    let ctx = match resolve_project(&req, &db, (username, project_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
     };
 
-    // Находим проект
-    let project_result =
-        Project::find_by_name_and_owner(&project_name, owner.id.unwrap(), pool).await;
-    let project = match project_result {
-        Ok(Some(project)) => project,
-        Ok(None) => return Ok(create_not_found_response("Project not found")),
-        Err(e) => {
-            error!("Database error: {}", e);
-            return Ok(create_error_response("Database error"));
-        }
-    };
-
-    // Проверяем права доступа
-    if user.id.unwrap() != project.owner_id {
+    if ctx.project.is_archived() {
+        return Ok(create_forbidden_response(ARCHIVED_MESSAGE));
+    }
+    if ctx.permission < Permission::Admin {
         return Ok(create_forbidden_response(
-            "Only project owner can create repositories",
+            "Admin access is required to create repositories",
+        ));
+    }
+    let ProjectContext { project, owner, .. } = ctx;
+    let pool = db.get_pool();
+
+    // Имена вида `name.wiki` заняты репозиториями вики
+    if repo_req.name.ends_with(wiki_service::WIKI_SUFFIX) {
+        return Ok(create_bad_request_response(
+            "Repository name cannot end with .wiki",
         ));
     }
+    if RESERVED_REPOSITORY_NAMES.contains(&repo_req.name.as_str()) {
+        return Ok(create_bad_request_response(&format!(
+            "Repository name '{}' is reserved",
+            repo_req.name
+        )));
+    }
 
     // Проверяем существование репозитория
     let existing_repo =
@@ -318,142 +573,449 @@ pub async fn create_repo_in_project(
         _ => {}
     }
 
+    // Шаблон должен быть доступен пользователю на чтение
+    let template = match &repo_req.template {
+        Some(source) => {
+            let path = (
+                source.owner.clone(),
+                source.project.clone(),
+                source.repo.clone(),
+            );
+            match resolve_repository(&req, &db, path).await {
+                Ok(ctx) if ctx.repo.is_template => Some(ctx.repo),
+                Ok(_) => return Ok(create_bad_request_response("Repository is not a template")),
+                Err(response) => return Ok(response),
+            }
+        }
+        None => None,
+    };
+
+    let config = match project_service::config::load(&project, &db).await {
+        Ok(config) => config,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+
     // Создаем репозиторий
-    let repo = Repository {
+    let mut repo = Repository {
         id: None,
         name: repo_req.name.clone(),
         project_id: project.id.unwrap(),
         owner_id: user.id.unwrap(),
         description: repo_req.description.clone(),
-        is_public: repo_req.is_public,
+        is_public: repo_req
+            .is_public
+            .unwrap_or_else(|| config.visibility.repository_public(project.is_public)),
+        is_template: repo_req.is_template,
         created_at: None,
     };
 
-    let create_result = repo.create(pool).await;
-    match create_result {
-        Ok(_) => Ok(HttpResponse::Ok().json(ApiResponse {
-            success: true,
-            message: Some("Repository created successfully".to_string()),
-            data: Some(repo),
-        })),
+    match repo.create(pool).await {
+        Ok(id) => repo.id = Some(id),
         Err(e) => {
             error!("Failed to create repository: {}", e);
-            Ok(create_error_response("Failed to create repository"))
+            return Ok(create_error_response("Failed to create repository"));
         }
     }
+
+    // Шаблон задаёт ветку сам, пустой репозиторий получает ветку из конфигурации
+    if template.is_none() {
+        let repo_path = git::repository_path(&repo.name);
+        if let Err(e) = git::set_head_branch(&repo_path, &config.default_branch) {
+            error!("Failed to set default branch of {}: {}", repo.name, e);
+        }
+    }
+
+    if let Some(template) = template {
+        let variables = TemplateVariables {
+            owner: owner.name().to_string(),
+            project_name: project.name.clone(),
+            repo_name: repo.name.clone(),
+            description: repo.description.clone().unwrap_or_default(),
+        };
+        if let Err(e) =
+            template_service::generate_from_template(&template, &repo, variables, &user).await
+        {
+            error!(
+                "Failed to generate repository {} from template {}: {:?}",
+                repo.name, template.name, e
+            );
+            // Пустой репозиторий вместо шаблонного не оставляем
+            remove_created_repository(&repo, &db).await;
+            return Ok(app_error_response(e));
+        }
+    }
+
+    let template_name = repo_req
+        .template
+        .as_ref()
+        .map(|source| format!("{}/{}/{}", source.owner, source.project, source.repo));
+    activity_service::record(
+        repo.project_id,
+        repo.id,
+        user.id,
+        EVENT_REPOSITORY_CREATED,
+        json!({"repository": repo.name, "template": template_name}),
+        &db,
+    )
+    .await;
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        message: Some("Repository created successfully".to_string()),
+        data: Some(repo),
+    }))
 }
 
 /// Обновление конфигурации проекта
+///
+/// Конфигурация проверяется по схеме и сохраняется новой ревизией.
 pub async fn update_project_config(
     req: HttpRequest,
     path: web::Path<(String, String)>,
     config_req: web::Json<UpdateProjectConfigRequest>,
     db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (ctx, author) = match resolve_config_admin(&req, &db, path.into_inner()).await {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
+
+    match project_service::config::update(&ctx.project, &author, &config_req.config, &db).await {
+        Ok(saved) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Project configuration updated successfully".to_string()),
+            data: Some(saved),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Получение конфигурации проекта
+pub async fn get_project_config(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
 ) -> Result<HttpResponse> {
     let (username, project_name) = path.into_inner();
+    let ctx = match resolve_project(&req, &db, (username, project_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
 
-    let user = check_auth_or_unauthorized(&req, &db).await;
-    if user.is_none() {
-        return Ok(create_unauthorized_response());
+    match project_service::config::load(&ctx.project, &db).await {
+        Ok(config) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(config),
+        })),
+        Err(e) => Ok(app_error_response(e)),
     }
-    let user = user.unwrap();
+}
 
-    let pool = db.get_pool();
+/// JSON Schema конфигурации проекта
+pub async fn get_config_schema() -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(project_service::config::schema()))
+}
 
-    // Находим владельца проекта
-    let owner = match User::find_by_username(&username, pool).await {
-        Ok(Some(user)) => user,
-        Ok(None) => return Ok(create_not_found_response("User not found")),
-        Err(e) => {
-            error!("Database error: {}", e);
-            return Ok(create_error_response("Database error"));
-        }
+/// История ревизий конфигурации проекта
+pub async fn list_config_revisions(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
     };
 
-    // Находим проект
-    let project_result =
-        Project::find_by_name_and_owner(&project_name, owner.id.unwrap(), pool).await;
-    let project = match project_result {
-        Ok(Some(project)) => project,
-        Ok(None) => return Ok(create_not_found_response("Project not found")),
-        Err(e) => {
-            error!("Database error: {}", e);
-            return Ok(create_error_response("Database error"));
-        }
+    match project_service::config::revisions(&ctx.project, &db).await {
+        Ok(revisions) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(revisions),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Ревизия конфигурации и её отличия от предыдущей
+pub async fn get_config_revision(
+    req: HttpRequest,
+    path: web::Path<(String, String, i32)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, revision) = path.into_inner();
+    let ctx = match resolve_project(&req, &db, (username, project_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
     };
 
-    // Проверяем права доступа
-    if user.id.unwrap() != project.owner_id {
-        return Ok(create_forbidden_response(
-            "Only project owner can update configuration",
-        ));
+    match project_service::config::revision(&ctx.project, revision, &db).await {
+        Ok(details) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(details),
+        })),
+        Err(e) => Ok(app_error_response(e)),
     }
+}
 
-    // Сохраняем конфигурацию
-    let update_result = project
-        .update_config(&config_req.config, db.get_pool())
-        .await;
-    match update_result {
-        Ok(_) => Ok(HttpResponse::Ok().json(ApiResponse {
+/// Изменения конфигурации между двумя ревизиями
+pub async fn diff_config_revisions(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<ConfigDiffQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match project_service::config::diff(&ctx.project, query.from, query.to, &db).await {
+        Ok(changes) => Ok(HttpResponse::Ok().json(ApiResponse {
             success: true,
-            message: Some("Project configuration updated successfully".to_string()),
-            data: Some(&config_req.config),
+            message: None,
+            data: Some(changes),
         })),
-        Err(e) => {
-            error!("Failed to save project configuration: {}", e);
-            Ok(create_error_response(
-                "Failed to save project configuration",
-            ))
-        }
+        Err(e) => Ok(app_error_response(e)),
     }
 }
 
-/// Получение конфигурации проекта
-pub async fn get_project_config(
+/// Откат конфигурации к ревизии; откат сохраняется новой ревизией
+pub async fn rollback_config(
+    req: HttpRequest,
+    path: web::Path<(String, String, i32)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, revision) = path.into_inner();
+    let (ctx, author) = match resolve_config_admin(&req, &db, (username, project_name)).await {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
+
+    match project_service::config::rollback(&ctx.project, &author, revision, &db).await {
+        Ok(saved) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Configuration restored from revision {}", revision)),
+            data: Some(saved),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Перевод проекта в архив: репозитории остаются доступны только для чтения
+pub async fn archive_project(
     req: HttpRequest,
     path: web::Path<(String, String)>,
     db: web::Data<Database>,
 ) -> Result<HttpResponse> {
-    let (username, project_name) = path.into_inner();
-    let pool = db.get_pool();
+    let (project, _) = match resolve_lifecycle_admin(&req, &db, path.into_inner(), false).await {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
 
-    // Находим владельца
-    let owner = match User::find_by_username(&username, pool).await {
-        Ok(Some(user)) => user,
-        Ok(None) => return Ok(create_not_found_response("User not found")),
-        Err(e) => {
-            error!("Database error: {}", e);
-            return Ok(create_error_response("Database error"));
-        }
+    match lifecycle::archive(&project, &db).await {
+        Ok(project) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Project archived".to_string()),
+            data: Some(project),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Возврат проекта из архива
+pub async fn unarchive_project(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (project, _) = match resolve_lifecycle_admin(&req, &db, path.into_inner(), false).await {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
     };
 
-    // Находим проект
-    let project_result =
-        Project::find_by_name_and_owner(&project_name, owner.id.unwrap(), pool).await;
-    let project = match project_result {
-        Ok(Some(project)) => project,
-        Ok(None) => return Ok(create_not_found_response("Project not found")),
-        Err(e) => {
-            error!("Database error: {}", e);
-            return Ok(create_error_response("Database error"));
-        }
+    match lifecycle::unarchive(&project, &db).await {
+        Ok(project) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Project unarchived".to_string()),
+            data: Some(project),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Удаление проекта; до окончательного удаления его можно восстановить
+pub async fn delete_project(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (project, actor) = match resolve_lifecycle_admin(&req, &db, path.into_inner(), false).await
+    {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
     };
 
-    // Проверяем права доступа
-    let current_user = user::check_auth(&req, &db).await;
-    let can_access =
-        project.is_public || current_user.as_ref().map(|u| u.id.unwrap()) == Some(project.owner_id);
+    match lifecycle::delete(&project, &actor, &db).await {
+        Ok(deleted) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!(
+                "Project deleted; it can be restored within {} days",
+                lifecycle::RETENTION_DAYS
+            )),
+            data: Some(deleted),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
 
-    if !can_access {
-        return Ok(create_forbidden_response("Access denied"));
+/// Восстановление удалённого проекта
+pub async fn restore_project(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (project, _) = match resolve_lifecycle_admin(&req, &db, path.into_inner(), true).await {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
+
+    match lifecycle::restore(&project, &db).await {
+        Ok(project) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Project restored".to_string()),
+            data: Some(project),
+        })),
+        Err(e) => Ok(app_error_response(e)),
     }
+}
 
-    // Загружаем конфигурацию
-    let config = project.get_config(db.get_pool()).await.unwrap_or_default();
+/// Удалённые проекты, которые текущий пользователь может восстановить
+pub async fn list_deleted_projects(
+    req: HttpRequest,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(user) = check_auth_or_unauthorized(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
 
-    Ok(HttpResponse::Ok().json(ApiResponse {
-        success: true,
-        message: None,
-        data: Some(config),
-    }))
+    match lifecycle::list_deleted(&user, &db).await {
+        Ok(projects) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(projects),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Запрос на передачу проекта другому пользователю или организации
+///
+/// Проект переходит к новому владельцу, когда тот примет запрос.
+pub async fn transfer_project(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    transfer_req: web::Json<TransferProjectRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (project, actor) = match resolve_lifecycle_admin(&req, &db, path.into_inner(), false).await
+    {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
+
+    match lifecycle::request_transfer(&project, &actor, &transfer_req.new_owner, &db).await {
+        Ok(transfer) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!(
+                "Transfer requested; {} has to accept it",
+                transfer_req.new_owner
+            )),
+            data: Some(transfer),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Отмена запроса на передачу проекта его владельцем
+pub async fn cancel_transfer(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (project, actor) = match resolve_lifecycle_admin(&req, &db, path.into_inner(), false).await
+    {
+        Ok(resolved) => resolved,
+        Err(response) => return Ok(response),
+    };
+    let Some(project_id) = project.id else {
+        return Ok(create_error_response("Project must have id"));
+    };
+
+    match lifecycle::decline_transfer(project_id, &actor, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Transfer cancelled".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Запросы на передачу проектов, которые текущий пользователь может принять
+pub async fn list_transfers(req: HttpRequest, db: web::Data<Database>) -> Result<HttpResponse> {
+    let Some(user) = check_auth_or_unauthorized(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match lifecycle::pending_transfers(&user, &db).await {
+        Ok(transfers) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(transfers),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Принятие передачи проекта новым владельцем
+pub async fn accept_transfer(
+    req: HttpRequest,
+    path: web::Path<i64>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(user) = check_auth_or_unauthorized(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match lifecycle::accept_transfer(path.into_inner(), &user, &db).await {
+        Ok(project) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Project transferred".to_string()),
+            data: Some(project),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Отказ от передачи проекта
+pub async fn decline_transfer(
+    req: HttpRequest,
+    path: web::Path<i64>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(user) = check_auth_or_unauthorized(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match lifecycle::decline_transfer(path.into_inner(), &user, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Transfer declined".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
 }
@@ -0,0 +1,481 @@
+//! API обработчики релизов и их файлов
+
+use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::releases::{Release, ReleaseAsset};
+use crate::services::releases::{self as release_service, NewAsset, NewRelease};
+use crate::transports::http::repositories::{resolve_repository, RepoContext};
+use crate::transports::http::users::ApiResponse;
+use actix_files::NamedFile;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{mime, web, HttpRequest, HttpResponse, Result};
+use log::error;
+use serde::{Deserialize, Serialize};
+
+/// Максимальный размер загружаемого файла релиза
+pub const MAX_ASSET_SIZE: usize = 256 * 1024 * 1024;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReleaseRequest {
+    pub tag_name: String,
+    /// Ветка или коммит, на котором создаётся тег, если его ещё нет
+    pub target: Option<String>,
+    pub title: Option<String>,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub is_draft: bool,
+    #[serde(default)]
+    pub is_prerelease: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateReleaseRequest {
+    pub title: Option<String>,
+    pub notes: Option<String>,
+    pub is_draft: Option<bool>,
+    pub is_prerelease: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadAssetQuery {
+    /// Имя файла, под которым он будет доступен для скачивания
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct ReleaseWithAssets {
+    #[serde(flatten)]
+    pub release: Release,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_error_response(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_not_found_response(message: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+/// Преобразует ошибку сервиса в HTTP ответ
+fn create_app_error_response(err: AppError) -> HttpResponse {
+    let mut response = match err.error_type {
+        ErrorType::ValidationError => HttpResponse::BadRequest(),
+        ErrorType::ConflictError => HttpResponse::Conflict(),
+        ErrorType::NotFoundError => HttpResponse::NotFound(),
+        ErrorType::AuthenticationError => HttpResponse::Unauthorized(),
+        ErrorType::AuthorizationError => HttpResponse::Forbidden(),
+        ErrorType::DatabaseError | ErrorType::InternalError => {
+            error!("{}: {}", err.message, err.details.as_deref().unwrap_or(""));
+            HttpResponse::InternalServerError()
+        }
+    };
+
+    response.json(ApiResponse::<()> {
+        success: false,
+        message: Some(err.message),
+        data: None,
+    })
+}
+
+/// Изменять релизы могут пользователи с правом записи
+fn check_write_access(ctx: &RepoContext) -> Option<HttpResponse> {
+    if ctx.viewer.is_none() {
+        return Some(create_unauthorized_response());
+    }
+    if !ctx.can_write() {
+        return Some(create_forbidden_response(
+            "Write access is required to manage releases",
+        ));
+    }
+    None
+}
+
+/// Находит релиз по тегу; черновики видны только пользователям с правом записи
+async fn load_release(
+    ctx: &RepoContext,
+    tag_name: &str,
+    db: &Database,
+) -> std::result::Result<Release, HttpResponse> {
+    match Release::find_by_tag(ctx.repo.id.unwrap(), tag_name, db.get_pool()).await {
+        Ok(Some(release)) if !release.is_draft || ctx.can_write() => Ok(release),
+        Ok(_) => Err(create_not_found_response("Release not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            Err(create_error_response("Database error"))
+        }
+    }
+}
+
+async fn with_assets(
+    release: Release,
+    db: &Database,
+) -> std::result::Result<ReleaseWithAssets, HttpResponse> {
+    match ReleaseAsset::find_by_release(release.id.unwrap(), db.get_pool()).await {
+        Ok(assets) => Ok(ReleaseWithAssets { release, assets }),
+        Err(e) => {
+            error!("Database error: {}", e);
+            Err(create_error_response("Database error"))
+        }
+    }
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Список релизов репозитория
+pub async fn list_releases(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let releases =
+        match Release::find_by_repository(ctx.repo.id.unwrap(), ctx.can_write(), db.get_pool())
+            .await
+        {
+            Ok(releases) => releases,
+            Err(e) => {
+                error!("Database error: {}", e);
+                return Ok(create_error_response("Database error"));
+            }
+        };
+
+    let mut result = Vec::with_capacity(releases.len());
+    for release in releases {
+        match with_assets(release, &db).await {
+            Ok(release) => result.push(release),
+            Err(response) => return Ok(response),
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        message: None,
+        data: Some(result),
+    }))
+}
+
+/// Создание релиза (и тега, если его ещё нет)
+pub async fn create_release(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    release_req: web::Json<CreateReleaseRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) = check_write_access(&ctx) {
+        return Ok(response);
+    }
+
+    let release_req = release_req.into_inner();
+    let input = NewRelease {
+        tag_name: release_req.tag_name.trim().to_string(),
+        target: release_req.target,
+        title: release_req.title,
+        notes: release_req.notes,
+        is_draft: release_req.is_draft,
+        is_prerelease: release_req.is_prerelease,
+    };
+    let author_id = ctx.viewer.as_ref().and_then(|u| u.id).unwrap();
+
+    match release_service::create_release(&ctx.repo, author_id, input, &db).await {
+        Ok(release) => Ok(HttpResponse::Created().json(ApiResponse {
+            success: true,
+            message: Some("Release created successfully".to_string()),
+            data: Some(ReleaseWithAssets {
+                release,
+                assets: Vec::new(),
+            }),
+        })),
+        Err(e) => Ok(create_app_error_response(e)),
+    }
+}
+
+/// Получение релиза по тегу
+pub async fn get_release(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, tag_name) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let release = match load_release(&ctx, &tag_name, &db).await {
+        Ok(release) => release,
+        Err(response) => return Ok(response),
+    };
+
+    match with_assets(release, &db).await {
+        Ok(release) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(release),
+        })),
+        Err(response) => Ok(response),
+    }
+}
+
+/// Обновление описания и флагов релиза (в том числе публикация черновика)
+pub async fn update_release(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    update_req: web::Json<UpdateReleaseRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, tag_name) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) = check_write_access(&ctx) {
+        return Ok(response);
+    }
+
+    let mut release = match load_release(&ctx, &tag_name, &db).await {
+        Ok(release) => release,
+        Err(response) => return Ok(response),
+    };
+
+    let update_req = update_req.into_inner();
+    if let Some(title) = update_req.title.filter(|title| !title.trim().is_empty()) {
+        release.title = title;
+    }
+    if let Some(notes) = update_req.notes {
+        release.notes = Some(notes);
+    }
+    if let Some(is_draft) = update_req.is_draft {
+        release.is_draft = is_draft;
+    }
+    if let Some(is_prerelease) = update_req.is_prerelease {
+        release.is_prerelease = is_prerelease;
+    }
+
+    if let Err(e) = release.update(db.get_pool()).await {
+        error!("Failed to update release: {}", e);
+        return Ok(create_error_response("Failed to update release"));
+    }
+
+    let release = match load_release(&ctx, &tag_name, &db).await {
+        Ok(release) => release,
+        Err(response) => return Ok(response),
+    };
+    match with_assets(release, &db).await {
+        Ok(release) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Release updated successfully".to_string()),
+            data: Some(release),
+        })),
+        Err(response) => Ok(response),
+    }
+}
+
+/// Удаление релиза вместе с файлами (тег остаётся в репозитории)
+pub async fn delete_release(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, tag_name) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) = check_write_access(&ctx) {
+        return Ok(response);
+    }
+
+    let release = match load_release(&ctx, &tag_name, &db).await {
+        Ok(release) => release,
+        Err(response) => return Ok(response),
+    };
+
+    match release_service::delete_release(&release, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Release deleted successfully".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(create_app_error_response(e)),
+    }
+}
+
+/// Загрузка файла релиза (тело запроса — содержимое файла)
+pub async fn upload_asset(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    query: web::Query<UploadAssetQuery>,
+    body: web::Bytes,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, tag_name) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) = check_write_access(&ctx) {
+        return Ok(response);
+    }
+
+    let release = match load_release(&ctx, &tag_name, &db).await {
+        Ok(release) => release,
+        Err(response) => return Ok(response),
+    };
+
+    let content_type = req
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    let asset = NewAsset {
+        file_name: query.into_inner().name,
+        content_type,
+        data: body.into(),
+    };
+    let uploader_id = ctx.viewer.as_ref().and_then(|u| u.id).unwrap();
+
+    match release_service::upload_asset(&release, uploader_id, asset, &db).await {
+        Ok(asset) => Ok(HttpResponse::Created().json(ApiResponse {
+            success: true,
+            message: Some("Asset uploaded successfully".to_string()),
+            data: Some(asset),
+        })),
+        Err(e) => Ok(create_app_error_response(e)),
+    }
+}
+
+/// Скачивание файла релиза
+pub async fn download_asset(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, tag_name, file_name) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let release = match load_release(&ctx, &tag_name, &db).await {
+        Ok(release) => release,
+        Err(response) => return Ok(response),
+    };
+
+    let asset =
+        match ReleaseAsset::find_by_name(release.id.unwrap(), &file_name, db.get_pool()).await {
+            Ok(Some(asset)) => asset,
+            Ok(None) => return Ok(create_not_found_response("Asset not found")),
+            Err(e) => {
+                error!("Database error: {}", e);
+                return Ok(create_error_response("Database error"));
+            }
+        };
+
+    let file = match NamedFile::open_async(release_service::asset_path(&asset.storage_path)).await {
+        Ok(file) => file,
+        Err(e) => {
+            error!("Failed to open asset file {}: {}", asset.storage_path, e);
+            return Ok(create_not_found_response("Asset file is missing"));
+        }
+    };
+
+    if let Err(e) = ReleaseAsset::increment_download_count(asset.id.unwrap(), db.get_pool()).await {
+        error!("Failed to update download count: {}", e);
+    }
+
+    let content_type = asset
+        .content_type
+        .parse::<mime::Mime>()
+        .unwrap_or(mime::APPLICATION_OCTET_STREAM);
+    Ok(file
+        .set_content_type(content_type)
+        .set_content_disposition(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(asset.file_name)],
+        })
+        .into_response(&req))
+}
+
+/// Удаление файла релиза
+pub async fn delete_asset(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, tag_name, file_name) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if let Some(response) = check_write_access(&ctx) {
+        return Ok(response);
+    }
+
+    let release = match load_release(&ctx, &tag_name, &db).await {
+        Ok(release) => release,
+        Err(response) => return Ok(response),
+    };
+
+    let asset =
+        match ReleaseAsset::find_by_name(release.id.unwrap(), &file_name, db.get_pool()).await {
+            Ok(Some(asset)) => asset,
+            Ok(None) => return Ok(create_not_found_response("Asset not found")),
+            Err(e) => {
+                error!("Database error: {}", e);
+                return Ok(create_error_response("Database error"));
+            }
+        };
+
+    match release_service::delete_asset(&asset, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Asset deleted successfully".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(create_app_error_response(e)),
+    }
+}
@@ -1,10 +1,18 @@
 use crate::core::database::Database;
+use crate::domain::projects::Project;
 use crate::domain::repos::Repository;
+use crate::domain::users::User;
+use crate::services::permissions::{self as permission_service, Permission};
+use crate::services::projects as project_service;
+use crate::services::signatures::{self as signature_service, CommitVerification};
+use crate::services::traffic as traffic_service;
 use crate::transports::http::users::{self as user, ApiResponse};
+use crate::utils::git::{self, GitCommit};
+use crate::utils::graph;
 use actix_web::{web, HttpRequest, HttpResponse, Result};
 use log::error;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::collections::HashMap;
 
 // Структура для автоматического парсинга query-параметров
 #[derive(Debug, Deserialize)]
@@ -12,6 +20,72 @@ pub struct RepoQuery {
     branch: Option<String>, // Опциональный параметр
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CommitsQuery {
+    /// Ветка, тег или SHA (по умолчанию — ветка по умолчанию)
+    pub branch: Option<String>,
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+/// Сколько коммитов от вершин веток раскладывает граф; страницы глубже не отдаются,
+/// так как каждая раскладывается вместе со всеми предыдущими
+const MAX_GRAPH_COMMITS: usize = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct CommitGraphQuery {
+    /// Ветки через запятую (по умолчанию — ветка по умолчанию)
+    pub branches: Option<String>,
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTemplateRequest {
+    pub is_template: bool,
+}
+
+/// Коммит вместе с результатом проверки подписи
+#[derive(Debug, Serialize)]
+pub struct CommitWithVerification {
+    #[serde(flatten)]
+    pub commit: GitCommit,
+    /// Полное сообщение коммита (только в детальной информации)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub verification: CommitVerification,
+}
+
+/// Окно графа коммитов
+#[derive(Debug, Serialize)]
+pub struct CommitGraph {
+    /// Число колонок, нужное для отрисовки
+    pub lanes: usize,
+    pub commits: Vec<GraphCommit>,
+    /// Есть ли более старые коммиты за пределами окна
+    pub has_more: bool,
+}
+
+/// Коммит графа с его колонкой и ссылками на него
+#[derive(Debug, Serialize)]
+pub struct GraphCommit {
+    #[serde(flatten)]
+    pub commit: GitCommit,
+    pub lane: usize,
+    /// Колонки рёбер к родителям, в порядке `parents`
+    pub parent_lanes: Vec<usize>,
+    pub refs: Vec<GraphRef>,
+}
+
+/// Ветка или тег, указывающие на коммит
+#[derive(Debug, Serialize)]
+pub struct GraphRef {
+    pub name: String,
+    /// branch или tag
+    #[serde(rename = "type")]
+    pub ref_type: &'static str,
+}
+
 #[derive(Debug, Serialize)]
 struct GitFile {
     name: String,
@@ -21,6 +95,127 @@ struct GitFile {
     size: Option<u64>,   // Размер файла (если есть)
 }
 
+/// Репозиторий вместе с проектом, текущим пользователем и его правами
+pub struct RepoContext {
+    pub project: Project,
+    pub repo: Repository,
+    pub viewer: Option<User>,
+    pub permission: Permission,
+}
+
+impl RepoContext {
+    /// Push, редактирование файлов, статусы и релизы
+    pub fn can_write(&self) -> bool {
+        self.permission >= Permission::Write
+    }
+
+    /// Настройки репозитория и управление соавторами
+    pub fn can_admin(&self) -> bool {
+        self.permission >= Permission::Admin
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_error_response(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_bad_request_response(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_not_found_response(message: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+/// Находит репозиторий по пути `/{user}/{project}/{repo}` и проверяет право чтения
+///
+/// Права определяет `services::permissions`. При ошибке возвращает готовый HTTP ответ.
+pub(crate) async fn resolve_repository(
+    req: &HttpRequest,
+    db: &Database,
+    (username, project_name, repo_name): (String, String, String),
+) -> std::result::Result<RepoContext, HttpResponse> {
+    let pool = db.get_pool();
+    let owner = match project_service::find_owner(&username, db).await {
+        Ok(Some(owner)) => owner,
+        Ok(None) => return Err(create_not_found_response("User or organization not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err(create_error_response("Database error"));
+        }
+    };
+
+    let project = match project_service::find_project(&project_name, &owner, db).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return Err(create_not_found_response("Project not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err(create_error_response("Database error"));
+        }
+    };
+
+    let repo = match Repository::find_by_name_and_project(&repo_name, project.id.unwrap(), pool)
+        .await
+    {
+        Ok(Some(repo)) => repo,
+        Ok(None) => return Err(create_not_found_response("Repository not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err(create_error_response("Database error"));
+        }
+    };
+
+    let viewer = user::check_auth(req, db).await;
+    let permission =
+        match permission_service::repository_permission(&project, &repo, viewer.as_ref(), db)
+            .await
+        {
+            Ok(permission) => permission,
+            Err(e) => {
+                error!("Database error: {}", e);
+                return Err(create_error_response("Database error"));
+            }
+        };
+    if permission < Permission::Read {
+        return Err(create_forbidden_response("Access denied"));
+    }
+
+    Ok(RepoContext {
+        project,
+        repo,
+        viewer,
+        permission,
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
 /// Получение списка репозиториев
 pub async fn list_repos(req: HttpRequest, db: web::Data<Database>) -> Result<HttpResponse> {
     if let Some(user) = user::check_auth(&req, &db).await {
@@ -93,57 +288,34 @@ pub async fn get_repo(
 
     match Repository::find_by_name(&repo_name, pool).await {
         Ok(Some(repo)) => {
-            // Получаем ветки репозитория
-            let repo_path = format!("repositories/{}.git/", repo_name);
-
-            let branches_output = Command::new("git")
-                .args(&[
-                    "--git-dir",
-                    &repo_path,
-                    "branch",
-                    "--format=%(refname:short)",
-                ])
-                .output();
-
-            let files_output = Command::new("git")
-                .args(&["--git-dir", &repo_path, "ls-tree", "-l", branch])
-                .output();
-
-            let branches = match branches_output {
-                Ok(output) if output.status.success() => {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    stdout
-                        .lines()
-                        .map(|s| s.to_string())
-                        .collect::<Vec<String>>()
-                }
-                _ => Vec::new(),
-            };
+            // Ветки и файлы читаются вне воркеров actix
+            let repo_path = git::repository_path(&repo_name);
+            let branch = branch.to_string();
+            let contents = tokio::task::spawn_blocking(move || {
+                (
+                    git::get_repository_branches(&repo_path).unwrap_or_default(),
+                    git::get_repository_files(&repo_path, &branch).unwrap_or_default(),
+                )
+            })
+            .await;
 
-            let files = match files_output {
-                Ok(output) if output.status.success() => {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    stdout
-                        .lines()
-                        .filter_map(|line| {
-                            let mut parts = line.split_whitespace();
-                            let _mode = parts.next()?;
-                            let type_ = parts.next()?.to_string();
-                            let branch = parts.next()?.to_string();
-                            let size = parts.next()?.parse().ok();
-                            let name = parts.collect::<Vec<&str>>().join(" ");
-
-                            Some(GitFile {
-                                name,
-                                type_: type_.clone(),
-                                last_branch: branch,
-                                size: if type_ == "blob" { size } else { None },
-                            })
-                        })
-                        .collect()
+            let (branches, files) = match contents {
+                Ok(contents) => contents,
+                Err(e) => {
+                    error!("Failed to read repository: {}", e);
+                    (Vec::new(), Vec::new())
                 }
-                _ => Vec::new(),
             };
+            let files: Vec<GitFile> = files
+                .into_iter()
+                .map(|file| GitFile {
+                    name: file.name,
+                    type_: file.file_type,
+                    last_branch: file.hash,
+                    size: file.size,
+                })
+                .collect();
+
             #[derive(Serialize)]
             struct RepoDetails {
                 repo: Repository,
@@ -184,11 +356,23 @@ pub async fn get_repo_in_project(
     query: web::Query<RepoQuery>,
     db: web::Data<Database>,
 ) -> Result<HttpResponse> {
-    let (_username, _project_name, repo_name) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    // Просмотром считается только открытие страницы репозитория, а не
+    // остальные запросы к его API
+    traffic_service::schedule_view(
+        ctx.repo.id.unwrap(),
+        traffic_service::visitor(&req, ctx.viewer.as_ref()),
+        traffic_service::referrer_path(&req),
+        db.get_ref().clone(),
+    );
 
     // For now, delegate to the existing get_repo function
     // In the future, this could include project-specific logic
-    let repo_path = web::Path::from(repo_name);
+    let repo_path = web::Path::from(ctx.repo.name);
     get_repo(req, repo_path, query, db).await
 }
 
@@ -206,17 +390,266 @@ pub async fn get_repo_contents(
     }))
 }
 
-/// Get repository commits (project-based route)
+/// История коммитов ветки с проверкой подписей
 pub async fn get_repo_commits(
-    _req: HttpRequest,
-    _path: web::Path<(String, String, String)>,
-    _db: web::Data<Database>,
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<CommitsQuery>,
+    db: web::Data<Database>,
 ) -> Result<HttpResponse> {
-    // Placeholder implementation
-    Ok(HttpResponse::Ok().json(ApiResponse::<Vec<String>> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    let repo_path = git::repository_path(&ctx.repo.name);
+
+    let per_page = query.per_page.unwrap_or(30).clamp(1, 100);
+    let page = query.page.unwrap_or(1).max(1);
+    let branch = query.branch.clone();
+
+    let commits = {
+        let repo_path = repo_path.clone();
+        tokio::task::spawn_blocking(
+            move || -> std::result::Result<Option<Vec<GitCommit>>, String> {
+                let reader = git::open_reader(&repo_path);
+                let head = match branch {
+                    Some(branch) => reader.resolve_revision(&branch),
+                    None => reader.default_head().map(|(_, sha)| sha),
+                };
+                match head {
+                    Some(head) => reader
+                        .commits(&head, Some(per_page), (page - 1) * per_page)
+                        .map(Some),
+                    None => Ok(None),
+                }
+            },
+        )
+        .await
+    };
+
+    let commits = match commits {
+        Ok(Ok(Some(commits))) => commits,
+        // Пустой репозиторий без указанной ветки — истории ещё нет
+        Ok(Ok(None)) if query.branch.is_none() => Vec::new(),
+        Ok(Ok(None)) => return Ok(create_not_found_response("Branch not found")),
+        Ok(Err(e)) => {
+            error!("Failed to get commits: {}", e);
+            return Ok(create_error_response("Failed to get commits"));
+        }
+        Err(e) => {
+            error!("Failed to get commits: {}", e);
+            return Ok(create_error_response("Failed to get commits"));
+        }
+    };
+
+    let verifications = match signature_service::verify_commits(&repo_path, &commits, &db).await {
+        Ok(verifications) => verifications,
+        Err(e) => {
+            error!("Failed to verify commit signatures: {}", e);
+            return Ok(create_error_response("Failed to verify commit signatures"));
+        }
+    };
+
+    let data: Vec<CommitWithVerification> = commits
+        .into_iter()
+        .zip(verifications)
+        .map(|(commit, verification)| CommitWithVerification {
+            commit,
+            message: None,
+            verification,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         message: None,
-        data: Some(vec![]),
+        data: Some(data),
+    }))
+}
+
+/// Граф коммитов одной или нескольких веток для отрисовки истории
+///
+/// Колонки считаются от вершин веток, поэтому страница `page` раскладывается
+/// вместе со всеми предыдущими и совпадает с соседними страницами по колонкам.
+pub async fn get_commit_graph(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<CommitGraphQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    let repo_path = git::repository_path(&ctx.repo.name);
+
+    let per_page = query.per_page.unwrap_or(100).clamp(1, 500);
+    let skip = match (query.page.unwrap_or(1).max(1) - 1).checked_mul(per_page) {
+        Some(skip) if skip < MAX_GRAPH_COMMITS => skip,
+        _ => {
+            return Ok(create_bad_request_response(&format!(
+                "The commit graph is limited to the first {} commits",
+                MAX_GRAPH_COMMITS
+            )))
+        }
+    };
+    let per_page = per_page.min(MAX_GRAPH_COMMITS - skip);
+    let branches: Vec<String> = query
+        .branches
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|branch| !branch.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let graph = tokio::task::spawn_blocking(
+        move || -> std::result::Result<std::result::Result<CommitGraph, String>, String> {
+            let reader = git::open_reader(&repo_path);
+            let mut heads = Vec::new();
+            if branches.is_empty() {
+                match reader.default_head() {
+                    Some((_, sha)) => heads.push(sha),
+                    // Пустой репозиторий
+                    None => {
+                        return Ok(Ok(CommitGraph {
+                            lanes: 0,
+                            commits: Vec::new(),
+                            has_more: false,
+                        }))
+                    }
+                }
+            }
+            for branch in &branches {
+                match reader.resolve_revision(branch) {
+                    Some(sha) => heads.push(sha),
+                    None => return Ok(Err(format!("Branch not found: {}", branch))),
+                }
+            }
+
+            let mut commits = git::get_topo_commits(&repo_path, &heads, skip + per_page + 1)?;
+            let has_more = commits.len() > skip + per_page && skip + per_page < MAX_GRAPH_COMMITS;
+            commits.truncate(skip + per_page);
+
+            let (assignments, lanes) = graph::assign_lanes(
+                commits
+                    .iter()
+                    .map(|commit| (commit.sha.as_str(), commit.parents.as_slice())),
+            );
+
+            let mut refs: HashMap<String, Vec<GraphRef>> = HashMap::new();
+            for (name, target) in git::list_ref_targets(&repo_path)? {
+                let (name, ref_type) = match name.strip_prefix("refs/heads/") {
+                    Some(branch) => (branch.to_string(), "branch"),
+                    None => (name.trim_start_matches("refs/tags/").to_string(), "tag"),
+                };
+                refs.entry(target)
+                    .or_default()
+                    .push(GraphRef { name, ref_type });
+            }
+
+            let commits = commits
+                .into_iter()
+                .zip(assignments)
+                .skip(skip)
+                .map(|(commit, assignment)| GraphCommit {
+                    refs: refs.remove(&commit.sha).unwrap_or_default(),
+                    commit,
+                    lane: assignment.lane,
+                    parent_lanes: assignment.parent_lanes,
+                })
+                .collect();
+
+            Ok(Ok(CommitGraph {
+                lanes,
+                commits,
+                has_more,
+            }))
+        },
+    )
+    .await;
+
+    match graph {
+        Ok(Ok(Ok(graph))) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(graph),
+        })),
+        Ok(Ok(Err(message))) => Ok(create_not_found_response(&message)),
+        Ok(Err(e)) => {
+            error!("Failed to build commit graph: {}", e);
+            Ok(create_error_response("Failed to build commit graph"))
+        }
+        Err(e) => {
+            error!("Failed to build commit graph: {}", e);
+            Ok(create_error_response("Failed to build commit graph"))
+        }
+    }
+}
+
+/// Информация о коммите с полным сообщением и проверкой подписи
+pub async fn get_repo_commit(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, sha) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    let repo_path = git::repository_path(&ctx.repo.name);
+
+    let commit = {
+        let repo_path = repo_path.clone();
+        tokio::task::spawn_blocking(
+            move || -> std::result::Result<Option<(GitCommit, String)>, String> {
+                let reader = git::open_reader(&repo_path);
+                let Some(sha) = reader.resolve_revision(&sha) else {
+                    return Ok(None);
+                };
+                match reader.commits(&sha, Some(1), 0)?.pop() {
+                    Some(commit) => Ok(Some((commit, reader.commit_message(&sha)?))),
+                    None => Ok(None),
+                }
+            },
+        )
+        .await
+    };
+
+    let (commit, message) = match commit {
+        Ok(Ok(Some(commit))) => commit,
+        Ok(Ok(None)) => return Ok(create_not_found_response("Commit not found")),
+        Ok(Err(e)) => {
+            error!("Failed to get commit: {}", e);
+            return Ok(create_error_response("Failed to get commit"));
+        }
+        Err(e) => {
+            error!("Failed to get commit: {}", e);
+            return Ok(create_error_response("Failed to get commit"));
+        }
+    };
+
+    let verification =
+        match signature_service::verify_commits(&repo_path, std::slice::from_ref(&commit), &db)
+            .await
+        {
+            Ok(mut verifications) => verifications.remove(0),
+            Err(e) => {
+                error!("Failed to verify commit signature: {}", e);
+                return Ok(create_error_response("Failed to verify commit signature"));
+            }
+        };
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        message: None,
+        data: Some(CommitWithVerification {
+            commit,
+            message: Some(message),
+            verification,
+        }),
     }))
 }
 
@@ -247,3 +680,35 @@ pub async fn get_repo_readme(
         data: Some("# Repository README\n\nThis is a placeholder README file.".to_string()),
     }))
 }
+
+/// Пометка репозитория как шаблона (или снятие отметки)
+pub async fn update_repo_template(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    template_req: web::Json<UpdateTemplateRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    if !ctx.can_admin() {
+        return Ok(create_forbidden_response(
+            "Admin access is required to change repository settings",
+        ));
+    }
+
+    let mut repo = ctx.repo;
+    repo.is_template = template_req.is_template;
+    match Repository::set_template(repo.id.unwrap(), repo.is_template, db.get_pool()).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Repository updated successfully".to_string()),
+            data: Some(repo),
+        })),
+        Err(e) => {
+            error!("Database error: {}", e);
+            Ok(create_error_response("Database error"))
+        }
+    }
+}
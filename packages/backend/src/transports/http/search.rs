@@ -0,0 +1,96 @@
+//! API обработчики поиска по коду
+
+use crate::core::database::Database;
+use crate::domain::search::CodeSearchFilters;
+use crate::services::search as search_service;
+use crate::transports::http::users::{self as user, ApiResponse};
+use crate::utils::helpers::glob_to_regex;
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use log::error;
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CodeSearchQuery {
+    pub q: String,
+    pub project: Option<String>,
+    pub repo: Option<String>,
+    /// Glob-шаблон пути, например `src/**/*.rs`
+    pub path: Option<String>,
+    pub language: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_error_response(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_bad_request_response(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Поиск по коду во всех доступных пользователю репозиториях
+pub async fn search_code(
+    req: HttpRequest,
+    query: web::Query<CodeSearchQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let search_query = query.q.trim();
+    if search_query.is_empty() {
+        return Ok(create_bad_request_response("Search query 'q' is required"));
+    }
+
+    // Анонимные пользователи видят только публичные репозитории
+    let current_user = user::check_auth(&req, &db).await;
+
+    let filters = CodeSearchFilters {
+        project: query.project.clone(),
+        repository: query.repo.clone(),
+        path_regex: query.path.as_deref().map(glob_to_regex),
+        language: query.language.clone(),
+    };
+
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100) as i64;
+    let page = query.page.unwrap_or(1).max(1) as i64;
+
+    match search_service::search_code(
+        search_query,
+        &filters,
+        current_user.as_ref(),
+        per_page,
+        (page - 1) * per_page,
+        &db,
+    )
+    .await
+    {
+        Ok(results) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(results),
+        })),
+        Err(e) => {
+            error!("Code search failed: {}", e);
+            Ok(create_error_response("Code search failed"))
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! API обработчики найденных в push секретов
+
+use crate::core::database::Database;
+use crate::services::secret_scanning as secret_scanning_service;
+use crate::transports::http::repositories::{resolve_repository, RepoContext};
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct FindingsQuery {
+    /// open, resolved или ignored (по умолчанию — все)
+    pub state: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateFindingRequest {
+    /// open, resolved или ignored
+    pub state: String,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+/// Находит репозиторий и проверяет права администратора
+async fn resolve_admin(
+    req: &HttpRequest,
+    db: &Database,
+    path: (String, String, String),
+) -> std::result::Result<RepoContext, HttpResponse> {
+    let ctx = resolve_repository(req, db, path).await?;
+    if ctx.viewer.is_none() {
+        return Err(create_unauthorized_response());
+    }
+    if !ctx.can_admin() {
+        return Err(create_forbidden_response(
+            "Admin access is required to view secret findings",
+        ));
+    }
+    Ok(ctx)
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Секреты, найденные в репозитории
+pub async fn list_secret_findings(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<FindingsQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_admin(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match secret_scanning_service::list_findings(&ctx.repo, query.state.as_deref(), &db).await {
+        Ok(findings) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(findings),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Решение по находке: resolved, ignored или снова open
+pub async fn update_secret_finding(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, i64)>,
+    update_req: web::Json<UpdateFindingRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, id) = path.into_inner();
+    let ctx = match resolve_admin(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match secret_scanning_service::update_finding_state(
+        &ctx.repo,
+        id,
+        &update_req.state,
+        ctx.viewer.as_ref().unwrap(),
+        &db,
+    )
+    .await
+    {
+        Ok(finding) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Secret finding updated".to_string()),
+            data: Some(finding),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
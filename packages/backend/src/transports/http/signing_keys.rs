@@ -0,0 +1,118 @@
+//! API обработчики ключей подписи пользователя
+
+use crate::core::database::Database;
+use crate::domain::signing_keys::SigningKey;
+use crate::services::signatures as signature_service;
+use crate::transports::http::users::{self as user, app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use log::error;
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct AddSigningKeyRequest {
+    pub title: Option<String>,
+    /// ASCII-armored GPG ключ или SSH ключ в формате `ssh-ed25519 AAAA...`
+    pub public_key: String,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_error_response(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_not_found_response(message: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Список ключей подписи текущего пользователя
+pub async fn list_keys(req: HttpRequest, db: web::Data<Database>) -> Result<HttpResponse> {
+    let Some(current_user) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match SigningKey::find_by_user(current_user.id.unwrap(), db.get_pool()).await {
+        Ok(keys) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(keys),
+        })),
+        Err(e) => {
+            error!("Database error: {}", e);
+            Ok(create_error_response("Database error"))
+        }
+    }
+}
+
+/// Добавление GPG или SSH ключа подписи
+pub async fn add_key(
+    req: HttpRequest,
+    key_req: web::Json<AddSigningKeyRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(current_user) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    let title = key_req.title.as_deref().unwrap_or("");
+    match signature_service::add_key(current_user.id.unwrap(), title, &key_req.public_key, &db)
+        .await
+    {
+        Ok(key) => Ok(HttpResponse::Created().json(ApiResponse {
+            success: true,
+            message: Some("Signing key added successfully".to_string()),
+            data: Some(key),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Удаление ключа подписи
+pub async fn delete_key(
+    req: HttpRequest,
+    path: web::Path<i64>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(current_user) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match SigningKey::delete(path.into_inner(), current_user.id.unwrap(), db.get_pool()).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Signing key deleted successfully".to_string()),
+            data: None,
+        })),
+        Ok(false) => Ok(create_not_found_response("Signing key not found")),
+        Err(e) => {
+            error!("Database error: {}", e);
+            Ok(create_error_response("Database error"))
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! API обработчики статусов коммитов (интеграция с CI)
+
+use crate::core::database::Database;
+use crate::services::statuses::{self as status_service, NewStatus};
+use crate::transports::http::repositories::resolve_repository;
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateStatusRequest {
+    /// pending, success, failure или error
+    pub state: String,
+    /// Имя проверки, например `ci/build` (по умолчанию `default`)
+    pub context: Option<String>,
+    pub description: Option<String>,
+    /// Ссылка на сборку во внешней системе
+    pub target_url: Option<String>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+fn create_not_found_response(message: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Публикация статуса коммита (CI авторизуется API токеном)
+pub async fn create_status(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    status_req: web::Json<CreateStatusRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, sha) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    let Some(viewer_id) = ctx.viewer.as_ref().and_then(|u| u.id) else {
+        return Ok(create_unauthorized_response());
+    };
+    if !ctx.can_write() {
+        return Ok(create_forbidden_response(
+            "Write access is required to set commit statuses",
+        ));
+    }
+
+    let status_req = status_req.into_inner();
+    let input = NewStatus {
+        state: status_req.state,
+        context: status_req.context,
+        description: status_req.description,
+        target_url: status_req.target_url,
+    };
+
+    match status_service::create_status(&ctx.repo, &sha, viewer_id, input, &db).await {
+        Ok(status) => Ok(HttpResponse::Created().json(ApiResponse {
+            success: true,
+            message: Some("Status created successfully".to_string()),
+            data: Some(status),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Статусы ревизии по отдельности
+pub async fn list_statuses(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, reference) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match status_service::combined_status(&ctx.repo, &reference, &db).await {
+        Ok(Some(combined)) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(combined.statuses),
+        })),
+        Ok(None) => Ok(create_not_found_response("Reference not found")),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Сводный статус ревизии (ветки, тега или SHA)
+pub async fn get_combined_status(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, reference) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match status_service::combined_status(&ctx.repo, &reference, &db).await {
+        Ok(Some(combined)) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(combined),
+        })),
+        Ok(None) => Ok(create_not_found_response("Reference not found")),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
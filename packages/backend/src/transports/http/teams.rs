@@ -0,0 +1,176 @@
+//! API обработчики команд пользователей
+
+use crate::core::database::Database;
+use crate::services::members as member_service;
+use crate::transports::http::users::{self as user, app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTeamRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Команды текущего пользователя: свои и те, в которых он состоит
+pub async fn list_teams(req: HttpRequest, db: web::Data<Database>) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match member_service::list_teams(&viewer, &db).await {
+        Ok(teams) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(teams),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Создание команды
+pub async fn create_team(
+    req: HttpRequest,
+    team_req: web::Json<CreateTeamRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+
+    match member_service::create_team(
+        &viewer,
+        &team_req.name,
+        team_req.description.as_deref(),
+        &db,
+    )
+    .await
+    {
+        Ok(team) => Ok(HttpResponse::Created().json(ApiResponse {
+            success: true,
+            message: Some("Team created".to_string()),
+            data: Some(team),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Команда с участниками и проектами
+pub async fn get_team(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+    let (owner, name) = path.into_inner();
+
+    let team = match member_service::find_team(&owner, &name, &db).await {
+        Ok(team) => team,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+    match member_service::team_details(team, &viewer, &db).await {
+        Ok(details) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(details),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Удаление команды
+pub async fn delete_team(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+    let (owner, name) = path.into_inner();
+
+    let team = match member_service::find_team(&owner, &name, &db).await {
+        Ok(team) => team,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+    match member_service::delete_team(&team, &viewer, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Team deleted".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Добавление пользователя в команду
+pub async fn add_team_member(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+    let (owner, name, member) = path.into_inner();
+
+    let team = match member_service::find_team(&owner, &name, &db).await {
+        Ok(team) => team,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+    match member_service::add_team_member(&team, &viewer, &member, &db).await {
+        Ok(members) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Team member added".to_string()),
+            data: Some(members),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Исключение пользователя из команды
+pub async fn remove_team_member(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let Some(viewer) = user::check_auth(&req, &db).await else {
+        return Ok(create_unauthorized_response());
+    };
+    let (owner, name, member) = path.into_inner();
+
+    let team = match member_service::find_team(&owner, &name, &db).await {
+        Ok(team) => team,
+        Err(e) => return Ok(app_error_response(e)),
+    };
+    match member_service::remove_team_member(&team, &viewer, &member, &db).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            message: Some("Team member removed".to_string()),
+            data: None,
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
@@ -0,0 +1,82 @@
+//! API обработчики статистики посещений репозитория
+
+use crate::core::database::Database;
+use crate::services::traffic as traffic_service;
+use crate::transports::http::repositories::{resolve_repository, RepoContext};
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct TrafficQuery {
+    /// Период в днях: 14 (по умолчанию) или 30
+    pub days: Option<i64>,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+/// Находит репозиторий и проверяет права администратора
+async fn resolve_admin(
+    req: &HttpRequest,
+    db: &Database,
+    path: (String, String, String),
+) -> std::result::Result<RepoContext, HttpResponse> {
+    let ctx = resolve_repository(req, db, path).await?;
+    if ctx.viewer.is_none() {
+        return Err(create_unauthorized_response());
+    }
+    if !ctx.can_admin() {
+        return Err(create_forbidden_response(
+            "Admin access is required to view repository traffic",
+        ));
+    }
+    Ok(ctx)
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Просмотры, клоны и fetch по дням и популярные пути переходов
+pub async fn get_traffic(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    query: web::Query<TrafficQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_admin(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match traffic_service::summary(&ctx.repo, query.days.unwrap_or(14), &db).await {
+        Ok(summary) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(summary),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
@@ -1,4 +1,6 @@
 use crate::core::database::Database;
+use crate::core::types::{AppError, ErrorType};
+use crate::domain::organizations::Organization;
 use crate::domain::users::User;
 use crate::services::auth as auth_service;
 use actix_web::{web, HttpRequest, HttpResponse, Result};
@@ -79,6 +81,27 @@ pub async fn check_auth(req: &HttpRequest, db: &Database) -> Option<User> {
     auth_service::authenticate_request(req, db).await
 }
 
+/// Преобразует ошибку сервисного слоя в HTTP ответ
+pub fn app_error_response(err: AppError) -> HttpResponse {
+    let mut response = match err.error_type {
+        ErrorType::ValidationError => HttpResponse::BadRequest(),
+        ErrorType::ConflictError => HttpResponse::Conflict(),
+        ErrorType::NotFoundError => HttpResponse::NotFound(),
+        ErrorType::AuthenticationError => HttpResponse::Unauthorized(),
+        ErrorType::AuthorizationError => HttpResponse::Forbidden(),
+        ErrorType::DatabaseError | ErrorType::InternalError => {
+            error!("{}: {}", err.message, err.details.as_deref().unwrap_or(""));
+            HttpResponse::InternalServerError()
+        }
+    };
+
+    response.json(ApiResponse::<()> {
+        success: false,
+        message: Some(err.message),
+        data: None,
+    })
+}
+
 // ============================================================================
 // API HANDLERS
 // ============================================================================
@@ -128,9 +151,9 @@ pub async fn register(
 ) -> Result<HttpResponse> {
     let pool = db.get_pool();
 
-    // Проверяем, что пользователь не существует
-    match User::find_by_username(&register_req.username, pool).await {
-        Ok(Some(_)) => return Ok(create_bad_request_response("Username already exists")),
+    // Имя не должно быть занято ни пользователем, ни организацией
+    match Organization::is_name_taken(&register_req.username, pool).await {
+        Ok(true) => return Ok(create_bad_request_response("Username already exists")),
         Err(e) => {
             error!("Database error during registration check: {}", e);
             return Ok(create_error_response("Database error"));
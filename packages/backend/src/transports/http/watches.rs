@@ -0,0 +1,163 @@
+//! API обработчики звёзд и подписок на проекты
+
+use crate::core::database::Database;
+use crate::services::watches as watch_service;
+use crate::transports::http::projects::{resolve_project, ProjectContext};
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Serialize, Deserialize)]
+pub struct SetWatchRequest {
+    /// all, releases или ignore
+    pub level: String,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_success_response<T: Serialize>(data: T) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        message: None,
+        data: Some(data),
+    })
+}
+
+/// Проект из пути запроса; звёзды и подписки требуют входа
+async fn resolve_viewer_project(
+    req: &HttpRequest,
+    db: &Database,
+    path: (String, String),
+) -> std::result::Result<ProjectContext, HttpResponse> {
+    let ctx = resolve_project(req, db, path).await?;
+    if ctx.viewer.is_none() {
+        return Err(create_unauthorized_response());
+    }
+    Ok(ctx)
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Отмечает проект звездой
+pub async fn star_project(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_viewer_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    let viewer = ctx.viewer.as_ref().unwrap();
+
+    match watch_service::star(&ctx.project, viewer, &db).await {
+        Ok(state) => Ok(create_success_response(state)),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Снимает звезду с проекта
+pub async fn unstar_project(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_viewer_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    let viewer = ctx.viewer.as_ref().unwrap();
+
+    match watch_service::unstar(&ctx.project, viewer, &db).await {
+        Ok(state) => Ok(create_success_response(state)),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Пользователи, отметившие проект
+pub async fn list_stargazers(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match watch_service::stargazers(&ctx.project, &db).await {
+        Ok(stargazers) => Ok(create_success_response(stargazers)),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Подписка текущего пользователя на проект
+pub async fn get_watch(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_viewer_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    let viewer = ctx.viewer.as_ref().unwrap();
+
+    match watch_service::watch_state(&ctx.project, viewer, &db).await {
+        Ok(state) => Ok(create_success_response(state)),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Задаёт уровень подписки на проект
+pub async fn set_watch(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    watch_req: web::Json<SetWatchRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_viewer_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    let viewer = ctx.viewer.as_ref().unwrap();
+
+    match watch_service::set_watch(&ctx.project, viewer, &watch_req.level, &db).await {
+        Ok(state) => Ok(create_success_response(state)),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Сбрасывает подписку к уровню по умолчанию
+pub async fn reset_watch(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_viewer_project(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+    let viewer = ctx.viewer.as_ref().unwrap();
+
+    match watch_service::reset_watch(&ctx.project, viewer, &db).await {
+        Ok(state) => Ok(create_success_response(state)),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
@@ -0,0 +1,242 @@
+//! API обработчики вики репозитория
+
+use crate::core::database::Database;
+use crate::core::types::AppError;
+use crate::services::files::FileCommit;
+use crate::services::wiki as wiki_service;
+use crate::transports::http::repositories::{resolve_repository, RepoContext};
+use crate::transports::http::users::{app_error_response, ApiResponse};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+
+// ============================================================================
+// СТРУКТУРЫ ЗАПРОСОВ И ОТВЕТОВ
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    /// Ревизия вики (по умолчанию — последняя)
+    pub revision: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavePageRequest {
+    pub content: String,
+    /// SHA страницы, которую видел клиент (не передаётся при создании)
+    pub sha: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeletePageRequest {
+    pub sha: String,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevertPageRequest {
+    /// Коммит вики, к состоянию в котором возвращается страница
+    pub revision: String,
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn create_unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        message: Some("Unauthorized".to_string()),
+        data: None,
+    })
+}
+
+fn create_forbidden_response(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()> {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    })
+}
+
+/// Находит репозиторий и проверяет право записи
+async fn resolve_writable(
+    req: &HttpRequest,
+    db: &Database,
+    path: (String, String, String),
+) -> std::result::Result<RepoContext, HttpResponse> {
+    let ctx = resolve_repository(req, db, path).await?;
+    if ctx.viewer.is_none() {
+        return Err(create_unauthorized_response());
+    }
+    if !ctx.can_write() {
+        return Err(create_forbidden_response(
+            "Write access is required to edit the wiki",
+        ));
+    }
+    Ok(ctx)
+}
+
+fn commit_response(result: std::result::Result<FileCommit, AppError>) -> HttpResponse {
+    match result {
+        Ok(commit) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some("Wiki updated successfully".to_string()),
+            data: Some(commit),
+        }),
+        Err(e) => app_error_response(e),
+    }
+}
+
+// ============================================================================
+// API HANDLERS
+// ============================================================================
+
+/// Список страниц вики
+pub async fn list_pages(
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let ctx = match resolve_repository(&req, &db, path.into_inner()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match wiki_service::list_pages(&ctx.repo).await {
+        Ok(pages) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(pages),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Содержимое страницы
+pub async fn get_page(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    query: web::Query<PageQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, page) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    match wiki_service::get_page(&ctx.repo, &page, query.into_inner().revision).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(page),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Создание или изменение страницы
+pub async fn save_page(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    page_req: web::Json<SavePageRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, page) = path.into_inner();
+    let ctx = match resolve_writable(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let page_req = page_req.into_inner();
+    let result = wiki_service::save_page(
+        &ctx.repo,
+        &page,
+        page_req.content,
+        page_req.sha,
+        page_req.message,
+        ctx.viewer.as_ref().unwrap(),
+    )
+    .await;
+    Ok(commit_response(result))
+}
+
+/// Удаление страницы
+pub async fn delete_page(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    page_req: web::Json<DeletePageRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, page) = path.into_inner();
+    let ctx = match resolve_writable(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let page_req = page_req.into_inner();
+    let result = wiki_service::delete_page(
+        &ctx.repo,
+        &page,
+        page_req.sha,
+        page_req.message,
+        ctx.viewer.as_ref().unwrap(),
+    )
+    .await;
+    Ok(commit_response(result))
+}
+
+/// История изменений страницы
+pub async fn get_page_history(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    query: web::Query<HistoryQuery>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, page) = path.into_inner();
+    let ctx = match resolve_repository(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let per_page = query.per_page.unwrap_or(30).clamp(1, 100);
+    let skip = (query.page.unwrap_or(1).max(1) - 1) * per_page;
+    match wiki_service::page_history(&ctx.repo, &page, per_page, skip).await {
+        Ok(commits) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: None,
+            data: Some(commits),
+        })),
+        Err(e) => Ok(app_error_response(e)),
+    }
+}
+
+/// Откат страницы к одной из прошлых ревизий
+pub async fn revert_page(
+    req: HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    revert_req: web::Json<RevertPageRequest>,
+    db: web::Data<Database>,
+) -> Result<HttpResponse> {
+    let (username, project_name, repo_name, page) = path.into_inner();
+    let ctx = match resolve_writable(&req, &db, (username, project_name, repo_name)).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let result = wiki_service::revert_page(
+        &ctx.repo,
+        &page,
+        &revert_req.revision,
+        ctx.viewer.as_ref().unwrap(),
+    )
+    .await;
+    Ok(commit_response(result))
+}
@@ -0,0 +1,936 @@
+//! Разбор манифестов зависимостей и построение графа
+//!
+//! Поддерживаются Cargo (`Cargo.toml`, `Cargo.lock`), npm (`package.json`,
+//! `package-lock.json`) и pip (`requirements*.txt`). Lock-файл описывает
+//! манифесты своей директории и поддиректорий и даёт точные версии вместе с
+//! транзитивными зависимостями; манифест без lock-файла даёт только прямые
+//! зависимости с требованиями к версии.
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+/// Директории со сторонним кодом, манифесты в которых не учитываются
+const SKIPPED_DIRECTORIES: &[&str] = &["node_modules", "target", "vendor", ".git"];
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Экосистема пакетов
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Pypi,
+}
+
+/// Для чего нужна зависимость; при объединении побеждает более важная
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyScope {
+    Runtime,
+    Build,
+    Optional,
+    Development,
+}
+
+/// Пакет в графе зависимостей
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    /// Package URL (`pkg:cargo/serde@1.0.219`); без версии, если она не зафиксирована
+    pub purl: String,
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub version: Option<String>,
+    /// Требование к версии из манифеста (только для прямых зависимостей)
+    pub requirement: Option<String>,
+    pub scope: DependencyScope,
+    /// Указана в манифесте репозитория, а не подтянута транзитивно
+    pub direct: bool,
+    /// Файлы, из которых известна зависимость
+    pub manifests: Vec<String>,
+    /// purl зависимостей этого пакета
+    pub dependencies: Vec<String>,
+}
+
+/// Граф зависимостей ревизии
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DependencyGraph {
+    /// Разобранные манифесты и lock-файлы
+    pub manifests: Vec<String>,
+    pub dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    CargoManifest,
+    CargoLock,
+    NpmManifest,
+    NpmLock,
+    PipRequirements,
+}
+
+/// Зависимость, объявленная в манифесте
+#[derive(Debug, Clone, PartialEq)]
+struct DeclaredDependency {
+    name: String,
+    requirement: Option<String>,
+    scope: DependencyScope,
+}
+
+/// Пакет из lock-файла
+#[derive(Debug, Clone)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    /// Пакет самого репозитория (корень или член workspace)
+    local: bool,
+    /// Индексы зависимостей в списке пакетов lock-файла
+    dependencies: Vec<usize>,
+    /// Назначение, записанное в lock-файле (npm)
+    scope: Option<DependencyScope>,
+}
+
+// ============================================================================
+// ПОСТРОЕНИЕ ГРАФА
+// ============================================================================
+
+/// Является ли файл манифестом или lock-файлом, который нужно разобрать
+pub fn is_manifest(path: &str) -> bool {
+    file_kind(path).is_some()
+        && !path
+            .split('/')
+            .any(|segment| SKIPPED_DIRECTORIES.contains(&segment))
+}
+
+/// Строит граф по содержимому манифестов (`путь`, `содержимое`)
+///
+/// Файлы, которые не удалось разобрать, пропускаются.
+pub fn build_graph(files: &[(String, Vec<u8>)]) -> DependencyGraph {
+    let mut graph = GraphBuilder::default();
+
+    for (ecosystem, manifest_kind, lock_kind) in [
+        (
+            Ecosystem::Cargo,
+            FileKind::CargoManifest,
+            FileKind::CargoLock,
+        ),
+        (Ecosystem::Npm, FileKind::NpmManifest, FileKind::NpmLock),
+    ] {
+        let mut declared = Vec::new();
+        for (path, content) in files_of_kind(files, manifest_kind) {
+            let parsed = match ecosystem {
+                Ecosystem::Cargo => parse_cargo_manifest(content),
+                _ => parse_npm_manifest(content),
+            };
+            match parsed {
+                Ok(dependencies) => declared.push((path.to_string(), dependencies)),
+                Err(e) => warn!("Skipping manifest {}: {}", path, e),
+            }
+        }
+
+        let mut lock_dirs = Vec::new();
+        for (path, content) in files_of_kind(files, lock_kind) {
+            let lock_dir = parent_dir(path);
+            let covered: Vec<&(String, Vec<DeclaredDependency>)> = declared
+                .iter()
+                .filter(|(manifest, _)| is_within(lock_dir, manifest))
+                .collect();
+
+            let parsed = match ecosystem {
+                Ecosystem::Cargo => parse_cargo_lock(content),
+                _ => {
+                    // Lock-файл npm v1 не перечисляет зависимости корня
+                    let root: Vec<String> = covered
+                        .iter()
+                        .filter(|(manifest, _)| parent_dir(manifest) == lock_dir)
+                        .flat_map(|(_, deps)| deps.iter().map(|dep| dep.name.clone()))
+                        .collect();
+                    parse_npm_lock(content, &root)
+                }
+            };
+            match parsed {
+                Ok(packages) => {
+                    graph.add_locked(ecosystem, path, &packages, &covered);
+                    lock_dirs.push(lock_dir);
+                }
+                Err(e) => warn!("Skipping lock file {}: {}", path, e),
+            }
+        }
+
+        for (path, dependencies) in &declared {
+            if !lock_dirs.iter().any(|dir| is_within(dir, path)) {
+                graph.add_declared(ecosystem, path, dependencies);
+            }
+            graph.manifests.insert(path.clone());
+        }
+    }
+
+    for (path, content) in files_of_kind(files, FileKind::PipRequirements) {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let scope = if file_name.contains("dev") || file_name.contains("test") {
+            DependencyScope::Development
+        } else {
+            DependencyScope::Runtime
+        };
+        graph.add_declared(Ecosystem::Pypi, path, &parse_requirements(content, scope));
+        graph.manifests.insert(path.to_string());
+    }
+
+    graph.build()
+}
+
+#[derive(Default)]
+struct GraphBuilder {
+    manifests: BTreeSet<String>,
+    nodes: BTreeMap<String, Dependency>,
+}
+
+impl GraphBuilder {
+    /// Добавляет пакеты lock-файла и связи между ними
+    fn add_locked(
+        &mut self,
+        ecosystem: Ecosystem,
+        lock_path: &str,
+        packages: &[LockedPackage],
+        declared: &[&(String, Vec<DeclaredDependency>)],
+    ) {
+        self.manifests.insert(lock_path.to_string());
+
+        let purls: Vec<String> = packages
+            .iter()
+            .map(|package| purl(ecosystem, &package.name, Some(&package.version)))
+            .collect();
+
+        // Прямые зависимости — зависимости пакетов самого репозитория
+        let mut scopes: Vec<Option<DependencyScope>> = vec![None; packages.len()];
+        let mut direct = vec![false; packages.len()];
+        let mut queue = VecDeque::new();
+        for package in packages.iter().filter(|package| package.local) {
+            for &index in &package.dependencies {
+                if packages[index].local {
+                    continue;
+                }
+                let scope = declared_scope(declared, &packages[index].name)
+                    .unwrap_or(DependencyScope::Runtime);
+                direct[index] = true;
+                if scopes[index].is_none_or(|current| scope < current) {
+                    scopes[index] = Some(scope);
+                    queue.push_back(index);
+                }
+            }
+        }
+
+        // Транзитивные зависимости наследуют самое важное назначение
+        while let Some(index) = queue.pop_front() {
+            let scope = scopes[index].unwrap_or(DependencyScope::Runtime);
+            for &child in &packages[index].dependencies {
+                if !packages[child].local && scopes[child].is_none_or(|current| scope < current) {
+                    scopes[child] = Some(scope);
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        for (index, package) in packages.iter().enumerate() {
+            if package.local {
+                continue;
+            }
+
+            let mut manifests = vec![lock_path.to_string()];
+            let mut requirement = None;
+            if direct[index] {
+                for (manifest, dependencies) in declared {
+                    if let Some(dep) = dependencies.iter().find(|dep| dep.name == package.name) {
+                        manifests.push(manifest.clone());
+                        requirement = requirement.or_else(|| dep.requirement.clone());
+                    }
+                }
+            }
+
+            self.add(Dependency {
+                purl: purls[index].clone(),
+                ecosystem,
+                name: package.name.clone(),
+                version: Some(package.version.clone()),
+                requirement,
+                scope: scopes[index]
+                    .or(package.scope)
+                    .unwrap_or(DependencyScope::Runtime),
+                direct: direct[index],
+                manifests,
+                dependencies: package
+                    .dependencies
+                    .iter()
+                    .filter(|&&child| !packages[child].local)
+                    .map(|&child| purls[child].clone())
+                    .collect(),
+            });
+        }
+    }
+
+    /// Добавляет прямые зависимости манифеста без lock-файла
+    fn add_declared(
+        &mut self,
+        ecosystem: Ecosystem,
+        manifest: &str,
+        dependencies: &[DeclaredDependency],
+    ) {
+        for dep in dependencies {
+            let version = dep
+                .requirement
+                .as_deref()
+                .and_then(|requirement| exact_version(ecosystem, requirement));
+            self.add(Dependency {
+                purl: purl(ecosystem, &dep.name, version.as_deref()),
+                ecosystem,
+                name: dep.name.clone(),
+                version,
+                requirement: dep.requirement.clone(),
+                scope: dep.scope,
+                direct: true,
+                manifests: vec![manifest.to_string()],
+                dependencies: Vec::new(),
+            });
+        }
+    }
+
+    /// Объединяет пакет с уже известным пакетом того же purl
+    fn add(&mut self, dependency: Dependency) {
+        match self.nodes.get_mut(&dependency.purl) {
+            Some(node) => {
+                node.direct |= dependency.direct;
+                node.scope = node.scope.min(dependency.scope);
+                if node.requirement.is_none() {
+                    node.requirement = dependency.requirement;
+                }
+                for manifest in dependency.manifests {
+                    if !node.manifests.contains(&manifest) {
+                        node.manifests.push(manifest);
+                    }
+                }
+                for child in dependency.dependencies {
+                    if !node.dependencies.contains(&child) {
+                        node.dependencies.push(child);
+                    }
+                }
+            }
+            None => {
+                self.nodes.insert(dependency.purl.clone(), dependency);
+            }
+        }
+    }
+
+    fn build(self) -> DependencyGraph {
+        DependencyGraph {
+            manifests: self.manifests.into_iter().collect(),
+            dependencies: self
+                .nodes
+                .into_values()
+                .map(|mut node| {
+                    node.manifests.sort();
+                    node.dependencies.sort();
+                    node
+                })
+                .collect(),
+        }
+    }
+}
+
+// ============================================================================
+// CARGO
+// ============================================================================
+
+/// Секции зависимостей `Cargo.toml`
+const CARGO_SECTIONS: &[(&str, DependencyScope)] = &[
+    ("dependencies", DependencyScope::Runtime),
+    ("build-dependencies", DependencyScope::Build),
+    ("dev-dependencies", DependencyScope::Development),
+];
+
+fn parse_cargo_manifest(content: &[u8]) -> Result<Vec<DeclaredDependency>, String> {
+    let manifest: toml::Table = toml::from_str(&String::from_utf8_lossy(content))
+        .map_err(|e| format!("Invalid Cargo.toml: {}", e))?;
+
+    // Секции верхнего уровня и `[target.'cfg(...)'.dependencies]`
+    let mut tables = vec![&manifest];
+    if let Some(targets) = manifest.get("target").and_then(|value| value.as_table()) {
+        tables.extend(targets.values().filter_map(|value| value.as_table()));
+    }
+
+    let mut declared = Vec::new();
+    for table in tables {
+        for (section, scope) in CARGO_SECTIONS {
+            let Some(dependencies) = table.get(*section).and_then(|value| value.as_table()) else {
+                continue;
+            };
+            for (key, spec) in dependencies {
+                let (name, requirement) = match spec {
+                    toml::Value::String(requirement) => (key.as_str(), Some(requirement.clone())),
+                    toml::Value::Table(spec) => {
+                        // Зависимости по пути — код самого репозитория
+                        if spec.contains_key("path") {
+                            continue;
+                        }
+                        let name = spec
+                            .get("package")
+                            .and_then(|value| value.as_str())
+                            .unwrap_or(key);
+                        let requirement = spec
+                            .get("version")
+                            .and_then(|value| value.as_str())
+                            .map(str::to_string);
+                        (name, requirement)
+                    }
+                    _ => continue,
+                };
+                declared.push(DeclaredDependency {
+                    name: name.to_string(),
+                    requirement,
+                    scope: *scope,
+                });
+            }
+        }
+    }
+
+    Ok(declared)
+}
+
+#[derive(Deserialize)]
+struct CargoLock {
+    #[serde(default)]
+    package: Vec<CargoLockPackage>,
+}
+
+#[derive(Deserialize)]
+struct CargoLockPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+fn parse_cargo_lock(content: &[u8]) -> Result<Vec<LockedPackage>, String> {
+    let lock: CargoLock = toml::from_str(&String::from_utf8_lossy(content))
+        .map_err(|e| format!("Invalid Cargo.lock: {}", e))?;
+
+    let mut by_name: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+    for (index, package) in lock.package.iter().enumerate() {
+        by_name
+            .entry(&package.name)
+            .or_default()
+            .push((index, &package.version));
+    }
+
+    // Ссылка на зависимость: `name`, `name version` или `name version (source)`
+    let resolve = |reference: &str| -> Option<usize> {
+        let mut parts = reference.split_whitespace();
+        let candidates = by_name.get(parts.next()?)?;
+        match parts.next() {
+            Some(version) => candidates
+                .iter()
+                .find(|(_, candidate)| *candidate == version)
+                .map(|(index, _)| *index),
+            None => candidates.first().map(|(index, _)| *index),
+        }
+    };
+
+    Ok(lock
+        .package
+        .iter()
+        .map(|package| LockedPackage {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            local: package.source.is_none(),
+            dependencies: package
+                .dependencies
+                .iter()
+                .filter_map(|reference| resolve(reference))
+                .collect(),
+            scope: None,
+        })
+        .collect())
+}
+
+// ============================================================================
+// NPM
+// ============================================================================
+
+/// Секции зависимостей `package.json`
+const NPM_SECTIONS: &[(&str, DependencyScope)] = &[
+    ("dependencies", DependencyScope::Runtime),
+    ("peerDependencies", DependencyScope::Runtime),
+    ("optionalDependencies", DependencyScope::Optional),
+    ("devDependencies", DependencyScope::Development),
+];
+
+/// Требования, указывающие на код вне реестра
+const NPM_LOCAL_PREFIXES: &[&str] = &["file:", "link:", "workspace:", "portal:"];
+
+fn parse_npm_manifest(content: &[u8]) -> Result<Vec<DeclaredDependency>, String> {
+    let manifest: Value =
+        serde_json::from_slice(content).map_err(|e| format!("Invalid package.json: {}", e))?;
+
+    let mut declared = Vec::new();
+    for (section, scope) in NPM_SECTIONS {
+        let Some(dependencies) = manifest.get(*section).and_then(|value| value.as_object()) else {
+            continue;
+        };
+        for (name, requirement) in dependencies {
+            let requirement = requirement.as_str().map(str::to_string);
+            if requirement.as_deref().is_some_and(|requirement| {
+                NPM_LOCAL_PREFIXES
+                    .iter()
+                    .any(|prefix| requirement.starts_with(prefix))
+            }) {
+                continue;
+            }
+            declared.push(DeclaredDependency {
+                name: name.clone(),
+                requirement,
+                scope: *scope,
+            });
+        }
+    }
+
+    Ok(declared)
+}
+
+/// Разбирает `package-lock.json` версий 1–3
+///
+/// Пакеты адресуются путём установки (`node_modules/a/node_modules/b`), и
+/// зависимость ищется так же, как её находит Node.js: от вложенных
+/// `node_modules` к корневому.
+fn parse_npm_lock(
+    content: &[u8],
+    root_dependencies: &[String],
+) -> Result<Vec<LockedPackage>, String> {
+    let lock: Value =
+        serde_json::from_slice(content).map_err(|e| format!("Invalid package-lock.json: {}", e))?;
+
+    // (путь установки, запись, имена зависимостей)
+    let mut entries: Vec<(String, &Map<String, Value>, Vec<String>)> = Vec::new();
+    if let Some(packages) = lock.get("packages").and_then(|value| value.as_object()) {
+        for (key, entry) in packages {
+            let Some(entry) = entry.as_object() else {
+                continue;
+            };
+            let mut sections = vec!["dependencies", "optionalDependencies", "peerDependencies"];
+            if !key.contains("node_modules/") {
+                sections.push("devDependencies");
+            }
+            entries.push((key.clone(), entry, object_keys(entry, &sections)));
+        }
+    } else {
+        let root = lock.as_object().ok_or("Invalid package-lock.json")?;
+        entries.push((String::new(), root, root_dependencies.to_vec()));
+        if let Some(dependencies) = lock.get("dependencies").and_then(|value| value.as_object()) {
+            flatten_npm_v1("", dependencies, &mut entries);
+        }
+    }
+
+    let mut packages = Vec::new();
+    let mut index_by_key: HashMap<String, usize> = HashMap::new();
+    let mut links = Vec::new();
+    for (key, entry, _) in &entries {
+        if let Some(target) = entry
+            .get("link")
+            .and_then(|value| value.as_bool())
+            .filter(|link| *link)
+            .and(entry.get("resolved").and_then(|value| value.as_str()))
+        {
+            links.push((key.clone(), target.to_string()));
+            packages.push(None);
+            continue;
+        }
+
+        let local = !key.contains("node_modules/");
+        let name = match key.rfind("node_modules/") {
+            Some(position) => key[position + "node_modules/".len()..].to_string(),
+            None => entry
+                .get("name")
+                .and_then(|value| value.as_str())
+                .unwrap_or(key)
+                .to_string(),
+        };
+        let flag = |name: &str| entry.get(name).and_then(|value| value.as_bool()) == Some(true);
+        let scope = if flag("dev") || flag("devOptional") {
+            Some(DependencyScope::Development)
+        } else if flag("optional") {
+            Some(DependencyScope::Optional)
+        } else {
+            None
+        };
+
+        index_by_key.insert(key.clone(), packages.len());
+        packages.push(Some(LockedPackage {
+            name,
+            version: entry
+                .get("version")
+                .and_then(|value| value.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            local,
+            dependencies: Vec::new(),
+            scope,
+        }));
+    }
+
+    // Ссылки workspace указывают на локальные пакеты
+    for (key, target) in links {
+        if let Some(&index) = index_by_key.get(&target) {
+            index_by_key.insert(key, index);
+        }
+    }
+
+    for (index, (key, _, names)) in entries.iter().enumerate() {
+        let Some(package) = packages[index].as_mut() else {
+            continue;
+        };
+        package.dependencies = names
+            .iter()
+            .filter_map(|name| resolve_npm_dependency(&index_by_key, key, name))
+            .collect();
+    }
+
+    // Индексы пересчитываются без записей-ссылок
+    let mut remap = vec![None; packages.len()];
+    let mut next = 0;
+    for (index, package) in packages.iter().enumerate() {
+        if package.is_some() {
+            remap[index] = Some(next);
+            next += 1;
+        }
+    }
+    let mut packages: Vec<LockedPackage> = packages.into_iter().flatten().collect();
+    for package in &mut packages {
+        package.dependencies = package
+            .dependencies
+            .iter()
+            .filter_map(|&index| remap[index])
+            .collect();
+        package.dependencies.sort_unstable();
+        package.dependencies.dedup();
+    }
+
+    Ok(packages)
+}
+
+/// Переводит вложенные `dependencies` lock-файла v1 в пути установки
+fn flatten_npm_v1<'a>(
+    prefix: &str,
+    dependencies: &'a Map<String, Value>,
+    entries: &mut Vec<(String, &'a Map<String, Value>, Vec<String>)>,
+) {
+    for (name, entry) in dependencies {
+        let Some(entry) = entry.as_object() else {
+            continue;
+        };
+        let key = if prefix.is_empty() {
+            format!("node_modules/{}", name)
+        } else {
+            format!("{}/node_modules/{}", prefix, name)
+        };
+        entries.push((key.clone(), entry, object_keys(entry, &["requires"])));
+        if let Some(nested) = entry
+            .get("dependencies")
+            .and_then(|value| value.as_object())
+        {
+            flatten_npm_v1(&key, nested, entries);
+        }
+    }
+}
+
+fn resolve_npm_dependency(
+    index_by_key: &HashMap<String, usize>,
+    from: &str,
+    name: &str,
+) -> Option<usize> {
+    let mut base = from;
+    loop {
+        let candidate = if base.is_empty() {
+            format!("node_modules/{}", name)
+        } else {
+            format!("{}/node_modules/{}", base, name)
+        };
+        if let Some(&index) = index_by_key.get(&candidate) {
+            return Some(index);
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = match base.rfind("/node_modules/") {
+            Some(position) => &base[..position],
+            None => "",
+        };
+    }
+}
+
+fn object_keys(entry: &Map<String, Value>, sections: &[&str]) -> Vec<String> {
+    sections
+        .iter()
+        .filter_map(|section| entry.get(*section).and_then(|value| value.as_object()))
+        .flat_map(|map| map.keys().cloned())
+        .collect()
+}
+
+// ============================================================================
+// PIP
+// ============================================================================
+
+fn parse_requirements(content: &[u8], scope: DependencyScope) -> Vec<DeclaredDependency> {
+    String::from_utf8_lossy(content)
+        .lines()
+        .filter_map(|line| {
+            let line = line.split(" #").next().unwrap_or_default();
+            // Маркеры окружения (`; python_version < "3.8"`) не влияют на пакет
+            let line = line.split(';').next().unwrap_or_default().trim();
+            // Опции pip, включения других файлов, пути и URL
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with('-')
+                || line.starts_with('.')
+                || line.starts_with('/')
+                || line.contains("://")
+            {
+                return None;
+            }
+
+            let name_end = line
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+                .unwrap_or(line.len());
+            let name = normalize_python_name(&line[..name_end]);
+            if name.is_empty() {
+                return None;
+            }
+
+            // Дополнения `package[extra]` не меняют пакет
+            let rest = line[name_end..].trim_start();
+            let rest = match rest.strip_prefix('[') {
+                Some(extras) => extras.split_once(']').map(|(_, rest)| rest).unwrap_or(""),
+                None => rest,
+            };
+            let requirement = rest.replace(' ', "");
+
+            Some(DeclaredDependency {
+                name,
+                requirement: (!requirement.is_empty()).then_some(requirement),
+                scope,
+            })
+        })
+        .collect()
+}
+
+/// Нормализованное имя пакета PyPI (PEP 503)
+fn normalize_python_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+fn file_kind(path: &str) -> Option<FileKind> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    match file_name {
+        "Cargo.toml" => Some(FileKind::CargoManifest),
+        "Cargo.lock" => Some(FileKind::CargoLock),
+        "package.json" => Some(FileKind::NpmManifest),
+        "package-lock.json" => Some(FileKind::NpmLock),
+        _ if file_name.starts_with("requirements") && file_name.ends_with(".txt") => {
+            Some(FileKind::PipRequirements)
+        }
+        _ => None,
+    }
+}
+
+fn files_of_kind(
+    files: &[(String, Vec<u8>)],
+    kind: FileKind,
+) -> impl Iterator<Item = (&str, &[u8])> {
+    files
+        .iter()
+        .filter(move |(path, _)| file_kind(path) == Some(kind))
+        .map(|(path, content)| (path.as_str(), content.as_slice()))
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+/// Лежит ли файл в директории `dir` или глубже
+fn is_within(dir: &str, path: &str) -> bool {
+    dir.is_empty()
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn declared_scope(
+    declared: &[&(String, Vec<DeclaredDependency>)],
+    name: &str,
+) -> Option<DependencyScope> {
+    declared
+        .iter()
+        .flat_map(|(_, dependencies)| dependencies.iter())
+        .filter(|dep| dep.name == name)
+        .map(|dep| dep.scope)
+        .min()
+}
+
+/// Точная версия из требования, если оно фиксирует одну версию
+fn exact_version(ecosystem: Ecosystem, requirement: &str) -> Option<String> {
+    let version = match ecosystem {
+        Ecosystem::Pypi => requirement
+            .strip_prefix("===")
+            .or_else(|| requirement.strip_prefix("=="))?,
+        Ecosystem::Cargo => requirement.strip_prefix('=')?.trim(),
+        Ecosystem::Npm => requirement,
+    };
+    let is_exact = !version.is_empty()
+        && version.starts_with(|c: char| c.is_ascii_digit())
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'));
+    is_exact.then(|| version.to_string())
+}
+
+/// Package URL пакета
+pub fn purl(ecosystem: Ecosystem, name: &str, version: Option<&str>) -> String {
+    let (kind, name) = match ecosystem {
+        Ecosystem::Cargo => ("cargo", name.to_string()),
+        Ecosystem::Npm => ("npm", name.replacen('@', "%40", 1)),
+        Ecosystem::Pypi => ("pypi", name.to_string()),
+    };
+    match version {
+        Some(version) => format!("pkg:{}/{}@{}", kind, name, version),
+        None => format!("pkg:{}/{}", kind, name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> (String, Vec<u8>) {
+        (path.to_string(), content.as_bytes().to_vec())
+    }
+
+    fn find<'a>(graph: &'a DependencyGraph, purl: &str) -> &'a Dependency {
+        graph
+            .dependencies
+            .iter()
+            .find(|dep| dep.purl == purl)
+            .unwrap_or_else(|| panic!("{} not found", purl))
+    }
+
+    #[test]
+    fn test_cargo_lock_graph() {
+        let graph = build_graph(&[
+            file(
+                "Cargo.toml",
+                "[package]\nname = \"app\"\n[dependencies]\nserde = \"1.0\"\n\
+                 [dev-dependencies]\ntempfile = \"3\"\n",
+            ),
+            file(
+                "Cargo.lock",
+                "version = 3\n\
+                 [[package]]\nname = \"app\"\nversion = \"0.1.0\"\ndependencies = [\"serde\", \"tempfile\"]\n\
+                 [[package]]\nname = \"serde\"\nversion = \"1.0.219\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\ndependencies = [\"serde_derive 1.0.219\"]\n\
+                 [[package]]\nname = \"serde_derive\"\nversion = \"1.0.219\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\
+                 [[package]]\nname = \"tempfile\"\nversion = \"3.10.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+            ),
+        ]);
+
+        assert_eq!(graph.manifests, vec!["Cargo.lock", "Cargo.toml"]);
+        assert_eq!(graph.dependencies.len(), 3);
+
+        let serde = find(&graph, "pkg:cargo/serde@1.0.219");
+        assert!(serde.direct);
+        assert_eq!(serde.requirement.as_deref(), Some("1.0"));
+        assert_eq!(serde.dependencies, vec!["pkg:cargo/serde_derive@1.0.219"]);
+
+        let derive = find(&graph, "pkg:cargo/serde_derive@1.0.219");
+        assert!(!derive.direct);
+        assert_eq!(derive.scope, DependencyScope::Runtime);
+
+        let tempfile = find(&graph, "pkg:cargo/tempfile@3.10.0");
+        assert_eq!(tempfile.scope, DependencyScope::Development);
+    }
+
+    #[test]
+    fn test_npm_lock_resolves_nested_packages() {
+        let graph = build_graph(&[
+            file(
+                "web/package.json",
+                r#"{"dependencies": {"@scope/ui": "^2.0.0"}, "devDependencies": {"jest": "29"}}"#,
+            ),
+            file(
+                "web/package-lock.json",
+                r#"{"lockfileVersion": 3, "packages": {
+                    "": {"dependencies": {"@scope/ui": "^2.0.0"}, "devDependencies": {"jest": "29"}},
+                    "node_modules/@scope/ui": {"version": "2.1.0", "dependencies": {"lodash": "^4"}},
+                    "node_modules/@scope/ui/node_modules/lodash": {"version": "4.17.21"},
+                    "node_modules/lodash": {"version": "3.10.1", "dev": true},
+                    "node_modules/jest": {"version": "29.7.0", "dev": true, "dependencies": {"lodash": "^3"}}
+                }}"#,
+            ),
+        ]);
+
+        let ui = find(&graph, "pkg:npm/%40scope/ui@2.1.0");
+        assert!(ui.direct);
+        assert_eq!(ui.dependencies, vec!["pkg:npm/lodash@4.17.21"]);
+        assert_eq!(
+            find(&graph, "pkg:npm/lodash@4.17.21").scope,
+            DependencyScope::Runtime
+        );
+        assert_eq!(
+            find(&graph, "pkg:npm/lodash@3.10.1").scope,
+            DependencyScope::Development
+        );
+    }
+
+    #[test]
+    fn test_manifest_without_lock() {
+        let graph = build_graph(&[file(
+            "package.json",
+            r#"{"dependencies": {"react": "18.2.0", "local": "file:../local"}}"#,
+        )]);
+        assert_eq!(graph.dependencies.len(), 1);
+        assert_eq!(graph.dependencies[0].purl, "pkg:npm/react@18.2.0");
+    }
+
+    #[test]
+    fn test_requirements() {
+        let graph = build_graph(&[file(
+            "requirements-dev.txt",
+            "# tools\n-r requirements.txt\nDjango_REST.framework[extra] == 3.15.1 ; python_version > \"3.8\"\nrequests>=2.31,<3\n",
+        )]);
+
+        let django = find(&graph, "pkg:pypi/django-rest-framework@3.15.1");
+        assert_eq!(django.scope, DependencyScope::Development);
+        let requests = find(&graph, "pkg:pypi/requests");
+        assert_eq!(requests.requirement.as_deref(), Some(">=2.31,<3"));
+    }
+
+    #[test]
+    fn test_skips_vendored_manifests() {
+        assert!(is_manifest("packages/backend/Cargo.toml"));
+        assert!(!is_manifest("web/node_modules/react/package.json"));
+        assert!(!is_manifest("README.md"));
+    }
+}
@@ -0,0 +1,261 @@
+//! Ленты Atom 1.0 и RSS 2.0 для подписки в читалках
+
+use chrono::{DateTime, Utc};
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Формат ленты
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Atom,
+    Rss,
+}
+
+impl FeedFormat {
+    pub fn parse(value: &str) -> Option<FeedFormat> {
+        match value.to_lowercase().as_str() {
+            "atom" => Some(FeedFormat::Atom),
+            "rss" => Some(FeedFormat::Rss),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+        }
+    }
+}
+
+/// Лента
+#[derive(Debug, Clone)]
+pub struct Feed {
+    /// Адрес самой ленты (он же её идентификатор)
+    pub url: String,
+    pub title: String,
+    /// Страница, которую описывает лента
+    pub link: String,
+    pub description: String,
+    /// Автор по умолчанию для записей без автора
+    pub author: String,
+    /// Записи, начиная с самых новых
+    pub entries: Vec<FeedEntry>,
+}
+
+/// Запись ленты
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    /// Постоянный идентификатор записи
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub updated: DateTime<Utc>,
+    pub author: Option<String>,
+    pub summary: Option<String>,
+}
+
+// ============================================================================
+// ФУНКЦИИ
+// ============================================================================
+
+/// Формирует XML ленты в выбранном формате
+pub fn render(format: FeedFormat, feed: &Feed) -> String {
+    match format {
+        FeedFormat::Atom => atom(feed),
+        FeedFormat::Rss => rss(feed),
+    }
+}
+
+fn atom(feed: &Feed) -> String {
+    // Пустая лента считается обновлённой сейчас
+    let updated = feed
+        .entries
+        .iter()
+        .map(|entry| entry.updated)
+        .max()
+        .unwrap_or_else(Utc::now);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <id>{}</id>\n", escape_xml(&feed.url)));
+    xml.push_str(&format!("  <title>{}</title>\n", escape_xml(&feed.title)));
+    xml.push_str(&format!(
+        "  <subtitle>{}</subtitle>\n",
+        escape_xml(&feed.description)
+    ));
+    xml.push_str(&format!(
+        "  <link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>\n",
+        escape_xml(&feed.url)
+    ));
+    xml.push_str(&format!(
+        "  <link rel=\"alternate\" href=\"{}\"/>\n",
+        escape_xml(&feed.link)
+    ));
+    xml.push_str(&format!("  <updated>{}</updated>\n", updated.to_rfc3339()));
+    xml.push_str(&format!(
+        "  <author><name>{}</name></author>\n",
+        escape_xml(&feed.author)
+    ));
+
+    for entry in &feed.entries {
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <id>{}</id>\n", escape_xml(&entry.id)));
+        xml.push_str(&format!(
+            "    <title>{}</title>\n",
+            escape_xml(&entry.title)
+        ));
+        xml.push_str(&format!(
+            "    <link rel=\"alternate\" href=\"{}\"/>\n",
+            escape_xml(&entry.link)
+        ));
+        xml.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            entry.updated.to_rfc3339()
+        ));
+        if let Some(author) = &entry.author {
+            xml.push_str(&format!(
+                "    <author><name>{}</name></author>\n",
+                escape_xml(author)
+            ));
+        }
+        if let Some(summary) = &entry.summary {
+            xml.push_str(&format!("    <summary>{}</summary>\n", escape_xml(summary)));
+        }
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn rss(feed: &Feed) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str(
+        "<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n",
+    );
+    xml.push_str("  <channel>\n");
+    xml.push_str(&format!("    <title>{}</title>\n", escape_xml(&feed.title)));
+    xml.push_str(&format!("    <link>{}</link>\n", escape_xml(&feed.link)));
+    xml.push_str(&format!(
+        "    <description>{}</description>\n",
+        escape_xml(&feed.description)
+    ));
+    xml.push_str(&format!(
+        "    <atom:link rel=\"self\" type=\"application/rss+xml\" href=\"{}\"/>\n",
+        escape_xml(&feed.url)
+    ));
+    if let Some(latest) = feed.entries.iter().map(|entry| entry.updated).max() {
+        xml.push_str(&format!(
+            "    <lastBuildDate>{}</lastBuildDate>\n",
+            latest.to_rfc2822()
+        ));
+    }
+
+    for entry in &feed.entries {
+        xml.push_str("    <item>\n");
+        xml.push_str(&format!(
+            "      <title>{}</title>\n",
+            escape_xml(&entry.title)
+        ));
+        xml.push_str(&format!("      <link>{}</link>\n", escape_xml(&entry.link)));
+        xml.push_str(&format!(
+            "      <guid isPermaLink=\"false\">{}</guid>\n",
+            escape_xml(&entry.id)
+        ));
+        xml.push_str(&format!(
+            "      <pubDate>{}</pubDate>\n",
+            entry.updated.to_rfc2822()
+        ));
+        // <author> в RSS — адрес почты, поэтому имя передаётся через Dublin Core
+        xml.push_str(&format!(
+            "      <dc:creator>{}</dc:creator>\n",
+            escape_xml(entry.author.as_deref().unwrap_or(&feed.author))
+        ));
+        if let Some(summary) = &entry.summary {
+            xml.push_str(&format!(
+                "      <description>{}</description>\n",
+                escape_xml(summary)
+            ));
+        }
+        xml.push_str("    </item>\n");
+    }
+
+    xml.push_str("  </channel>\n</rss>\n");
+    xml
+}
+
+/// Экранирует текст и убирает символы, запрещённые в XML 1.0
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample() -> Feed {
+        Feed {
+            url: "https://example.com/feed?format=atom&x=1".to_string(),
+            title: "alice/demo/hello: commits".to_string(),
+            link: "https://example.com/hello".to_string(),
+            description: "Recent commits".to_string(),
+            author: "alice".to_string(),
+            entries: vec![
+                FeedEntry {
+                    id: "commit-2".to_string(),
+                    title: "Fix <script> & \u{1}escape".to_string(),
+                    link: "https://example.com/c/2".to_string(),
+                    updated: Utc.with_ymd_and_hms(2024, 5, 2, 10, 0, 0).unwrap(),
+                    author: Some("bob".to_string()),
+                    summary: None,
+                },
+                FeedEntry {
+                    id: "commit-1".to_string(),
+                    title: "Initial".to_string(),
+                    link: "https://example.com/c/1".to_string(),
+                    updated: Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap(),
+                    author: None,
+                    summary: Some("first".to_string()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_atom_feed() {
+        let xml = render(FeedFormat::Atom, &sample());
+        assert!(xml.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+        assert!(xml.contains("<updated>2024-05-02T10:00:00+00:00</updated>"));
+        assert!(xml.contains("href=\"https://example.com/feed?format=atom&amp;x=1\""));
+        assert!(xml.contains("<title>Fix &lt;script&gt; &amp; escape</title>"));
+        assert_eq!(xml.matches("<entry>").count(), 2);
+    }
+
+    #[test]
+    fn test_rss_feed() {
+        let xml = render(FeedFormat::Rss, &sample());
+        assert!(xml.contains("<rss version=\"2.0\""));
+        assert!(xml.contains("<pubDate>Thu, 2 May 2024 10:00:00 +0000</pubDate>"));
+        // Запись без автора получает автора ленты
+        assert!(xml.contains("<dc:creator>alice</dc:creator>"));
+        assert!(xml.contains("<description>first</description>"));
+    }
+}
@@ -1,8 +1,16 @@
 //! Утилиты для работы с Git
 
-use log::{debug, error};
+use crate::core::config::load_config;
+use log::{debug, error, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::sync::LazyLock;
+
+/// Корневая директория с bare-репозиториями (REPOSITORIES_PATH)
+static REPOSITORIES_PATH: LazyLock<String> = LazyLock::new(|| load_config().repositories_path);
 
 // ============================================================================
 // СТРУКТУРЫ ДАННЫХ
@@ -30,6 +38,11 @@ pub struct GitCommandResult {
 // ОСНОВНЫЕ ФУНКЦИИ
 // ============================================================================
 
+/// Возвращает путь к bare-репозиторию на диске по имени репозитория
+pub fn repository_path(repo_name: &str) -> String {
+    format!("{}/{}.git", REPOSITORIES_PATH.as_str(), repo_name)
+}
+
 /// Создает новый bare Git репозиторий
 pub fn create_bare_repository(repo_path: &str) -> Result<(), String> {
     debug!("Creating bare repository at: {}", repo_path);
@@ -52,56 +65,225 @@ pub fn create_bare_repository(repo_path: &str) -> Result<(), String> {
 /// Получает список веток репозитория
 pub fn get_repository_branches(repo_path: &str) -> Result<Vec<String>, String> {
     debug!("Getting branches for repository: {}", repo_path);
+    open_reader(repo_path).branches()
+}
+
+/// Получает список файлов в определенной ветке
+pub fn get_repository_files(repo_path: &str, branch: &str) -> Result<Vec<GitFile>, String> {
+    debug!(
+        "Getting files for repository: {} branch: {}",
+        repo_path, branch
+    );
+    open_reader(repo_path).list_tree(branch, false)
+}
+
+/// Получает рекурсивный список файлов ревизии (имена содержат полный путь)
+pub fn get_repository_tree(repo_path: &str, revision: &str) -> Result<Vec<GitFile>, String> {
+    debug!(
+        "Getting recursive tree for repository: {} revision: {}",
+        repo_path, revision
+    );
+    open_reader(repo_path).list_tree(revision, true)
+}
 
+/// Определяет ветку по умолчанию и SHA её последнего коммита
+pub fn resolve_default_head(repo_path: &str) -> Option<(String, String)> {
+    open_reader(repo_path).default_head()
+}
+
+/// Разрешает ревизию (ветку, тег, SHA) в SHA коммита
+pub fn resolve_revision(repo_path: &str, revision: &str) -> Option<String> {
+    open_reader(repo_path).resolve_revision(revision)
+}
+
+/// Читает содержимое объектов заданного типа (`blob`, `commit`, `tree`, `tag`)
+///
+/// Объекты другого типа, как и отсутствующие, представлены как `None`.
+pub fn read_objects(
+    repo_path: &str,
+    hashes: &[String],
+    object_type: &str,
+) -> Result<Vec<Option<Vec<u8>>>, String> {
+    if hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+    open_reader(repo_path).read_objects(hashes, object_type)
+}
+
+/// Читает файл ревизии по пути от корня репозитория
+///
+/// Возвращает `None`, если ревизии или файла нет.
+pub fn read_file(repo_path: &str, revision: &str, path: &str) -> Result<Option<Vec<u8>>, String> {
+    open_reader(repo_path).read_file(revision, path)
+}
+
+/// Получает историю коммитов, начиная с ревизии (новые коммиты первыми)
+///
+/// `limit = None` возвращает всю историю.
+pub fn get_commits(
+    repo_path: &str,
+    revision: &str,
+    limit: Option<usize>,
+    skip: usize,
+) -> Result<Vec<GitCommit>, String> {
+    debug!(
+        "Getting commits for repository: {} revision: {}",
+        repo_path, revision
+    );
+    open_reader(repo_path).commits(revision, limit, skip)
+}
+
+/// История коммитов ревизии, изменявших файл или директорию `path`
+pub fn get_path_commits(
+    repo_path: &str,
+    revision: &str,
+    path: &str,
+    limit: usize,
+    skip: usize,
+) -> Result<Vec<GitCommit>, String> {
     let output = Command::new("git")
-        .args(&[
-            "--git-dir",
-            repo_path,
-            "branch",
-            "--format=%(refname:short)",
-        ])
+        .args(["--git-dir", repo_path, "log"])
+        .arg(format!("--format={}", COMMIT_LOG_FORMAT))
+        .arg(format!("--skip={}", skip))
+        .arg(format!("--max-count={}", limit))
+        .args([revision, "--", path])
         .output()
         .map_err(|e| format!("Failed to execute git command: {}", e))?;
 
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let branches: Vec<String> = stdout
-            .lines()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        debug!("Found {} branches", branches.len());
-        Ok(branches)
+        Ok(parse_git_log_output(&stdout))
     } else {
         let error_msg = String::from_utf8_lossy(&output.stderr);
-        error!("Failed to get branches: {}", error_msg);
-        Err(format!("Git branch command failed: {}", error_msg))
+        error!("Failed to get path commits: {}", error_msg);
+        Err(format!("Git log command failed: {}", error_msg))
     }
 }
 
-/// Получает список файлов в определенной ветке
-pub fn get_repository_files(repo_path: &str, branch: &str) -> Result<Vec<GitFile>, String> {
-    debug!(
-        "Getting files for repository: {} branch: {}",
-        repo_path, branch
-    );
-
+/// Коммиты, достижимые из нескольких ревизий, в топологическом порядке
+///
+/// Потомки идут раньше предков, как в `git log --graph`.
+pub fn get_topo_commits(
+    repo_path: &str,
+    revisions: &[String],
+    limit: usize,
+) -> Result<Vec<GitCommit>, String> {
     let output = Command::new("git")
-        .args(&["--git-dir", repo_path, "ls-tree", "-l", branch])
+        .args(["--git-dir", repo_path, "log", "--topo-order"])
+        .arg(format!("--format={}", COMMIT_LOG_FORMAT))
+        .arg(format!("--max-count={}", limit))
+        .args(revisions)
+        .arg("--")
         .output()
         .map_err(|e| format!("Failed to execute git command: {}", e))?;
 
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let files = parse_git_ls_tree_output(&stdout);
+        Ok(parse_git_log_output(&stdout))
+    } else {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        error!("Failed to get topo-ordered commits: {}", error_msg);
+        Err(format!("Git log command failed: {}", error_msg))
+    }
+}
+
+/// Ветки и теги с коммитами, на которые они указывают
+///
+/// Аннотированные теги разыменовываются до коммита.
+pub fn list_ref_targets(repo_path: &str) -> Result<Vec<(String, String)>, String> {
+    let output = Command::new("git")
+        .args([
+            "--git-dir",
+            repo_path,
+            "for-each-ref",
+            "--format=%(refname) %(objectname) %(*objectname)",
+            "refs/heads",
+            "refs/tags",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git for-each-ref failed: {}", error_msg.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let object = parts.next()?;
+            let target = parts.next().unwrap_or(object);
+            Some((name.to_string(), target.to_string()))
+        })
+        .collect())
+}
+
+/// Теги репозитория, начиная с самых новых
+///
+/// Дата аннотированного тега — дата его создания, легковесного — дата коммита.
+pub fn list_tags(repo_path: &str) -> Result<Vec<GitTag>, String> {
+    let output = Command::new("git")
+        .args([
+            "--git-dir",
+            repo_path,
+            "for-each-ref",
+            "--sort=-creatordate",
+            "--format=%(refname:short)%00%(objectname)%00%(*objectname)%00%(creatordate:unix)%00%(contents:subject)",
+            "refs/tags",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git for-each-ref failed: {}", error_msg.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('\0').collect();
+            let [name, object, peeled, created_at, subject] = parts[..] else {
+                return None;
+            };
+            Some(GitTag {
+                name: name.to_string(),
+                target_sha: if peeled.is_empty() { object } else { peeled }.to_string(),
+                created_at: created_at.parse().unwrap_or_default(),
+                subject: subject.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Проверяет, что имя допустимо для ссылки `refs/{namespace}/{name}`
+pub fn is_valid_ref_name(namespace: &str, name: &str) -> bool {
+    Command::new("git")
+        .args(["check-ref-format", &format!("refs/{}/{}", namespace, name)])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Создает легковесный тег, указывающий на коммит
+///
+/// Имя передаётся после `--`, чтобы его нельзя было принять за опцию.
+pub fn create_tag(repo_path: &str, tag_name: &str, target_sha: &str) -> Result<(), String> {
+    debug!("Creating tag {} at {} in {}", tag_name, target_sha, repo_path);
+
+    let output = Command::new("git")
+        .args(["--git-dir", repo_path, "tag", "--", tag_name, target_sha])
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
 
-        debug!("Found {} files", files.len());
-        Ok(files)
+    if output.status.success() {
+        Ok(())
     } else {
         let error_msg = String::from_utf8_lossy(&output.stderr);
-        error!("Failed to get files: {}", error_msg);
-        Err(format!("Git ls-tree command failed: {}", error_msg))
+        error!("Failed to create tag: {}", error_msg);
+        Err(format!("Git tag command failed: {}", error_msg))
     }
 }
 
@@ -193,63 +375,2061 @@ pub fn execute_git_command(args: &[&str], working_dir: Option<&str>) -> GitComma
 }
 
 // ============================================================================
-// ВСПОМОГАТЕЛЬНЫЕ СТРУКТУРЫ И ФУНКЦИИ
+// ОБСЛУЖИВАНИЕ
 // ============================================================================
 
-/// Информация о файле в Git репозитории
+/// Считает loose-объекты и pack-файлы репозитория (`git count-objects -v`)
+pub fn count_objects(repo_path: &str) -> Result<ObjectCounts, String> {
+    let output = Command::new("git")
+        .args(["--git-dir", repo_path, "count-objects", "-v"])
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git count-objects command failed: {}", error_msg));
+    }
+
+    // Формат вывода: строки "count: 12", "packs: 1", ...
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let field = |name: &str| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(0)
+    };
+
+    Ok(ObjectCounts {
+        loose_objects: field("count"),
+        packs: field("packs"),
+    })
+}
+
+/// Выполняет обслуживание репозитория: gc (repack, prune, pack-refs) и запись commit-graph
+///
+/// Упаковка идёт в один поток, чтобы не отнимать CPU у push и fetch.
+pub fn run_maintenance(repo_path: &str) -> Result<(), String> {
+    let steps: [&[&str]; 2] = [
+        &["-c", "pack.threads=1", "gc", "--quiet"],
+        &["commit-graph", "write", "--reachable"],
+    ];
+
+    for step in steps {
+        debug!("Running git {:?} in {}", step, repo_path);
+        let output = Command::new("git")
+            .args(["--git-dir", repo_path])
+            .args(step)
+            .output()
+            .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+        if !output.status.success() {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            return Err(format!(
+                "git {} failed: {}",
+                step.join(" "),
+                error_msg.trim()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// PUSH И РАБОЧИЕ КОПИИ
+// ============================================================================
+
+/// Нулевой SHA: в командах push означает создание или удаление ссылки
+pub const ZERO_SHA: &str = "0000000000000000000000000000000000000000";
+
+/// Извлекает команды обновления ссылок из тела запроса `git-receive-pack`
+///
+/// Команды передаются pkt-line строками `<old> <new> <ref>` до flush-pkt,
+/// после которого идёт packfile.
+pub fn parse_push_commands(body: &[u8]) -> Vec<RefUpdate> {
+    split_push_request(body)
+        .0
+        .iter()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.trim_end().split(' ').collect();
+            match parts[..] {
+                [old_sha, new_sha, ref_name] if old_sha.len() == 40 && new_sha.len() == 40 => {
+                    Some(RefUpdate {
+                        new_sha: new_sha.to_string(),
+                        ref_name: ref_name.to_string(),
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Проверяет, запросил ли клиент при push capability `name`
+pub fn push_has_capability(body: &[u8], name: &str) -> bool {
+    let Some(len) = pkt_line_length(body, 0) else {
+        return false;
+    };
+    let line = &body[4..len];
+    line.iter()
+        .position(|b| *b == 0)
+        .map(|nul| String::from_utf8_lossy(&line[nul + 1..]).into_owned())
+        .is_some_and(|caps| caps.split_whitespace().any(|cap| cap == name))
+}
+
+/// Оставляет из `updates` ссылки, которые receive-pack действительно обновил
+///
+/// Итог берётся из report-status ответа `response` (с side-band — из канала 1);
+/// если клиент report-status не запросил, ссылки перечитываются из репозитория.
+pub fn applied_updates(
+    repo_path: &str,
+    updates: Vec<RefUpdate>,
+    response: &[u8],
+) -> Vec<RefUpdate> {
+    match report_status_refs(response) {
+        Some(accepted) => updates
+            .into_iter()
+            .filter(|update| accepted.contains(&update.ref_name))
+            .collect(),
+        None => updates
+            .into_iter()
+            .filter(|update| {
+                let current = Command::new("git")
+                    .args(["--git-dir", repo_path, "rev-parse", "--verify", "-q"])
+                    .arg(&update.ref_name)
+                    .output()
+                    .ok()
+                    .filter(|output| output.status.success())
+                    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+                current.as_deref().unwrap_or(ZERO_SHA) == update.new_sha
+            })
+            .collect(),
+    }
+}
+
+/// Ссылки со статусом `ok` из report-status; `None`, если отчёта в ответе нет
+fn report_status_refs(response: &[u8]) -> Option<Vec<String>> {
+    let payloads = pkt_line_payloads(response);
+    // С side-band отчёт передаётся pkt-line строками внутри канала 1
+    let sideband = payloads
+        .first()
+        .is_some_and(|line| matches!(line.first(), Some(1..=3)));
+    let demuxed: Vec<u8> = payloads
+        .iter()
+        .filter(|line| sideband && line.first() == Some(&1))
+        .flat_map(|line| line[1..].to_vec())
+        .collect();
+    let lines = if sideband {
+        pkt_line_payloads(&demuxed)
+    } else {
+        payloads
+    };
+
+    let mut lines = lines.iter().map(|line| String::from_utf8_lossy(line));
+    if !lines.next()?.starts_with("unpack ") {
+        return None;
+    }
+    Some(
+        lines
+            .filter_map(|line| line.trim_end().strip_prefix("ok ").map(str::to_string))
+            .collect(),
+    )
+}
+
+/// Данные pkt-line строк подряд, без flush-pkt
+fn pkt_line_payloads(data: &[u8]) -> Vec<&[u8]> {
+    let mut payloads = Vec::new();
+    let mut pos = 0;
+    while let Some(len) = pkt_line_length(data, pos) {
+        if len > 4 {
+            payloads.push(&data[pos + 4..pos + len]);
+        }
+        pos += len.max(4);
+    }
+    payloads
+}
+
+/// Передача объектов, которой завершился запрос upload-pack
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UploadPackTransfer {
+    /// Клиент не сообщил ни одного имеющегося объекта
+    Clone,
+    Fetch,
+}
+
+/// Определяет, отправил ли upload-pack packfile в ответ на запрос
+///
+/// В stateless-rpc согласование может занять несколько запросов, и только
+/// последний из них получает packfile; промежуточные раунды дают `None`.
+/// Запрос без строк `have` означает клон.
+pub fn upload_pack_transfer(request: &[u8], response: &[u8]) -> Option<UploadPackTransfer> {
+    let mut pos = 0;
+    let mut sends_pack = false;
+    while let Some(len) = pkt_line_length(response, pos) {
+        if len <= 4 {
+            pos += 4;
+            continue;
+        }
+        // С side-band данные packfile идут в канале 1
+        if response[pos + 4] == 1 {
+            sends_pack = true;
+            break;
+        }
+        pos += len;
+    }
+    // Без side-band packfile следует сразу за строками ACK/NAK
+    if !sends_pack && !response[pos.min(response.len())..].starts_with(b"PACK") {
+        return None;
+    }
+
+    let mut pos = 0;
+    while let Some(len) = pkt_line_length(request, pos) {
+        if len > 4 && request[pos + 4..pos + len].starts_with(b"have ") {
+            return Some(UploadPackTransfer::Fetch);
+        }
+        pos += len.max(4);
+    }
+    Some(UploadPackTransfer::Clone)
+}
+
+/// Разбирает тело push на строки команд (без capabilities) и packfile
+fn split_push_request(body: &[u8]) -> (Vec<String>, &[u8]) {
+    let mut lines = Vec::new();
+    let mut pos = 0;
+
+    while let Some(len) = pkt_line_length(body, pos) {
+        // flush-pkt завершает список команд
+        if len < 4 {
+            return (lines, &body[pos + 4..]);
+        }
+
+        // В первой строке после NUL перечислены capabilities
+        let line = &body[pos + 4..pos + len];
+        let line = line.split(|b| *b == 0).next().unwrap_or_default();
+        lines.push(String::from_utf8_lossy(line).into_owned());
+        pos += len;
+    }
+
+    (lines, &[])
+}
+
+/// Длина pkt-line, начинающейся с `pos` (0 — flush-pkt)
+fn pkt_line_length(body: &[u8], pos: usize) -> Option<usize> {
+    let len = std::str::from_utf8(body.get(pos..pos + 4)?)
+        .ok()
+        .and_then(|hex| usize::from_str_radix(hex, 16).ok())?;
+    (len < 4 || pos + len <= body.len()).then_some(len)
+}
+
+/// Формирует pkt-line с данными
+pub fn pkt_line(data: &[u8]) -> Vec<u8> {
+    let mut line = format!("{:04x}", data.len() + 4).into_bytes();
+    line.extend_from_slice(data);
+    line
+}
+
+/// Новый блоб из push
 #[derive(Debug, Clone)]
-pub struct GitFile {
-    pub name: String,
-    pub file_type: String, // "blob" (файл), "tree" (директория)
-    pub hash: String,
-    pub size: Option<u64>,
+pub struct PushedBlob {
+    pub sha: String,
+    /// Путь, под которым блоб впервые встретился
+    pub path: String,
+    /// Ссылка и её новая вершина, через которую блоб попал в репозиторий
+    pub ref_name: String,
+    pub commit_sha: String,
+    pub content: Vec<u8>,
 }
 
-/// Парсит вывод команды git ls-tree
-fn parse_git_ls_tree_output(output: &str) -> Vec<GitFile> {
-    output
+/// Блобы, которых ещё нет в репозитории, из packfile тела push
+///
+/// Packfile распаковывается во временный репозиторий, объекты основного
+/// репозитория подключаются через `objects/info/alternates`, поэтому сам
+/// репозиторий не меняется до `git receive-pack`. Блобы больше `max_size`
+/// пропускаются.
+pub fn pushed_blobs(
+    repo_path: &str,
+    body: &[u8],
+    updates: &[RefUpdate],
+    max_size: usize,
+) -> Result<Vec<PushedBlob>, String> {
+    let (_, pack) = split_push_request(body);
+    let updates: Vec<&RefUpdate> = updates.iter().filter(|u| u.new_sha != ZERO_SHA).collect();
+    if pack.is_empty() || updates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let quarantine = quarantine_pack(repo_path, pack)?;
+    let quarantine_path = quarantine.path().to_string_lossy().into_owned();
+
+    // Объекты, достижимые из существующих ссылок, уже проверены
+    let known_tips = run_with_input(repo_path, &["for-each-ref", "--format=%(objectname)"], &[])?;
+    let known_tips: Vec<String> = String::from_utf8_lossy(&known_tips)
         .lines()
-        .filter_map(|line| {
-            let mut parts = line.split_whitespace();
-            let _mode = parts.next()?;
-            let file_type = parts.next()?.to_string();
-            let hash = parts.next()?.to_string();
-            let size_str = parts.next()?;
-            let name = parts.collect::<Vec<&str>>().join(" ");
+        .map(|sha| format!("^{}", sha))
+        .collect();
 
-            let size = if file_type == "blob" {
-                size_str.parse().ok()
-            } else {
-                None
+    let mut seen = std::collections::HashSet::new();
+    let mut candidates = Vec::new();
+    for update in updates {
+        let mut input = format!("{}\n", update.new_sha);
+        for tip in &known_tips {
+            input.push_str(tip);
+            input.push('\n');
+        }
+        let listing = run_with_input(
+            &quarantine_path,
+            &["rev-list", "--objects", "--stdin"],
+            input.as_bytes(),
+        )?;
+
+        for line in String::from_utf8_lossy(&listing).lines() {
+            // У коммитов пути нет, у деревьев и блобов — есть
+            let Some((sha, path)) = line.split_once(' ') else {
+                continue;
             };
+            if !path.is_empty() && seen.insert(sha.to_string()) {
+                candidates.push((sha.to_string(), path.to_string(), update));
+            }
+        }
+    }
 
-            Some(GitFile {
-                name,
-                file_type,
-                hash,
-                size,
+    let check_input: String = candidates
+        .iter()
+        .map(|(sha, _, _)| format!("{}\n", sha))
+        .collect();
+    let sizes = run_with_input(
+        &quarantine_path,
+        &["cat-file", "--batch-check=%(objecttype) %(objectsize)"],
+        check_input.as_bytes(),
+    )?;
+    let candidates: Vec<_> = candidates
+        .into_iter()
+        .zip(String::from_utf8_lossy(&sizes).lines())
+        .filter(|(_, info)| {
+            info.split_once(' ').is_some_and(|(kind, size)| {
+                kind == "blob" && size.parse::<usize>().is_ok_and(|size| size <= max_size)
             })
         })
-        .collect()
+        .map(|(candidate, _)| candidate)
+        .collect();
+
+    let hashes: Vec<String> = candidates.iter().map(|(sha, _, _)| sha.clone()).collect();
+    let contents = read_objects(&quarantine_path, &hashes, "blob")?;
+
+    Ok(candidates
+        .into_iter()
+        .zip(contents)
+        .filter_map(|((sha, path, update), content)| {
+            Some(PushedBlob {
+                sha,
+                path,
+                ref_name: update.ref_name.clone(),
+                commit_sha: update.new_sha.clone(),
+                content: content?,
+            })
+        })
+        .collect())
 }
 
-/// Проверяет доступность Git в системе
-pub fn check_git_availability() -> bool {
-    Command::new("git")
-        .arg("--version")
-        .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
+/// Ссылки из push, новая вершина которых не продолжает прежнюю
+///
+/// Создание и удаление ссылок не считаются. Объекты packfile проверяются во
+/// временном репозитории, как в [`pushed_blobs`].
+pub fn forced_updates(repo_path: &str, body: &[u8]) -> Result<Vec<String>, String> {
+    let (commands, pack) = split_push_request(body);
+    let updates: Vec<(String, String, String)> = commands
+        .iter()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.trim_end().split(' ').collect();
+            match parts[..] {
+                [old_sha, new_sha, ref_name]
+                    if old_sha.len() == 40
+                        && new_sha.len() == 40
+                        && old_sha != ZERO_SHA
+                        && new_sha != ZERO_SHA =>
+                {
+                    Some((
+                        old_sha.to_string(),
+                        new_sha.to_string(),
+                        ref_name.to_string(),
+                    ))
+                }
+                _ => None,
+            }
+        })
+        .collect();
+    if updates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Без packfile новые вершины уже есть в репозитории
+    let quarantine = if pack.is_empty() {
+        None
+    } else {
+        Some(quarantine_pack(repo_path, pack)?)
+    };
+    let check_path = quarantine
+        .as_ref()
+        .map(|dir| dir.path().to_string_lossy().into_owned())
+        .unwrap_or_else(|| repo_path.to_string());
+
+    let mut forced = Vec::new();
+    for (old_sha, new_sha, ref_name) in updates {
+        let status = Command::new("git")
+            .args(["--git-dir", &check_path, "merge-base", "--is-ancestor"])
+            .args([&old_sha, &new_sha])
+            .status()
+            .map_err(|e| format!("Failed to execute git merge-base: {}", e))?;
+        if !status.success() {
+            forced.push(ref_name);
+        }
+    }
+    Ok(forced)
 }
 
-/// Получает версию Git
-pub fn get_git_version() -> Option<String> {
-    let output = Command::new("git").arg("--version").output().ok()?;
+/// Распаковывает packfile во временный репозиторий, который видит объекты
+/// основного через `objects/info/alternates`
+fn quarantine_pack(repo_path: &str, pack: &[u8]) -> Result<tempfile::TempDir, String> {
+    let objects_dir = std::fs::canonicalize(Path::new(repo_path).join("objects"))
+        .map_err(|e| format!("Failed to resolve objects directory: {}", e))?;
+    let quarantine =
+        tempfile::tempdir().map_err(|e| format!("Failed to create quarantine: {}", e))?;
+    let quarantine_path = quarantine.path().to_string_lossy().into_owned();
+    create_bare_repository(&quarantine_path)?;
+    std::fs::write(
+        quarantine.path().join("objects/info/alternates"),
+        format!("{}\n", objects_dir.display()),
+    )
+    .map_err(|e| format!("Failed to write alternates: {}", e))?;
+
+    run_with_input(
+        &quarantine_path,
+        &["index-pack", "--stdin", "--fix-thin"],
+        pack,
+    )?;
+    Ok(quarantine)
+}
+
+/// Запускает git в репозитории, передавая `input` в stdin
+fn run_with_input(repo_path: &str, args: &[&str], input: &[u8]) -> Result<Vec<u8>, String> {
+    let mut child = Command::new("git")
+        .arg("--git-dir")
+        .arg(repo_path)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute git {}: {}", args[0], e))?;
+
+    // Пишем в отдельном потоке, чтобы не заблокироваться на заполненном stdout
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for git {}: {}", args[0], e))?;
+    writer
+        .join()
+        .map_err(|_| format!("Failed to write to git {}", args[0]))?
+        .map_err(|e| format!("Failed to write to git {}: {}", args[0], e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// Создаёт временную рабочую копию коммита (`git worktree add --detach`)
+pub fn add_worktree(repo_path: &str, dir: &Path, sha: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args([
+            "--git-dir",
+            repo_path,
+            "worktree",
+            "add",
+            "--detach",
+            "--force",
+        ])
+        .arg(dir)
+        .arg(sha)
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
 
     if output.status.success() {
-        let version = String::from_utf8_lossy(&output.stdout);
-        Some(version.trim().to_string())
+        Ok(())
     } else {
-        None
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Git worktree add failed: {}", error_msg.trim()))
+    }
+}
+
+/// Удаляет рабочую копию и её служебные данные в репозитории
+pub fn remove_worktree(repo_path: &str, dir: &Path) {
+    let removed = Command::new("git")
+        .args(["--git-dir", repo_path, "worktree", "remove", "--force"])
+        .arg(dir)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+
+    if !removed {
+        let _ = std::fs::remove_dir_all(dir);
+        let _ = Command::new("git")
+            .args(["--git-dir", repo_path, "worktree", "prune"])
+            .output();
+    }
+}
+
+// ============================================================================
+// СОЗДАНИЕ ИСТОРИИ
+// ============================================================================
+
+/// Все файлы ревизии с режимами (`git ls-tree -r`), включая подмодули
+pub fn list_tree_entries(repo_path: &str, revision: &str) -> Result<Vec<TreeEntry>, String> {
+    let output = Command::new("git")
+        .args(["--git-dir", repo_path, "ls-tree", "-r", "-z", "--full-tree"])
+        .arg(revision)
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git ls-tree failed: {}", error_msg.trim()));
+    }
+
+    // Формат записи: `<mode> <type> <hash>\t<path>\0`
+    let entries = output
+        .stdout
+        .split(|b| *b == 0)
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| {
+            let record = String::from_utf8_lossy(record);
+            let (meta, path) = record.split_once('\t')?;
+            let mut parts = meta.split(' ');
+            let mode = parts.next()?.to_string();
+            let object_type = parts.next()?.to_string();
+            let hash = parts.next()?.to_string();
+            Some(TreeEntry {
+                mode,
+                object_type,
+                hash,
+                path: path.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// Создаёт в репозитории ветку из одного коммита с заданными файлами
+///
+/// Используется `git fast-import`, поэтому объекты пишутся одним процессом.
+/// Для подмодулей (`content = None`) сохраняется ссылка на коммит из `hash`.
+/// HEAD репозитория переключается на созданную ветку.
+pub fn write_initial_commit(
+    repo_path: &str,
+    branch: &str,
+    files: &[(TreeEntry, Option<Vec<u8>>)],
+    author_name: &str,
+    author_email: &str,
+    message: &str,
+) -> Result<(), String> {
+    let mut stream = Vec::new();
+    let timestamp = chrono::Utc::now().timestamp();
+    let identity = format!(
+        "{} <{}> {} +0000",
+        author_name.replace(['<', '>', '\n'], ""),
+        author_email.replace(['<', '>', '\n'], ""),
+        timestamp
+    );
+
+    stream.extend_from_slice(format!("commit refs/heads/{}\n", branch).as_bytes());
+    stream.extend_from_slice(format!("author {}\n", identity).as_bytes());
+    stream.extend_from_slice(format!("committer {}\n", identity).as_bytes());
+    stream.extend_from_slice(format!("data {}\n{}\n", message.len(), message).as_bytes());
+
+    for (entry, content) in files {
+        let path = quote_fast_import_path(&entry.path);
+        match content {
+            Some(content) => {
+                stream.extend_from_slice(
+                    format!("M {} inline {}\ndata {}\n", entry.mode, path, content.len())
+                        .as_bytes(),
+                );
+                stream.extend_from_slice(content);
+                stream.push(b'\n');
+            }
+            None => stream.extend_from_slice(
+                format!("M {} {} {}\n", entry.mode, entry.hash, path).as_bytes(),
+            ),
+        }
+    }
+    stream.extend_from_slice(b"done\n");
+
+    let mut child = Command::new("git")
+        .args([
+            "--git-dir",
+            repo_path,
+            "fast-import",
+            "--quiet",
+            "--date-format=raw",
+            "--done",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(&stream)
+            .map_err(|e| format!("Failed to write to git fast-import: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for git fast-import: {}", e))?;
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git fast-import failed: {}", error_msg.trim()));
+    }
+
+    set_head_branch(repo_path, branch)
+}
+
+/// Переключает HEAD репозитория на ветку; ветке не обязательно существовать
+pub fn set_head_branch(repo_path: &str, branch: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["--git-dir", repo_path, "symbolic-ref", "HEAD"])
+        .arg(format!("refs/heads/{}", branch))
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git symbolic-ref failed: {}", error_msg.trim()));
+    }
+
+    Ok(())
+}
+
+/// Запись дерева по пути (`git ls-tree`); `None`, если пути нет в ревизии
+pub fn tree_entry(
+    repo_path: &str,
+    revision: &str,
+    path: &str,
+) -> Result<Option<TreeEntry>, String> {
+    let output = Command::new("git")
+        .args(["--git-dir", repo_path, "ls-tree", "-z", "--full-tree"])
+        .arg(revision)
+        .arg("--")
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git ls-tree failed: {}", error_msg.trim()));
+    }
+
+    let entry = output
+        .stdout
+        .split(|b| *b == 0)
+        .filter_map(|record| parse_tree_record(&String::from_utf8_lossy(record)))
+        .find(|entry| entry.path == path);
+    Ok(entry)
+}
+
+/// Создаёт коммит с изменениями файлов поверх `parent` (или корневой коммит)
+///
+/// Изменения применяются во временном индексе, рабочая копия не нужна.
+/// Ссылки не обновляются — для этого есть `update_ref`. Возвращает SHA коммита;
+/// файл на месте каталога (или наоборот) даёт [`CommitError::PathConflict`].
+pub fn commit_changes(
+    repo_path: &str,
+    parent: Option<&str>,
+    changes: &[FileChange],
+    author_name: &str,
+    author_email: &str,
+    message: &str,
+) -> Result<String, CommitError> {
+    let index_dir = tempfile::tempdir()
+        .map_err(|e| CommitError::Failed(format!("Failed to create index: {}", e)))?;
+    let index_file = index_dir.path().join("index");
+    let git = |args: &[&str], input: Option<&[u8]>| -> Result<String, CommitError> {
+        let mut child = Command::new("git")
+            .args(["--git-dir", repo_path])
+            .args(args)
+            .env("GIT_INDEX_FILE", &index_file)
+            .env("GIT_AUTHOR_NAME", author_name)
+            .env("GIT_AUTHOR_EMAIL", author_email)
+            .env("GIT_COMMITTER_NAME", author_name)
+            .env("GIT_COMMITTER_EMAIL", author_email)
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| CommitError::Failed(format!("Failed to execute git command: {}", e)))?;
+
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin
+                .write_all(input)
+                .map_err(|e| CommitError::Failed(format!("Failed to write to git: {}", e)))?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| CommitError::Failed(format!("Failed to wait for git: {}", e)))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            if error_msg.contains("appears as both a file and as a directory") {
+                return Err(CommitError::PathConflict);
+            }
+            Err(CommitError::Failed(format!(
+                "Git {} failed: {}",
+                args[0],
+                error_msg.trim()
+            )))
+        }
+    };
+
+    match parent {
+        Some(parent) => git(&["read-tree", parent], None)?,
+        None => git(&["read-tree", "--empty"], None)?,
+    };
+
+    for change in changes {
+        match change {
+            FileChange::Write {
+                path,
+                mode,
+                content,
+            } => {
+                let hash = git(&["hash-object", "-w", "--stdin"], Some(content))?;
+                git(
+                    &[
+                        "update-index",
+                        "--add",
+                        "--cacheinfo",
+                        &format!("{},{},{}", mode, hash, path),
+                    ],
+                    None,
+                )?;
+            }
+            FileChange::Delete { path } => {
+                // В bare-репозитории `--force-remove` недоступен, режим 0 удаляет запись
+                let record = format!("0 {}\t{}\0", ZERO_SHA, path);
+                git(
+                    &["update-index", "-z", "--index-info"],
+                    Some(record.as_bytes()),
+                )?;
+            }
+        }
+    }
+
+    let tree = git(&["write-tree"], None)?;
+    match parent {
+        Some(parent) => git(
+            &["commit-tree", &tree, "-p", parent, "-F", "-"],
+            Some(message.as_bytes()),
+        ),
+        None => git(&["commit-tree", &tree, "-F", "-"], Some(message.as_bytes())),
+    }
+}
+
+/// Атомарно переводит ссылку на `new_sha`, если она указывает на `old_sha`
+///
+/// `old_sha = ZERO_SHA` означает, что ссылка ещё не должна существовать.
+pub fn update_ref(
+    repo_path: &str,
+    ref_name: &str,
+    new_sha: &str,
+    old_sha: &str,
+) -> Result<(), String> {
+    let output = Command::new("git")
+        .args([
+            "--git-dir",
+            repo_path,
+            "update-ref",
+            ref_name,
+            new_sha,
+            old_sha,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Git update-ref failed: {}", error_msg.trim()))
+    }
+}
+
+/// Проверяет, что путь в дереве относительный и не выходит за его пределы
+pub fn is_safe_tree_path(path: &str) -> bool {
+    !path.starts_with('/')
+        && !path.contains('\0')
+        && path
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != ".." && part != ".git")
+}
+
+/// Разбирает запись `git ls-tree -z`: `<mode> <type> <hash>\t<path>`
+fn parse_tree_record(record: &str) -> Option<TreeEntry> {
+    let (meta, path) = record.split_once('\t')?;
+    let mut parts = meta.split(' ');
+    Some(TreeEntry {
+        mode: parts.next()?.to_string(),
+        object_type: parts.next()?.to_string(),
+        hash: parts.next()?.to_string(),
+        path: path.to_string(),
+    })
+}
+
+/// Экранирует путь для команды `M` в потоке fast-import
+fn quote_fast_import_path(path: &str) -> String {
+    if !path.starts_with('"') && !path.contains(['\n', '\\']) {
+        return path.to_string();
+    }
+
+    let escaped = path
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+// ============================================================================
+// ЧТЕНИЕ РЕПОЗИТОРИЯ
+// ============================================================================
+
+/// Размер кеша объектов gix (ускоряет обход истории)
+const OBJECT_CACHE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Операции чтения ссылок и объектов репозитория
+///
+/// Основная реализация — [`GixReader`], читающая репозиторий в процессе сервера;
+/// [`CliReader`] запускает `git` и используется как запасной вариант. Обе
+/// реализации возвращают одинаковые результаты, в том числе применяют
+/// `.mailmap` из HEAD к авторам и коммитерам, как `git log`.
+pub trait GitReader {
+    /// Короткие имена локальных веток
+    fn branches(&self) -> Result<Vec<String>, String>;
+
+    /// Ветка, на которую указывает HEAD
+    fn default_branch(&self) -> Option<String>;
+
+    /// Разрешает ревизию (ветку, тег, SHA) в SHA коммита
+    fn resolve_revision(&self, revision: &str) -> Option<String>;
+
+    /// Содержимое дерева ревизии; при `recursive` — все файлы с полными путями
+    fn list_tree(&self, revision: &str, recursive: bool) -> Result<Vec<GitFile>, String>;
+
+    /// Содержимое объектов заданного типа в порядке `hashes`
+    ///
+    /// Объекты другого типа, как и отсутствующие, представлены как `None`.
+    fn read_objects(
+        &self,
+        hashes: &[String],
+        object_type: &str,
+    ) -> Result<Vec<Option<Vec<u8>>>, String>;
+
+    /// Содержимое файла ревизии (`None`, если ревизии или файла нет)
+    fn read_file(&self, revision: &str, path: &str) -> Result<Option<Vec<u8>>, String>;
+
+    /// История коммитов начиная с ревизии (новые коммиты первыми)
+    ///
+    /// Имена и адреса авторов и коммитеров приведены по `.mailmap`.
+    fn commits(
+        &self,
+        revision: &str,
+        limit: Option<usize>,
+        skip: usize,
+    ) -> Result<Vec<GitCommit>, String>;
+
+    /// Полное сообщение коммита (тема и тело)
+    fn commit_message(&self, sha: &str) -> Result<String, String>;
+
+    /// Ветка по умолчанию и SHA её последнего коммита
+    ///
+    /// Если HEAD указывает на ветку без коммитов (например, пушили только в другую
+    /// ветку), используется первая существующая ветка.
+    fn default_head(&self) -> Option<(String, String)> {
+        if let Some(branch) = self.default_branch() {
+            if let Some(sha) = self.resolve_revision(&branch) {
+                return Some((branch, sha));
+            }
+        }
+
+        self.branches()
+            .ok()?
+            .into_iter()
+            .find_map(|branch| self.resolve_revision(&branch).map(|sha| (branch, sha)))
+    }
+}
+
+/// Открывает репозиторий для чтения
+///
+/// Если gix не смог открыть репозиторий, чтение выполняется через `git` CLI.
+pub fn open_reader(repo_path: &str) -> Box<dyn GitReader> {
+    match GixReader::open(repo_path) {
+        Ok(reader) => Box::new(reader),
+        Err(e) => {
+            warn!("Falling back to git CLI for {}: {}", repo_path, e);
+            Box::new(CliReader::new(repo_path))
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// gix
+// ----------------------------------------------------------------------------
+
+/// Чтение репозитория через gix, без запуска внешних процессов
+pub struct GixReader {
+    repo: gix::Repository,
+}
+
+impl GixReader {
+    pub fn open(repo_path: &str) -> Result<Self, String> {
+        let mut repo =
+            gix::open(repo_path).map_err(|e| format!("Failed to open repository: {}", e))?;
+        repo.object_cache_size_if_unset(OBJECT_CACHE_SIZE);
+        Ok(Self { repo })
+    }
+
+    /// `.mailmap` из HEAD, как его читает `git log` в bare-репозитории
+    fn mailmap(&self) -> Mailmap {
+        match self.read_file("HEAD", ".mailmap") {
+            Ok(Some(content)) => Mailmap::parse(&String::from_utf8_lossy(&content)),
+            _ => Mailmap::default(),
+        }
+    }
+
+    /// Разрешает ревизию в объект заданного вида (`commit`, `tree`)
+    fn peel(&self, revision: &str, kind: &str) -> Result<gix::Id<'_>, String> {
+        self.repo
+            .rev_parse_single(format!("{}^{{{}}}", revision, kind).as_str())
+            .map_err(|e| format!("Failed to resolve revision {}: {}", revision, e))
+    }
+
+    fn git_file(
+        &self,
+        mode: gix::object::tree::EntryMode,
+        name: String,
+        oid: gix::ObjectId,
+    ) -> Result<GitFile, String> {
+        let (file_type, size) = if mode.is_tree() {
+            ("tree", None)
+        } else if mode.is_commit() {
+            // Подмодуль
+            ("commit", None)
+        } else {
+            let header = self
+                .repo
+                .find_header(oid)
+                .map_err(|e| format!("Failed to read object {}: {}", oid, e))?;
+            ("blob", Some(header.size()))
+        };
+
+        Ok(GitFile {
+            name,
+            file_type: file_type.to_string(),
+            hash: oid.to_string(),
+            size,
+        })
+    }
+}
+
+impl GitReader for GixReader {
+    fn branches(&self) -> Result<Vec<String>, String> {
+        let references = self
+            .repo
+            .references()
+            .map_err(|e| format!("Failed to read references: {}", e))?;
+        let branches = references
+            .local_branches()
+            .map_err(|e| format!("Failed to read branches: {}", e))?;
+
+        branches
+            .map(|branch| {
+                branch
+                    .map(|branch| branch.name().shorten().to_string())
+                    .map_err(|e| format!("Failed to read branch: {}", e))
+            })
+            .collect()
+    }
+
+    fn default_branch(&self) -> Option<String> {
+        let name = self.repo.head_name().ok()??;
+        Some(name.shorten().to_string())
+    }
+
+    fn resolve_revision(&self, revision: &str) -> Option<String> {
+        self.peel(revision, "commit").ok().map(|id| id.to_string())
+    }
+
+    fn list_tree(&self, revision: &str, recursive: bool) -> Result<Vec<GitFile>, String> {
+        let tree = self
+            .peel(revision, "tree")?
+            .object()
+            .map_err(|e| format!("Failed to read tree: {}", e))?
+            .into_tree();
+
+        if !recursive {
+            return tree
+                .iter()
+                .map(|entry| {
+                    let entry = entry.map_err(|e| format!("Failed to decode tree: {}", e))?;
+                    self.git_file(
+                        entry.mode(),
+                        entry.filename().to_string(),
+                        entry.object_id(),
+                    )
+                })
+                .collect();
+        }
+
+        // Как `git ls-tree -r`: только файлы и подмодули, в порядке обхода в глубину
+        let mut recorder = gix::traverse::tree::Recorder::default();
+        tree.traverse()
+            .depthfirst(&mut recorder)
+            .map_err(|e| format!("Failed to traverse tree: {}", e))?;
+
+        recorder
+            .records
+            .into_iter()
+            .filter(|entry| !entry.mode.is_tree())
+            .map(|entry| self.git_file(entry.mode, entry.filepath.to_string(), entry.oid))
+            .collect()
+    }
+
+    fn read_objects(
+        &self,
+        hashes: &[String],
+        object_type: &str,
+    ) -> Result<Vec<Option<Vec<u8>>>, String> {
+        hashes
+            .iter()
+            .map(|hash| {
+                let Ok(id) = gix::ObjectId::from_hex(hash.as_bytes()) else {
+                    return Ok(None);
+                };
+                let object = self
+                    .repo
+                    .try_find_object(id)
+                    .map_err(|e| format!("Failed to read object {}: {}", hash, e))?;
+
+                Ok(object
+                    .filter(|object| object.kind.as_bytes() == object_type.as_bytes())
+                    .map(|object| object.detach().data))
+            })
+            .collect()
+    }
+
+    fn read_file(&self, revision: &str, path: &str) -> Result<Option<Vec<u8>>, String> {
+        let Ok(id) = self
+            .repo
+            .rev_parse_single(format!("{}:{}", revision, path).as_str())
+        else {
+            return Ok(None);
+        };
+        let object = id
+            .object()
+            .map_err(|e| format!("Failed to read object {}: {}", id, e))?;
+
+        Ok((object.kind == gix::object::Kind::Blob).then(|| object.detach().data))
+    }
+
+    fn commits(
+        &self,
+        revision: &str,
+        limit: Option<usize>,
+        skip: usize,
+    ) -> Result<Vec<GitCommit>, String> {
+        let tip = self.peel(revision, "commit")?;
+        let mailmap = self.mailmap();
+
+        // Порядок как у `git log`: по времени коммита, новые первыми
+        let walk = self
+            .repo
+            .rev_walk([tip])
+            .sorting(gix::revision::walk::Sorting::ByCommitTime(
+                gix::traverse::commit::simple::CommitTimeOrder::NewestFirst,
+            ))
+            .all()
+            .map_err(|e| format!("Failed to walk history: {}", e))?;
+
+        walk.skip(skip)
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|info| {
+                let commit = info
+                    .map_err(|e| format!("Failed to walk history: {}", e))?
+                    .object()
+                    .map_err(|e| format!("Failed to read commit: {}", e))?;
+                gix_commit(&commit, &mailmap)
+            })
+            .collect()
+    }
+
+    fn commit_message(&self, sha: &str) -> Result<String, String> {
+        let commit = self
+            .peel(sha, "commit")?
+            .object()
+            .map_err(|e| format!("Failed to read commit: {}", e))?
+            .into_commit();
+        let decoded = commit
+            .decode()
+            .map_err(|e| format!("Failed to decode commit: {}", e))?;
+
+        Ok(decoded.message.to_string().trim_end().to_string())
+    }
+}
+
+/// Преобразует коммит gix в `GitCommit`
+fn gix_commit(commit: &gix::Commit<'_>, mailmap: &Mailmap) -> Result<GitCommit, String> {
+    let decoded = commit
+        .decode()
+        .map_err(|e| format!("Failed to decode commit {}: {}", commit.id, e))?;
+    let author = decoded.author();
+    let committer = decoded.committer();
+    let (author_name, author_email) =
+        mailmap.resolve(&author.name.to_string(), &author.email.to_string());
+    let (committer_name, committer_email) =
+        mailmap.resolve(&committer.name.to_string(), &committer.email.to_string());
+
+    Ok(GitCommit {
+        sha: commit.id.to_string(),
+        parents: decoded.parents().map(|id| id.to_string()).collect(),
+        author_name,
+        author_email,
+        authored_at: author.seconds(),
+        committer_name,
+        committer_email,
+        committed_at: committer.seconds(),
+        subject: decoded.message().summary().to_string(),
+    })
+}
+
+/// Сопоставление имён и адресов из `.mailmap`
+///
+/// Правила те же, что у git: запись выбирается по адресу (без учёта регистра)
+/// и, если указано, по старому имени; более поздние строки дополняют ранние.
+#[derive(Debug, Default)]
+struct Mailmap {
+    /// Адрес в нижнем регистре → записи с необязательным старым именем
+    entries: HashMap<String, Vec<MailmapEntry>>,
+}
+
+#[derive(Debug)]
+struct MailmapEntry {
+    /// Старое имя в нижнем регистре; `None` — запись для любого имени
+    old_name: Option<String>,
+    name: Option<String>,
+    email: Option<String>,
+}
+
+impl Mailmap {
+    fn parse(content: &str) -> Self {
+        let mut mailmap = Mailmap::default();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // "Имя <адрес>", до двух пар: новая, затем старая
+            let mut pairs = Vec::new();
+            let mut rest = line;
+            while let Some(start) = rest.find('<') {
+                let Some(end) = rest[start..].find('>') else {
+                    break;
+                };
+                let name = rest[..start].trim();
+                let email = &rest[start + 1..start + end];
+                pairs.push(((!name.is_empty()).then_some(name), email));
+                rest = &rest[start + end + 1..];
+                if pairs.len() == 2 {
+                    break;
+                }
+            }
+
+            match pairs.as_slice() {
+                [(name, old_email)] => mailmap.add(*name, None, None, old_email),
+                [(name, email), (old_name, old_email)] => {
+                    mailmap.add(*name, Some(email), *old_name, old_email)
+                }
+                _ => {}
+            }
+        }
+        mailmap
+    }
+
+    fn add(
+        &mut self,
+        name: Option<&str>,
+        email: Option<&str>,
+        old_name: Option<&str>,
+        old_email: &str,
+    ) {
+        let old_name = old_name.map(str::to_lowercase);
+        let entries = self.entries.entry(old_email.to_lowercase()).or_default();
+        let index = match entries.iter().position(|entry| entry.old_name == old_name) {
+            Some(index) => index,
+            None => {
+                entries.push(MailmapEntry {
+                    old_name,
+                    name: None,
+                    email: None,
+                });
+                entries.len() - 1
+            }
+        };
+
+        let entry = &mut entries[index];
+        if let Some(name) = name {
+            entry.name = Some(name.to_string());
+        }
+        if let Some(email) = email {
+            entry.email = Some(email.to_string());
+        }
+    }
+
+    /// Имя и адрес после применения `.mailmap`
+    fn resolve(&self, name: &str, email: &str) -> (String, String) {
+        let entry = self.entries.get(&email.to_lowercase()).and_then(|entries| {
+            let name = name.to_lowercase();
+            entries
+                .iter()
+                .find(|entry| entry.old_name.as_deref() == Some(name.as_str()))
+                .or_else(|| entries.iter().find(|entry| entry.old_name.is_none()))
+        });
+
+        match entry {
+            Some(entry) => (
+                entry.name.clone().unwrap_or_else(|| name.to_string()),
+                entry.email.clone().unwrap_or_else(|| email.to_string()),
+            ),
+            None => (name.to_string(), email.to_string()),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// git CLI
+// ----------------------------------------------------------------------------
+
+/// Чтение репозитория через `git` CLI (запасной вариант)
+pub struct CliReader {
+    repo_path: String,
+}
+
+impl CliReader {
+    pub fn new(repo_path: &str) -> Self {
+        Self {
+            repo_path: repo_path.to_string(),
+        }
+    }
+
+    fn git(&self, args: &[&str]) -> Result<std::process::Output, String> {
+        Command::new("git")
+            .args(["--git-dir", &self.repo_path])
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to execute git command: {}", e))
+    }
+}
+
+impl GitReader for CliReader {
+    fn branches(&self) -> Result<Vec<String>, String> {
+        let output = self.git(&["branch", "--format=%(refname:short)"])?;
+
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let branches: Vec<String> = stdout
+                .lines()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+
+            debug!("Found {} branches", branches.len());
+            Ok(branches)
+        } else {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            error!("Failed to get branches: {}", error_msg);
+            Err(format!("Git branch command failed: {}", error_msg))
+        }
+    }
+
+    fn default_branch(&self) -> Option<String> {
+        let output = self.git(&["symbolic-ref", "--short", "HEAD"]).ok()?;
+
+        if output.status.success() {
+            let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (!branch.is_empty()).then_some(branch)
+        } else {
+            None
+        }
+    }
+
+    fn resolve_revision(&self, revision: &str) -> Option<String> {
+        let output = self
+            .git(&[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", revision),
+            ])
+            .ok()?;
+
+        if output.status.success() {
+            Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            None
+        }
+    }
+
+    fn list_tree(&self, revision: &str, recursive: bool) -> Result<Vec<GitFile>, String> {
+        let output = if recursive {
+            self.git(&["ls-tree", "-r", "-l", revision])?
+        } else {
+            self.git(&["ls-tree", "-l", revision])?
+        };
+
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let files = parse_git_ls_tree_output(&stdout);
+
+            debug!("Found {} files", files.len());
+            Ok(files)
+        } else {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            error!("Failed to get files: {}", error_msg);
+            Err(format!("Git ls-tree command failed: {}", error_msg))
+        }
+    }
+
+    /// Читает все объекты одним процессом `git cat-file --batch`
+    fn read_objects(
+        &self,
+        hashes: &[String],
+        object_type: &str,
+    ) -> Result<Vec<Option<Vec<u8>>>, String> {
+        let mut child = Command::new("git")
+            .args(["--git-dir", &self.repo_path, "cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+        // Пишем запросы в отдельном потоке, чтобы не заблокироваться на заполненном stdout
+        let mut stdin = child.stdin.take().ok_or("Failed to open git stdin")?;
+        let input: String = hashes.iter().map(|h| format!("{}\n", h)).collect();
+        let writer = std::thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+
+        let stdout = child.stdout.take().ok_or("Failed to open git stdout")?;
+        let mut reader = BufReader::new(stdout);
+        let mut blobs = Vec::with_capacity(hashes.len());
+
+        for _ in hashes {
+            let mut header = String::new();
+            reader
+                .read_line(&mut header)
+                .map_err(|e| format!("Failed to read git output: {}", e))?;
+
+            // Формат заголовка: "<sha> <type> <size>" или "<sha> missing"
+            let parts: Vec<&str> = header.split_whitespace().collect();
+            if parts.len() != 3 {
+                blobs.push(None);
+                continue;
+            }
+
+            let size: usize = parts[2]
+                .parse()
+                .map_err(|_| format!("Unexpected git cat-file header: {}", header.trim()))?;
+            let mut content = vec![0u8; size + 1]; // + завершающий перевод строки
+            reader
+                .read_exact(&mut content)
+                .map_err(|e| format!("Failed to read git object: {}", e))?;
+            content.truncate(size);
+
+            blobs.push((parts[1] == object_type).then_some(content));
+        }
+
+        let _ = writer.join();
+        let _ = child.wait();
+
+        Ok(blobs)
+    }
+
+    fn read_file(&self, revision: &str, path: &str) -> Result<Option<Vec<u8>>, String> {
+        let output = self.git(&["cat-file", "blob", &format!("{}:{}", revision, path)])?;
+        Ok(output.status.success().then_some(output.stdout))
+    }
+
+    fn commits(
+        &self,
+        revision: &str,
+        limit: Option<usize>,
+        skip: usize,
+    ) -> Result<Vec<GitCommit>, String> {
+        let format = format!("--format={}", COMMIT_LOG_FORMAT);
+        let skip = format!("--skip={}", skip);
+        let max_count = limit.map(|limit| format!("--max-count={}", limit));
+
+        let mut args = vec!["log", format.as_str(), skip.as_str()];
+        if let Some(max_count) = &max_count {
+            args.push(max_count);
+        }
+        args.extend([revision, "--"]);
+
+        let output = self.git(&args)?;
+
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            Ok(parse_git_log_output(&stdout))
+        } else {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            error!("Failed to get commits: {}", error_msg);
+            Err(format!("Git log command failed: {}", error_msg))
+        }
+    }
+
+    fn commit_message(&self, sha: &str) -> Result<String, String> {
+        let output = self.git(&["log", "-1", "--format=%B", sha, "--"])?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string())
+        } else {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            error!("Failed to get commit message: {}", error_msg);
+            Err(format!("Git log command failed: {}", error_msg))
+        }
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ СТРУКТУРЫ И ФУНКЦИИ
+// ============================================================================
+
+/// Информация о файле в Git репозитории
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitFile {
+    pub name: String,
+    pub file_type: String, // "blob" (файл), "tree" (директория)
+    pub hash: String,
+    pub size: Option<u64>,
+}
+
+/// Тег репозитория
+#[derive(Debug, Clone)]
+pub struct GitTag {
+    pub name: String,
+    /// Коммит, на который указывает тег (аннотированный тег разыменован)
+    pub target_sha: String,
+    /// Время создания (Unix timestamp)
+    pub created_at: i64,
+    /// Первая строка сообщения аннотированного тега или коммита
+    pub subject: String,
+}
+
+/// Запись дерева с режимом файла (`100644`, `100755`, `120000`, `160000`)
+#[derive(Debug, Clone)]
+pub struct TreeEntry {
+    pub mode: String,
+    /// blob или commit (подмодуль)
+    pub object_type: String,
+    pub hash: String,
+    /// Путь от корня репозитория
+    pub path: String,
+}
+
+/// Ошибка `commit_changes`
+#[derive(Debug)]
+pub enum CommitError {
+    /// Путь занят файлом там, где нужен каталог, или наоборот
+    PathConflict,
+    Failed(String),
+}
+
+/// Изменение файла в коммите, создаваемом через API
+#[derive(Debug, Clone)]
+pub enum FileChange {
+    /// Создание или замена файла
+    Write {
+        path: String,
+        mode: String,
+        content: Vec<u8>,
+    },
+    Delete {
+        path: String,
+    },
+}
+
+/// Обновление ссылки, запрошенное при push
+#[derive(Debug, Clone)]
+pub struct RefUpdate {
+    /// `ZERO_SHA`, если ссылка удаляется
+    pub new_sha: String,
+    pub ref_name: String,
+}
+
+/// Количество объектов в хранилище репозитория
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ObjectCounts {
+    pub loose_objects: i64,
+    pub packs: i64,
+}
+
+/// Информация о коммите
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GitCommit {
+    pub sha: String,
+    pub parents: Vec<String>,
+    pub author_name: String,
+    pub author_email: String,
+    /// Время создания коммита автором (Unix timestamp)
+    pub authored_at: i64,
+    pub committer_name: String,
+    pub committer_email: String,
+    pub committed_at: i64,
+    /// Первая строка сообщения коммита
+    pub subject: String,
+}
+
+/// Формат `git log`: поля разделены 0x1f, записи — 0x1e
+const COMMIT_LOG_FORMAT: &str = "%H%x1f%P%x1f%aN%x1f%aE%x1f%at%x1f%cN%x1f%cE%x1f%ct%x1f%s%x1e";
+
+/// Парсит вывод `git log` в формате `COMMIT_LOG_FORMAT`
+fn parse_git_log_output(output: &str) -> Vec<GitCommit> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let fields: Vec<&str> = record.trim_start_matches('\n').split('\x1f').collect();
+            if fields.len() != 9 {
+                return None;
+            }
+
+            Some(GitCommit {
+                sha: fields[0].to_string(),
+                parents: fields[1].split_whitespace().map(str::to_string).collect(),
+                author_name: fields[2].to_string(),
+                author_email: fields[3].to_string(),
+                authored_at: fields[4].parse().unwrap_or(0),
+                committer_name: fields[5].to_string(),
+                committer_email: fields[6].to_string(),
+                committed_at: fields[7].parse().unwrap_or(0),
+                subject: fields[8].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Парсит вывод команды git ls-tree
+fn parse_git_ls_tree_output(output: &str) -> Vec<GitFile> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let _mode = parts.next()?;
+            let file_type = parts.next()?.to_string();
+            let hash = parts.next()?.to_string();
+            let size_str = parts.next()?;
+            let name = parts.collect::<Vec<&str>>().join(" ");
+
+            let size = if file_type == "blob" {
+                size_str.parse().ok()
+            } else {
+                None
+            };
+
+            Some(GitFile {
+                name,
+                file_type,
+                hash,
+                size,
+            })
+        })
+        .collect()
+}
+
+/// Проверяет доступность Git в системе
+pub fn check_git_availability() -> bool {
+    Command::new("git")
+        .arg("--version")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Получает версию Git
+pub fn get_git_version() -> Option<String> {
+    let output = Command::new("git").arg("--version").output().ok()?;
+
+    if output.status.success() {
+        let version = String::from_utf8_lossy(&output.stdout);
+        Some(version.trim().to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn run_git(dir: &Path, args: &[&str], date: &str) {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("HOME", dir)
+            .output()
+            .expect("git is installed");
+        assert!(
+            output.status.success(),
+            "git {:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn commit_file(dir: &Path, path: &str, content: &str, author: &str, date: &str) {
+        let file = dir.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+        run_git(dir, &["add", path], date);
+        run_git(
+            dir,
+            &[
+                "commit",
+                "-q",
+                "--author",
+                author,
+                "-m",
+                &format!("Update {}\n\nBody of {}", path, path),
+            ],
+            date,
+        );
+    }
+
+    /// Bare-репозиторий с ветками, тегом, вложенными каталогами и `.mailmap`
+    fn fixture_repo() -> (TempDir, String) {
+        let tmp = TempDir::new().unwrap();
+        let work = tmp.path().join("work");
+        fs::create_dir_all(&work).unwrap();
+
+        run_git(&work, &["init", "-q", "-b", "main"], "1700000000 +0000");
+        commit_file(
+            &work,
+            "README.md",
+            "# Demo\n",
+            "Old Name <old@example.com>",
+            "1700000000 +0000",
+        );
+        commit_file(
+            &work,
+            "src/lib.rs",
+            "pub fn f() {}\n",
+            "Other <Other@Example.com>",
+            "1700000100 +0000",
+        );
+        commit_file(
+            &work,
+            ".mailmap",
+            "# comment\nNew Name <old@example.com>\nProper <proper@example.com> Other <other@example.com>\n",
+            "Test <test@example.com>",
+            "1700000200 +0000",
+        );
+        run_git(
+            &work,
+            &["tag", "-a", "v1.0", "-m", "Release"],
+            "1700000200 +0000",
+        );
+        run_git(
+            &work,
+            &["checkout", "-q", "-b", "feature"],
+            "1700000300 +0000",
+        );
+        commit_file(
+            &work,
+            "src/nested/mod.rs",
+            "mod a;\n",
+            "Old Name <OLD@example.com>",
+            "1700000300 +0000",
+        );
+        run_git(&work, &["checkout", "-q", "main"], "1700000300 +0000");
+
+        let bare = tmp.path().join("repo.git");
+        run_git(
+            tmp.path(),
+            &[
+                "clone",
+                "-q",
+                "--bare",
+                work.to_str().unwrap(),
+                bare.to_str().unwrap(),
+            ],
+            "1700000300 +0000",
+        );
+        let bare = bare.to_str().unwrap().to_string();
+        (tmp, bare)
+    }
+
+    #[test]
+    fn test_readers_parity() {
+        let (_tmp, repo) = fixture_repo();
+        let gix = GixReader::open(&repo).unwrap();
+        let cli = CliReader::new(&repo);
+
+        assert_eq!(gix.branches().unwrap(), cli.branches().unwrap());
+        assert_eq!(gix.default_branch(), cli.default_branch());
+        assert_eq!(gix.default_head(), cli.default_head());
+
+        for revision in ["main", "feature", "v1.0", "HEAD", "missing"] {
+            assert_eq!(
+                gix.resolve_revision(revision),
+                cli.resolve_revision(revision),
+                "{}",
+                revision
+            );
+        }
+        let feature = gix.resolve_revision("feature").unwrap();
+        assert_eq!(gix.resolve_revision(&feature), Some(feature.clone()));
+        assert_eq!(cli.resolve_revision(&feature), Some(feature.clone()));
+
+        for recursive in [false, true] {
+            assert_eq!(
+                gix.list_tree("feature", recursive).unwrap(),
+                cli.list_tree("feature", recursive).unwrap()
+            );
+        }
+
+        let tree = gix.list_tree("feature", true).unwrap();
+        let mut hashes: Vec<String> = tree.iter().map(|file| file.hash.clone()).collect();
+        hashes.push(feature.clone());
+        assert_eq!(
+            gix.read_objects(&hashes, "blob").unwrap(),
+            cli.read_objects(&hashes, "blob").unwrap()
+        );
+
+        for path in ["README.md", "src/nested/mod.rs", "missing.txt"] {
+            assert_eq!(
+                gix.read_file("feature", path).unwrap(),
+                cli.read_file("feature", path).unwrap(),
+                "{}",
+                path
+            );
+        }
+
+        for (limit, skip) in [(None, 0), (Some(2), 1), (Some(10), 3)] {
+            assert_eq!(
+                gix.commits("feature", limit, skip).unwrap(),
+                cli.commits("feature", limit, skip).unwrap()
+            );
+        }
+        assert_eq!(
+            gix.commit_message(&feature).unwrap(),
+            cli.commit_message(&feature).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_readers_apply_mailmap() {
+        let (_tmp, repo) = fixture_repo();
+        let gix = GixReader::open(&repo).unwrap();
+
+        let authors: Vec<(String, String)> = gix
+            .commits("feature", None, 0)
+            .unwrap()
+            .into_iter()
+            .map(|commit| (commit.author_name, commit.author_email))
+            .collect();
+        assert_eq!(
+            authors,
+            vec![
+                ("New Name".to_string(), "OLD@example.com".to_string()),
+                ("Test".to_string(), "test@example.com".to_string()),
+                ("Proper".to_string(), "proper@example.com".to_string()),
+                ("New Name".to_string(), "old@example.com".to_string()),
+            ]
+        );
+    }
+
+    fn pkt_line(line: &[u8]) -> Vec<u8> {
+        let mut pkt = format!("{:04x}", line.len() + 4).into_bytes();
+        pkt.extend_from_slice(line);
+        pkt
+    }
+
+    #[test]
+    fn test_parse_push_commands() {
+        let old = "a".repeat(40);
+        let new = "b".repeat(40);
+        let mut body = pkt_line(
+            format!(
+                "{} {} refs/heads/main\0report-status side-band-64k\n",
+                old, new
+            )
+            .as_bytes(),
+        );
+        body.extend(pkt_line(
+            format!("{} {} refs/heads/gone\n", old, ZERO_SHA).as_bytes(),
+        ));
+        body.extend(pkt_line(b"not a command\n"));
+        body.extend(pkt_line(
+            format!("{} short refs/heads/bad\n", old).as_bytes(),
+        ));
+        body.extend_from_slice(b"0000PACK\x00\x00\x00\x02");
+
+        let updates = parse_push_commands(&body);
+        let updates: Vec<(&str, &str)> = updates
+            .iter()
+            .map(|update| (update.ref_name.as_str(), update.new_sha.as_str()))
+            .collect();
+        assert_eq!(
+            updates,
+            vec![
+                ("refs/heads/main", new.as_str()),
+                ("refs/heads/gone", ZERO_SHA)
+            ]
+        );
+        assert!(push_has_capability(&body, "report-status"));
+        assert!(!push_has_capability(&body, "atomic"));
+    }
+
+    #[test]
+    fn test_parse_push_commands_malformed() {
+        assert!(parse_push_commands(b"").is_empty());
+        assert!(parse_push_commands(b"zzzz").is_empty());
+        assert!(parse_push_commands(b"0000").is_empty());
+        // Длина строки больше тела запроса
+        assert!(parse_push_commands(b"00ffabc").is_empty());
+    }
+
+    #[test]
+    fn test_applied_updates_report_status() {
+        let updates = || {
+            vec![
+                RefUpdate {
+                    new_sha: "b".repeat(40),
+                    ref_name: "refs/heads/main".to_string(),
+                },
+                RefUpdate {
+                    new_sha: "c".repeat(40),
+                    ref_name: "refs/heads/protected".to_string(),
+                },
+            ]
+        };
+        let ref_names = |updates: Vec<RefUpdate>| -> Vec<String> {
+            updates.into_iter().map(|update| update.ref_name).collect()
+        };
+
+        let mut report = pkt_line(b"unpack ok\n");
+        report.extend(pkt_line(b"ok refs/heads/main\n"));
+        report.extend(pkt_line(
+            b"ng refs/heads/protected pre-receive hook declined\n",
+        ));
+        report.extend_from_slice(b"0000");
+        assert_eq!(
+            ref_names(applied_updates("/nonexistent", updates(), &report)),
+            vec!["refs/heads/main"]
+        );
+
+        // Тот же отчёт в канале 1 side-band, сообщения хука — в канале 2
+        let mut sideband = pkt_line(b"\x02remote: rejected\n");
+        for chunk in report.chunks(10) {
+            sideband.extend(pkt_line(&[&[1u8][..], chunk].concat()));
+        }
+        sideband.extend_from_slice(b"0000");
+        assert_eq!(
+            ref_names(applied_updates("/nonexistent", updates(), &sideband)),
+            vec!["refs/heads/main"]
+        );
+
+        let mut unpack_failed = pkt_line(b"unpack index-pack abnormal exit\n");
+        unpack_failed.extend(pkt_line(b"ng refs/heads/main unpacker error\n"));
+        unpack_failed.extend_from_slice(b"0000");
+        assert!(applied_updates("/nonexistent", updates(), &unpack_failed).is_empty());
+    }
+
+    #[test]
+    fn test_applied_updates_without_report() {
+        let (_tmp, repo_path) = fixture_repo();
+        let head = resolve_revision(&repo_path, "main").unwrap();
+
+        let updates = vec![
+            RefUpdate {
+                new_sha: head,
+                ref_name: "refs/heads/main".to_string(),
+            },
+            RefUpdate {
+                new_sha: "b".repeat(40),
+                ref_name: "refs/heads/rejected".to_string(),
+            },
+            RefUpdate {
+                new_sha: ZERO_SHA.to_string(),
+                ref_name: "refs/heads/deleted".to_string(),
+            },
+        ];
+        let applied: Vec<String> = applied_updates(&repo_path, updates, b"")
+            .into_iter()
+            .map(|update| update.ref_name)
+            .collect();
+        assert_eq!(applied, vec!["refs/heads/main", "refs/heads/deleted"]);
+    }
+
+    #[test]
+    fn test_upload_pack_transfer() {
+        let want = format!("want {} side-band-64k\n", "a".repeat(40));
+        let have = format!("have {}\n", "b".repeat(40));
+
+        let mut clone = pkt_line(want.as_bytes());
+        clone.extend_from_slice(b"0000");
+        clone.extend(pkt_line(b"done\n"));
+
+        let mut fetch = pkt_line(want.as_bytes());
+        fetch.extend_from_slice(b"0000");
+        fetch.extend(pkt_line(have.as_bytes()));
+        fetch.extend(pkt_line(b"done\n"));
+
+        // packfile в канале 1 side-band
+        let mut sideband = pkt_line(b"NAK\n");
+        sideband.extend(pkt_line(b"\x01PACK\x00\x00\x00\x02"));
+        sideband.extend_from_slice(b"0000");
+        // packfile сразу за NAK без side-band
+        let mut plain = pkt_line(b"NAK\n");
+        plain.extend_from_slice(b"PACK\x00\x00\x00\x02");
+        // промежуточный раунд согласования
+        let mut negotiation = pkt_line(format!("ACK {} common\n", "b".repeat(40)).as_bytes());
+        negotiation.extend(pkt_line(b"NAK\n"));
+
+        assert_eq!(
+            upload_pack_transfer(&clone, &sideband),
+            Some(UploadPackTransfer::Clone)
+        );
+        assert_eq!(
+            upload_pack_transfer(&fetch, &sideband),
+            Some(UploadPackTransfer::Fetch)
+        );
+        assert_eq!(
+            upload_pack_transfer(&clone, &plain),
+            Some(UploadPackTransfer::Clone)
+        );
+        assert_eq!(upload_pack_transfer(&fetch, &negotiation), None);
+        assert_eq!(upload_pack_transfer(&fetch, b""), None);
+    }
+
+    #[test]
+    fn test_quote_fast_import_path() {
+        assert_eq!(quote_fast_import_path("src/main.rs"), "src/main.rs");
+        assert_eq!(quote_fast_import_path("with space.txt"), "with space.txt");
+        assert_eq!(quote_fast_import_path("mid\"quote"), "mid\"quote");
+        assert_eq!(quote_fast_import_path("\"quoted\""), "\"\\\"quoted\\\"\"");
+        assert_eq!(quote_fast_import_path("a\nb"), "\"a\\nb\"");
+        assert_eq!(quote_fast_import_path("back\\slash"), "\"back\\\\slash\"");
+    }
+
+    #[test]
+    fn test_is_safe_tree_path() {
+        assert!(is_safe_tree_path("README.md"));
+        assert!(is_safe_tree_path("docs/guide/intro.md"));
+        assert!(is_safe_tree_path(".gitignore"));
+
+        for path in [
+            "",
+            "/abs",
+            "a//b",
+            "a/",
+            ".",
+            "a/./b",
+            "..",
+            "a/../../b",
+            ".git",
+            "a/.git/config",
+            "nul\0byte",
+        ] {
+            assert!(!is_safe_tree_path(path), "{:?}", path);
+        }
+    }
+
+    #[test]
+    fn test_commit_changes_path_conflict() {
+        let (_tmp, repo) = fixture_repo();
+        let head = resolve_revision(&repo, "main").unwrap();
+        let write = |path: &str| FileChange::Write {
+            path: path.to_string(),
+            mode: "100644".to_string(),
+            content: b"x".to_vec(),
+        };
+
+        // Файл внутри пути, занятого файлом, и файл на месте каталога
+        for path in ["README.md/inner.txt", "src"] {
+            let result = commit_changes(&repo, Some(&head), &[write(path)], "T", "t@x", "m");
+            assert!(
+                matches!(result, Err(CommitError::PathConflict)),
+                "{}: {:?}",
+                path,
+                result
+            );
+        }
+
+        let sha = commit_changes(&repo, Some(&head), &[write("src/new.rs")], "T", "t@x", "m");
+        assert!(sha.is_ok());
+    }
+
+    #[test]
+    fn test_mailmap_resolve() {
+        let mailmap = Mailmap::parse(
+            "Jane <jane@example.com>\n\
+             <jane@new.example.com> <jane@old.example.com>\n\
+             Joe Work <joe@work.example.com> joe <joe@example.com>\n\
+             broken <line\n",
+        );
+
+        assert_eq!(
+            mailmap.resolve("jane d", "JANE@example.com"),
+            ("Jane".to_string(), "JANE@example.com".to_string())
+        );
+        assert_eq!(
+            mailmap.resolve("Jane", "jane@old.example.com"),
+            ("Jane".to_string(), "jane@new.example.com".to_string())
+        );
+        assert_eq!(
+            mailmap.resolve("Joe", "joe@example.com"),
+            ("Joe Work".to_string(), "joe@work.example.com".to_string())
+        );
+        assert_eq!(
+            mailmap.resolve("Joseph", "joe@example.com"),
+            ("Joseph".to_string(), "joe@example.com".to_string())
+        );
+        assert_eq!(
+            mailmap.resolve("Anyone", "anyone@example.com"),
+            ("Anyone".to_string(), "anyone@example.com".to_string())
+        );
     }
 }
@@ -0,0 +1,155 @@
+//! Раскладка графа коммитов по колонкам для отрисовки истории
+//!
+//! Коммиты передаются в топологическом порядке (потомки раньше предков), как
+//! их выдаёт `git log --topo-order`. Каждая колонка ждёт определённый коммит:
+//! коммит занимает колонку, которая его ждёт, первый родитель продолжает эту
+//! колонку, остальные родители получают свободные колонки. Если родителя уже
+//! ждёт другая колонка, ребро уходит в неё и линии сходятся.
+
+use serde::Serialize;
+
+/// Положение коммита в графе
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LaneAssignment {
+    /// Колонка коммита, начиная с 0
+    pub lane: usize,
+    /// Колонки, в которых идут рёбра к родителям (в порядке родителей)
+    pub parent_lanes: Vec<usize>,
+}
+
+/// Раскладывает коммиты `(sha, родители)` по колонкам
+///
+/// Возвращает положение каждого коммита и общее число колонок.
+pub fn assign_lanes<'a, I>(commits: I) -> (Vec<LaneAssignment>, usize)
+where
+    I: IntoIterator<Item = (&'a str, &'a [String])>,
+{
+    // Для каждой колонки — SHA коммита, который она ждёт
+    let mut lanes: Vec<Option<&str>> = Vec::new();
+    let mut width = 0;
+    let mut assignments = Vec::new();
+
+    for (sha, parents) in commits {
+        let lane = match lanes.iter().position(|expected| *expected == Some(sha)) {
+            Some(lane) => lane,
+            None => free_lane(&mut lanes),
+        };
+        // Все колонки, ждавшие этот коммит, сходятся в нём
+        for expected in lanes.iter_mut() {
+            if *expected == Some(sha) {
+                *expected = None;
+            }
+        }
+
+        let mut parent_lanes = Vec::with_capacity(parents.len());
+        for (index, parent) in parents.iter().enumerate() {
+            let parent = parent.as_str();
+            if let Some(existing) = lanes.iter().position(|expected| *expected == Some(parent)) {
+                parent_lanes.push(existing);
+                continue;
+            }
+            let target = if index == 0 && lanes[lane].is_none() {
+                lane
+            } else {
+                free_lane(&mut lanes)
+            };
+            lanes[target] = Some(parent);
+            parent_lanes.push(target);
+        }
+
+        width = width.max(lanes.len());
+        while lanes.last() == Some(&None) {
+            lanes.pop();
+        }
+
+        assignments.push(LaneAssignment { lane, parent_lanes });
+    }
+
+    (assignments, width)
+}
+
+/// Первая свободная колонка (при необходимости добавляется новая)
+fn free_lane(lanes: &mut Vec<Option<&str>>) -> usize {
+    match lanes.iter().position(Option::is_none) {
+        Some(lane) => lane,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(commits: &[(&str, &[&str])]) -> (Vec<(usize, Vec<usize>)>, usize) {
+        let owned: Vec<(String, Vec<String>)> = commits
+            .iter()
+            .map(|(sha, parents)| {
+                (
+                    sha.to_string(),
+                    parents.iter().map(|parent| parent.to_string()).collect(),
+                )
+            })
+            .collect();
+        let (assignments, width) = assign_lanes(
+            owned
+                .iter()
+                .map(|(sha, parents)| (sha.as_str(), parents.as_slice())),
+        );
+        (
+            assignments
+                .into_iter()
+                .map(|assignment| (assignment.lane, assignment.parent_lanes))
+                .collect(),
+            width,
+        )
+    }
+
+    #[test]
+    fn test_linear_history() {
+        let (rows, width) = layout(&[("c", &["b"]), ("b", &["a"]), ("a", &[])]);
+        assert_eq!(rows, vec![(0, vec![0]), (0, vec![0]), (0, vec![])]);
+        assert_eq!(width, 1);
+    }
+
+    #[test]
+    fn test_merge_and_branch_converge() {
+        // m сливает f в main; f и b ответвились от a
+        let (rows, width) = layout(&[("m", &["b", "f"]), ("f", &["a"]), ("b", &["a"]), ("a", &[])]);
+        assert_eq!(
+            rows,
+            vec![
+                (0, vec![0, 1]),
+                (1, vec![1]),
+                // Ребро b уходит в колонку 1, которая уже ждёт a
+                (0, vec![1]),
+                (1, vec![]),
+            ]
+        );
+        assert_eq!(width, 2);
+    }
+
+    #[test]
+    fn test_independent_branch_tips() {
+        // Две ветки с общим предком: x и y — вершины веток
+        let (rows, width) = layout(&[("x", &["a"]), ("y", &["a"]), ("a", &[])]);
+        assert_eq!(rows, vec![(0, vec![0]), (1, vec![0]), (0, vec![])]);
+        assert_eq!(width, 2);
+    }
+
+    #[test]
+    fn test_freed_lane_is_reused() {
+        let (rows, _) = layout(&[
+            ("m", &["b", "f"]),
+            ("f", &["b"]),
+            ("b", &["a"]),
+            ("n", &["a"]),
+            ("a", &[]),
+        ]);
+        assert_eq!(rows[1], (1, vec![0]));
+        // Колонка 1 освободилась после слияния f, новая вершина n занимает её
+        assert_eq!(rows[3], (1, vec![0]));
+    }
+}
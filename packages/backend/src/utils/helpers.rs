@@ -85,6 +85,42 @@ pub fn is_valid_project_name(name: &str) -> bool {
         && !name.ends_with('.')
 }
 
+/// Преобразует glob-шаблон пути (`*`, `**`, `?`) в регулярное выражение POSIX
+///
+/// `*` и `?` не пересекают границу директории, `**/` соответствует
+/// любому количеству вложенных директорий.
+pub fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::from("^");
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    regex.push_str("(.*/)?");
+                    i += 3;
+                } else {
+                    regex.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c if "\\.+()[]{}|^$".contains(c) => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+        i += 1;
+    }
+
+    regex.push('$');
+    regex
+}
+
 /// Проверяет валидность email адреса (простая проверка)
 pub fn is_valid_email(email: &str) -> bool {
     email.contains('@') && email.contains('.') && email.len() > 5
@@ -243,6 +279,13 @@ mod tests {
         assert!(!is_valid_username("user@domain")); // недопустимый символ
     }
 
+    #[test]
+    fn test_glob_to_regex() {
+        assert_eq!(glob_to_regex("*.rs"), "^[^/]*\\.rs$");
+        assert_eq!(glob_to_regex("src/**/*.rs"), "^src/(.*/)?[^/]*\\.rs$");
+        assert_eq!(glob_to_regex("docs/**"), "^docs/.*$");
+    }
+
     #[test]
     fn test_format_file_size() {
         assert_eq!(format_file_size(1024), "1.0 KB");
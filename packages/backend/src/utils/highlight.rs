@@ -0,0 +1,213 @@
+//! Подсветка синтаксиса и превью файлов для отрисовки на сервере
+//!
+//! Код размечается встроенными стилями темы, так что клиенту не нужны ни
+//! подсветка, ни таблица стилей. Каждая строка получает якорь `L{номер}`.
+
+use base64::{engine::general_purpose, Engine as _};
+use std::path::Path;
+use std::sync::LazyLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Встроенные грамматики (загружаются один раз, это заметно дорого)
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+/// Тема оформления кода
+static THEME: LazyLock<Theme> = LazyLock::new(|| {
+    ThemeSet::load_defaults()
+        .themes
+        .remove("InspiredGitHub")
+        .unwrap_or_default()
+});
+
+/// Файлы крупнее подсвечиваются как обычный текст
+pub const MAX_HIGHLIGHT_SIZE: usize = 1024 * 1024;
+
+/// Сигнатуры форматов изображений: (смещение, байты, MIME-тип)
+const IMAGE_SIGNATURES: &[(usize, &[u8], &str)] = &[
+    (0, b"\x89PNG\r\n\x1a\n", "image/png"),
+    (0, b"\xff\xd8\xff", "image/jpeg"),
+    (0, b"GIF87a", "image/gif"),
+    (0, b"GIF89a", "image/gif"),
+    (8, b"WEBP", "image/webp"),
+    (0, b"BM", "image/bmp"),
+    (0, b"\x00\x00\x01\x00", "image/x-icon"),
+];
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Размеченный код
+#[derive(Debug, Clone)]
+pub struct HighlightedCode {
+    /// Название языка (`Plain Text`, если он не определён)
+    pub language: String,
+    pub lines: usize,
+    pub html: String,
+}
+
+// ============================================================================
+// ФУНКЦИИ
+// ============================================================================
+
+/// Размечает текст файла `path` по строкам с якорями
+///
+/// Язык определяется по расширению, затем по первой строке (shebang,
+/// modeline), затем по полному имени файла. Текст больше `MAX_HIGHLIGHT_SIZE` не подсвечивается.
+pub fn highlight(path: &str, content: &str) -> HighlightedCode {
+    let syntax = detect_syntax(path, content);
+    let mut highlighter =
+        (content.len() <= MAX_HIGHLIGHT_SIZE).then(|| HighlightLines::new(syntax, &THEME));
+
+    let background = THEME
+        .settings
+        .background
+        .map(|color| {
+            format!(
+                " style=\"background-color:#{:02x}{:02x}{:02x}\"",
+                color.r, color.g, color.b
+            )
+        })
+        .unwrap_or_default();
+    let mut html = format!("<pre class=\"highlight\"{}><code>", background);
+    let mut lines = 0;
+
+    for line in LinesWithEndings::from(content) {
+        lines += 1;
+        let text = line.trim_end_matches(['\n', '\r']);
+        let markup = highlighter
+            .as_mut()
+            .and_then(|highlighter| {
+                let regions = highlighter.highlight_line(line, &SYNTAXES).ok()?;
+                // Перевод строки остаётся за пределами разметки строки
+                let mut rest = text.len();
+                let regions: Vec<_> = regions
+                    .into_iter()
+                    .map(|(style, part)| {
+                        let part = &part[..part.len().min(rest)];
+                        rest -= part.len();
+                        (style, part)
+                    })
+                    .collect();
+                styled_line_to_highlighted_html(&regions, IncludeBackground::No).ok()
+            })
+            .unwrap_or_else(|| escape_html(text));
+
+        html.push_str(&format!(
+            "<span class=\"line\" id=\"L{0}\"><a class=\"line-number\" href=\"#L{0}\">{0}</a>\
+             <span class=\"line-content\">{1}</span></span>\n",
+            lines, markup
+        ));
+    }
+    html.push_str("</code></pre>");
+
+    HighlightedCode {
+        language: syntax.name.clone(),
+        lines,
+        html,
+    }
+}
+
+/// MIME-тип изображения по сигнатуре содержимого
+pub fn image_mime_type(content: &[u8]) -> Option<&'static str> {
+    IMAGE_SIGNATURES
+        .iter()
+        .find(|(offset, signature, _)| {
+            content
+                .get(*offset..offset + signature.len())
+                .is_some_and(|bytes| bytes == *signature)
+        })
+        .map(|(_, _, mime)| *mime)
+}
+
+/// Превью изображения с содержимым внутри `data:` URI
+pub fn image_preview(path: &str, mime: &str, content: &[u8]) -> String {
+    let alt = Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path);
+    format!(
+        "<img class=\"image-preview\" alt=\"{}\" src=\"data:{};base64,{}\">",
+        escape_html(alt),
+        mime,
+        general_purpose::STANDARD.encode(content)
+    )
+}
+
+fn detect_syntax(path: &str, content: &str) -> &'static SyntaxReference {
+    let path = Path::new(path);
+    let by_name = |name: Option<&std::ffi::OsStr>| {
+        name.and_then(|name| name.to_str())
+            .and_then(|name| SYNTAXES.find_syntax_by_extension(name))
+    };
+
+    // Shebang надёжнее полного имени файла без расширения (`build`, `test`)
+    by_name(path.extension())
+        .or_else(|| {
+            content
+                .lines()
+                .next()
+                .and_then(|line| SYNTAXES.find_syntax_by_first_line(line))
+        })
+        .or_else(|| by_name(path.file_name()))
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text())
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_from_extension_and_shebang() {
+        assert_eq!(highlight("src/main.rs", "fn main() {}\n").language, "Rust");
+        assert_eq!(
+            highlight("scripts/build", "#!/usr/bin/env python3\nprint(1)\n").language,
+            "Python"
+        );
+        assert_eq!(highlight("Makefile", "all:\n\techo\n").language, "Makefile");
+        assert_eq!(highlight("notes", "hello\n").language, "Plain Text");
+    }
+
+    #[test]
+    fn test_line_anchors_and_escaping() {
+        let code = highlight("notes.txt", "a < b\r\n\nlast");
+        assert_eq!(code.lines, 3);
+        assert!(code.html.contains("id=\"L1\""));
+        assert!(code.html.contains("href=\"#L3\""));
+        assert!(code.html.contains("&lt;"));
+        assert!(!code.html.contains("a < b"));
+        assert!(!code.html.contains('\r'));
+    }
+
+    #[test]
+    fn test_image_signatures() {
+        assert_eq!(
+            image_mime_type(b"\x89PNG\r\n\x1a\n\x00\x00"),
+            Some("image/png")
+        );
+        assert_eq!(
+            image_mime_type(b"RIFF\x10\x00\x00\x00WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(image_mime_type(b"\x7fELF\x02\x01"), None);
+    }
+}
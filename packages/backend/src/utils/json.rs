@@ -0,0 +1,349 @@
+//! Проверка JSON документов по схеме и структурное сравнение
+//!
+//! Поддерживается подмножество JSON Schema, которого хватает для схем
+//! конфигураций: `type`, `enum`, `properties`, `required`,
+//! `additionalProperties`, `items`, `maxItems`, `minLength`, `maxLength`,
+//! `pattern`, `minimum` и `maximum`. Остальные ключевые слова игнорируются.
+//! Пути в ошибках и изменениях записываются как JSON Pointer.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+// ============================================================================
+// ПРОВЕРКА ПО СХЕМЕ
+// ============================================================================
+
+/// Нарушение схемы
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaError {
+    /// JSON Pointer на значение; пустая строка — корень документа
+    pub path: String,
+    pub message: String,
+}
+
+/// Проверяет документ по схеме и возвращает все найденные нарушения
+pub fn validate(schema: &Value, value: &Value) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    validate_at(schema, value, "", &mut errors);
+    errors
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str, errors: &mut Vec<SchemaError>) {
+    let mut error = |message: String| {
+        errors.push(SchemaError {
+            path: path.to_string(),
+            message,
+        })
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|name| has_type(value, name)) {
+            error(format!(
+                "expected {}, got {}",
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            error(format!("must be one of: {}", allowed.join(", ")));
+        }
+    }
+
+    match value {
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    error(format!("must be at least {} characters", min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    error(format!("must be at most {} characters", max));
+                }
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                match Regex::new(pattern) {
+                    Ok(regex) if !regex.is_match(text) => {
+                        error(format!("must match pattern {}", pattern))
+                    }
+                    Ok(_) => {}
+                    Err(_) => error(format!("schema pattern {} is invalid", pattern)),
+                }
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if number < min {
+                    error(format!("must be at least {}", min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if number > max {
+                    error(format!("must be at most {}", max));
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    error(format!("must contain at most {} items", max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{}/{}", path, index), errors);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !fields.contains_key(name) {
+                        error(format!("missing required property \"{}\"", name));
+                    }
+                }
+            }
+
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (name, field) in fields {
+                let field_path = format!("{}/{}", path, escape_pointer(name));
+                match properties.and_then(|properties| properties.get(name)) {
+                    Some(field_schema) => validate_at(field_schema, field, &field_path, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => errors.push(SchemaError {
+                            path: field_path,
+                            message: "unknown property".to_string(),
+                        }),
+                        Some(extra @ Value::Object(_)) => {
+                            validate_at(extra, field, &field_path, errors)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn escape_pointer(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+// ============================================================================
+// СРАВНЕНИЕ ДОКУМЕНТОВ
+// ============================================================================
+
+/// Вид изменения значения
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// Изменение одного значения между двумя документами
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JsonChange {
+    /// JSON Pointer на изменённое значение
+    pub path: String,
+    pub change: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// Изменения, превращающие `old` в `new`
+///
+/// Объекты сравниваются по ключам, массивы — по позициям; изменившиеся
+/// листья и значения другого типа попадают в результат целиком.
+pub fn diff(old: &Value, new: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    diff_at(old, new, "", &mut changes);
+    changes
+}
+
+fn diff_at(old: &Value, new: &Value, path: &str, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(old_fields), Value::Object(new_fields)) => {
+            for (name, old_value) in old_fields {
+                let field_path = format!("{}/{}", path, escape_pointer(name));
+                match new_fields.get(name) {
+                    Some(new_value) => diff_at(old_value, new_value, &field_path, changes),
+                    None => changes.push(removed(field_path, old_value)),
+                }
+            }
+            for (name, new_value) in new_fields {
+                if !old_fields.contains_key(name) {
+                    let field_path = format!("{}/{}", path, escape_pointer(name));
+                    changes.push(added(field_path, new_value));
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for index in 0..old_items.len().max(new_items.len()) {
+                let item_path = format!("{}/{}", path, index);
+                match (old_items.get(index), new_items.get(index)) {
+                    (Some(old_item), Some(new_item)) => {
+                        diff_at(old_item, new_item, &item_path, changes)
+                    }
+                    (Some(old_item), None) => changes.push(removed(item_path, old_item)),
+                    (None, Some(new_item)) => changes.push(added(item_path, new_item)),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => changes.push(JsonChange {
+            path: path.to_string(),
+            change: ChangeKind::Changed,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn added(path: String, value: &Value) -> JsonChange {
+    JsonChange {
+        path,
+        change: ChangeKind::Added,
+        old: None,
+        new: Some(value.clone()),
+    }
+}
+
+fn removed(path: String, value: &Value) -> JsonChange {
+    JsonChange {
+        path,
+        change: ChangeKind::Removed,
+        old: Some(value.clone()),
+        new: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["name"],
+            "additionalProperties": false,
+            "properties": {
+                "name": {"type": "string", "minLength": 2, "pattern": "^[a-z]+$"},
+                "mode": {"enum": ["off", "on"]},
+                "limit": {"type": "integer", "minimum": 1, "maximum": 10},
+                "tags": {"type": "array", "maxItems": 2, "items": {"type": "string"}}
+            }
+        })
+    }
+
+    #[test]
+    fn test_validate_accepts_valid_document() {
+        let document = json!({"name": "demo", "mode": "on", "limit": 3, "tags": ["a"]});
+        assert!(validate(&schema(), &document).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_paths() {
+        let document = json!({
+            "name": "X",
+            "mode": "auto",
+            "limit": 20,
+            "tags": ["a", 1, "c"],
+            "extra": true
+        });
+        let paths: Vec<String> = validate(&schema(), &document)
+            .into_iter()
+            .map(|error| error.path)
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec!["/extra", "/limit", "/mode", "/name", "/name", "/tags", "/tags/1"]
+        );
+    }
+
+    #[test]
+    fn test_validate_required_and_type() {
+        let errors = validate(&schema(), &json!({}));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "missing required property \"name\"");
+
+        let errors = validate(&schema(), &json!([]));
+        assert_eq!(errors[0].message, "expected object, got array");
+        assert!(validate(&json!({"type": "integer"}), &json!(1.5)).len() == 1);
+    }
+
+    #[test]
+    fn test_diff_objects_and_arrays() {
+        let old = json!({"a": 1, "b": {"c": true}, "list": [1, 2], "gone": "x"});
+        let new = json!({"a": 2, "b": {"c": true}, "list": [1, 3, 4], "new/key": null});
+        let changes = diff(&old, &new);
+
+        let summary: Vec<(&str, ChangeKind)> = changes
+            .iter()
+            .map(|change| (change.path.as_str(), change.change))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/a", ChangeKind::Changed),
+                ("/gone", ChangeKind::Removed),
+                ("/list/1", ChangeKind::Changed),
+                ("/list/2", ChangeKind::Added),
+                ("/new~1key", ChangeKind::Added),
+            ]
+        );
+        assert_eq!(changes[0].old, Some(json!(1)));
+        assert_eq!(changes[0].new, Some(json!(2)));
+    }
+
+    #[test]
+    fn test_diff_identical_documents() {
+        let value = json!({"a": [1, {"b": "c"}]});
+        assert!(diff(&value, &value).is_empty());
+        assert_eq!(diff(&json!(1), &json!("1")).len(), 1);
+    }
+}
@@ -0,0 +1,82 @@
+//! Определение языка программирования по имени файла
+
+use std::path::Path;
+
+// ============================================================================
+// ТАБЛИЦЫ СООТВЕТСТВИЙ
+// ============================================================================
+
+/// Соответствие расширений файлов языкам
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("py", "Python"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("swift", "Swift"),
+    ("dart", "Dart"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("cxx", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("sql", "SQL"),
+    ("html", "HTML"),
+    ("htm", "HTML"),
+    ("css", "CSS"),
+    ("scss", "SCSS"),
+    ("md", "Markdown"),
+    ("json", "JSON"),
+    ("toml", "TOML"),
+    ("yaml", "YAML"),
+    ("yml", "YAML"),
+    ("xml", "XML"),
+    ("lua", "Lua"),
+    ("cmake", "CMake"),
+];
+
+/// Файлы, язык которых определяется по полному имени
+const FILE_NAMES: &[(&str, &str)] = &[
+    ("Dockerfile", "Dockerfile"),
+    ("Makefile", "Makefile"),
+    ("CMakeLists.txt", "CMake"),
+];
+
+// ============================================================================
+// ФУНКЦИИ
+// ============================================================================
+
+/// Определяет язык файла по имени или расширению
+pub fn detect_language(path: &str) -> Option<&'static str> {
+    let path = Path::new(path);
+    let file_name = path.file_name()?.to_str()?;
+
+    if let Some((_, language)) = FILE_NAMES.iter().find(|(name, _)| *name == file_name) {
+        return Some(language);
+    }
+
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    EXTENSIONS
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, language)| *language)
+}
+
+/// Проверяет, похоже ли содержимое на бинарные данные
+pub fn is_binary(content: &[u8]) -> bool {
+    content.iter().take(8000).any(|b| *b == 0)
+}
@@ -1,4 +1,13 @@
 //! Утилиты и вспомогательные функции
 
+pub mod dependencies;
+pub mod feed;
 pub mod git;
+pub mod graph;
 pub mod helpers;
+pub mod highlight;
+pub mod json;
+pub mod languages;
+pub mod sbom;
+pub mod secrets;
+pub mod signing;
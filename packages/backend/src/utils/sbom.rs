@@ -0,0 +1,349 @@
+//! Экспорт графа зависимостей в SBOM: CycloneDX 1.5 и SPDX 2.3 в JSON
+
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+use crate::utils::dependencies::{DependencyGraph, DependencyScope};
+
+/// Название инструмента в метаданных документов
+const TOOL_NAME: &str = "NetBit";
+
+/// Формат SBOM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomFormat {
+    CycloneDx,
+    Spdx,
+}
+
+impl SbomFormat {
+    pub fn parse(value: &str) -> Option<SbomFormat> {
+        match value.to_lowercase().as_str() {
+            "cyclonedx" => Some(SbomFormat::CycloneDx),
+            "spdx" => Some(SbomFormat::Spdx),
+            _ => None,
+        }
+    }
+
+    /// Суффикс имени файла для скачивания
+    pub fn file_suffix(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cdx.json",
+            SbomFormat::Spdx => "spdx.json",
+        }
+    }
+}
+
+/// Описываемое приложение: репозиторий в ревизии
+pub struct SbomSubject<'a> {
+    /// Полное имя репозитория (`owner/project/repo`)
+    pub name: &'a str,
+    pub revision: &'a str,
+}
+
+/// Строит документ в выбранном формате
+pub fn render(format: SbomFormat, graph: &DependencyGraph, subject: &SbomSubject) -> Value {
+    match format {
+        SbomFormat::CycloneDx => cyclonedx(graph, subject),
+        SbomFormat::Spdx => spdx(graph, subject),
+    }
+}
+
+// ============================================================================
+// CYCLONEDX
+// ============================================================================
+
+fn cyclonedx(graph: &DependencyGraph, subject: &SbomSubject) -> Value {
+    let root_ref = format!("repository:{}", subject.name);
+
+    let components: Vec<Value> = graph
+        .dependencies
+        .iter()
+        .map(|dep| {
+            let mut component = Map::new();
+            component.insert("type".into(), json!("library"));
+            component.insert("bom-ref".into(), json!(dep.purl));
+            component.insert("name".into(), json!(dep.name));
+            if let Some(version) = &dep.version {
+                component.insert("version".into(), json!(version));
+            }
+            component.insert("purl".into(), json!(dep.purl));
+            // Зависимости для разработки не попадают в поставляемый продукт
+            let scope = match dep.scope {
+                DependencyScope::Runtime | DependencyScope::Build => "required",
+                DependencyScope::Optional => "optional",
+                DependencyScope::Development => "excluded",
+            };
+            component.insert("scope".into(), json!(scope));
+            Value::Object(component)
+        })
+        .collect();
+
+    let mut dependencies = vec![json!({
+        "ref": root_ref,
+        "dependsOn": graph
+            .dependencies
+            .iter()
+            .filter(|dep| dep.direct)
+            .map(|dep| dep.purl.as_str())
+            .collect::<Vec<_>>(),
+    })];
+    dependencies.extend(graph.dependencies.iter().map(|dep| {
+        json!({
+            "ref": dep.purl,
+            "dependsOn": dep.dependencies,
+        })
+    }));
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", uuid::Uuid::new_v4()),
+        "version": 1,
+        "metadata": {
+            "timestamp": Utc::now().to_rfc3339(),
+            "tools": {
+                "components": [{ "type": "application", "name": TOOL_NAME }]
+            },
+            "component": {
+                "type": "application",
+                "bom-ref": root_ref,
+                "name": subject.name,
+                "version": subject.revision,
+            }
+        },
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+// ============================================================================
+// SPDX
+// ============================================================================
+
+const SPDX_ROOT_ID: &str = "SPDXRef-Repository";
+
+fn spdx(graph: &DependencyGraph, subject: &SbomSubject) -> Value {
+    let ids: HashMap<&str, String> = graph
+        .dependencies
+        .iter()
+        .enumerate()
+        .map(|(index, dep)| (dep.purl.as_str(), format!("SPDXRef-Package-{}", index + 1)))
+        .collect();
+
+    let mut packages = vec![json!({
+        "name": subject.name,
+        "SPDXID": SPDX_ROOT_ID,
+        "versionInfo": subject.revision,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": "NOASSERTION",
+        "copyrightText": "NOASSERTION",
+    })];
+    for dep in &graph.dependencies {
+        let mut package = Map::new();
+        package.insert("name".into(), json!(dep.name));
+        package.insert("SPDXID".into(), json!(ids[dep.purl.as_str()]));
+        if let Some(version) = &dep.version {
+            package.insert("versionInfo".into(), json!(version));
+        }
+        for field in [
+            "downloadLocation",
+            "licenseConcluded",
+            "licenseDeclared",
+            "copyrightText",
+        ] {
+            package.insert(field.into(), json!("NOASSERTION"));
+        }
+        package.insert("filesAnalyzed".into(), json!(false));
+        package.insert(
+            "externalRefs".into(),
+            json!([{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": dep.purl,
+            }]),
+        );
+        packages.push(Value::Object(package));
+    }
+
+    let mut relationships = vec![relationship("SPDXRef-DOCUMENT", "DESCRIBES", SPDX_ROOT_ID)];
+    for dep in &graph.dependencies {
+        let id = ids[dep.purl.as_str()].as_str();
+        if dep.direct {
+            relationships.push(match dep.scope {
+                DependencyScope::Runtime => relationship(SPDX_ROOT_ID, "DEPENDS_ON", id),
+                DependencyScope::Build => relationship(id, "BUILD_DEPENDENCY_OF", SPDX_ROOT_ID),
+                DependencyScope::Optional => {
+                    relationship(id, "OPTIONAL_DEPENDENCY_OF", SPDX_ROOT_ID)
+                }
+                DependencyScope::Development => relationship(id, "DEV_DEPENDENCY_OF", SPDX_ROOT_ID),
+            });
+        }
+        for child in &dep.dependencies {
+            if let Some(child_id) = ids.get(child.as_str()) {
+                relationships.push(relationship(id, "DEPENDS_ON", child_id));
+            }
+        }
+    }
+
+    let document_name = subject.name.replace('/', "-");
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": format!("{}@{}", document_name, subject.revision),
+        "documentNamespace": format!(
+            "https://spdx.org/spdxdocs/{}-{}",
+            document_name,
+            uuid::Uuid::new_v4()
+        ),
+        "creationInfo": {
+            "created": Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            "creators": [format!("Tool: {}", TOOL_NAME)],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+fn relationship(element: &str, kind: &str, related: &str) -> Value {
+    json!({
+        "spdxElementId": element,
+        "relationshipType": kind,
+        "relatedSpdxElement": related,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::dependencies::build_graph;
+
+    const SUBJECT: SbomSubject<'static> = SbomSubject {
+        name: "alice/demo/app",
+        revision: "abc123",
+    };
+
+    /// serde (runtime) → serde_derive и tempfile (dev)
+    fn graph() -> DependencyGraph {
+        let files = [
+            (
+                "Cargo.toml",
+                "[package]\nname = \"app\"\n[dependencies]\nserde = \"1.0\"\n\
+                 [dev-dependencies]\ntempfile = \"3\"\n",
+            ),
+            (
+                "Cargo.lock",
+                "version = 3\n\
+                 [[package]]\nname = \"app\"\nversion = \"0.1.0\"\ndependencies = [\"serde\", \"tempfile\"]\n\
+                 [[package]]\nname = \"serde\"\nversion = \"1.0.219\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\ndependencies = [\"serde_derive\"]\n\
+                 [[package]]\nname = \"serde_derive\"\nversion = \"1.0.219\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\
+                 [[package]]\nname = \"tempfile\"\nversion = \"3.10.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+            ),
+        ];
+        build_graph(
+            &files
+                .iter()
+                .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn find<'a>(items: &'a Value, key: &str, value: &str) -> &'a Value {
+        items
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item[key] == value)
+            .unwrap_or_else(|| panic!("{} = {} not found", key, value))
+    }
+
+    #[test]
+    fn test_format_parse() {
+        assert_eq!(SbomFormat::parse("CycloneDX"), Some(SbomFormat::CycloneDx));
+        assert_eq!(SbomFormat::parse("spdx"), Some(SbomFormat::Spdx));
+        assert_eq!(SbomFormat::parse("swid"), None);
+        assert_eq!(SbomFormat::Spdx.file_suffix(), "spdx.json");
+    }
+
+    #[test]
+    fn test_cyclonedx() {
+        let document = render(SbomFormat::CycloneDx, &graph(), &SUBJECT);
+
+        assert_eq!(document["bomFormat"], "CycloneDX");
+        assert_eq!(document["specVersion"], "1.5");
+        assert_eq!(document["metadata"]["component"]["name"], "alice/demo/app");
+        assert_eq!(document["metadata"]["component"]["version"], "abc123");
+
+        let components = &document["components"];
+        assert_eq!(components.as_array().unwrap().len(), 3);
+        let serde = find(components, "purl", "pkg:cargo/serde@1.0.219");
+        assert_eq!(serde["name"], "serde");
+        assert_eq!(serde["version"], "1.0.219");
+        assert_eq!(serde["scope"], "required");
+        let tempfile = find(components, "purl", "pkg:cargo/tempfile@3.10.0");
+        assert_eq!(tempfile["scope"], "excluded");
+
+        let dependencies = &document["dependencies"];
+        let root = find(dependencies, "ref", "repository:alice/demo/app");
+        let mut direct: Vec<_> = root["dependsOn"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|purl| purl.as_str().unwrap())
+            .collect();
+        direct.sort();
+        assert_eq!(
+            direct,
+            vec!["pkg:cargo/serde@1.0.219", "pkg:cargo/tempfile@3.10.0"]
+        );
+        let serde = find(dependencies, "ref", "pkg:cargo/serde@1.0.219");
+        assert_eq!(
+            serde["dependsOn"],
+            json!(["pkg:cargo/serde_derive@1.0.219"])
+        );
+    }
+
+    #[test]
+    fn test_spdx() {
+        let document = render(SbomFormat::Spdx, &graph(), &SUBJECT);
+
+        assert_eq!(document["spdxVersion"], "SPDX-2.3");
+        assert_eq!(document["dataLicense"], "CC0-1.0");
+        assert_eq!(document["name"], "alice-demo-app@abc123");
+        assert!(document["documentNamespace"]
+            .as_str()
+            .unwrap()
+            .starts_with("https://spdx.org/spdxdocs/alice-demo-app-"));
+
+        let packages = &document["packages"];
+        assert_eq!(packages.as_array().unwrap().len(), 4);
+        find(packages, "SPDXID", SPDX_ROOT_ID);
+
+        // SPDX идентификатор пакета по его purl
+        let id = |purl: &str| -> String {
+            packages
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|package| package["externalRefs"][0]["referenceLocator"] == purl)
+                .map(|package| package["SPDXID"].as_str().unwrap().to_string())
+                .unwrap_or_else(|| panic!("{} not found", purl))
+        };
+        let serde = id("pkg:cargo/serde@1.0.219");
+        let derive = id("pkg:cargo/serde_derive@1.0.219");
+        let tempfile = id("pkg:cargo/tempfile@3.10.0");
+
+        let relationships = document["relationships"].as_array().unwrap();
+        let has = |element: &str, kind: &str, related: &str| {
+            relationships.contains(&relationship(element, kind, related))
+        };
+        assert!(has("SPDXRef-DOCUMENT", "DESCRIBES", SPDX_ROOT_ID));
+        assert!(has(SPDX_ROOT_ID, "DEPENDS_ON", &serde));
+        assert!(has(&tempfile, "DEV_DEPENDENCY_OF", SPDX_ROOT_ID));
+        assert!(has(&serde, "DEPENDS_ON", &derive));
+        assert_eq!(relationships.len(), 4);
+    }
+}
@@ -0,0 +1,499 @@
+//! Проверка подписей коммитов (GPG и SSH)
+//!
+//! Проверка выполняется через `git verify-commit` во временном окружении.
+//! Сначала из подписи извлекается идентификатор ключа (`signature_key_id`),
+//! затем подпись проверяется только зарегистрированными ключами с этим
+//! идентификатором. Адрес автора коммита в проверке не участвует.
+
+use log::{debug, error};
+use serde::Serialize;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+/// Тип ключа GPG
+pub const KEY_TYPE_GPG: &str = "gpg";
+/// Тип ключа SSH
+pub const KEY_TYPE_SSH: &str = "ssh";
+/// Principal в allowed_signers: подписавший определяется по ключу, а не по имени
+const SSH_PRINCIPAL: &str = "netbit";
+
+// ============================================================================
+// СТРУКТУРЫ ДАННЫХ
+// ============================================================================
+
+/// Итог проверки подписи
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// Подпись корректна и сделана зарегистрированным ключом
+    Verified,
+    /// Подписи нет или она некорректна
+    Unverified,
+    /// Подпись сделана ключом, который никто не зарегистрировал
+    UnknownKey,
+}
+
+/// Результат проверки подписи коммита
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureCheck {
+    pub status: SignatureStatus,
+    /// Код причины: valid, unsigned, invalid, unknown_key, expired_key,
+    /// revoked_key, expired_signature, unsupported_signature
+    pub reason: &'static str,
+    /// Отпечаток ключа, которым сделана подпись (если удалось определить);
+    /// у неизвестного GPG ключа — отпечаток или ID ключа из подписи
+    pub fingerprint: Option<String>,
+}
+
+impl SignatureCheck {
+    pub fn unverified(reason: &'static str) -> Self {
+        Self {
+            status: SignatureStatus::Unverified,
+            reason,
+            fingerprint: None,
+        }
+    }
+
+    pub fn unknown_key(fingerprint: Option<String>) -> Self {
+        Self {
+            status: SignatureStatus::UnknownKey,
+            reason: "unknown_key",
+            fingerprint,
+        }
+    }
+}
+
+// ============================================================================
+// КЛЮЧИ
+// ============================================================================
+
+/// Определяет тип публичного ключа по его содержимому
+pub fn detect_key_type(public_key: &str) -> Option<&'static str> {
+    let public_key = public_key.trim_start();
+    if public_key.starts_with("-----BEGIN PGP PUBLIC KEY BLOCK-----") {
+        Some(KEY_TYPE_GPG)
+    } else if public_key.starts_with("ssh-")
+        || public_key.starts_with("ecdsa-")
+        || public_key.starts_with("sk-")
+    {
+        Some(KEY_TYPE_SSH)
+    } else {
+        None
+    }
+}
+
+/// Возвращает отпечатки основного ключа и подключей из ASCII-armored GPG ключа
+pub fn gpg_key_fingerprints(armored_key: &str) -> Result<(String, Vec<String>), String> {
+    let home = gpg_home()?;
+    let output = run_with_stdin(
+        Command::new("gpg").env("GNUPGHOME", home.path()).args([
+            "--batch",
+            "--with-colons",
+            "--import-options",
+            "show-only",
+            "--import",
+        ]),
+        armored_key.as_bytes(),
+    )?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.lines().any(|line| line.starts_with("sec:")) {
+        return Err("Private keys are not accepted".to_string());
+    }
+
+    parse_gpg_key_listing(&stdout).ok_or_else(|| "Invalid GPG public key".to_string())
+}
+
+/// Разбирает вывод `gpg --with-colons` для одного ключа
+///
+/// За строкой "pub:" или "sub:" следует "fpr:::::::::<FPR>:" с отпечатком.
+fn parse_gpg_key_listing(listing: &str) -> Option<(String, Vec<String>)> {
+    let mut primary = None;
+    let mut subkeys = Vec::new();
+    let mut current = "";
+
+    for line in listing.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        match fields[0] {
+            // Второй основной ключ означает, что в блоке несколько ключей
+            "pub" if primary.is_some() => return None,
+            "pub" | "sub" => current = fields[0],
+            "fpr" => {
+                let fingerprint = fields.get(9).filter(|fpr| !fpr.is_empty())?.to_string();
+                match current {
+                    "pub" => primary = Some(fingerprint),
+                    "sub" => subkeys.push(fingerprint),
+                    _ => {}
+                }
+                current = "";
+            }
+            _ => {}
+        }
+    }
+
+    primary.map(|primary| (primary, subkeys))
+}
+
+/// Нормализует SSH ключ (без комментария) и возвращает его SHA256 отпечаток
+pub fn ssh_key_fingerprint(public_key: &str) -> Result<(String, String), String> {
+    let parts: Vec<&str> = public_key.split_whitespace().collect();
+    if parts.len() < 2 {
+        return Err("Invalid SSH public key".to_string());
+    }
+    let normalized = format!("{} {}", parts[0], parts[1]);
+
+    let output = run_with_stdin(
+        Command::new("ssh-keygen").args(["-l", "-E", "sha256", "-f", "-"]),
+        format!("{}\n", normalized).as_bytes(),
+    )?;
+    if !output.status.success() {
+        return Err("Invalid SSH public key".to_string());
+    }
+
+    // Формат вывода: "256 SHA256:... comment (ED25519)"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fingerprint = stdout
+        .split_whitespace()
+        .nth(1)
+        .filter(|fpr| fpr.starts_with("SHA256:"))
+        .ok_or_else(|| "Invalid SSH public key".to_string())?;
+
+    Ok((normalized, fingerprint.to_string()))
+}
+
+// ============================================================================
+// ПОДПИСИ КОММИТОВ
+// ============================================================================
+
+/// Определяет тип подписи по сырому объекту коммита (`git cat-file commit`)
+///
+/// Возвращает `None` для неподписанных коммитов.
+pub fn commit_signature_type(raw_commit: &[u8]) -> Option<&'static str> {
+    let raw_commit = String::from_utf8_lossy(raw_commit);
+    let mut headers = raw_commit.lines().take_while(|line| !line.is_empty());
+
+    let signature = headers.find_map(|line| {
+        line.strip_prefix("gpgsig ")
+            .or_else(|| line.strip_prefix("gpgsig-sha256 "))
+    })?;
+
+    Some(match signature.trim() {
+        "-----BEGIN PGP SIGNATURE-----" => KEY_TYPE_GPG,
+        "-----BEGIN SSH SIGNATURE-----" => KEY_TYPE_SSH,
+        _ => "unsupported",
+    })
+}
+
+/// Идентификатор ключа, которым подписан коммит
+///
+/// Для SSH — SHA256 отпечаток ключа из корректной подписи, для GPG — длинный
+/// ID ключа (16 символов). `None`, если ключ определить не удалось.
+pub fn signature_key_id(
+    repo_path: &str,
+    sha: &str,
+    signature_type: &str,
+) -> Result<Option<String>, String> {
+    // Проверка без ключей: GPG сообщает ID ключа, SSH — отпечаток ключа из подписи
+    let check = match signature_type {
+        KEY_TYPE_GPG => verify_gpg(repo_path, sha, &[])?,
+        KEY_TYPE_SSH => verify_ssh(repo_path, sha, &[])?,
+        _ => return Ok(None),
+    };
+    if check.status != SignatureStatus::UnknownKey {
+        return Ok(None);
+    }
+
+    Ok(check.fingerprint.map(|fingerprint| match signature_type {
+        KEY_TYPE_GPG => gpg_key_id(&fingerprint),
+        _ => fingerprint,
+    }))
+}
+
+/// Длинный ID GPG ключа — последние 16 символов отпечатка
+pub fn gpg_key_id(fingerprint: &str) -> String {
+    let fingerprint = fingerprint.to_uppercase();
+    fingerprint[fingerprint.len().saturating_sub(16)..].to_string()
+}
+
+/// Проверяет подпись коммита заданными ключами
+///
+/// `keys` — публичные ключи того же типа, что и подпись. У корректной
+/// подписи `fingerprint` — отпечаток ключа (основного ключа для GPG),
+/// по которому определяется подписавший.
+pub fn verify_commit(
+    repo_path: &str,
+    sha: &str,
+    signature_type: &str,
+    keys: &[String],
+) -> SignatureCheck {
+    let result = match signature_type {
+        KEY_TYPE_GPG => verify_gpg(repo_path, sha, keys),
+        KEY_TYPE_SSH => verify_ssh(repo_path, sha, keys),
+        _ => Ok(SignatureCheck::unverified("unsupported_signature")),
+    };
+
+    result.unwrap_or_else(|e| {
+        error!("Failed to verify signature of commit {}: {}", sha, e);
+        SignatureCheck::unverified("invalid")
+    })
+}
+
+fn verify_gpg(repo_path: &str, sha: &str, keys: &[String]) -> Result<SignatureCheck, String> {
+    let home = gpg_home()?;
+    if !keys.is_empty() {
+        run_with_stdin(
+            Command::new("gpg")
+                .env("GNUPGHOME", home.path())
+                .args(["--batch", "--import"]),
+            keys.join("\n").as_bytes(),
+        )?;
+    }
+
+    let output = Command::new("git")
+        .env("GNUPGHOME", home.path())
+        .args(["--git-dir", repo_path, "verify-commit", "--raw", sha])
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    Ok(parse_gpg_status(&String::from_utf8_lossy(&output.stderr)))
+}
+
+/// Разбирает строки `[GNUPG:]` из вывода `git verify-commit --raw`
+fn parse_gpg_status(status: &str) -> SignatureCheck {
+    let mut check = SignatureCheck::unverified("invalid");
+
+    for line in status.lines() {
+        let Some(line) = line.strip_prefix("[GNUPG:] ") else {
+            continue;
+        };
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        match fields.first().copied() {
+            Some("GOODSIG") => {
+                check.status = SignatureStatus::Verified;
+                check.reason = "valid";
+            }
+            // Последнее поле VALIDSIG — отпечаток основного ключа
+            Some("VALIDSIG") => check.fingerprint = fields.last().map(|f| f.to_string()),
+            Some("BADSIG") => return SignatureCheck::unverified("invalid"),
+            Some("EXPKEYSIG") => return SignatureCheck::unverified("expired_key"),
+            Some("REVKEYSIG") => return SignatureCheck::unverified("revoked_key"),
+            Some("EXPSIG") => return SignatureCheck::unverified("expired_signature"),
+            // ERRSIG <keyid> ... <rc> [<fpr>]: отпечаток есть не во всех версиях gpg
+            Some("ERRSIG") => {
+                let key = fields.get(7).or_else(|| fields.get(1));
+                return SignatureCheck::unknown_key(key.map(|f| f.to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    check
+}
+
+fn verify_ssh(repo_path: &str, sha: &str, keys: &[String]) -> Result<SignatureCheck, String> {
+    let dir = TempDir::new().map_err(|e| format!("Failed to create temp dir: {}", e))?;
+    let allowed_signers = dir.path().join("allowed_signers");
+    let content: String = keys
+        .iter()
+        .map(|key| format!("{} namespaces=\"git\" {}\n", SSH_PRINCIPAL, key))
+        .collect();
+    std::fs::write(&allowed_signers, content)
+        .map_err(|e| format!("Failed to write allowed signers: {}", e))?;
+
+    let output = Command::new("git")
+        .args([
+            "--git-dir",
+            repo_path,
+            "-c",
+            &format!("gpg.ssh.allowedSignersFile={}", allowed_signers.display()),
+            "verify-commit",
+            "--raw",
+            sha,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    debug!("ssh signature check for {}: {}", sha, text.trim());
+
+    Ok(parse_ssh_status(&text))
+}
+
+/// Разбирает вывод `git verify-commit` для SSH подписи
+fn parse_ssh_status(output: &str) -> SignatureCheck {
+    let Some(good) = output
+        .lines()
+        .find(|line| line.starts_with("Good \"git\" signature"))
+    else {
+        return SignatureCheck::unverified("invalid");
+    };
+
+    let fingerprint = good
+        .split_whitespace()
+        .find(|part| part.starts_with("SHA256:"))
+        .map(str::to_string);
+
+    // Подпись математически корректна, но ключа нет среди переданных
+    if !good.starts_with("Good \"git\" signature for ") {
+        return SignatureCheck::unknown_key(fingerprint);
+    }
+
+    SignatureCheck {
+        status: SignatureStatus::Verified,
+        reason: "valid",
+        fingerprint,
+    }
+}
+
+// ============================================================================
+// ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ
+// ============================================================================
+
+/// Создает изолированный GNUPGHOME (удаляется вместе с TempDir)
+fn gpg_home() -> Result<TempDir, String> {
+    let home = TempDir::new().map_err(|e| format!("Failed to create temp dir: {}", e))?;
+    // Не запускаем gpg-agent: для импорта и проверки публичных ключей он не нужен
+    std::fs::write(home.path().join("gpg.conf"), "no-autostart\n")
+        .map_err(|e| format!("Failed to write gpg.conf: {}", e))?;
+    Ok(home)
+}
+
+fn run_with_stdin(command: &mut Command, input: &[u8]) -> Result<std::process::Output, String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute command: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input)
+            .map_err(|e| format!("Failed to write to stdin: {}", e))?;
+    }
+
+    child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for command: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: &str = "3B325FAEDF06D3C0B5D04A3D24E430E4ED8AD383";
+    const SUBKEY: &str = "F12D553AB71B379746016244459F87EFFD473C89";
+
+    #[test]
+    fn test_commit_signature_type() {
+        let gpg = b"tree 4b825dc6\nauthor a <a@b> 1 +0000\ngpgsig -----BEGIN PGP SIGNATURE-----\n \n wnUEABYKAB0=\n -----END PGP SIGNATURE-----\n\nmessage\n";
+        let ssh = b"tree 4b825dc6\ngpgsig -----BEGIN SSH SIGNATURE-----\n U1NIU0lH\n -----END SSH SIGNATURE-----\n\nmessage\n";
+        let x509 = b"tree 4b825dc6\ngpgsig -----BEGIN SIGNED MESSAGE-----\n\nmessage\n";
+        assert_eq!(commit_signature_type(gpg), Some(KEY_TYPE_GPG));
+        assert_eq!(commit_signature_type(ssh), Some(KEY_TYPE_SSH));
+        assert_eq!(commit_signature_type(x509), Some("unsupported"));
+        assert_eq!(commit_signature_type(b"tree 4b825dc6\n\nmessage\n"), None);
+        // Подпись в теле сообщения не считается подписью коммита
+        assert_eq!(
+            commit_signature_type(b"tree 4b825dc6\n\ngpgsig -----BEGIN PGP SIGNATURE-----\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_gpg_status_valid() {
+        let status = format!(
+            "[GNUPG:] NEWSIG\n\
+             [GNUPG:] GOODSIG 459F87EFFD473C89 T <t@x>\n\
+             [GNUPG:] VALIDSIG {} 2026-10-18 1792365265 0 4 0 22 8 00 {}\n\
+             [GNUPG:] TRUST_UNDEFINED 0 pgp\n",
+            SUBKEY, PRIMARY
+        );
+        let check = parse_gpg_status(&status);
+        assert_eq!(check.status, SignatureStatus::Verified);
+        assert_eq!(check.reason, "valid");
+        assert_eq!(check.fingerprint.as_deref(), Some(PRIMARY));
+    }
+
+    #[test]
+    fn test_parse_gpg_status_failures() {
+        let missing = format!(
+            "[GNUPG:] ERRSIG 459F87EFFD473C89 22 8 00 1792365265 9 {}\n\
+             [GNUPG:] NO_PUBKEY 459F87EFFD473C89\n",
+            SUBKEY
+        );
+        let check = parse_gpg_status(&missing);
+        assert_eq!(check.status, SignatureStatus::UnknownKey);
+        assert_eq!(check.fingerprint.as_deref(), Some(SUBKEY));
+
+        // Старые версии gpg не сообщают отпечаток
+        let check = parse_gpg_status("[GNUPG:] ERRSIG 459F87EFFD473C89 22 8 00 1792365265 9\n");
+        assert_eq!(check.fingerprint.as_deref(), Some("459F87EFFD473C89"));
+
+        assert_eq!(
+            parse_gpg_status("[GNUPG:] BADSIG 459F87EFFD473C89 T <t@x>\n").reason,
+            "invalid"
+        );
+        assert_eq!(
+            parse_gpg_status("[GNUPG:] EXPKEYSIG 459F87EFFD473C89 T <t@x>\n").reason,
+            "expired_key"
+        );
+        assert_eq!(
+            parse_gpg_status("[GNUPG:] REVKEYSIG 459F87EFFD473C89 T <t@x>\n").reason,
+            "revoked_key"
+        );
+        assert_eq!(parse_gpg_status("").status, SignatureStatus::Unverified);
+    }
+
+    #[test]
+    fn test_parse_ssh_status() {
+        let fingerprint = "SHA256:/mv5eg2LWIczQhD9uO7eeCWMLvMzq3oc3T4vY4GaUxg";
+
+        let check = parse_ssh_status(&format!(
+            "Good \"git\" signature for netbit with ED25519 key {}\n",
+            fingerprint
+        ));
+        assert_eq!(check.status, SignatureStatus::Verified);
+        assert_eq!(check.fingerprint.as_deref(), Some(fingerprint));
+
+        let check = parse_ssh_status(&format!(
+            "Good \"git\" signature with ED25519 key {}\nNo principal matched.\n",
+            fingerprint
+        ));
+        assert_eq!(check.status, SignatureStatus::UnknownKey);
+        assert_eq!(check.fingerprint.as_deref(), Some(fingerprint));
+
+        let check = parse_ssh_status("Could not verify signature.\n");
+        assert_eq!(check.status, SignatureStatus::Unverified);
+        assert_eq!(check.reason, "invalid");
+    }
+
+    #[test]
+    fn test_parse_gpg_key_listing() {
+        let listing = format!(
+            "pub:-:255:22:24E430E4ED8AD383:1792365265:::-:::scSC:::::ed25519:::0:\n\
+             fpr:::::::::{}:\n\
+             uid:-::::1792365265::0::T <t@x>::::::::::0:\n\
+             sub:-:255:22:459F87EFFD473C89:1792365265::::::s:::::ed25519::\n\
+             fpr:::::::::{}:\n",
+            PRIMARY, SUBKEY
+        );
+        assert_eq!(
+            parse_gpg_key_listing(&listing),
+            Some((PRIMARY.to_string(), vec![SUBKEY.to_string()]))
+        );
+
+        let two_keys = format!("{}{}", listing, listing);
+        assert_eq!(parse_gpg_key_listing(&two_keys), None);
+        assert_eq!(parse_gpg_key_listing(""), None);
+    }
+
+    #[test]
+    fn test_gpg_key_id() {
+        assert_eq!(gpg_key_id(SUBKEY), "459F87EFFD473C89");
+        assert_eq!(gpg_key_id("459f87effd473c89"), "459F87EFFD473C89");
+    }
+}